@@ -0,0 +1,138 @@
+//! Types of the beacon node API served by anvil
+//!
+//! Anvil has no consensus layer, slots map to block numbers and block roots to block hashes.
+
+use alloy_consensus::{Blob, Bytes48};
+use alloy_primitives::{Bytes, B256};
+use alloy_rpc_types::{BlockId, BlockNumberOrTag};
+use std::{fmt, str::FromStr};
+
+/// Identifies a block in the beacon API: `head`, `genesis`, `finalized`, a slot or a block root
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BeaconBlockId {
+    Head,
+    Genesis,
+    Finalized,
+    Slot(u64),
+    Root(B256),
+}
+
+impl FromStr for BeaconBlockId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "head" => Ok(Self::Head),
+            "genesis" => Ok(Self::Genesis),
+            "finalized" => Ok(Self::Finalized),
+            s if s.starts_with("0x") => {
+                B256::from_str(s).map(Self::Root).map_err(|_| format!("invalid block root: {s}"))
+            }
+            s => s.parse().map(Self::Slot).map_err(|_| format!("invalid block id: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for BeaconBlockId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Head => f.write_str("head"),
+            Self::Genesis => f.write_str("genesis"),
+            Self::Finalized => f.write_str("finalized"),
+            Self::Slot(slot) => write!(f, "{slot}"),
+            Self::Root(root) => write!(f, "{root}"),
+        }
+    }
+}
+
+impl From<BeaconBlockId> for BlockId {
+    fn from(id: BeaconBlockId) -> Self {
+        match id {
+            BeaconBlockId::Head => BlockNumberOrTag::Latest.into(),
+            BeaconBlockId::Genesis => BlockNumberOrTag::Earliest.into(),
+            BeaconBlockId::Finalized => BlockNumberOrTag::Finalized.into(),
+            BeaconBlockId::Slot(slot) => BlockNumberOrTag::Number(slot).into(),
+            BeaconBlockId::Root(root) => root.into(),
+        }
+    }
+}
+
+/// The response of the `/eth/v1/beacon/blob_sidecars/{block_id}` endpoint
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlobSidecars {
+    pub data: Vec<BlobSidecar>,
+}
+
+/// A blob of a mined EIP-4844 transaction together with its KZG commitment and proof
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlobSidecar {
+    /// The index of the blob in the block
+    #[cfg_attr(feature = "serde", serde(with = "crate::eth::serde_helpers::decimal_string"))]
+    pub index: u64,
+    pub blob: Box<Blob>,
+    pub kzg_commitment: Bytes48,
+    pub kzg_proof: Bytes48,
+    pub signed_block_header: SignedBeaconBlockHeader,
+    /// Always empty, anvil does not build beacon block bodies
+    pub kzg_commitment_inclusion_proof: Vec<B256>,
+}
+
+/// A beacon block header and its signature
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignedBeaconBlockHeader {
+    pub message: BeaconBlockHeader,
+    /// Always zero, anvil does not sign beacon blocks
+    pub signature: Bytes,
+}
+
+/// A beacon block header, derived from the header of the execution block
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BeaconBlockHeader {
+    /// The block number
+    #[cfg_attr(feature = "serde", serde(with = "crate::eth::serde_helpers::decimal_string"))]
+    pub slot: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::eth::serde_helpers::decimal_string"))]
+    pub proposer_index: u64,
+    /// The hash of the parent block
+    pub parent_root: B256,
+    pub state_root: B256,
+    /// The transactions root of the block
+    pub body_root: B256,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_beacon_block_id() {
+        for (s, id) in [
+            ("head", BeaconBlockId::Head),
+            ("genesis", BeaconBlockId::Genesis),
+            ("finalized", BeaconBlockId::Finalized),
+            ("42", BeaconBlockId::Slot(42)),
+            (
+                "0x0000000000000000000000000000000000000000000000000000000000000001",
+                BeaconBlockId::Root(B256::with_last_byte(1)),
+            ),
+        ] {
+            assert_eq!(s.parse::<BeaconBlockId>().unwrap(), id);
+            assert_eq!(id.to_string(), s);
+        }
+        assert!("justified".parse::<BeaconBlockId>().is_err());
+        assert!("0x01".parse::<BeaconBlockId>().is_err());
+    }
+
+    #[test]
+    fn can_serialize_beacon_block_header() {
+        let header = BeaconBlockHeader { slot: 42, ..Default::default() };
+        let value = serde_json::to_value(&header).unwrap();
+        assert_eq!(value["slot"], "42");
+        assert_eq!(value["proposer_index"], "0");
+        assert_eq!(serde_json::from_value::<BeaconBlockHeader>(value).unwrap(), header);
+    }
+}
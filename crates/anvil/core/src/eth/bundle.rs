@@ -0,0 +1,109 @@
+//! Request and response types for the Flashbots-style `eth_sendBundle` and `eth_callBundle`
+
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256, U64};
+use alloy_rpc_types::BlockNumberOrTag;
+
+/// The payload of an `eth_sendBundle` request
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SendBundleRequest {
+    /// The signed transactions of the bundle, executed in the given order
+    pub txs: Vec<Bytes>,
+    /// The block the bundle must be included in
+    pub block_number: U64,
+    /// The minimum timestamp of the block the bundle is included in
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub min_timestamp: Option<u64>,
+    /// The maximum timestamp of the block the bundle is included in
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub max_timestamp: Option<u64>,
+    /// The hashes of the transactions that are allowed to revert
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub reverting_tx_hashes: Vec<B256>,
+}
+
+/// The response of `eth_sendBundle`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SendBundleResponse {
+    pub bundle_hash: B256,
+}
+
+/// The payload of an `eth_callBundle` request
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct CallBundleRequest {
+    /// The signed transactions of the bundle, executed in the given order
+    pub txs: Vec<Bytes>,
+    /// The number of the block the bundle is simulated in
+    pub block_number: U64,
+    /// The block whose state the bundle is executed on top of
+    pub state_block_number: BlockNumberOrTag,
+    /// The timestamp of the block the bundle is simulated in, defaults to the timestamp of the
+    /// state block
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub timestamp: Option<u64>,
+}
+
+/// The response of `eth_callBundle`
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct CallBundleResponse {
+    pub bundle_hash: B256,
+    /// The coinbase payment of the bundle divided by the gas it used
+    pub bundle_gas_price: U256,
+    /// The change of the coinbase balance, the gas fees and all direct payments
+    pub coinbase_diff: U256,
+    /// The payments to the coinbase that are not gas fees
+    pub eth_sent_to_coinbase: U256,
+    /// The priority fees paid for the gas used by the bundle
+    pub gas_fees: U256,
+    pub results: Vec<CallBundleResult>,
+    pub state_block_number: u64,
+    pub total_gas_used: u64,
+}
+
+/// The outcome of a single transaction of a simulated bundle
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct CallBundleResult {
+    pub tx_hash: B256,
+    pub from_address: Address,
+    pub to_address: Option<Address>,
+    pub gas_used: u64,
+    /// The effective gas price of the transaction
+    pub gas_price: U256,
+    pub gas_fees: U256,
+    pub coinbase_diff: U256,
+    pub eth_sent_to_coinbase: U256,
+    /// The data returned by the transaction, if it succeeded
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub value: Option<Bytes>,
+    /// The reason the transaction failed, if it did
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub error: Option<String>,
+    /// The data returned by the transaction, if it reverted
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub revert: Option<Bytes>,
+}
+
+impl CallBundleResult {
+    /// Returns true if the transaction neither reverted nor halted
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Returns the hash of a bundle: the hash of the concatenated hashes of its transactions
+pub fn bundle_hash<'a>(tx_hashes: impl IntoIterator<Item = &'a B256>) -> B256 {
+    let mut buf = Vec::new();
+    for hash in tx_hashes {
+        buf.extend_from_slice(hash.as_slice());
+    }
+    keccak256(buf)
+}
@@ -1,18 +1,31 @@
 use crate::{
-    eth::subscription::SubscriptionId,
-    types::{EvmMineOptions, Forking, Index},
+    eth::{
+        bundle::{CallBundleRequest, SendBundleRequest},
+        simulate::SimulatePayload,
+        subscription::{AnvilSubscriptionKind, StateDiffFilter, SubscriptionId},
+    },
+    types::{EvmMineOptions, Forking, Index, ReorgOptions},
 };
 use alloy_primitives::{Address, Bytes, TxHash, B256, B64, U256};
 use alloy_rpc_types::{
     pubsub::{Params as SubscriptionParams, SubscriptionKind},
     request::TransactionRequest,
     state::StateOverride,
-    BlockId, BlockNumberOrTag as BlockNumber, Filter, WithOtherFields,
+    BlockId, BlockNumberOrTag as BlockNumber, BlockOverrides, Filter, WithOtherFields,
 };
-use alloy_rpc_types_trace::geth::{GethDebugTracingOptions, GethDefaultTracingOptions};
+use alloy_rpc_types_engine::{ExecutionPayloadV3, ForkchoiceState, PayloadAttributes, PayloadId};
+use alloy_rpc_types_trace::{
+    filter::TraceFilter,
+    geth::{GethDebugTracingCallOptions, GethDebugTracingOptions},
+    parity::TraceType,
+};
+use std::collections::HashSet;
 
+pub mod beacon;
 pub mod block;
+pub mod bundle;
 pub mod proof;
+pub mod simulate;
 pub mod subscription;
 pub mod transaction;
 pub mod trie;
@@ -171,12 +184,24 @@ pub enum EthRequest {
         WithOtherFields<TransactionRequest>,
         #[cfg_attr(feature = "serde", serde(default))] Option<BlockId>,
         #[cfg_attr(feature = "serde", serde(default))] Option<StateOverride>,
+        #[cfg_attr(feature = "serde", serde(default))] Option<BlockOverrides>,
     ),
 
+    #[cfg_attr(feature = "serde", serde(rename = "eth_simulateV1"))]
+    EthSimulateV1(SimulatePayload, #[cfg_attr(feature = "serde", serde(default))] Option<BlockId>),
+
+    #[cfg_attr(feature = "serde", serde(rename = "eth_callBundle", with = "sequence"))]
+    EthCallBundle(CallBundleRequest),
+
+    #[cfg_attr(feature = "serde", serde(rename = "eth_sendBundle", with = "sequence"))]
+    EthSendBundle(SendBundleRequest),
+
     #[cfg_attr(feature = "serde", serde(rename = "eth_createAccessList"))]
     EthCreateAccessList(
         WithOtherFields<TransactionRequest>,
         #[cfg_attr(feature = "serde", serde(default))] Option<BlockId>,
+        #[cfg_attr(feature = "serde", serde(default))] Option<StateOverride>,
+        #[cfg_attr(feature = "serde", serde(default))] Option<BlockOverrides>,
     ),
 
     #[cfg_attr(feature = "serde", serde(rename = "eth_estimateGas"))]
@@ -184,6 +209,7 @@ pub enum EthRequest {
         WithOtherFields<TransactionRequest>,
         #[cfg_attr(feature = "serde", serde(default))] Option<BlockId>,
         #[cfg_attr(feature = "serde", serde(default))] Option<StateOverride>,
+        #[cfg_attr(feature = "serde", serde(default))] Option<BlockOverrides>,
     ),
 
     #[cfg_attr(feature = "serde", serde(rename = "eth_getTransactionByHash", with = "sequence"))]
@@ -277,7 +303,25 @@ pub enum EthRequest {
     DebugTraceCall(
         WithOtherFields<TransactionRequest>,
         #[cfg_attr(feature = "serde", serde(default))] Option<BlockId>,
-        #[cfg_attr(feature = "serde", serde(default))] GethDefaultTracingOptions,
+        #[cfg_attr(feature = "serde", serde(default))] GethDebugTracingCallOptions,
+    ),
+
+    /// geth's `debug_traceBlockByNumber` endpoint
+    #[cfg_attr(feature = "serde", serde(rename = "debug_traceBlockByNumber"))]
+    DebugTraceBlockByNumber(
+        #[cfg_attr(
+            feature = "serde",
+            serde(deserialize_with = "lenient_block_number::lenient_block_number")
+        )]
+        BlockNumber,
+        #[cfg_attr(feature = "serde", serde(default))] GethDebugTracingOptions,
+    ),
+
+    /// geth's `debug_traceBlockByHash` endpoint
+    #[cfg_attr(feature = "serde", serde(rename = "debug_traceBlockByHash"))]
+    DebugTraceBlockByHash(
+        B256,
+        #[cfg_attr(feature = "serde", serde(default))] GethDebugTracingOptions,
     ),
 
     /// Trace transaction endpoint for parity's `trace_transaction`
@@ -294,6 +338,25 @@ pub enum EthRequest {
     )]
     TraceBlock(BlockNumber),
 
+    /// Trace endpoint for parity's `trace_filter`
+    #[cfg_attr(feature = "serde", serde(rename = "trace_filter", with = "sequence"))]
+    TraceFilter(TraceFilter),
+
+    /// Trace endpoint for parity's `trace_replayTransaction`
+    #[cfg_attr(feature = "serde", serde(rename = "trace_replayTransaction"))]
+    TraceReplayTransaction(B256, HashSet<TraceType>),
+
+    /// Trace endpoint for parity's `trace_replayBlockTransactions`
+    #[cfg_attr(feature = "serde", serde(rename = "trace_replayBlockTransactions"))]
+    TraceReplayBlockTransactions(
+        #[cfg_attr(
+            feature = "serde",
+            serde(deserialize_with = "lenient_block_number::lenient_block_number")
+        )]
+        BlockNumber,
+        HashSet<TraceType>,
+    ),
+
     // Custom endpoints, they're not extracted to a separate type out of serde convenience
     /// send transactions impersonating specific account and contract addresses.
     #[cfg_attr(
@@ -434,6 +497,33 @@ pub enum EthRequest {
         B256,
     ),
 
+    /// Sets the ERC-20 balance of an account
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_dealERC20"))]
+    DealERC20(
+        /// token
+        Address,
+        /// account
+        Address,
+        /// amount
+        U256,
+        /// whether to adjust the total supply
+        #[cfg_attr(feature = "serde", serde(default))]
+        Option<bool>,
+    ),
+
+    /// Sets the ERC-20 allowance of a spender
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setERC20Allowance"))]
+    SetERC20Allowance(
+        /// token
+        Address,
+        /// owner
+        Address,
+        /// spender
+        Address,
+        /// amount
+        U256,
+    ),
+
     /// Sets the coinbase address
     #[cfg_attr(
         feature = "serde",
@@ -517,6 +607,17 @@ pub enum EthRequest {
     )]
     AnvilMetadata(()),
 
+    /// Retrieves statistics of the fork cache
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_forkCacheStats", with = "empty_params"))]
+    AnvilForkCacheStats(()),
+
+    /// Retrieves the blobs of a mined EIP-4844 transaction
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_getBlobsByTransactionHash", with = "sequence")
+    )]
+    GetBlobsByTransactionHash(TxHash),
+
     // Ganache compatible calls
     /// Snapshot the state of the blockchain at the current block.
     ///
@@ -636,6 +737,13 @@ pub enum EthRequest {
     #[cfg_attr(feature = "serde", serde(rename = "txpool_content", with = "empty_params"))]
     TxPoolContent(()),
 
+    /// Returns the details of all transactions of the given sender that are currently pending for
+    /// inclusion in the next block(s), as well as the ones that are being scheduled for future
+    /// execution only.
+    /// Ref: [Here](https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-txpool#txpool-contentfrom)
+    #[cfg_attr(feature = "serde", serde(rename = "txpool_contentFrom", with = "sequence"))]
+    TxPoolContentFrom(Address),
+
     /// Otterscan's `ots_getApiLevel` endpoint
     /// Otterscan currently requires this endpoint, even though it's not part of the ots_*
     /// https://github.com/otterscan/otterscan/blob/071d8c55202badf01804f6f8d53ef9311d4a9e47/src/useProvider.ts#L71
@@ -738,6 +846,50 @@ pub enum EthRequest {
         serde(rename = "anvil_removePoolTransactions", with = "sequence")
     )]
     RemovePoolTransactions(Address),
+
+    /// Reorg the chain: removes the given number of blocks from the tip of the chain and mines
+    /// the same number of new blocks in their place, optionally including the given transactions
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_reorg"))]
+    Reorg(ReorgOptions),
+
+    /// Rollback the chain by the given number of blocks
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_rollback", with = "sequence"))]
+    Rollback(u64),
+
+    /// Executes the given transactions first and in the given order in the next mined block
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_setNextBlockTransactions", with = "sequence")
+    )]
+    SetNextBlockTransactions(Vec<B256>),
+
+    /// Engine API: sets the head of the chain and optionally starts building a payload on top of
+    /// it
+    #[cfg_attr(feature = "serde", serde(rename = "engine_forkchoiceUpdatedV3"))]
+    EngineForkchoiceUpdatedV3(
+        ForkchoiceState,
+        #[cfg_attr(feature = "serde", serde(default))] Option<PayloadAttributes>,
+    ),
+
+    /// Engine API: returns the payload that was started with `engine_forkchoiceUpdatedV3`
+    #[cfg_attr(feature = "serde", serde(rename = "engine_getPayloadV3", with = "sequence"))]
+    EngineGetPayloadV3(PayloadId),
+
+    /// Engine API: validates the payload and appends it to the chain
+    #[cfg_attr(feature = "serde", serde(rename = "engine_newPayloadV3"))]
+    EngineNewPayloadV3(ExecutionPayloadV3, Vec<B256>, B256),
+}
+
+impl EthRequest {
+    /// Returns true if this is an engine API request, which must be authenticated
+    pub fn is_engine(&self) -> bool {
+        matches!(
+            self,
+            Self::EngineForkchoiceUpdatedV3(..) |
+                Self::EngineGetPayloadV3(_) |
+                Self::EngineNewPayloadV3(..)
+        )
+    }
 }
 
 /// Represents ethereum JSON-RPC API
@@ -752,8 +904,18 @@ pub enum EthPubSub {
         #[cfg_attr(feature = "serde", serde(default))] Box<SubscriptionParams>,
     ),
 
+    /// Subscribe to an anvil specific subscription
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_subscribe"))]
+    AnvilSubscribe(
+        AnvilSubscriptionKind,
+        #[cfg_attr(feature = "serde", serde(default))] StateDiffFilter,
+    ),
+
     /// Unsubscribe from an eth subscription
-    #[cfg_attr(feature = "serde", serde(rename = "eth_unsubscribe", with = "sequence"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "eth_unsubscribe", alias = "anvil_unsubscribe", with = "sequence")
+    )]
     EthUnSubscribe(SubscriptionId),
 }
 
@@ -769,6 +931,9 @@ pub enum EthRpcCall {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::TransactionData;
+    use alloy_primitives::{address, U64};
+    use alloy_rpc_types_trace::geth::GethDebugBuiltInTracerType;
 
     #[test]
     fn test_web3_client_version() {
@@ -1134,6 +1299,32 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_deal_erc20() {
+        let s = r#"{"method": "anvil_dealERC20", "params":
+["0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "0x295a70b2de5e3953354a6a8344e616ed314d7251",
+"0x3039"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::DealERC20(_, _, _, None)));
+
+        let s = r#"{"method": "anvil_dealERC20", "params":
+["0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "0x295a70b2de5e3953354a6a8344e616ed314d7251",
+"0x3039", true]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::DealERC20(_, _, _, Some(true))));
+    }
+
+    #[test]
+    fn test_serde_custom_set_erc20_allowance() {
+        let s = r#"{"method": "anvil_setERC20Allowance", "params":
+["0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", "0x295a70b2de5e3953354a6a8344e616ed314d7251",
+"0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56", "0x3039"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_serde_custom_coinbase() {
         let s = r#"{"method": "anvil_setCoinbase", "params":
@@ -1188,6 +1379,49 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_fork_cache_stats() {
+        let s = r#"{"method": "anvil_forkCacheStats", "params": [] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::AnvilForkCacheStats(())));
+    }
+
+    #[test]
+    fn test_serde_engine_forkchoice_updated() {
+        let s = r#"{"method": "engine_forkchoiceUpdatedV3", "params": [{"headBlockHash": "0x3b8fb240d288781d4aac94d3fd16809ee413bc99294a085798a589dae51ddd4a", "safeBlockHash": "0x3b8fb240d288781d4aac94d3fd16809ee413bc99294a085798a589dae51ddd4a", "finalizedBlockHash": "0x0000000000000000000000000000000000000000000000000000000000000000"}, {"timestamp": "0x6705f8c6", "prevRandao": "0x0000000000000000000000000000000000000000000000000000000000000000", "suggestedFeeRecipient": "0x0000000000000000000000000000000000000000", "withdrawals": [], "parentBeaconBlockRoot": "0x0000000000000000000000000000000000000000000000000000000000000000"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(req.is_engine());
+        match req {
+            EthRequest::EngineForkchoiceUpdatedV3(_, Some(attributes)) => {
+                assert_eq!(attributes.timestamp, 0x6705f8c6);
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "engine_forkchoiceUpdatedV3", "params": [{"headBlockHash": "0x3b8fb240d288781d4aac94d3fd16809ee413bc99294a085798a589dae51ddd4a", "safeBlockHash": "0x3b8fb240d288781d4aac94d3fd16809ee413bc99294a085798a589dae51ddd4a", "finalizedBlockHash": "0x0000000000000000000000000000000000000000000000000000000000000000"}, null]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::EngineForkchoiceUpdatedV3(_, None)));
+    }
+
+    #[test]
+    fn test_serde_engine_get_payload() {
+        let s = r#"{"method": "engine_getPayloadV3", "params": ["0x0000000021f32cc1"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::EngineGetPayloadV3(_)));
+    }
+
+    #[test]
+    fn test_serde_custom_get_blobs_by_transaction_hash() {
+        let s = r#"{"method": "anvil_getBlobsByTransactionHash", "params": ["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff"] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::GetBlobsByTransactionHash(_)));
+    }
+
     #[test]
     fn test_serde_custom_snapshot() {
         let s = r#"{"method": "anvil_snapshot", "params": [] }"#;
@@ -1420,6 +1654,36 @@ mod tests {
         let _req = serde_json::from_value::<EthPubSub>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_anvil_subscribe() {
+        let s = r#"{"id": 1, "method": "anvil_subscribe", "params": ["stateDiff"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthPubSub>(value).unwrap();
+        assert_eq!(
+            req,
+            EthPubSub::AnvilSubscribe(AnvilSubscriptionKind::StateDiff, Default::default())
+        );
+
+        let s = r#"{"id": 1, "method": "anvil_subscribe", "params": ["stateDiff", {"addresses":
+["0x8320fe7702b96808f7bbc0d4a888ed1468216cfd"]}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthPubSub>(value).unwrap();
+        assert_eq!(
+            req,
+            EthPubSub::AnvilSubscribe(
+                AnvilSubscriptionKind::StateDiff,
+                StateDiffFilter {
+                    addresses: vec![address!("8320fe7702b96808f7bbc0d4a888ed1468216cfd")]
+                }
+            )
+        );
+
+        let s = r#"{"id": 1, "method": "anvil_unsubscribe", "params":
+["0x9cef478923ff08bf67fde6c64013158d"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthPubSub>(value).unwrap();
+    }
+
     #[test]
     fn test_serde_debug_trace_transaction() {
         let s = r#"{"method": "debug_traceTransaction", "params":
@@ -1460,6 +1724,144 @@ true}]}"#;
         let s = r#"{"method": "debug_traceCall", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, { "blockNumber": "0x0" }, {"disableStorage": true}]}"#;
         let value: serde_json::Value = serde_json::from_str(s).unwrap();
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "debug_traceCall", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, "latest", {"tracer": "callTracer", "tracerConfig": {"onlyTopCall": true, "withLog": true}}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::DebugTraceCall(_, _, opts) => {
+                let opts = opts.tracing_options;
+                assert_eq!(opts.tracer, Some(GethDebugBuiltInTracerType::CallTracer.into()));
+                let config = opts.tracer_config.into_call_config().unwrap();
+                assert_eq!(config.only_top_call, Some(true));
+                assert_eq!(config.with_log, Some(true));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "debug_traceCall", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, "latest", {"tracer": "callTracer", "stateOverrides": {"0xd84de507f3fada7df80908082d3239466db55a71": {"balance": "0x1"}}, "blockOverrides": {"number": "0x2a", "coinbase": "0xd84de507f3fada7df80908082d3239466db55a71"}}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::DebugTraceCall(_, _, opts) => {
+                assert_eq!(
+                    opts.tracing_options.tracer,
+                    Some(GethDebugBuiltInTracerType::CallTracer.into())
+                );
+                assert_eq!(opts.state_overrides.unwrap().len(), 1);
+                let block_overrides = opts.block_overrides.unwrap();
+                assert_eq!(block_overrides.number, Some(U256::from(42)));
+                assert!(block_overrides.coinbase.is_some());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_eth_call_overrides() {
+        let s = r#"{"method": "eth_call", "params":[{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, "latest", {"0xd84de507f3fada7df80908082d3239466db55a71": {"balance": "0x1"}}, {"time": "0x64", "baseFee": "0x0"}]}"#;
+        let req = serde_json::from_str::<EthRequest>(s).unwrap();
+        match req {
+            EthRequest::EthCall(_, _, state_overrides, block_overrides) => {
+                assert_eq!(state_overrides.unwrap().len(), 1);
+                let block_overrides = block_overrides.unwrap();
+                assert_eq!(block_overrides.time, Some(U64::from(100)));
+                assert_eq!(block_overrides.base_fee, Some(U256::ZERO));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "eth_estimateGas", "params":[{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, "latest", null, {"number": "0x1"}]}"#;
+        let req = serde_json::from_str::<EthRequest>(s).unwrap();
+        match req {
+            EthRequest::EthEstimateGas(_, _, state_overrides, block_overrides) => {
+                assert!(state_overrides.is_none());
+                assert_eq!(block_overrides.unwrap().number, Some(U256::from(1)));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "eth_createAccessList", "params":[{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, "latest", {"0xd84de507f3fada7df80908082d3239466db55a71": {"nonce": "0x1"}}]}"#;
+        let req = serde_json::from_str::<EthRequest>(s).unwrap();
+        match req {
+            EthRequest::EthCreateAccessList(_, _, state_overrides, block_overrides) => {
+                assert_eq!(state_overrides.unwrap().len(), 1);
+                assert!(block_overrides.is_none());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_debug_trace_transaction_prestate() {
+        let s = r#"{"method": "debug_traceTransaction", "params":
+["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff", {"tracer": "prestateTracer", "tracerConfig": {"diffMode": true}}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::DebugTraceTransaction(_, opts) => {
+                assert_eq!(opts.tracer, Some(GethDebugBuiltInTracerType::PreStateTracer.into()));
+                let config = opts.tracer_config.into_pre_state_config().unwrap();
+                assert_eq!(config.diff_mode, Some(true));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "debug_traceTransaction", "params":
+["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff", {"tracer": "4byteTracer"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_debug_trace_block() {
+        let s = r#"{"method": "debug_traceBlockByNumber", "params": ["0x1"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "debug_traceBlockByNumber", "params": ["latest", {"tracer": "callTracer"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "debug_traceBlockByHash", "params":
+["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff", {"tracer": "prestateTracer", "tracerConfig": {"diffMode": true}}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_trace_filter() {
+        let s = r#"{"method": "trace_filter", "params": [{"fromBlock": "0x1", "toBlock": "0x2", "toAddress": ["0x295a70b2de5e3953354a6a8344e616ed314d7251"], "count": 10}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::TraceFilter(filter) => {
+                assert_eq!(filter.from_block, Some(1));
+                assert_eq!(filter.to_block, Some(2));
+                assert_eq!(filter.to_address.len(), 1);
+                assert_eq!(filter.count, Some(10));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_trace_replay() {
+        let s = r#"{"method": "trace_replayTransaction", "params": ["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff", ["trace", "stateDiff", "vmTrace"]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::TraceReplayTransaction(_, trace_types) => {
+                assert_eq!(trace_types.len(), 3);
+                assert!(trace_types.contains(&TraceType::VmTrace));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "trace_replayBlockTransactions", "params": ["latest", ["trace"]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::TraceReplayBlockTransactions(BlockNumber::Latest, _)));
     }
 
     #[test]
@@ -1491,6 +1893,78 @@ true}]}"#;
         let _req = serde_json::from_str::<EthRequest>(s).unwrap();
     }
 
+    #[test]
+    fn test_serde_eth_simulate_v1() {
+        let s = r#"{"method": "eth_simulateV1", "params":[{"blockStateCalls":[{"blockOverrides":{"number":"0x64","time":"0x65"},"stateOverrides":{"0xd84de507f3fada7df80908082d3239466db55a71":{"balance":"0xde0b6b3a7640000"}},"calls":[{"from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d","value":"0x1"}]},{"calls":[]}],"traceTransfers":true},"latest"]}"#;
+        let req = serde_json::from_str::<EthRequest>(s).unwrap();
+        match req {
+            EthRequest::EthSimulateV1(payload, block) => {
+                assert_eq!(block, Some(BlockId::Number(BlockNumber::Latest)));
+                assert!(payload.trace_transfers);
+                assert!(!payload.validation);
+                assert_eq!(payload.block_state_calls.len(), 2);
+
+                let first = &payload.block_state_calls[0];
+                let overrides = first.block_overrides.as_ref().unwrap();
+                assert_eq!(overrides.number, Some(U256::from(100)));
+                assert_eq!(overrides.time, Some(U64::from(101)));
+                assert_eq!(first.state_overrides.as_ref().unwrap().len(), 1);
+                assert_eq!(first.calls.len(), 1);
+                assert_eq!(first.calls[0].value, Some(U256::from(1)));
+
+                assert!(payload.block_state_calls[1].calls.is_empty());
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "eth_simulateV1", "params":[{"blockStateCalls":[]}]}"#;
+        let _req = serde_json::from_str::<EthRequest>(s).unwrap();
+    }
+
+    #[test]
+    fn test_serde_eth_call_bundle() {
+        let s = r#"{"method": "eth_callBundle", "params":[{"txs":["0x01","0x02"],"blockNumber":"0x5","stateBlockNumber":"latest"}]}"#;
+        let req = serde_json::from_str::<EthRequest>(s).unwrap();
+        match req {
+            EthRequest::EthCallBundle(request) => {
+                assert_eq!(request.txs.len(), 2);
+                assert_eq!(request.block_number, U64::from(5));
+                assert_eq!(request.state_block_number, BlockNumber::Latest);
+                assert_eq!(request.timestamp, None);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_eth_send_bundle() {
+        let s = r#"{"method": "eth_sendBundle", "params":[{"txs":["0x01"],"blockNumber":"0x5","minTimestamp":100,"revertingTxHashes":["0x0000000000000000000000000000000000000000000000000000000000000001"]}]}"#;
+        let req = serde_json::from_str::<EthRequest>(s).unwrap();
+        match req {
+            EthRequest::EthSendBundle(request) => {
+                assert_eq!(request.txs, vec![Bytes::from_static(&[1])]);
+                assert_eq!(request.block_number, U64::from(5));
+                assert_eq!(request.min_timestamp, Some(100));
+                assert_eq!(request.max_timestamp, None);
+                assert_eq!(request.reverting_tx_hashes, vec![B256::with_last_byte(1)]);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_txpool_content_from() {
+        let s = r#"{"method": "txpool_contentFrom", "params": ["0x295a70b2de5e3953354a6a8344e616ed314d7251"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::TxPoolContentFrom(address) => {
+                assert_eq!(address, address!("295a70b2de5e3953354a6a8344e616ed314d7251"));
+            }
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn test_serde_eth_balance() {
         let s = r#"{"method": "eth_getBalance", "params":
@@ -1546,4 +2020,66 @@ true}]}"#;
         let value: serde_json::Value = serde_json::from_str(s).unwrap();
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
+
+    #[test]
+    fn test_serde_anvil_reorg() {
+        let s = r#"{"method": "anvil_reorg", "params": [2]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::Reorg(options) => {
+                assert_eq!(options.depth, 2);
+                assert!(options.tx_block_pairs.is_empty());
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_reorg", "params": ["0x3", [
+            [{"from": "0x364d6D0333432C3Ac016Ca832fb8594A8cE43Ca6", "to": "0xd84de507f3fada7df80908082d3239466db55a71", "value": "0x64"}, 0],
+            ["0x02f86c0180843b9aca00850c570bd200825208941111111111111111111111111111111111111111880de0b6b3a764000080c080a0", 2]
+        ]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::Reorg(options) => {
+                assert_eq!(options.depth, 3);
+                assert_eq!(options.tx_block_pairs.len(), 2);
+                assert!(matches!(options.tx_block_pairs[0], (TransactionData::Json(_), 0)));
+                assert!(matches!(options.tx_block_pairs[1], (TransactionData::Raw(_), 2)));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_reorg", "params": {"depth": 1, "txBlockPairs": []}}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_anvil_rollback() {
+        let s = r#"{"method": "anvil_rollback", "params": [5]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::Rollback(depth) => assert_eq!(depth, 5),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_anvil_set_next_block_transactions() {
+        let s = r#"{"method": "anvil_setNextBlockTransactions", "params": [[
+            "0x4ecfb1a9c0a6c4a3b6a8e1e3c0f8ca3e4e1e8b8c2b4d5a6f7e8d9c0b1a2f3e4d",
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
+        ]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::SetNextBlockTransactions(hashes) => {
+                assert_eq!(hashes.len(), 2);
+                assert_eq!(hashes[1], B256::with_last_byte(1));
+            }
+            _ => unreachable!(),
+        }
+    }
 }
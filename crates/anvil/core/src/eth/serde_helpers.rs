@@ -108,3 +108,22 @@ pub mod lenient_block_number {
         }
     }
 }
+
+/// A module that (de)serializes numbers as decimal strings, as used by the beacon API
+pub mod decimal_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(val: &u64, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.collect_str(val)
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(d)?.parse().map_err(serde::de::Error::custom)
+    }
+}
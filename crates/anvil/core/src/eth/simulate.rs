@@ -0,0 +1,84 @@
+//! Request and response types for `eth_simulateV1`
+
+use alloy_primitives::{Bytes, U64};
+use alloy_rpc_types::{
+    request::TransactionRequest, state::StateOverride, Block, BlockOverrides, Log, WithOtherFields,
+};
+
+/// The payload of an `eth_simulateV1` request
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SimulatePayload {
+    /// The blocks to simulate, executed in order on top of the requested block
+    pub block_state_calls: Vec<SimBlock>,
+    /// Whether to emit a `Transfer` log for every ETH value transfer
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub trace_transfers: bool,
+    /// Whether to run the calls with the same nonce, balance and base fee checks as real
+    /// transactions
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub validation: bool,
+}
+
+/// A single simulated block: the overrides to apply before the block and the calls to execute in
+/// it
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SimBlock {
+    /// Overrides for the block environment
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub block_overrides: Option<BlockOverrides>,
+    /// Overrides for the state before executing the calls of this block
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub state_overrides: Option<StateOverride>,
+    /// The calls to execute in this block
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub calls: Vec<WithOtherFields<TransactionRequest>>,
+}
+
+/// A block produced by `eth_simulateV1`, together with the results of its calls
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulatedBlock {
+    /// The simulated block
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub inner: Block,
+    /// The results of the calls executed in this block
+    pub calls: Vec<SimCallResult>,
+}
+
+/// The outcome of a single call of a simulated block
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SimCallResult {
+    /// The data returned by the call
+    pub return_data: Bytes,
+    /// The logs emitted by the call
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub logs: Vec<Log>,
+    /// The gas used by the call
+    pub gas_used: U64,
+    /// `1` if the call succeeded, `0` otherwise
+    pub status: U64,
+    /// The reason the call failed, if any
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub error: Option<SimulateError>,
+}
+
+/// The error of a failed call of a simulated block
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulateError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl SimulateError {
+    /// Error code of a reverted call
+    pub const REVERTED: i64 = 3;
+    /// Error code of a call that halted
+    pub const VM_ERROR: i64 = -32015;
+}
@@ -1,7 +1,7 @@
 //! Subscription types
-use alloy_primitives::hex;
+use alloy_primitives::{hex, Address, Bytes, B256, U256};
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
-use std::fmt;
+use std::{collections::BTreeMap, fmt};
 
 /// Unique subscription id
 #[derive(Clone, PartialEq, Eq, Hash)]
@@ -41,6 +41,82 @@ impl fmt::Debug for SubscriptionId {
     }
 }
 
+/// Kinds of subscriptions that are specific to anvil, see `anvil_subscribe`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum AnvilSubscriptionKind {
+    /// The state changes of every mined block, see [StateDiff]
+    StateDiff,
+}
+
+/// Restricts the accounts included in a [StateDiff] subscription
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase", deny_unknown_fields))]
+pub struct StateDiffFilter {
+    /// Only include these accounts, all accounts are included if empty
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub addresses: Vec<Address>,
+}
+
+// === impl StateDiffFilter ===
+
+impl StateDiffFilter {
+    /// Returns the diff of the accounts that match the filter, or `None` if there are none
+    pub fn filter(&self, diff: &StateDiff) -> Option<StateDiff> {
+        let accounts: BTreeMap<_, _> = diff
+            .accounts
+            .iter()
+            .filter(|(address, _)| self.addresses.is_empty() || self.addresses.contains(address))
+            .map(|(address, account)| (*address, account.clone()))
+            .collect();
+        if accounts.is_empty() {
+            return None
+        }
+        Some(StateDiff { block_number: diff.block_number, block_hash: diff.block_hash, accounts })
+    }
+}
+
+/// The changes a mined block made to the state
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct StateDiff {
+    pub block_number: u64,
+    pub block_hash: B256,
+    /// All accounts that were changed by the block
+    pub accounts: BTreeMap<Address, AccountDiff>,
+}
+
+/// The new values of the fields of an account that were changed
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct AccountDiff {
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub balance: Option<U256>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub nonce: Option<u64>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub code: Option<Bytes>,
+    /// The changed storage slots
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "BTreeMap::is_empty"))]
+    pub storage: BTreeMap<B256, B256>,
+}
+
+// === impl AccountDiff ===
+
+impl AccountDiff {
+    /// Returns true if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.balance.is_none() &&
+            self.nonce.is_none() &&
+            self.code.is_none() &&
+            self.storage.is_empty()
+    }
+}
+
 /// Provides random hex identifier with a certain length
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct HexIdProvider {
@@ -69,3 +145,38 @@ impl Default for HexIdProvider {
 pub fn hex_id() -> String {
     HexIdProvider::default().gen()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn can_filter_state_diff() {
+        let changed = AccountDiff { nonce: Some(1), ..Default::default() };
+        let diff = StateDiff {
+            block_number: 1,
+            block_hash: B256::ZERO,
+            accounts: BTreeMap::from([
+                (address!("0000000000000000000000000000000000000001"), changed.clone()),
+                (address!("0000000000000000000000000000000000000002"), changed),
+            ]),
+        };
+
+        assert_eq!(StateDiffFilter::default().filter(&diff), Some(diff.clone()));
+
+        let filter = StateDiffFilter {
+            addresses: vec![address!("0000000000000000000000000000000000000002")],
+        };
+        let filtered = filter.filter(&diff).unwrap();
+        assert_eq!(
+            filtered.accounts.keys().collect::<Vec<_>>(),
+            [&address!("0000000000000000000000000000000000000002")]
+        );
+
+        let filter = StateDiffFilter {
+            addresses: vec![address!("0000000000000000000000000000000000000003")],
+        };
+        assert_eq!(filter.filter(&diff), None);
+    }
+}
@@ -0,0 +1,327 @@
+//! [EIP-7702](https://eips.ethereum.org/EIPS/eip-7702) set code transactions
+
+use alloy_consensus::{SignableTransaction, Signed, Transaction};
+use alloy_eips::eip2930::AccessList;
+use alloy_primitives::{keccak256, Address, Bytes, ChainId, Signature, TxKind, B256, U256};
+use alloy_rlp::{BufMut, Decodable, Encodable, Header, RlpDecodable, RlpEncodable};
+use serde::{Deserialize, Serialize};
+use std::mem;
+
+/// Identifier of the EIP-7702 set code transaction type
+pub const EIP7702_TX_TYPE_ID: u8 = 4;
+
+/// The magic byte that is prepended to an authorization before hashing it
+pub const AUTHORIZATION_MAGIC: u8 = 0x05;
+
+/// The intrinsic gas charged for every authorization in the list
+pub const PER_EMPTY_ACCOUNT_COST: u64 = 25_000;
+
+/// The intrinsic gas charged for an authorization of an account that already exists
+pub const PER_AUTH_BASE_COST: u64 = 12_500;
+
+/// The prefix of the code of an account that delegates to another account
+pub const DELEGATION_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
+
+/// Returns the code that delegates execution to the given address
+pub fn delegation_designator(address: Address) -> Bytes {
+    let mut code = Vec::with_capacity(DELEGATION_PREFIX.len() + 20);
+    code.extend_from_slice(&DELEGATION_PREFIX);
+    code.extend_from_slice(address.as_slice());
+    code.into()
+}
+
+/// Returns the address the given code delegates to, if it is a delegation designator
+pub fn delegated_address(code: &[u8]) -> Option<Address> {
+    if code.len() == DELEGATION_PREFIX.len() + 20 && code.starts_with(&DELEGATION_PREFIX) {
+        return Some(Address::from_slice(&code[DELEGATION_PREFIX.len()..]))
+    }
+    None
+}
+
+/// A signed authorization to set the code of the signing account to a delegation designator
+#[derive(Clone, Debug, PartialEq, Eq, Hash, RlpEncodable, RlpDecodable, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedAuthorization {
+    /// The chain the authorization is valid on, `0` for all chains
+    #[serde(with = "alloy_serde::quantity")]
+    pub chain_id: ChainId,
+    /// The address the authority delegates to
+    pub address: Address,
+    /// The nonce the authority must have
+    #[serde(with = "alloy_serde::quantity")]
+    pub nonce: u64,
+    #[serde(with = "alloy_serde::quantity")]
+    pub y_parity: u8,
+    pub r: U256,
+    pub s: U256,
+}
+
+impl SignedAuthorization {
+    /// Signs an authorization with the given signature
+    pub fn new(chain_id: ChainId, address: Address, nonce: u64, signature: Signature) -> Self {
+        Self {
+            chain_id,
+            address,
+            nonce,
+            y_parity: signature.v().y_parity_byte(),
+            r: signature.r(),
+            s: signature.s(),
+        }
+    }
+
+    /// Returns the hash that is signed by the authority:
+    /// `keccak256(MAGIC || rlp([chain_id, address, nonce]))`
+    pub fn signature_hash(chain_id: ChainId, address: Address, nonce: u64) -> B256 {
+        #[derive(RlpEncodable)]
+        struct Authorization {
+            chain_id: ChainId,
+            address: Address,
+            nonce: u64,
+        }
+
+        let mut buf = vec![AUTHORIZATION_MAGIC];
+        Authorization { chain_id, address, nonce }.encode(&mut buf);
+        keccak256(buf)
+    }
+
+    /// Recovers the address of the account that signed this authorization
+    pub fn recover_authority(&self) -> Result<Address, alloy_primitives::SignatureError> {
+        let signature = Signature::from_scalars_and_parity(
+            B256::from(self.r),
+            B256::from(self.s),
+            self.y_parity != 0,
+        )?;
+        signature.recover_address_from_prehash(&Self::signature_hash(
+            self.chain_id,
+            self.address,
+            self.nonce,
+        ))
+    }
+}
+
+/// An EIP-7702 set code transaction
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxEip7702 {
+    #[serde(with = "alloy_serde::quantity")]
+    pub chain_id: ChainId,
+    #[serde(with = "alloy_serde::quantity")]
+    pub nonce: u64,
+    #[serde(with = "alloy_serde::quantity")]
+    pub gas_limit: u128,
+    #[serde(with = "alloy_serde::quantity")]
+    pub max_fee_per_gas: u128,
+    #[serde(with = "alloy_serde::quantity")]
+    pub max_priority_fee_per_gas: u128,
+    /// Set code transactions can't create contracts, so this is always a call
+    pub to: Address,
+    pub value: U256,
+    pub access_list: AccessList,
+    /// The authorizations that are applied before the call is executed
+    pub authorization_list: Vec<SignedAuthorization>,
+    pub input: Bytes,
+}
+
+impl TxEip7702 {
+    /// Decodes the inner [TxEip7702] fields from RLP bytes.
+    ///
+    /// NOTE: This assumes a RLP header has already been decoded, and _just_ decodes the following
+    /// RLP fields in the following order:
+    ///
+    /// - `chain_id`
+    /// - `nonce`
+    /// - `max_priority_fee_per_gas`
+    /// - `max_fee_per_gas`
+    /// - `gas_limit`
+    /// - `to`
+    /// - `value`
+    /// - `data` (`input`)
+    /// - `access_list`
+    /// - `authorization_list`
+    pub fn decode_fields(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        Ok(Self {
+            chain_id: Decodable::decode(buf)?,
+            nonce: Decodable::decode(buf)?,
+            max_priority_fee_per_gas: Decodable::decode(buf)?,
+            max_fee_per_gas: Decodable::decode(buf)?,
+            gas_limit: Decodable::decode(buf)?,
+            to: Decodable::decode(buf)?,
+            value: Decodable::decode(buf)?,
+            input: Decodable::decode(buf)?,
+            access_list: Decodable::decode(buf)?,
+            authorization_list: Decodable::decode(buf)?,
+        })
+    }
+
+    /// Outputs the length of the transaction's fields, without a RLP header.
+    pub fn fields_len(&self) -> usize {
+        let mut len = 0;
+        len += self.chain_id.length();
+        len += self.nonce.length();
+        len += self.max_priority_fee_per_gas.length();
+        len += self.max_fee_per_gas.length();
+        len += self.gas_limit.length();
+        len += self.to.length();
+        len += self.value.length();
+        len += self.input.0.length();
+        len += self.access_list.length();
+        len += self.authorization_list.length();
+        len
+    }
+
+    /// Encodes only the transaction's fields into the desired buffer, without a RLP header.
+    pub(crate) fn encode_fields(&self, out: &mut dyn BufMut) {
+        self.chain_id.encode(out);
+        self.nonce.encode(out);
+        self.max_priority_fee_per_gas.encode(out);
+        self.max_fee_per_gas.encode(out);
+        self.gas_limit.encode(out);
+        self.to.encode(out);
+        self.value.encode(out);
+        self.input.0.encode(out);
+        self.access_list.encode(out);
+        self.authorization_list.encode(out);
+    }
+
+    /// Returns the length of the EIP-2718 encoding with the given signature: the type byte
+    /// followed by the RLP list of the fields and the signature.
+    pub fn encoded_len_with_signature(&self, signature: &Signature) -> usize {
+        let payload_length = self.fields_len() + signature.rlp_vrs_len();
+        1 + Header { list: true, payload_length }.length() + payload_length
+    }
+
+    /// Writes the EIP-2718 encoding with the given signature: the type byte followed by the RLP
+    /// list of the fields and the signature.
+    pub fn encode_with_signature(&self, signature: &Signature, out: &mut dyn BufMut) {
+        let payload_length = self.fields_len() + signature.rlp_vrs_len();
+        out.put_u8(EIP7702_TX_TYPE_ID);
+        Header { list: true, payload_length }.encode(out);
+        self.encode_fields(out);
+        signature.write_rlp_vrs(out);
+    }
+
+    /// Decodes the transaction from RLP bytes, including the signature.
+    ///
+    /// This __does not__ expect the bytes to start with a transaction type byte or string
+    /// header.
+    pub fn decode_signed_fields(buf: &mut &[u8]) -> alloy_rlp::Result<Signed<Self>> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+
+        // record original length so we can check encoding
+        let original_len = buf.len();
+
+        let tx = Self::decode_fields(buf)?;
+        let signature = Signature::decode_rlp_vrs(buf)?;
+
+        if buf.len() + header.payload_length != original_len {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: original_len - buf.len(),
+            })
+        }
+
+        Ok(tx.into_signed(signature))
+    }
+
+    /// Returns the intrinsic gas of the authorization list
+    pub fn authorization_gas(&self) -> u64 {
+        self.authorization_list.len() as u64 * PER_EMPTY_ACCOUNT_COST
+    }
+
+    /// Calculates a heuristic for the in-memory size of the [TxEip7702] transaction.
+    #[inline]
+    pub fn size(&self) -> usize {
+        mem::size_of::<ChainId>() + // chain_id
+        mem::size_of::<u64>() + // nonce
+        mem::size_of::<u128>() + // gas_limit
+        mem::size_of::<u128>() + // max_fee_per_gas
+        mem::size_of::<u128>() + // max_priority_fee_per_gas
+        mem::size_of::<Address>() + // to
+        mem::size_of::<U256>() + // value
+        self.access_list.size() + // access_list
+        self.authorization_list.len() * mem::size_of::<SignedAuthorization>() + // authorization_list
+        self.input.len() // input
+    }
+}
+
+impl Transaction for TxEip7702 {
+    fn input(&self) -> &[u8] {
+        &self.input
+    }
+
+    fn to(&self) -> TxKind {
+        TxKind::Call(self.to)
+    }
+
+    fn value(&self) -> U256 {
+        self.value
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        Some(self.chain_id)
+    }
+
+    fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    fn gas_limit(&self) -> u128 {
+        self.gas_limit
+    }
+
+    fn gas_price(&self) -> Option<u128> {
+        None
+    }
+}
+
+impl SignableTransaction<Signature> for TxEip7702 {
+    fn set_chain_id(&mut self, chain_id: ChainId) {
+        self.chain_id = chain_id;
+    }
+
+    fn payload_len_for_signature(&self) -> usize {
+        self.length() + 1
+    }
+
+    fn into_signed(self, signature: Signature) -> Signed<Self> {
+        // V should indicate the y-parity of the signature.
+        let signature = signature.with_parity_bool();
+
+        let mut buf = Vec::with_capacity(self.encoded_len_with_signature(&signature));
+        self.encode_with_signature(&signature, &mut buf);
+        let hash = keccak256(&buf);
+
+        Signed::new_unchecked(self, signature, hash)
+    }
+
+    fn encode_for_signing(&self, out: &mut dyn BufMut) {
+        out.put_u8(EIP7702_TX_TYPE_ID);
+        self.encode(out)
+    }
+}
+
+impl Encodable for TxEip7702 {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.fields_len() }.encode(out);
+        self.encode_fields(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.fields_len();
+        Header { list: true, payload_length }.length() + payload_length
+    }
+}
+
+impl Decodable for TxEip7702 {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if header.payload_length > buf.len() {
+            return Err(alloy_rlp::Error::InputTooShort)
+        }
+
+        Self::decode_fields(buf)
+    }
+}
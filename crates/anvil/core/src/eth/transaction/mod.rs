@@ -1,10 +1,13 @@
 //! Transaction related types
 
-use crate::eth::transaction::optimism::{DepositTransaction, DepositTransactionRequest};
+use crate::eth::transaction::{
+    eip7702::{SignedAuthorization, TxEip7702, EIP7702_TX_TYPE_ID},
+    optimism::{DepositTransaction, DepositTransactionRequest},
+};
 use alloy_consensus::{
     transaction::eip4844::{TxEip4844, TxEip4844Variant, TxEip4844WithSidecar},
-    AnyReceiptEnvelope, Receipt, ReceiptEnvelope, ReceiptWithBloom, Signed, TxEip1559, TxEip2930,
-    TxEnvelope, TxLegacy, TxReceipt,
+    AnyReceiptEnvelope, BlobTransactionSidecar, Receipt, ReceiptEnvelope, ReceiptWithBloom, Signed,
+    TxEip1559, TxEip2930, TxEnvelope, TxLegacy, TxReceipt,
 };
 use alloy_eips::eip2718::{Decodable2718, Encodable2718};
 use alloy_primitives::{Address, Bloom, Bytes, Log, Signature, TxHash, TxKind, B256, U256};
@@ -22,6 +25,7 @@ use revm::{
 use serde::{Deserialize, Serialize};
 use std::ops::{Deref, Mul};
 
+pub mod eip7702;
 pub mod optimism;
 
 /// The signature used to bypass signing via the `eth_sendUnsignedTransaction` cheat RPC
@@ -72,6 +76,24 @@ pub fn transaction_request_to_typed(
         }))
     }
 
+    // Special case: EIP-7702 set code tx, the authorization list is not part of the request type
+    if transaction_type == Some(EIP7702_TX_TYPE_ID) || other.contains_key("authorizationList") {
+        return Some(TypedTransactionRequest::EIP7702(TxEip7702 {
+            nonce: nonce.unwrap_or_default(),
+            max_fee_per_gas: max_fee_per_gas.unwrap_or_default(),
+            max_priority_fee_per_gas: max_priority_fee_per_gas.unwrap_or_default(),
+            gas_limit: gas.unwrap_or_default(),
+            value: value.unwrap_or(U256::ZERO),
+            input: input.into_input().unwrap_or_default(),
+            to: *to?.to()?,
+            chain_id: 0,
+            access_list: access_list.unwrap_or_default(),
+            authorization_list: other
+                .get_deserialized::<Vec<SignedAuthorization>>("authorizationList")?
+                .ok()?,
+        }))
+    }
+
     match (
         transaction_type,
         gas_price,
@@ -166,6 +188,7 @@ pub enum TypedTransactionRequest {
     EIP2930(TxEip2930),
     EIP1559(TxEip1559),
     EIP4844(TxEip4844Variant),
+    EIP7702(TxEip7702),
     Deposit(DepositTransactionRequest),
 }
 
@@ -173,7 +196,7 @@ pub enum TypedTransactionRequest {
 ///
 /// This is a helper that carries the `impersonated` sender so that the right hash
 /// [TypedTransaction::impersonated_hash] can be created.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MaybeImpersonatedTransaction {
     pub transaction: TypedTransaction,
     pub impersonated_sender: Option<Address>,
@@ -377,6 +400,36 @@ pub fn to_alloy_transaction_with_hash_and_sender(
             blob_versioned_hashes: Some(t.tx().tx().blob_versioned_hashes.clone()),
             other: Default::default(),
         },
+        TypedTransaction::EIP7702(t) => RpcTransaction {
+            hash,
+            nonce: t.tx().nonce,
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            from,
+            to: None,
+            value: t.tx().value,
+            gas_price: None,
+            max_fee_per_gas: Some(t.tx().max_fee_per_gas),
+            max_priority_fee_per_gas: Some(t.tx().max_priority_fee_per_gas),
+            gas: t.tx().gas_limit,
+            input: t.tx().input.clone(),
+            chain_id: Some(t.tx().chain_id),
+            signature: Some(RpcSignature {
+                r: t.signature().r(),
+                s: t.signature().s(),
+                v: U256::from(t.signature().v().y_parity_byte()),
+                y_parity: Some(alloy_rpc_types::Parity::from(t.signature().v().y_parity())),
+            }),
+            access_list: Some(t.tx().access_list.clone()),
+            transaction_type: Some(EIP7702_TX_TYPE_ID),
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
+            other: OtherFields::from_iter([(
+                "authorizationList",
+                serde_json::to_value(&t.tx().authorization_list).unwrap_or_default(),
+            )]),
+        },
         TypedTransaction::Deposit(t) => RpcTransaction {
             hash,
             nonce: t.nonce,
@@ -430,6 +483,18 @@ impl PendingTransaction {
         }
     }
 
+    /// Creates a new pending transaction from an already mined [MaybeImpersonatedTransaction],
+    /// recovering the sender unless it was impersonated.
+    #[cfg(feature = "impersonated-tx")]
+    pub fn from_maybe_impersonated(
+        transaction: MaybeImpersonatedTransaction,
+    ) -> Result<Self, alloy_primitives::SignatureError> {
+        if let Some(sender) = transaction.impersonated_sender {
+            return Ok(Self::with_impersonated(transaction.transaction, sender))
+        }
+        Self::new(transaction.transaction)
+    }
+
     pub fn nonce(&self) -> u64 {
         self.transaction.nonce()
     }
@@ -555,6 +620,33 @@ impl PendingTransaction {
                     ..Default::default()
                 }
             }
+            TypedTransaction::EIP7702(tx) => {
+                let TxEip7702 {
+                    chain_id,
+                    nonce,
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                    gas_limit,
+                    to,
+                    value,
+                    input,
+                    access_list,
+                    ..
+                } = tx.tx();
+                TxEnv {
+                    caller,
+                    transact_to: TransactTo::call(*to),
+                    data: input.clone(),
+                    chain_id: Some(*chain_id),
+                    nonce: Some(*nonce),
+                    value: *value,
+                    gas_price: U256::from(*max_fee_per_gas),
+                    gas_priority_fee: Some(U256::from(*max_priority_fee_per_gas)),
+                    gas_limit: *gas_limit as u64,
+                    access_list: access_list.flattened(),
+                    ..Default::default()
+                }
+            }
             TypedTransaction::Deposit(tx) => {
                 let chain_id = tx.chain_id();
                 let DepositTransaction {
@@ -593,7 +685,7 @@ impl PendingTransaction {
 }
 
 /// Container type for signed, typed transactions.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TypedTransaction {
     /// Legacy transaction type
     Legacy(Signed<TxLegacy>),
@@ -603,14 +695,21 @@ pub enum TypedTransaction {
     EIP1559(Signed<TxEip1559>),
     /// EIP-4844 transaction
     EIP4844(Signed<TxEip4844Variant>),
+    /// EIP-7702 transaction
+    EIP7702(Signed<TxEip7702>),
     /// op-stack deposit transaction
     Deposit(DepositTransaction),
 }
 
 impl TypedTransaction {
-    /// Returns true if the transaction uses dynamic fees: EIP1559 or EIP4844
+    /// Returns true if the transaction uses dynamic fees: EIP1559, EIP4844 or EIP7702
     pub fn is_dynamic_fee(&self) -> bool {
-        matches!(self, TypedTransaction::EIP1559(_)) || matches!(self, TypedTransaction::EIP4844(_))
+        matches!(
+            self,
+            TypedTransaction::EIP1559(_) |
+                TypedTransaction::EIP4844(_) |
+                TypedTransaction::EIP7702(_)
+        )
     }
 
     pub fn gas_price(&self) -> u128 {
@@ -619,6 +718,20 @@ impl TypedTransaction {
             TypedTransaction::EIP2930(tx) => tx.tx().gas_price,
             TypedTransaction::EIP1559(tx) => tx.tx().max_fee_per_gas,
             TypedTransaction::EIP4844(tx) => tx.tx().tx().max_fee_per_gas,
+            TypedTransaction::EIP7702(tx) => tx.tx().max_fee_per_gas,
+            TypedTransaction::Deposit(_) => 0,
+        }
+    }
+
+    /// Returns the max priority fee per gas, which is the gas price for transactions without
+    /// dynamic fees
+    pub fn max_priority_fee_per_gas(&self) -> u128 {
+        match self {
+            TypedTransaction::Legacy(tx) => tx.tx().gas_price,
+            TypedTransaction::EIP2930(tx) => tx.tx().gas_price,
+            TypedTransaction::EIP1559(tx) => tx.tx().max_priority_fee_per_gas,
+            TypedTransaction::EIP4844(tx) => tx.tx().tx().max_priority_fee_per_gas,
+            TypedTransaction::EIP7702(tx) => tx.tx().max_priority_fee_per_gas,
             TypedTransaction::Deposit(_) => 0,
         }
     }
@@ -629,6 +742,7 @@ impl TypedTransaction {
             TypedTransaction::EIP2930(tx) => tx.tx().gas_limit,
             TypedTransaction::EIP1559(tx) => tx.tx().gas_limit,
             TypedTransaction::EIP4844(tx) => tx.tx().tx().gas_limit,
+            TypedTransaction::EIP7702(tx) => tx.tx().gas_limit,
             TypedTransaction::Deposit(tx) => tx.gas_limit,
         }
     }
@@ -639,6 +753,7 @@ impl TypedTransaction {
             TypedTransaction::EIP2930(tx) => tx.tx().value,
             TypedTransaction::EIP1559(tx) => tx.tx().value,
             TypedTransaction::EIP4844(tx) => tx.tx().tx().value,
+            TypedTransaction::EIP7702(tx) => tx.tx().value,
             TypedTransaction::Deposit(tx) => tx.value,
         })
     }
@@ -649,6 +764,7 @@ impl TypedTransaction {
             TypedTransaction::EIP2930(tx) => &tx.tx().input,
             TypedTransaction::EIP1559(tx) => &tx.tx().input,
             TypedTransaction::EIP4844(tx) => &tx.tx().tx().input,
+            TypedTransaction::EIP7702(tx) => &tx.tx().input,
             TypedTransaction::Deposit(tx) => &tx.input,
         }
     }
@@ -660,6 +776,7 @@ impl TypedTransaction {
             TypedTransaction::EIP2930(_) => Some(1),
             TypedTransaction::EIP1559(_) => Some(2),
             TypedTransaction::EIP4844(_) => Some(3),
+            TypedTransaction::EIP7702(_) => Some(EIP7702_TX_TYPE_ID),
             TypedTransaction::Deposit(_) => Some(0x7E),
         }
     }
@@ -694,6 +811,17 @@ impl TypedTransaction {
         }
     }
 
+    /// Returns the blob sidecar if this is an EIP-4844 transaction that carries one
+    pub fn sidecar(&self) -> Option<&BlobTransactionSidecar> {
+        match self {
+            TypedTransaction::EIP4844(tx) => match tx.tx() {
+                TxEip4844Variant::TxEip4844WithSidecar(tx) => Some(tx.sidecar()),
+                TxEip4844Variant::TxEip4844(_) => None,
+            },
+            _ => None,
+        }
+    }
+
     /// Returns a helper type that contains commonly used values as fields
     pub fn essentials(&self) -> TransactionEssentials {
         match self {
@@ -753,6 +881,20 @@ impl TypedTransaction {
                 chain_id: Some(t.tx().tx().chain_id),
                 access_list: t.tx().tx().access_list.clone(),
             },
+            TypedTransaction::EIP7702(t) => TransactionEssentials {
+                kind: TxKind::Call(t.tx().to),
+                input: t.tx().input.clone(),
+                nonce: t.tx().nonce,
+                gas_limit: t.tx().gas_limit,
+                gas_price: None,
+                max_fee_per_gas: Some(U256::from(t.tx().max_fee_per_gas)),
+                max_priority_fee_per_gas: Some(U256::from(t.tx().max_priority_fee_per_gas)),
+                max_fee_per_blob_gas: None,
+                blob_versioned_hashes: None,
+                value: t.tx().value,
+                chain_id: Some(t.tx().chain_id),
+                access_list: t.tx().access_list.clone(),
+            },
             TypedTransaction::Deposit(t) => TransactionEssentials {
                 kind: t.kind,
                 input: t.input.clone(),
@@ -776,6 +918,7 @@ impl TypedTransaction {
             TypedTransaction::EIP2930(t) => t.tx().nonce,
             TypedTransaction::EIP1559(t) => t.tx().nonce,
             TypedTransaction::EIP4844(t) => t.tx().tx().nonce,
+            TypedTransaction::EIP7702(t) => t.tx().nonce,
             TypedTransaction::Deposit(t) => t.nonce,
         }
     }
@@ -786,6 +929,7 @@ impl TypedTransaction {
             TypedTransaction::EIP2930(t) => Some(t.tx().chain_id),
             TypedTransaction::EIP1559(t) => Some(t.tx().chain_id),
             TypedTransaction::EIP4844(t) => Some(t.tx().tx().chain_id),
+            TypedTransaction::EIP7702(t) => Some(t.tx().chain_id),
             TypedTransaction::Deposit(t) => t.chain_id(),
         }
     }
@@ -817,6 +961,19 @@ impl TypedTransaction {
         matches!(self, TypedTransaction::EIP4844(_))
     }
 
+    /// Returns true whether this tx is a EIP7702 transaction
+    pub fn is_eip7702(&self) -> bool {
+        matches!(self, TypedTransaction::EIP7702(_))
+    }
+
+    /// Returns the authorization list if this is a EIP7702 transaction
+    pub fn authorization_list(&self) -> Option<&[SignedAuthorization]> {
+        match self {
+            TypedTransaction::EIP7702(tx) => Some(&tx.tx().authorization_list),
+            _ => None,
+        }
+    }
+
     /// Returns the hash of the transaction.
     ///
     /// Note: If this transaction has the Impersonated signature then this returns a modified unique
@@ -827,6 +984,7 @@ impl TypedTransaction {
             TypedTransaction::EIP2930(t) => *t.hash(),
             TypedTransaction::EIP1559(t) => *t.hash(),
             TypedTransaction::EIP4844(t) => *t.hash(),
+            TypedTransaction::EIP7702(t) => *t.hash(),
             TypedTransaction::Deposit(t) => t.hash(),
         }
     }
@@ -855,6 +1013,7 @@ impl TypedTransaction {
             TypedTransaction::EIP2930(tx) => tx.recover_signer(),
             TypedTransaction::EIP1559(tx) => tx.recover_signer(),
             TypedTransaction::EIP4844(tx) => tx.recover_signer(),
+            TypedTransaction::EIP7702(tx) => tx.recover_signer(),
             TypedTransaction::Deposit(tx) => tx.recover(),
         }
     }
@@ -866,6 +1025,7 @@ impl TypedTransaction {
             TypedTransaction::EIP2930(tx) => tx.tx().to,
             TypedTransaction::EIP1559(tx) => tx.tx().to,
             TypedTransaction::EIP4844(tx) => TxKind::Call(tx.tx().tx().to),
+            TypedTransaction::EIP7702(tx) => TxKind::Call(tx.tx().to),
             TypedTransaction::Deposit(tx) => tx.kind,
         }
     }
@@ -882,6 +1042,7 @@ impl TypedTransaction {
             TypedTransaction::EIP2930(tx) => *tx.signature(),
             TypedTransaction::EIP1559(tx) => *tx.signature(),
             TypedTransaction::EIP4844(tx) => *tx.signature(),
+            TypedTransaction::EIP7702(tx) => *tx.signature(),
             TypedTransaction::Deposit(_) => Signature::from_scalars_and_parity(
                 B256::with_last_byte(1),
                 B256::with_last_byte(1),
@@ -899,6 +1060,11 @@ impl Encodable for TypedTransaction {
             TypedTransaction::EIP2930(tx) => TxEnvelope::from(tx.clone()).encode(out),
             TypedTransaction::EIP1559(tx) => TxEnvelope::from(tx.clone()).encode(out),
             TypedTransaction::EIP4844(tx) => TxEnvelope::from(tx.clone()).encode(out),
+            TypedTransaction::EIP7702(tx) => {
+                let payload_length = tx.tx().encoded_len_with_signature(tx.signature());
+                Header { list: false, payload_length }.encode(out);
+                tx.tx().encode_with_signature(tx.signature(), out);
+            }
             TypedTransaction::Deposit(tx) => {
                 let tx_payload_len = tx.fields_len();
                 let tx_header_len = Header { list: false, payload_length: tx_payload_len }.length();
@@ -924,10 +1090,17 @@ impl Decodable for TypedTransaction {
         // Check byte after header
         let ty = *h_decode_copy.first().ok_or(alloy_rlp::Error::Custom("empty slice"))?;
 
-        if ty != 0x7E {
-            Ok(TxEnvelope::decode(buf)?.into())
-        } else {
-            Ok(Self::Deposit(DepositTransaction::decode(&mut h_decode_copy)?))
+        match ty {
+            0x7E => Ok(Self::Deposit(DepositTransaction::decode(&mut h_decode_copy)?)),
+            EIP7702_TX_TYPE_ID => {
+                let mut payload = h_decode_copy
+                    .get(1..header.payload_length)
+                    .ok_or(alloy_rlp::Error::InputTooShort)?;
+                let tx = TxEip7702::decode_signed_fields(&mut payload)?;
+                *buf = &h_decode_copy[header.payload_length..];
+                Ok(Self::EIP7702(tx))
+            }
+            _ => Ok(TxEnvelope::decode(buf)?.into()),
         }
     }
 }
@@ -943,6 +1116,7 @@ impl Encodable2718 for TypedTransaction {
             TypedTransaction::EIP2930(tx) => TxEnvelope::from(tx.clone()).encode_2718_len(),
             TypedTransaction::EIP1559(tx) => TxEnvelope::from(tx.clone()).encode_2718_len(),
             TypedTransaction::EIP4844(tx) => TxEnvelope::from(tx.clone()).encode_2718_len(),
+            TypedTransaction::EIP7702(tx) => tx.tx().encoded_len_with_signature(tx.signature()),
             TypedTransaction::Deposit(tx) => 1 + tx.length(),
         }
     }
@@ -953,6 +1127,7 @@ impl Encodable2718 for TypedTransaction {
             TypedTransaction::EIP2930(tx) => TxEnvelope::from(tx.clone()).encode_2718(out),
             TypedTransaction::EIP1559(tx) => TxEnvelope::from(tx.clone()).encode_2718(out),
             TypedTransaction::EIP4844(tx) => TxEnvelope::from(tx.clone()).encode_2718(out),
+            TypedTransaction::EIP7702(tx) => tx.tx().encode_with_signature(tx.signature(), out),
             TypedTransaction::Deposit(tx) => {
                 out.put_u8(0x7E);
                 tx.encode(out);
@@ -966,6 +1141,9 @@ impl Decodable2718 for TypedTransaction {
         if ty == 0x7E {
             return Ok(Self::Deposit(DepositTransaction::decode(buf)?))
         }
+        if ty == EIP7702_TX_TYPE_ID {
+            return Ok(Self::EIP7702(TxEip7702::decode_signed_fields(buf)?))
+        }
         match TxEnvelope::typed_decode(ty, buf)? {
             TxEnvelope::Eip2930(tx) => Ok(Self::EIP2930(tx)),
             TxEnvelope::Eip1559(tx) => Ok(Self::EIP1559(tx)),
@@ -1011,7 +1189,7 @@ pub struct TransactionEssentials {
 }
 
 /// Represents all relevant information of an executed transaction
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TransactionInfo {
     pub transaction_hash: B256,
     pub transaction_index: u64,
@@ -1132,6 +1310,8 @@ pub enum TypedReceipt<T = alloy_primitives::Log> {
     EIP1559(ReceiptWithBloom<T>),
     #[serde(rename = "0x3", alias = "0x03")]
     EIP4844(ReceiptWithBloom<T>),
+    #[serde(rename = "0x4", alias = "0x04")]
+    EIP7702(ReceiptWithBloom<T>),
     #[serde(rename = "0x7E", alias = "0x7e")]
     Deposit(DepositReceipt<T>),
 }
@@ -1142,7 +1322,8 @@ impl<T> TypedReceipt<T> {
             TypedReceipt::Legacy(r) |
             TypedReceipt::EIP1559(r) |
             TypedReceipt::EIP2930(r) |
-            TypedReceipt::EIP4844(r) => r,
+            TypedReceipt::EIP4844(r) |
+            TypedReceipt::EIP7702(r) => r,
             TypedReceipt::Deposit(r) => &r.inner,
         }
     }
@@ -1183,6 +1364,7 @@ impl Encodable for TypedReceipt {
                     TypedReceipt::EIP2930(r) => r.length() + 1,
                     TypedReceipt::EIP1559(r) => r.length() + 1,
                     TypedReceipt::EIP4844(r) => r.length() + 1,
+                    TypedReceipt::EIP7702(r) => r.length() + 1,
                     TypedReceipt::Deposit(r) => r.length() + 1,
                     _ => unreachable!("receipt already matched"),
                 };
@@ -1203,6 +1385,11 @@ impl Encodable for TypedReceipt {
                         3u8.encode(out);
                         r.encode(out);
                     }
+                    TypedReceipt::EIP7702(r) => {
+                        Header { list: true, payload_length: payload_len }.encode(out);
+                        EIP7702_TX_TYPE_ID.encode(out);
+                        r.encode(out);
+                    }
                     TypedReceipt::Deposit(r) => {
                         Header { list: true, payload_length: payload_len }.encode(out);
                         0x7Eu8.encode(out);
@@ -1243,6 +1430,9 @@ impl Decodable for TypedReceipt {
                 } else if receipt_type == 0x03 {
                     buf.advance(1);
                     <ReceiptWithBloom as Decodable>::decode(buf).map(TypedReceipt::EIP4844)
+                } else if receipt_type == EIP7702_TX_TYPE_ID {
+                    buf.advance(1);
+                    <ReceiptWithBloom as Decodable>::decode(buf).map(TypedReceipt::EIP7702)
                 } else if receipt_type == 0x7E {
                     buf.advance(1);
                     <DepositReceipt as Decodable>::decode(buf).map(TypedReceipt::Deposit)
@@ -1301,6 +1491,7 @@ pub fn convert_to_anvil_receipt(receipt: AnyTransactionReceipt) -> Option<Receip
             0x01 => TypedReceipt::EIP2930(receipt_with_bloom),
             0x02 => TypedReceipt::EIP1559(receipt_with_bloom),
             0x03 => TypedReceipt::EIP4844(receipt_with_bloom),
+            EIP7702_TX_TYPE_ID => TypedReceipt::EIP7702(receipt_with_bloom),
             0x7E => TypedReceipt::Deposit(DepositReceipt {
                 inner: receipt_with_bloom,
                 deposit_nonce: other.get("depositNonce").and_then(|v| v.as_u64()),
@@ -1409,6 +1600,48 @@ mod tests {
         assert_eq!(from, address!("A83C816D4f9b2783761a22BA6FADB0eB0606D7B2"));
     }
 
+    #[test]
+    fn can_encode_decode_eip7702_tx() {
+        use alloy_consensus::SignableTransaction;
+        use alloy_primitives::address;
+
+        let signature = Signature::from_str("0eb96ca19e8a77102767a41fc85a36afd5c61ccb09911cec5d3e86e193d9c5ae3a456401896b1b6055311536bf00a718568c744d8c1f9df59879e8350220ca182b").unwrap();
+        let delegate = address!("11E9CA82A3a762b4B5bd264d4173a242e7a77064");
+        let tx = TxEip7702 {
+            chain_id: 31337,
+            nonce: 1,
+            gas_limit: 100_000,
+            max_fee_per_gas: 2_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            to: delegate,
+            value: U256::ZERO,
+            access_list: Default::default(),
+            authorization_list: vec![SignedAuthorization::new(0, delegate, 0, signature)],
+            input: Bytes::from_static(&[0xde, 0xad]),
+        };
+        let tx = TypedTransaction::EIP7702(tx.into_signed(signature));
+        assert_eq!(tx.r#type(), Some(EIP7702_TX_TYPE_ID));
+
+        let encoded = alloy_rlp::encode(&tx);
+        let decoded = TypedTransaction::decode(&mut &encoded[..]).unwrap();
+        assert_eq!(decoded, tx);
+
+        let mut encoded = Vec::new();
+        tx.encode_2718(&mut encoded);
+        assert_eq!(encoded.len(), tx.encode_2718_len());
+        assert_eq!(encoded[0], EIP7702_TX_TYPE_ID);
+        let decoded = TypedTransaction::decode_2718(&mut &encoded[..]).unwrap();
+        assert_eq!(decoded, tx);
+        assert_eq!(decoded.hash(), alloy_primitives::keccak256(&encoded));
+
+        let authorization = &decoded.authorization_list().unwrap()[0];
+        assert!(authorization.recover_authority().is_ok());
+        assert_eq!(
+            eip7702::delegated_address(&eip7702::delegation_designator(delegate)),
+            Some(delegate)
+        );
+    }
+
     #[test]
     fn can_recover_sender_not_normalized() {
         let bytes = hex::decode("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
@@ -1,8 +1,11 @@
 use alloy_consensus::{SignableTransaction, Signed, Transaction, TxType};
-use alloy_primitives::{keccak256, Address, Bytes, ChainId, Signature, TxKind, B256, U256};
+use alloy_primitives::{
+    b256, keccak256, Address, Bytes, ChainId, Log, Signature, TxKind, B256, U256,
+};
 use alloy_rlp::{
     length_of_length, Decodable, Encodable, Error as DecodeError, Header as RlpHeader,
 };
+use serde::{Deserialize, Serialize};
 use std::mem;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -226,9 +229,14 @@ impl Encodable for DepositTransactionRequest {
     }
 }
 
+/// The topic of the `TransactionDeposited(address,address,uint256,bytes)` event of the
+/// OptimismPortal
+pub const TRANSACTION_DEPOSITED_TOPIC: B256 =
+    b256!("b3813568d9991fc951961fcb4c784893574240a28925604d09fc577c55bb7c32");
+
 /// An op-stack deposit transaction.
 /// See <https://github.com/ethereum-optimism/optimism/blob/develop/specs/deposits.md#the-deposited-transaction-type>
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct DepositTransaction {
     pub nonce: u64,
     pub source_hash: B256,
@@ -246,6 +254,54 @@ impl DepositTransaction {
         &self.nonce
     }
 
+    /// Converts a `TransactionDeposited` log of the OptimismPortal, emitted in the L1 block with
+    /// the given hash at the given log index, into the deposit transaction it requests.
+    ///
+    /// Returns `None` if the log isn't a `TransactionDeposited` event of a known version.
+    /// See <https://github.com/ethereum-optimism/optimism/blob/develop/specs/deposits.md#deposit-contract>
+    pub fn from_deposit_log(log: &Log, l1_block_hash: B256, log_index: u64) -> Option<Self> {
+        let topics = log.data.topics();
+        if topics.len() != 4 || topics[0] != TRANSACTION_DEPOSITED_TOPIC || !topics[3].is_zero() {
+            return None
+        }
+        let from = Address::from_word(topics[1]);
+        let to = Address::from_word(topics[2]);
+
+        // the event data is the ABI encoded `bytes opaqueData`
+        let data = &log.data.data;
+        let len = usize::try_from(U256::try_from_be_slice(data.get(32..64)?)?).ok()?;
+        let opaque = data.get(64..64usize.checked_add(len)?)?;
+
+        // version 0: abi.encodePacked(mint, value, gasLimit, isCreation, data)
+        if opaque.len() < 73 {
+            return None
+        }
+        let mint = U256::from_be_slice(&opaque[..32]);
+        let value = U256::from_be_slice(&opaque[32..64]);
+        let gas_limit = u64::from_be_bytes(opaque[64..72].try_into().ok()?);
+        let kind = if opaque[72] != 0 { TxKind::Create } else { TxKind::Call(to) };
+        let input = Bytes::copy_from_slice(&opaque[73..]);
+
+        // user deposits use domain 0
+        let mut deposit_id = [0u8; 64];
+        deposit_id[..32].copy_from_slice(l1_block_hash.as_slice());
+        deposit_id[32..].copy_from_slice(&B256::from(U256::from(log_index))[..]);
+        let mut source = [0u8; 64];
+        source[32..].copy_from_slice(keccak256(deposit_id).as_slice());
+
+        Some(Self {
+            nonce: 0,
+            source_hash: keccak256(source),
+            from,
+            kind,
+            mint,
+            value,
+            gas_limit: gas_limit as u128,
+            is_system_tx: false,
+            input,
+        })
+    }
+
     pub fn hash(&self) -> B256 {
         B256::from_slice(alloy_primitives::keccak256(alloy_rlp::encode(self)).as_slice())
     }
@@ -333,3 +389,62 @@ impl Decodable for DepositTransaction {
         Self::decode_inner(buf)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, LogData};
+
+    #[test]
+    fn test_deposited_topic() {
+        assert_eq!(
+            TRANSACTION_DEPOSITED_TOPIC,
+            keccak256("TransactionDeposited(address,address,uint256,bytes)")
+        );
+    }
+
+    #[test]
+    fn test_from_deposit_log() {
+        let from = address!("00000000000000000000000000000000000000aa");
+        let to = address!("00000000000000000000000000000000000000bb");
+
+        let mut opaque = Vec::new();
+        opaque.extend_from_slice(&B256::from(U256::from(100)).0);
+        opaque.extend_from_slice(&B256::from(U256::from(40)).0);
+        opaque.extend_from_slice(&100_000u64.to_be_bytes());
+        opaque.push(0);
+        opaque.extend_from_slice(&[0xde, 0xad]);
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&B256::from(U256::from(32)).0);
+        data.extend_from_slice(&B256::from(U256::from(opaque.len())).0);
+        data.extend_from_slice(&opaque);
+        data.resize(64 + opaque.len().div_ceil(32) * 32, 0);
+
+        let log = Log {
+            address: Address::ZERO,
+            data: LogData::new_unchecked(
+                vec![TRANSACTION_DEPOSITED_TOPIC, from.into_word(), to.into_word(), B256::ZERO],
+                data.into(),
+            ),
+        };
+        let block_hash = B256::with_last_byte(1);
+        let tx = DepositTransaction::from_deposit_log(&log, block_hash, 3).unwrap();
+        assert_eq!(tx.from, from);
+        assert_eq!(tx.kind, TxKind::Call(to));
+        assert_eq!(tx.mint, U256::from(100));
+        assert_eq!(tx.value, U256::from(40));
+        assert_eq!(tx.gas_limit, 100_000);
+        assert_eq!(tx.input, Bytes::from_static(&[0xde, 0xad]));
+        assert!(!tx.is_system_tx);
+
+        // the source hash is unique per log
+        let other = DepositTransaction::from_deposit_log(&log, block_hash, 4).unwrap();
+        assert_ne!(tx.source_hash, other.source_hash);
+
+        // other events are ignored
+        let mut other = log.clone();
+        other.data = LogData::new_unchecked(vec![B256::ZERO], Default::default());
+        assert!(DepositTransaction::from_deposit_log(&other, block_hash, 0).is_none());
+    }
+}
@@ -1,4 +1,5 @@
-use alloy_primitives::{TxHash, B256, U256, U64};
+use alloy_primitives::{Bytes, TxHash, B256, U256, U64};
+use alloy_rpc_types::{request::TransactionRequest, WithOtherFields};
 use revm::primitives::SpecId;
 use std::collections::BTreeMap;
 
@@ -74,6 +75,30 @@ impl Default for EvmMineOptions {
     }
 }
 
+/// Additional `anvil_reorg` options
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ReorgOptions {
+    /// The number of blocks to remove from the tip of the chain and replace with new blocks
+    #[cfg_attr(feature = "serde", serde(with = "alloy_serde::num::u64_via_ruint"))]
+    pub depth: u64,
+    /// The transactions to include in the new blocks, each paired with the offset of the block
+    /// it should be mined in, relative to the first new block
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tx_block_pairs: Vec<(TransactionData, u64)>,
+}
+
+/// A transaction to include in a block, either as a request that is signed by one of the node's
+/// accounts or as a raw signed transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum TransactionData {
+    Json(WithOtherFields<TransactionRequest>),
+    Raw(Bytes),
+}
+
 /// Represents the result of `eth_getWork`
 /// This may or may not include the block number
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -219,6 +244,30 @@ pub struct ForkedNetwork {
     pub fork_block_hash: TxHash,
 }
 
+/// Statistics of the cache that holds the state fetched from the forked network
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ForkCacheStats {
+    /// Number of lookups that were served from the cache
+    pub hits: u64,
+    /// Number of lookups that had to be fetched from the forked network
+    pub misses: u64,
+    /// Number of state requests sent to the forked network
+    pub requests: u64,
+    /// The fork blocks whose state is cached
+    pub cached_blocks: Vec<CachedForkBlock>,
+}
+
+/// A fork block whose state is cached, see [ForkCacheStats]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct CachedForkBlock {
+    pub chain_id: u64,
+    pub block_number: u64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
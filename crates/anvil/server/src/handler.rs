@@ -9,6 +9,7 @@ use axum::{
     Json,
 };
 use futures::{future, FutureExt};
+use std::time::Instant;
 
 /// Handles incoming JSON-RPC Request.
 // NOTE: `handler` must come first because the `request` extractor consumes the request body.
@@ -56,7 +57,11 @@ async fn handle_call<Handler: RpcHandler>(call: RpcCall, handler: Handler) -> Op
     match call {
         RpcCall::MethodCall(call) => {
             trace!(target: "rpc", id = ?call.id , method = ?call.method,  "handling call");
-            Some(handler.on_call(call).await)
+            let method = call.method.clone();
+            let start = Instant::now();
+            let response = handler.on_call(call).await;
+            handler.on_call_handled(&method, start.elapsed(), &response);
+            Some(response)
         }
         RpcCall::Notification(notification) => {
             trace!(target: "rpc", method = ?notification.method, "received rpc notification");
@@ -16,7 +16,7 @@ use axum::{
     Router,
 };
 use serde::de::DeserializeOwned;
-use std::fmt;
+use std::{fmt, time::Duration};
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 
 mod config;
@@ -83,6 +83,11 @@ pub trait RpcHandler: Clone + Send + Sync + 'static {
     /// Invoked when the request was received
     async fn on_request(&self, request: Self::Request) -> ResponseResult;
 
+    /// Invoked after a method call was handled with the time it took and its response
+    ///
+    /// This does nothing by default and can be used to collect metrics.
+    fn on_call_handled(&self, _method: &str, _elapsed: Duration, _response: &RpcResponse) {}
+
     /// Invoked for every incoming `RpcMethodCall`
     ///
     /// This will attempt to deserialize a `{ "method" : "<name>", "params": "<params>" }` message
@@ -2,7 +2,7 @@ use crate::{error::RequestError, handler::handle_request, RpcHandler};
 use anvil_rpc::{
     error::RpcError,
     request::Request,
-    response::{Response, ResponseResult},
+    response::{Response, ResponseResult, RpcResponse},
 };
 
 use futures::{FutureExt, Sink, SinkExt, Stream, StreamExt};
@@ -16,6 +16,7 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 
 /// The general purpose trait for handling RPC requests and subscriptions
@@ -30,6 +31,11 @@ pub trait PubSubRpcHandler: Clone + Send + Sync + Unpin + 'static {
 
     /// Invoked when the request was received
     async fn on_request(&self, request: Self::Request, cx: PubSubContext<Self>) -> ResponseResult;
+
+    /// Invoked after a method call was handled with the time it took and its response
+    ///
+    /// This does nothing by default and can be used to collect metrics.
+    fn on_call_handled(&self, _method: &str, _elapsed: Duration, _response: &RpcResponse) {}
 }
 
 type Subscriptions<SubscriptionId, Subscription> = Arc<Mutex<Vec<(SubscriptionId, Subscription)>>>;
@@ -107,6 +113,10 @@ impl<Handler: PubSubRpcHandler> RpcHandler for ContextAwareHandler<Handler> {
     async fn on_request(&self, request: Self::Request) -> ResponseResult {
         self.handler.on_request(request, self.context.clone()).await
     }
+
+    fn on_call_handled(&self, method: &str, elapsed: Duration, response: &RpcResponse) {
+        self.handler.on_call_handled(method, elapsed, response)
+    }
 }
 
 /// Represents a connection to a client via websocket
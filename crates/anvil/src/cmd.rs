@@ -1,10 +1,15 @@
 use crate::{
     config::DEFAULT_MNEMONIC,
-    eth::{backend::db::SerializableState, pool::transactions::TransactionOrder, EthApi},
+    eth::{
+        backend::db::SerializableState,
+        pool::{transactions::TransactionOrder, PoolConfig},
+        EthApi,
+    },
+    multichain::{try_spawn_chains, MultiChainConfig},
     AccountGenerator, Hardfork, NodeConfig, CHAIN_ID,
 };
 use alloy_genesis::Genesis;
-use alloy_primitives::{utils::Unit, U256};
+use alloy_primitives::{utils::Unit, Address, U256};
 use alloy_signer_wallet::coins_bip39::{English, Mnemonic};
 use anvil_server::ServerConfig;
 use clap::Parser;
@@ -109,10 +114,32 @@ pub struct NodeArgs {
     )]
     pub host: Vec<IpAddr>,
 
-    /// How transactions are sorted in the mempool.
+    /// How transactions are sorted in the mempool and in new blocks.
+    ///
+    /// One of `fees`, `fifo`, `sender` or `random:<seed>`.
     #[arg(long, default_value = "fees")]
     pub order: TransactionOrder,
 
+    /// The minimum increase of the max fee and the priority fee in percent to replace a pending
+    /// transaction.
+    ///
+    /// Without a bump, the max fee of the replacement still has to be higher.
+    #[arg(long, value_name = "PERCENT", default_value_t = 0)]
+    pub txpool_price_bump: u64,
+
+    /// The maximum number of transactions per account in the mempool.
+    #[arg(long, value_name = "NUM")]
+    pub txpool_account_slots: Option<usize>,
+
+    /// The maximum number of transactions in the mempool, the cheapest transactions are evicted
+    /// if it is full.
+    #[arg(long, value_name = "NUM")]
+    pub txpool_global_slots: Option<usize>,
+
+    /// How long, in seconds, a transaction with a nonce gap can stay in the mempool.
+    #[arg(long, value_name = "SECONDS")]
+    pub txpool_lifetime: Option<u64>,
+
     /// Initialize the genesis block with the given `genesis.json` file.
     #[arg(long, value_name = "PATH", value_parser= read_genesis_file)]
     pub init: Option<Genesis>,
@@ -154,6 +181,18 @@ pub struct NodeArgs {
     )]
     pub load_state: Option<SerializableState>,
 
+    /// Persist the chain in the given directory.
+    ///
+    /// Every mined block is written to the directory together with its transactions, receipts and
+    /// state changes. If the directory already contains a chain, anvil resumes it including its
+    /// full history.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = &["fork_url", "load_state", "state"]
+    )]
+    pub db_path: Option<PathBuf>,
+
     #[arg(long, help = IPC_HELP, value_name = "PATH", visible_alias = "ipcpath")]
     pub ipc: Option<Option<String>>,
 
@@ -166,6 +205,36 @@ pub struct NodeArgs {
     #[arg(long)]
     pub transaction_block_keeper: Option<usize>,
 
+    /// Collect metrics about RPC calls, the pool, mined blocks and the fork cache and serve them
+    /// in the Prometheus format at `/metrics`.
+    #[arg(long)]
+    pub metrics: bool,
+
+    /// Port of the authenticated server that serves the engine API in addition to all other
+    /// methods.
+    ///
+    /// Clients must send a JWT signed by the --jwt-secret. Use with --no-mining to let the
+    /// consensus client drive block production.
+    #[arg(long, value_name = "NUM", requires = "jwt_secret")]
+    pub auth_port: Option<u16>,
+
+    /// File containing the hex encoded secret used to authenticate engine API clients.
+    ///
+    /// A random secret is written to the file if it does not exist.
+    #[arg(long, value_name = "PATH", requires = "auth_port")]
+    pub jwt_secret: Option<PathBuf>,
+
+    /// Launch several chains in one process, configured by the given JSON file.
+    ///
+    /// Every chain can set its own chain id, hardfork, fork URL, port and URL path, all other
+    /// options apply to all chains.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["fork_url", "db_path", "ipc", "auth_port"]
+    )]
+    pub chains: Option<PathBuf>,
+
     #[command(flatten)]
     pub evm_opts: AnvilEvmArgs,
 
@@ -224,6 +293,12 @@ impl NodeArgs {
             .set_config_out(self.config_out)
             .with_chain_id(self.evm_opts.chain_id)
             .with_transaction_order(self.order)
+            .with_pool_config(PoolConfig {
+                price_bump: self.txpool_price_bump,
+                account_slots: self.txpool_account_slots,
+                global_slots: self.txpool_global_slots,
+                queue_lifetime: self.txpool_lifetime.map(Duration::from_secs),
+            })
             .with_genesis(self.init)
             .with_steps_tracing(self.evm_opts.steps_tracing)
             .with_auto_impersonate(self.evm_opts.auto_impersonate)
@@ -231,11 +306,16 @@ impl NodeArgs {
             .with_code_size_limit(self.evm_opts.code_size_limit)
             .set_pruned_history(self.prune_history)
             .with_init_state(self.load_state.or_else(|| self.state.and_then(|s| s.state)))
+            .with_db_path(self.db_path)
             .with_transaction_block_keeper(self.transaction_block_keeper)
             .with_optimism(self.evm_opts.optimism)
+            .with_deposit_relay(self.evm_opts.optimism_l1_url, self.evm_opts.optimism_portal)
             .with_disable_default_create2_deployer(self.evm_opts.disable_default_create2_deployer)
             .with_slots_in_an_epoch(self.slots_in_an_epoch)
             .with_memory_limit(self.evm_opts.memory_limit)
+            .with_metrics(self.metrics)
+            .with_engine_api(self.auth_port, None)
+            .with_jwt_secret_path(self.jwt_secret)
     }
 
     fn account_generator(&self) -> AccountGenerator {
@@ -271,6 +351,10 @@ impl NodeArgs {
     ///
     /// See also [crate::spawn()]
     pub async fn run(self) -> eyre::Result<()> {
+        if let Some(chains) = self.chains.clone() {
+            return self.run_chains(chains).await
+        }
+
         let dump_state = self.dump_state_path();
         let dump_interval =
             self.state_interval.map(Duration::from_secs).unwrap_or(DEFAULT_DUMP_INTERVAL);
@@ -347,6 +431,30 @@ impl NodeArgs {
 
         Ok(handle.await??)
     }
+
+    /// Starts all chains of the given multi-chain config file
+    async fn run_chains(self, path: PathBuf) -> eyre::Result<()> {
+        let config = MultiChainConfig::load(path)?;
+        let handle = try_spawn_chains(config, self.into_node_config()).await?;
+
+        let forks =
+            handle.chains().iter().filter_map(|chain| chain.api.get_fork()).collect::<Vec<_>>();
+        tokio::spawn(async move {
+            let _ = tokio::signal::ctrl_c().await;
+            trace!("received shutdown signal, shutting down");
+            // make sure that the fork RPC caches are flushed if caching is configured
+            for fork in forks {
+                fork.database
+                    .read()
+                    .await
+                    .maybe_flush_cache()
+                    .expect("Could not flush cache on fork DB");
+            }
+            std::process::exit(0);
+        });
+
+        Ok(handle.await??)
+    }
 }
 
 /// Anvil's EVM related arguments.
@@ -501,6 +609,16 @@ pub struct AnvilEvmArgs {
     #[arg(long, visible_alias = "optimism")]
     pub optimism: bool,
 
+    /// Relay the deposits of the OptimismPortal on the L1 at the given URL to this chain.
+    ///
+    /// Deposits are included at the top of the next block after the L1 block that emitted them.
+    #[arg(long, value_name = "URL", requires_all = ["optimism", "optimism_portal"])]
+    pub optimism_l1_url: Option<String>,
+
+    /// The address of the OptimismPortal whose deposits are relayed
+    #[arg(long, value_name = "ADDRESS", requires = "optimism_l1_url")]
+    pub optimism_portal: Option<Address>,
+
     /// Disable the default create2 deployer
     #[arg(long, visible_alias = "no-create2")]
     pub disable_default_create2_deployer: bool,
@@ -731,6 +849,18 @@ mod tests {
         assert_eq!(args.hardfork, Some(Hardfork::Berlin));
     }
 
+    #[test]
+    fn can_parse_order() {
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--order", "sender"]);
+        assert_eq!(args.order, TransactionOrder::Sender);
+
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--order", "random:42"]);
+        assert_eq!(args.order, TransactionOrder::Random(42));
+
+        let args = NodeArgs::try_parse_from(["anvil", "--order", "random:x"]);
+        assert!(args.is_err());
+    }
+
     #[test]
     fn can_parse_fork_headers() {
         let args: NodeArgs = NodeArgs::parse_from([
@@ -757,6 +887,91 @@ mod tests {
         assert_eq!(args.prune_history, Some(Some(100)));
     }
 
+    #[test]
+    fn can_parse_db_path() {
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--db-path", "chain"]);
+        assert_eq!(args.db_path, Some(PathBuf::from("chain")));
+
+        let args = NodeArgs::try_parse_from([
+            "anvil",
+            "--db-path",
+            "chain",
+            "--fork-url",
+            "http://localhost:8545",
+        ]);
+        assert!(args.is_err());
+
+        // the persisted chain would replace the loaded state
+        let args =
+            NodeArgs::try_parse_from(["anvil", "--db-path", "chain", "--state", "state.json"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_deposit_relay() {
+        let args: NodeArgs = NodeArgs::parse_from([
+            "anvil",
+            "--optimism",
+            "--optimism-l1-url",
+            "http://localhost:8545",
+            "--optimism-portal",
+            "0xbEb5Fc579115071764c7423A4f12eDde41f106Ed",
+        ]);
+        assert_eq!(args.evm_opts.optimism_l1_url, Some("http://localhost:8545".to_string()));
+        assert!(args.evm_opts.optimism_portal.is_some());
+
+        let args =
+            NodeArgs::try_parse_from(["anvil", "--optimism-l1-url", "http://localhost:8545"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_pool_config() {
+        let args: NodeArgs = NodeArgs::parse_from([
+            "anvil",
+            "--txpool-price-bump",
+            "10",
+            "--txpool-account-slots",
+            "16",
+            "--txpool-global-slots",
+            "1024",
+            "--txpool-lifetime",
+            "60",
+        ]);
+        assert_eq!(args.txpool_price_bump, 10);
+        assert_eq!(args.txpool_account_slots, Some(16));
+        assert_eq!(args.txpool_global_slots, Some(1024));
+        assert_eq!(args.txpool_lifetime, Some(60));
+    }
+
+    #[test]
+    fn can_parse_chains() {
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--chains", "chains.json"]);
+        assert_eq!(args.chains, Some(PathBuf::from("chains.json")));
+
+        let args = NodeArgs::try_parse_from([
+            "anvil",
+            "--chains",
+            "chains.json",
+            "--fork-url",
+            "http://localhost:8545",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_jwt_secret() {
+        let args =
+            NodeArgs::parse_from(["anvil", "--auth-port", "8551", "--jwt-secret", "jwt.hex"]);
+        assert_eq!(args.auth_port, Some(8551));
+        assert_eq!(args.jwt_secret, Some(PathBuf::from("jwt.hex")));
+
+        let args = NodeArgs::try_parse_from(["anvil", "--jwt-secret", "jwt.hex"]);
+        assert!(args.is_err());
+        let args = NodeArgs::try_parse_from(["anvil", "--auth-port", "8551"]);
+        assert!(args.is_err());
+    }
+
     #[test]
     fn can_parse_disable_block_gas_limit() {
         let args: NodeArgs = NodeArgs::parse_from(["anvil", "--disable-block-gas-limit"]);
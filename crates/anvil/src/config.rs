@@ -2,21 +2,22 @@ use crate::{
     cmd::StateFile,
     eth::{
         backend::{
+            block_builder::BlockBuilder,
             db::{Db, SerializableState},
             fork::{ClientFork, ClientForkConfig},
             genesis::GenesisConfig,
             mem::fork_db::ForkedDatabase,
-            time::duration_since_unix_epoch,
+            time::{duration_since_unix_epoch, SharedClock},
         },
         fees::{INITIAL_BASE_FEE, INITIAL_GAS_PRICE},
-        pool::transactions::TransactionOrder,
+        pool::{transactions::TransactionOrder, PoolConfig},
     },
     mem::{self, in_memory_db::MemDb},
     FeeManager, Hardfork, PrecompileFactory,
 };
 use alloy_genesis::Genesis;
 use alloy_network::AnyNetwork;
-use alloy_primitives::{hex, utils::Unit, U256};
+use alloy_primitives::{hex, utils::Unit, Address, B256, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::BlockNumberOrTag;
 use alloy_signer::Signer;
@@ -32,7 +33,7 @@ use foundry_common::{
 use foundry_config::Config;
 use foundry_evm::{
     constants::DEFAULT_CREATE2_DEPLOYER,
-    fork::{BlockchainDb, BlockchainDbMeta, SharedBackend},
+    fork::{BlockchainDb, BlockchainDbCache, BlockchainDbMeta, SharedBackend},
     revm::primitives::{BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, SpecId, TxEnv},
     utils::apply_chain_and_block_specific_env_changes,
 };
@@ -136,6 +137,8 @@ pub struct NodeConfig {
     pub host: Vec<IpAddr>,
     /// How transactions are sorted in the mempool
     pub transaction_order: TransactionOrder,
+    /// The replacement rules and limits of the mempool
+    pub pool_config: PoolConfig,
     /// Filename to write anvil output as json
     pub config_out: Option<String>,
     /// The genesis to use to initialize the node
@@ -162,18 +165,38 @@ pub struct NodeConfig {
     pub prune_history: PruneStateHistoryConfig,
     /// The file where to load the state from
     pub init_state: Option<SerializableState>,
+    /// The directory where the chain is persisted, blocks and state are restored from it on start
+    pub db_path: Option<PathBuf>,
     /// max number of blocks with transactions in memory
     pub transaction_block_keeper: Option<usize>,
     /// Disable the default CREATE2 deployer
     pub disable_default_create2_deployer: bool,
     /// Enable Optimism deposit transaction
     pub enable_optimism: bool,
+    /// The L1 endpoint whose OptimismPortal deposits are relayed to this chain
+    pub optimism_l1_url: Option<String>,
+    /// The address of the OptimismPortal on L1
+    pub optimism_portal: Option<Address>,
     /// Slots in an epoch
     pub slots_in_an_epoch: u64,
     /// The memory limit per EVM execution in bytes.
     pub memory_limit: Option<u64>,
     /// Factory used by `anvil` to extend the EVM's precompiles.
     pub precompile_factory: Option<Arc<dyn PrecompileFactory>>,
+    /// Determines the order of the transactions of new blocks, derived from the
+    /// `transaction_order` if not set
+    pub block_builder: Option<Arc<dyn BlockBuilder>>,
+    /// Whether to collect metrics and serve them at `/metrics`
+    pub enable_metrics: bool,
+    /// The clock shared with other nodes, if any
+    pub shared_clock: Option<SharedClock>,
+    /// The port of the authenticated server that also serves the engine API, disabled if not set
+    pub auth_port: Option<u16>,
+    /// The secret the JWTs of the authenticated server are signed with, read from the
+    /// `jwt_secret_path` or random if not set
+    pub jwt_secret: Option<B256>,
+    /// The file the JWT secret is read from, a random secret is written to it if it doesn't exist
+    pub jwt_secret_path: Option<PathBuf>,
 }
 
 impl NodeConfig {
@@ -404,6 +427,7 @@ impl Default for NodeConfig {
             server_config: Default::default(),
             host: vec![IpAddr::V4(Ipv4Addr::LOCALHOST)],
             transaction_order: Default::default(),
+            pool_config: Default::default(),
             config_out: None,
             genesis: None,
             fork_request_timeout: REQUEST_TIMEOUT,
@@ -417,12 +441,21 @@ impl Default for NodeConfig {
             code_size_limit: None,
             prune_history: Default::default(),
             init_state: None,
+            db_path: None,
             transaction_block_keeper: None,
             disable_default_create2_deployer: false,
             enable_optimism: false,
+            optimism_l1_url: None,
+            optimism_portal: None,
             slots_in_an_epoch: 32,
             memory_limit: None,
             precompile_factory: None,
+            block_builder: None,
+            enable_metrics: false,
+            shared_clock: None,
+            auth_port: None,
+            jwt_secret: None,
+            jwt_secret_path: None,
         }
     }
 }
@@ -483,6 +516,13 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the directory where the chain is persisted
+    #[must_use]
+    pub fn with_db_path(mut self, db_path: Option<PathBuf>) -> Self {
+        self.db_path = db_path;
+        self
+    }
+
     /// Sets the chain ID
     #[must_use]
     pub fn with_chain_id<U: Into<u64>>(mut self, chain_id: Option<U>) -> Self {
@@ -795,6 +835,13 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the replacement rules and limits of the mempool
+    #[must_use]
+    pub fn with_pool_config(mut self, pool_config: PoolConfig) -> Self {
+        self.pool_config = pool_config;
+        self
+    }
+
     /// Returns the ipc path for the ipc endpoint if any
     pub fn get_ipc_path(&self) -> Option<String> {
         match &self.ipc_path {
@@ -839,6 +886,16 @@ impl NodeConfig {
         self
     }
 
+    /// Relays the deposits of the OptimismPortal at `portal` on the L1 at `l1_url` to this chain.
+    ///
+    /// Requires optimism support, see [Self::with_optimism].
+    #[must_use]
+    pub fn with_deposit_relay(mut self, l1_url: Option<String>, portal: Option<Address>) -> Self {
+        self.optimism_l1_url = l1_url;
+        self.optimism_portal = portal;
+        self
+    }
+
     /// Sets whether to disable the default create2 deployer
     #[must_use]
     pub fn with_disable_default_create2_deployer(mut self, yes: bool) -> Self {
@@ -853,6 +910,42 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the block builder that determines the order of the transactions of new blocks
+    #[must_use]
+    pub fn with_block_builder(mut self, builder: impl BlockBuilder + 'static) -> Self {
+        self.block_builder = Some(Arc::new(builder));
+        self
+    }
+
+    /// Sets whether to collect metrics and serve them at `/metrics`
+    #[must_use]
+    pub fn with_metrics(mut self, enable_metrics: bool) -> Self {
+        self.enable_metrics = enable_metrics;
+        self
+    }
+
+    /// Sets the clock that is shared with other nodes
+    #[must_use]
+    pub fn with_shared_clock(mut self, clock: Option<SharedClock>) -> Self {
+        self.shared_clock = clock;
+        self
+    }
+
+    /// Serves the engine API on the given port, authenticated with JWTs signed by the secret
+    #[must_use]
+    pub fn with_engine_api(mut self, auth_port: Option<u16>, jwt_secret: Option<B256>) -> Self {
+        self.auth_port = auth_port;
+        self.jwt_secret = jwt_secret;
+        self
+    }
+
+    /// Sets the file the JWT secret of the engine API is read from
+    #[must_use]
+    pub fn with_jwt_secret_path(mut self, path: Option<PathBuf>) -> Self {
+        self.jwt_secret_path = path;
+        self
+    }
+
     /// Configures everything related to env, backend and database and returns the
     /// [Backend](mem::Backend)
     ///
@@ -949,6 +1042,10 @@ impl NodeConfig {
             backend.load_state(state).await.expect("Failed to load init state");
         }
 
+        if let Some(db_path) = self.db_path.clone() {
+            backend.open_disk_chain(db_path).await.expect("Failed to open chain database");
+        }
+
         backend
     }
 
@@ -1137,6 +1234,8 @@ latest block number: {latest_block}"
         } else {
             BlockchainDb::new(meta, self.block_cache_path(fork_block_number))
         };
+        let block_dbs = BlockchainDbCache::default();
+        let block_chain_db = block_dbs.insert(block_hash, block_chain_db);
 
         // This will spawn the background thread that will use the provider to fetch
         // blockchain data from the other client
@@ -1162,6 +1261,8 @@ latest block number: {latest_block}"
             total_difficulty: block.header.total_difficulty.unwrap_or_default(),
             blob_gas_used: block.header.blob_gas_used,
             blob_excess_gas_and_price: env.block.blob_excess_gas_and_price.clone(),
+            block_dbs,
+            storage_caching: !self.no_storage_caching,
         };
 
         let mut db = ForkedDatabase::new(backend, block_chain_db);
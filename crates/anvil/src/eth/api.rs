@@ -1,14 +1,15 @@
 use super::{
-    backend::mem::{state, BlockRequest, State},
+    backend::mem::{state, BlockAttributes, BlockRequest, State},
     sign::build_typed_transaction,
 };
 use crate::{
     eth::{
         backend,
         backend::{
+            bundle::Bundle,
             db::SerializableState,
             mem::{MIN_CREATE_GAS, MIN_TRANSACTION_GAS},
-            notifications::NewBlockNotifications,
+            notifications::{NewBlockNotifications, RemovedBlockNotifications},
             validate::TransactionValidator,
         },
         error::{
@@ -28,10 +29,11 @@ use crate::{
     },
     filter::{EthFilter, Filters, LogsFilter},
     mem::transaction_build,
+    metrics::NodeMetrics,
     revm::primitives::{BlobExcessGasAndPrice, Output},
     ClientFork, LoggingManager, Miner, MiningMode, StorageInfo,
 };
-use alloy_consensus::transaction::eip4844::TxEip4844Variant;
+use alloy_consensus::{transaction::eip4844::TxEip4844Variant, Blob};
 use alloy_dyn_abi::TypedData;
 use alloy_eips::eip2718::Encodable2718;
 use alloy_network::eip2718::Decodable2718;
@@ -39,28 +41,37 @@ use alloy_primitives::{Address, Bytes, TxHash, TxKind, B256, B64, U256, U64};
 use alloy_rpc_types::{
     request::TransactionRequest,
     state::StateOverride,
-    txpool::{TxpoolContent, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus},
+    txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus},
     AccessList, AccessListWithGasUsed, Block, BlockId, BlockNumberOrTag as BlockNumber,
-    BlockTransactions, EIP1186AccountProofResponse, FeeHistory, Filter, FilteredParams, Log,
-    Transaction, WithOtherFields,
+    BlockOverrides, BlockTransactions, EIP1186AccountProofResponse, FeeHistory, Filter,
+    FilteredParams, Log, Transaction, WithOtherFields,
 };
+use alloy_rpc_types_engine::PayloadId;
 use alloy_rpc_types_trace::{
-    geth::{DefaultFrame, GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace},
-    parity::LocalizedTransactionTrace,
+    filter::TraceFilter,
+    geth::{GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, TraceResult},
+    parity::{LocalizedTransactionTrace, TraceResults, TraceResultsWithTransactionHash, TraceType},
 };
+use alloy_sol_types::{sol, SolCall};
 use alloy_transport::TransportErrorKind;
 use anvil_core::{
     eth::{
+        beacon::{BeaconBlockId, BlobSidecar},
         block::BlockInfo,
+        bundle::{
+            bundle_hash, CallBundleRequest, CallBundleResponse, SendBundleRequest,
+            SendBundleResponse,
+        },
+        simulate::{SimulatePayload, SimulatedBlock},
         transaction::{
-            transaction_request_to_typed, PendingTransaction, ReceiptResponse, TypedTransaction,
-            TypedTransactionRequest,
+            optimism::DepositTransaction, transaction_request_to_typed, PendingTransaction,
+            ReceiptResponse, TypedTransaction, TypedTransactionRequest,
         },
         EthRequest,
     },
     types::{
-        AnvilMetadata, EvmMineOptions, ForkedNetwork, Forking, Index, NodeEnvironment,
-        NodeForkConfig, NodeInfo, Work,
+        AnvilMetadata, CachedForkBlock, EvmMineOptions, ForkCacheStats, ForkedNetwork, Forking,
+        Index, NodeEnvironment, NodeForkConfig, NodeInfo, ReorgOptions, TransactionData, Work,
     },
 };
 use anvil_rpc::{error::RpcError, response::ResponseResult};
@@ -75,8 +86,21 @@ use foundry_evm::{
     },
 };
 use futures::channel::{mpsc::Receiver, oneshot};
-use parking_lot::RwLock;
-use std::{collections::HashSet, future::Future, sync::Arc, time::Duration};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    sync::Arc,
+    time::Duration,
+};
+
+sol! {
+    interface IERC20 {
+        function balanceOf(address owner) external view returns (uint256);
+        function allowance(address owner, address spender) external view returns (uint256);
+        function totalSupply() external view returns (uint256);
+    }
+}
 
 /// The client version: `anvil/v{major}.{minor}.{patch}`
 pub const CLIENT_VERSION: &str = concat!("anvil/v", env!("CARGO_PKG_VERSION"));
@@ -87,7 +111,7 @@ pub const CLIENT_VERSION: &str = concat!("anvil/v", env!("CARGO_PKG_VERSION"));
 #[derive(Clone)]
 pub struct EthApi {
     /// The transaction pool
-    pool: Arc<Pool>,
+    pub(super) pool: Arc<Pool>,
     /// Holds all blockchain related data
     /// In-Memory only for now
     pub(super) backend: Arc<backend::mem::Backend>,
@@ -114,6 +138,8 @@ pub struct EthApi {
     net_listening: bool,
     /// The instance ID. Changes on every reset.
     instance_id: Arc<RwLock<B256>>,
+    /// Payloads started via `engine_forkchoiceUpdatedV3`, with the hash of their parent
+    pub(super) payloads: Arc<Mutex<HashMap<PayloadId, (B256, BlockAttributes)>>>,
 }
 
 // === impl Eth RPC API ===
@@ -145,6 +171,7 @@ impl EthApi {
             net_listening: true,
             transaction_order: Arc::new(RwLock::new(transactions_order)),
             instance_id: Arc::new(RwLock::new(B256::random())),
+            payloads: Default::default(),
         }
     }
 
@@ -227,15 +254,22 @@ impl EthApi {
             EthRequest::EthSendRawTransaction(tx) => {
                 self.send_raw_transaction(tx).await.to_rpc_result()
             }
-            EthRequest::EthCall(call, block, overrides) => {
-                self.call(call, block, overrides).await.to_rpc_result()
-            }
-            EthRequest::EthCreateAccessList(call, block) => {
-                self.create_access_list(call, block).await.to_rpc_result()
-            }
-            EthRequest::EthEstimateGas(call, block, overrides) => {
-                self.estimate_gas(call, block, overrides).await.to_rpc_result()
-            }
+            EthRequest::EthCall(call, block, state_overrides, block_overrides) => {
+                self.call(call, block, state_overrides, block_overrides).await.to_rpc_result()
+            }
+            EthRequest::EthSimulateV1(request, block) => {
+                self.simulate_v1(request, block).await.to_rpc_result()
+            }
+            EthRequest::EthCallBundle(request) => self.call_bundle(request).await.to_rpc_result(),
+            EthRequest::EthSendBundle(request) => self.send_bundle(request).await.to_rpc_result(),
+            EthRequest::EthCreateAccessList(call, block, state_overrides, block_overrides) => self
+                .create_access_list(call, block, state_overrides, block_overrides)
+                .await
+                .to_rpc_result(),
+            EthRequest::EthEstimateGas(call, block, state_overrides, block_overrides) => self
+                .estimate_gas(call, block, state_overrides, block_overrides)
+                .await
+                .to_rpc_result(),
             EthRequest::EthGetTransactionByBlockHashAndIndex(hash, index) => {
                 self.transaction_by_block_hash_and_index(hash, index).await.to_rpc_result()
             }
@@ -275,8 +309,21 @@ impl EthApi {
             EthRequest::DebugTraceCall(tx, block, opts) => {
                 self.debug_trace_call(tx, block, opts).await.to_rpc_result()
             }
+            EthRequest::DebugTraceBlockByNumber(number, opts) => {
+                self.debug_trace_block_by_number(number, opts).await.to_rpc_result()
+            }
+            EthRequest::DebugTraceBlockByHash(hash, opts) => {
+                self.debug_trace_block_by_hash(hash, opts).await.to_rpc_result()
+            }
             EthRequest::TraceTransaction(tx) => self.trace_transaction(tx).await.to_rpc_result(),
             EthRequest::TraceBlock(block) => self.trace_block(block).await.to_rpc_result(),
+            EthRequest::TraceFilter(filter) => self.trace_filter(filter).await.to_rpc_result(),
+            EthRequest::TraceReplayTransaction(hash, trace_types) => {
+                self.trace_replay_transaction(hash, trace_types).await.to_rpc_result()
+            }
+            EthRequest::TraceReplayBlockTransactions(block, trace_types) => {
+                self.trace_replay_block_transactions(block, trace_types).await.to_rpc_result()
+            }
             EthRequest::ImpersonateAccount(addr) => {
                 self.anvil_impersonate_account(addr).await.to_rpc_result()
             }
@@ -317,6 +364,12 @@ impl EthApi {
             EthRequest::SetStorageAt(addr, slot, val) => {
                 self.anvil_set_storage_at(addr, slot, val).await.to_rpc_result()
             }
+            EthRequest::DealERC20(token, account, amount, adjust_supply) => {
+                self.anvil_deal_erc20(token, account, amount, adjust_supply).await.to_rpc_result()
+            }
+            EthRequest::SetERC20Allowance(token, owner, spender, amount) => {
+                self.anvil_set_erc20_allowance(token, owner, spender, amount).await.to_rpc_result()
+            }
             EthRequest::SetCoinbase(addr) => self.anvil_set_coinbase(addr).await.to_rpc_result(),
             EthRequest::SetChainId(id) => self.anvil_set_chain_id(id).await.to_rpc_result(),
             EthRequest::SetLogging(log) => self.anvil_set_logging(log).await.to_rpc_result(),
@@ -330,6 +383,12 @@ impl EthApi {
             EthRequest::LoadState(buf) => self.anvil_load_state(buf).await.to_rpc_result(),
             EthRequest::NodeInfo(_) => self.anvil_node_info().await.to_rpc_result(),
             EthRequest::AnvilMetadata(_) => self.anvil_metadata().await.to_rpc_result(),
+            EthRequest::AnvilForkCacheStats(_) => {
+                self.anvil_fork_cache_stats().await.to_rpc_result()
+            }
+            EthRequest::GetBlobsByTransactionHash(hash) => {
+                self.anvil_get_blobs_by_transaction_hash(hash).await.to_rpc_result()
+            }
             EthRequest::EvmSnapshot(_) => self.evm_snapshot().await.to_rpc_result(),
             EthRequest::EvmRevert(id) => self.evm_revert(id).await.to_rpc_result(),
             EthRequest::EvmIncreaseTime(time) => self.evm_increase_time(time).await.to_rpc_result(),
@@ -382,6 +441,9 @@ impl EthApi {
             EthRequest::TxPoolStatus(_) => self.txpool_status().await.to_rpc_result(),
             EthRequest::TxPoolInspect(_) => self.txpool_inspect().await.to_rpc_result(),
             EthRequest::TxPoolContent(_) => self.txpool_content().await.to_rpc_result(),
+            EthRequest::TxPoolContentFrom(address) => {
+                self.txpool_content_from(address).await.to_rpc_result()
+            }
             EthRequest::ErigonGetHeaderByNumber(num) => {
                 self.erigon_get_header_by_number(num).await.to_rpc_result()
             }
@@ -420,6 +482,22 @@ impl EthApi {
             EthRequest::RemovePoolTransactions(address) => {
                 self.anvil_remove_pool_transactions(address).await.to_rpc_result()
             }
+            EthRequest::Reorg(options) => self.anvil_reorg(options).await.to_rpc_result(),
+            EthRequest::Rollback(depth) => self.anvil_rollback(depth).await.to_rpc_result(),
+            EthRequest::SetNextBlockTransactions(hashes) => {
+                self.anvil_set_next_block_transactions(hashes).await.to_rpc_result()
+            }
+            EthRequest::EngineForkchoiceUpdatedV3(state, attributes) => {
+                self.engine_forkchoice_updated_v3(state, attributes).await.to_rpc_result()
+            }
+            EthRequest::EngineGetPayloadV3(id) => {
+                self.engine_get_payload_v3(id).await.to_rpc_result()
+            }
+            EthRequest::EngineNewPayloadV3(payload, versioned_hashes, parent_beacon_block_root) => {
+                self.engine_new_payload_v3(payload, versioned_hashes, parent_beacon_block_root)
+                    .await
+                    .to_rpc_result()
+            }
         }
     }
 
@@ -870,7 +948,7 @@ impl EthApi {
 
         if request.gas.is_none() {
             // estimate if not provided
-            if let Ok(gas) = self.estimate_gas(request.clone(), None, None).await {
+            if let Ok(gas) = self.estimate_gas(request.clone(), None, None, None).await {
                 request.gas = Some(gas.to());
             }
         }
@@ -897,7 +975,7 @@ impl EthApi {
 
         if request.gas.is_none() {
             // estimate if not provided
-            if let Ok(gas) = self.estimate_gas(request.clone(), None, None).await {
+            if let Ok(gas) = self.estimate_gas(request.clone(), None, None, None).await {
                 request.gas = Some(gas.to());
             }
         }
@@ -969,7 +1047,8 @@ impl EthApi {
         &self,
         request: WithOtherFields<TransactionRequest>,
         block_number: Option<BlockId>,
-        overrides: Option<StateOverride>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
     ) -> Result<Bytes> {
         node_info!("eth_call");
         let block_request = self.block_request(block_number).await?;
@@ -977,7 +1056,7 @@ impl EthApi {
         if let BlockRequest::Number(number) = block_request {
             if let Some(fork) = self.get_fork() {
                 if fork.predates_fork(number) {
-                    if overrides.is_some() {
+                    if state_overrides.is_some() || block_overrides.is_some() {
                         return Err(BlockchainError::StateOverrideError(
                             "not available on past forked blocks".to_string(),
                         ));
@@ -997,8 +1076,10 @@ impl EthApi {
         // this can be blocking for a bit, especially in forking mode
         // <https://github.com/foundry-rs/foundry/issues/6036>
         self.on_blocking_task(|this| async move {
-            let (exit, out, gas, _) =
-                this.backend.call(request, fees, Some(block_request), overrides).await?;
+            let (exit, out, gas, _) = this
+                .backend
+                .call(request, fees, Some(block_request), state_overrides, block_overrides)
+                .await?;
             trace!(target : "node", "Call status {:?}, gas {}", exit, gas);
 
             ensure_return_ok(exit, &out)
@@ -1006,6 +1087,99 @@ impl EthApi {
         .await
     }
 
+    /// Simulates a sequence of blocks, each with its own block and state overrides and calls, on
+    /// top of the given block without changing the chain.
+    ///
+    /// Handler for ETH RPC call: `eth_simulateV1`
+    pub async fn simulate_v1(
+        &self,
+        request: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> Result<Vec<SimulatedBlock>> {
+        node_info!("eth_simulateV1");
+        let block_request = self.block_request(block_number).await?;
+        // check if the number predates the fork, if in fork mode
+        if let BlockRequest::Number(number) = block_request {
+            if let Some(fork) = self.get_fork() {
+                if fork.predates_fork(number) {
+                    return Ok(fork.simulate_v1(&request, Some(number.into())).await?)
+                }
+            }
+        }
+
+        // this can be blocking for a bit, especially in forking mode
+        // <https://github.com/foundry-rs/foundry/issues/6036>
+        self.on_blocking_task(|this| async move {
+            let blocks = this.backend.simulate(request, Some(block_request)).await?;
+            trace!(target : "node", "Simulated {} blocks", blocks.len());
+
+            Ok(blocks)
+        })
+        .await
+    }
+
+    /// Simulates a bundle of signed transactions on top of the state of the given block.
+    ///
+    /// Handler for ETH RPC call: `eth_callBundle`
+    pub async fn call_bundle(&self, request: CallBundleRequest) -> Result<CallBundleResponse> {
+        node_info!("eth_callBundle");
+        let CallBundleRequest { txs, block_number, state_block_number, timestamp } = request;
+        let transactions = self.decode_bundle_transactions(txs)?;
+        let block_request = self.block_request(Some(state_block_number.into())).await?;
+
+        self.on_blocking_task(|this| async move {
+            let response = this
+                .backend
+                .call_bundle(transactions, Some(block_request), block_number.to(), timestamp)
+                .await?;
+            trace!(target : "node", "Simulated bundle {:?}", response.bundle_hash);
+
+            Ok(response)
+        })
+        .await
+    }
+
+    /// Submits a bundle of signed transactions that is included at the top of the given block if
+    /// none of its transactions fails, except those that are allowed to revert.
+    ///
+    /// Handler for ETH RPC call: `eth_sendBundle`
+    pub async fn send_bundle(&self, request: SendBundleRequest) -> Result<SendBundleResponse> {
+        node_info!("eth_sendBundle");
+        let SendBundleRequest {
+            txs,
+            block_number,
+            min_timestamp,
+            max_timestamp,
+            reverting_tx_hashes,
+        } = request;
+        let block_number = block_number.to::<u64>();
+        if block_number <= self.backend.best_number() {
+            return Err(
+                RpcError::invalid_params("bundle must target a block that is not mined yet").into()
+            )
+        }
+
+        let transactions = self.decode_bundle_transactions(txs)?;
+        let bundle_hash = bundle_hash(transactions.iter().map(|tx| tx.hash()));
+        self.backend.add_bundle(Bundle {
+            hash: bundle_hash,
+            transactions,
+            block_number,
+            min_timestamp,
+            max_timestamp,
+            reverting_tx_hashes,
+        });
+        trace!(target: "node", "Added bundle: [{:?}] block={}", bundle_hash, block_number);
+
+        // like transactions, a bundle for the next block is mined right away if auto-mining is
+        // enabled
+        if self.miner.is_auto_mine() && block_number == self.backend.best_number() + 1 {
+            self.mine_one().await;
+        }
+
+        Ok(SendBundleResponse { bundle_hash })
+    }
+
     /// This method creates an EIP2930 type accessList based on a given Transaction. The accessList
     /// contains all storage slots and addresses read and written by the transaction, except for the
     /// sender account and the precompiles.
@@ -1023,6 +1197,8 @@ impl EthApi {
         &self,
         mut request: WithOtherFields<TransactionRequest>,
         block_number: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
     ) -> Result<AccessListWithGasUsed> {
         node_info!("eth_createAccessList");
         let block_request = self.block_request(block_number).await?;
@@ -1030,13 +1206,24 @@ impl EthApi {
         if let BlockRequest::Number(number) = block_request {
             if let Some(fork) = self.get_fork() {
                 if fork.predates_fork(number) {
+                    if state_overrides.is_some() || block_overrides.is_some() {
+                        return Err(BlockchainError::StateOverrideError(
+                            "not available on past forked blocks".to_string(),
+                        ));
+                    }
                     return Ok(fork.create_access_list(&request, Some(number.into())).await?)
                 }
             }
         }
 
         self.backend
-            .with_database_at(Some(block_request), |state, block_env| {
+            .with_database_at(Some(block_request), |state, mut block_env| {
+                let state = state::apply_overrides(
+                    state,
+                    &mut block_env,
+                    state_overrides,
+                    block_overrides,
+                )?;
                 let (exit, out, _, access_list) = self.backend.build_access_list_with_state(
                     &state,
                     request.clone(),
@@ -1072,13 +1259,15 @@ impl EthApi {
         &self,
         request: WithOtherFields<TransactionRequest>,
         block_number: Option<BlockId>,
-        overrides: Option<StateOverride>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
     ) -> Result<U256> {
         node_info!("eth_estimateGas");
         self.do_estimate_gas(
             request,
             block_number.or_else(|| Some(BlockNumber::Pending.into())),
-            overrides,
+            state_overrides,
+            block_overrides,
         )
         .await
         .map(U256::from)
@@ -1438,8 +1627,8 @@ impl EthApi {
         &self,
         request: WithOtherFields<TransactionRequest>,
         block_number: Option<BlockId>,
-        opts: GethDefaultTracingOptions,
-    ) -> Result<DefaultFrame> {
+        opts: GethDebugTracingCallOptions,
+    ) -> Result<GethTrace> {
         node_info!("debug_traceCall");
         let block_request = self.block_request(block_number).await?;
         let fees = FeeDetails::new(
@@ -1453,6 +1642,30 @@ impl EthApi {
         self.backend.call_with_tracing(request, fees, Some(block_request), opts).await
     }
 
+    /// Returns geth traces for all transactions in the block with the given number
+    ///
+    /// Handler for RPC call: `debug_traceBlockByNumber`
+    pub async fn debug_trace_block_by_number(
+        &self,
+        number: BlockNumber,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TraceResult>> {
+        node_info!("debug_traceBlockByNumber");
+        self.backend.debug_trace_block(number.into(), opts).await
+    }
+
+    /// Returns geth traces for all transactions in the block with the given hash
+    ///
+    /// Handler for RPC call: `debug_traceBlockByHash`
+    pub async fn debug_trace_block_by_hash(
+        &self,
+        hash: B256,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TraceResult>> {
+        node_info!("debug_traceBlockByHash");
+        self.backend.debug_trace_block(hash.into(), opts).await
+    }
+
     /// Returns traces for the transaction hash via parity's tracing endpoint
     ///
     /// Handler for RPC call: `trace_transaction`
@@ -1468,6 +1681,42 @@ impl EthApi {
         node_info!("trace_block");
         self.backend.trace_block(block).await
     }
+
+    /// Returns the traces of the blocks in the given range that match the from and to addresses
+    ///
+    /// Handler for RPC call: `trace_filter`
+    pub async fn trace_filter(
+        &self,
+        filter: TraceFilter,
+    ) -> Result<Vec<LocalizedTransactionTrace>> {
+        node_info!("trace_filter");
+        self.backend.trace_filter(filter).await
+    }
+
+    /// Replays the transaction and returns the requested traces: `trace`, `stateDiff` and
+    /// `vmTrace`
+    ///
+    /// Handler for RPC call: `trace_replayTransaction`
+    pub async fn trace_replay_transaction(
+        &self,
+        hash: B256,
+        trace_types: HashSet<TraceType>,
+    ) -> Result<TraceResults> {
+        node_info!("trace_replayTransaction");
+        self.backend.trace_replay_transaction(hash, trace_types).await
+    }
+
+    /// Replays all transactions of the block and returns the requested traces
+    ///
+    /// Handler for RPC call: `trace_replayBlockTransactions`
+    pub async fn trace_replay_block_transactions(
+        &self,
+        block: BlockNumber,
+        trace_types: HashSet<TraceType>,
+    ) -> Result<Vec<TraceResultsWithTransactionHash>> {
+        node_info!("trace_replayBlockTransactions");
+        self.backend.trace_replay_block_transactions(block, trace_types).await
+    }
 }
 
 // == impl EthApi anvil endpoints ==
@@ -1650,6 +1899,65 @@ impl EthApi {
         Ok(true)
     }
 
+    /// Sets the `token` balance of `account` to `amount`.
+    ///
+    /// The storage slot of the balance is found by recording the slots that `balanceOf` reads, the
+    /// way forge-std's `deal` does. If `adjust_supply` is set, the total supply changes by the same
+    /// amount as the balance.
+    ///
+    /// Handler for RPC call: `anvil_dealERC20`
+    pub async fn anvil_deal_erc20(
+        &self,
+        token: Address,
+        account: Address,
+        amount: U256,
+        adjust_supply: Option<bool>,
+    ) -> Result<()> {
+        node_info!("anvil_dealERC20");
+        let balance_slot = self
+            .erc20_storage_slot(token, IERC20::balanceOfCall { owner: account }.abi_encode())
+            .await?;
+        if adjust_supply.unwrap_or_default() {
+            let supply_slot =
+                self.erc20_storage_slot(token, IERC20::totalSupplyCall {}.abi_encode()).await?;
+            let balance = self.backend.storage_at(token, balance_slot, None).await?;
+            let supply = self.backend.storage_at(token, supply_slot, None).await?;
+            let supply = U256::from_be_bytes(supply.0)
+                .saturating_sub(U256::from_be_bytes(balance.0))
+                .saturating_add(amount);
+            self.backend.set_storage_at(token, supply_slot, supply.into()).await?;
+        }
+        self.backend.set_storage_at(token, balance_slot, amount.into()).await?;
+        Ok(())
+    }
+
+    /// Sets the `token` allowance of `spender` for the tokens of `owner` to `amount`.
+    ///
+    /// The storage slot of the allowance is found by recording the slots that `allowance` reads.
+    ///
+    /// Handler for RPC call: `anvil_setERC20Allowance`
+    pub async fn anvil_set_erc20_allowance(
+        &self,
+        token: Address,
+        owner: Address,
+        spender: Address,
+        amount: U256,
+    ) -> Result<()> {
+        node_info!("anvil_setERC20Allowance");
+        let input = IERC20::allowanceCall { owner, spender }.abi_encode();
+        let slot = self.erc20_storage_slot(token, input).await?;
+        self.backend.set_storage_at(token, slot, amount.into()).await?;
+        Ok(())
+    }
+
+    /// Returns the storage slot of `token` that holds the value returned by the call with `input`
+    async fn erc20_storage_slot(&self, token: Address, input: Vec<u8>) -> Result<U256> {
+        self.backend.find_storage_slot(token, input.into()).await?.ok_or_else(|| {
+            RpcError::invalid_params(format!("failed to find the storage slot of token {token}"))
+                .into()
+        })
+    }
+
     /// Enable or disable logging.
     ///
     /// Handler for RPC call: `anvil_setLoggingEnabled`
@@ -1736,10 +2044,7 @@ impl EthApi {
             current_block_timestamp: env.block.timestamp.try_into().unwrap_or(u64::MAX),
             current_block_hash: self.backend.best_hash(),
             hard_fork: env.handler_cfg.spec_id,
-            transaction_order: match *tx_order {
-                TransactionOrder::Fifo => "fifo".to_string(),
-                TransactionOrder::Fees => "fees".to_string(),
-            },
+            transaction_order: tx_order.to_string(),
             environment: NodeEnvironment {
                 base_fee: self.backend.base_fee(),
                 chain_id: self.backend.chain_id().to::<u64>(),
@@ -1783,12 +2088,211 @@ impl EthApi {
         })
     }
 
+    /// Returns statistics of the cache that holds the state fetched from the forked network
+    ///
+    /// Handler for RPC call: `anvil_forkCacheStats`
+    pub async fn anvil_fork_cache_stats(&self) -> Result<ForkCacheStats> {
+        node_info!("anvil_forkCacheStats");
+        let Some(fork) = self.get_fork() else {
+            return Err(RpcError::invalid_params("Forking not enabled").into())
+        };
+        let stats = fork.cache_stats();
+        let cached_blocks = fork
+            .cached_blocks()
+            .into_iter()
+            .map(|(chain_id, block_number)| CachedForkBlock { chain_id, block_number })
+            .collect();
+        Ok(ForkCacheStats {
+            hits: stats.hits(),
+            misses: stats.misses(),
+            requests: stats.requests(),
+            cached_blocks,
+        })
+    }
+
+    /// Returns the blobs of a mined EIP-4844 transaction, or `None` if the transaction is unknown
+    /// or has no blobs
+    ///
+    /// Handler for RPC call: `anvil_getBlobsByTransactionHash`
+    pub async fn anvil_get_blobs_by_transaction_hash(
+        &self,
+        hash: TxHash,
+    ) -> Result<Option<Vec<Blob>>> {
+        node_info!("anvil_getBlobsByTransactionHash");
+        Ok(self.backend.blobs_by_transaction_hash(hash))
+    }
+
+    /// Returns the blob sidecars of the given block, or `None` if the block is unknown
+    ///
+    /// Served at the `/eth/v1/beacon/blob_sidecars/{block_id}` endpoint of the beacon API.
+    pub fn beacon_blob_sidecars(&self, block_id: BeaconBlockId) -> Option<Vec<BlobSidecar>> {
+        self.backend.blob_sidecars(block_id)
+    }
+
     pub async fn anvil_remove_pool_transactions(&self, address: Address) -> Result<()> {
         node_info!("anvil_removePoolTransactions");
         self.pool.remove_transactions_by_address(address);
         Ok(())
     }
 
+    /// Reorgs the chain: removes the last `depth` blocks and mines `depth` new blocks in their
+    /// place, each including the transactions paired with its offset.
+    ///
+    /// Handler for RPC call: `anvil_reorg`
+    pub async fn anvil_reorg(&self, options: ReorgOptions) -> Result<()> {
+        node_info!("anvil_reorg");
+        let ReorgOptions { depth, tx_block_pairs } = options;
+
+        let best_number = self.backend.best_number();
+        let Some(common_number) = best_number.checked_sub(depth) else {
+            return Err(RpcError::invalid_params(format!(
+                "depth {depth} exceeds the current block height {best_number}"
+            ))
+            .into())
+        };
+
+        // build all transactions against the state of the block we reorg from before touching the
+        // chain, so that an invalid transaction leaves the chain untouched
+        let mut nonces: HashMap<Address, u64> = HashMap::new();
+        let mut block_txs: HashMap<u64, Vec<Arc<PoolTransaction>>> = HashMap::new();
+        for (tx, offset) in tx_block_pairs {
+            if offset >= depth {
+                return Err(RpcError::invalid_params(format!(
+                    "block offset {offset} exceeds the reorg depth {depth}"
+                ))
+                .into())
+            }
+
+            let pending_transaction = match tx {
+                TransactionData::Json(mut request) => {
+                    let from = request.from.map(Ok).unwrap_or_else(|| {
+                        self.accounts()?.first().cloned().ok_or(BlockchainError::NoSignerAvailable)
+                    })?;
+                    let nonce = match request.nonce {
+                        Some(nonce) => nonce,
+                        None => match nonces.get(&from) {
+                            Some(nonce) => *nonce,
+                            None => {
+                                self.get_transaction_count(from, Some(common_number.into())).await?
+                            }
+                        },
+                    };
+                    nonces.insert(from, nonce + 1);
+
+                    if request.gas.is_none() {
+                        // estimate if not provided
+                        if let Ok(gas) = self
+                            .estimate_gas(request.clone(), Some(common_number.into()), None, None)
+                            .await
+                        {
+                            request.gas = Some(gas.to());
+                        }
+                    }
+
+                    let request = self.build_typed_tx_request(request, nonce)?;
+                    if self.is_impersonated(from) {
+                        let bypass_signature = self.backend.cheats().bypass_signature();
+                        let transaction = sign::build_typed_transaction(request, bypass_signature)?;
+                        self.ensure_typed_transaction_supported(&transaction)?;
+                        PendingTransaction::with_impersonated(transaction, from)
+                    } else {
+                        let transaction = self.sign_request(&from, request)?;
+                        self.ensure_typed_transaction_supported(&transaction)?;
+                        PendingTransaction::new(transaction)?
+                    }
+                }
+                TransactionData::Raw(tx) => {
+                    let mut data = tx.as_ref();
+                    if data.is_empty() {
+                        return Err(BlockchainError::EmptyRawTransactionData);
+                    }
+                    let transaction = TypedTransaction::decode_2718(&mut data)
+                        .map_err(|_| BlockchainError::FailedToDecodeSignedTransaction)?;
+                    self.ensure_typed_transaction_supported(&transaction)?;
+                    let pending_transaction = PendingTransaction::new(transaction)?;
+                    nonces.insert(
+                        *pending_transaction.sender(),
+                        pending_transaction.transaction.nonce() + 1,
+                    );
+                    pending_transaction
+                }
+            };
+
+            let priority = self.transaction_priority(&pending_transaction.transaction);
+            let pool_transaction = PoolTransaction {
+                requires: vec![],
+                provides: vec![],
+                pending_transaction,
+                priority,
+            };
+            block_txs.entry(offset).or_default().push(Arc::new(pool_transaction));
+        }
+
+        let unwound = self.backend.rollback(depth).await?;
+
+        for offset in 0..depth {
+            let transactions = block_txs.remove(&offset).unwrap_or_default();
+            let outcome = self.backend.mine_block(transactions).await;
+            trace!(target: "node", blocknumber = ?outcome.block_number, "mined reorg block");
+            self.pool.on_mined_block(outcome);
+        }
+
+        // the transactions of the new blocks replace the unwound ones with the same nonces
+        self.add_unwound_transactions(unwound).await;
+
+        Ok(())
+    }
+
+    /// Removes the last `depth` blocks from the chain, their transactions are added back to the
+    /// pool.
+    ///
+    /// Handler for RPC call: `anvil_rollback`
+    pub async fn anvil_rollback(&self, depth: u64) -> Result<()> {
+        node_info!("anvil_rollback");
+        let unwound = self.backend.rollback(depth).await?;
+        self.add_unwound_transactions(unwound).await;
+        Ok(())
+    }
+
+    /// Adds the transactions of blocks that were removed from the chain back to the pool.
+    ///
+    /// Transactions that are no longer valid on top of the new best block are dropped. The
+    /// transactions don't trigger automine, so that they don't end up in additional blocks.
+    pub(super) async fn add_unwound_transactions(&self, transactions: Vec<PendingTransaction>) {
+        for pending_transaction in transactions {
+            let hash = *pending_transaction.hash();
+            if let Err(err) = self.add_unwound_transaction(pending_transaction).await {
+                trace!(target: "node", ?hash, ?err, "dropping unwound transaction");
+            }
+        }
+    }
+
+    async fn add_unwound_transaction(&self, pending_transaction: PendingTransaction) -> Result<()> {
+        self.backend.validate_pool_transaction(&pending_transaction).await?;
+
+        let from = *pending_transaction.sender();
+        let nonce = pending_transaction.transaction.nonce();
+        let on_chain_nonce = self.backend.current_nonce(from).await?;
+        let requires = required_marker(nonce, on_chain_nonce, from);
+        let provides = vec![to_marker(nonce, from)];
+        let priority = self.transaction_priority(&pending_transaction.transaction);
+        let pool_transaction =
+            PoolTransaction { requires, provides, pending_transaction, priority };
+        self.pool.add_transaction_silently(pool_transaction)?;
+        Ok(())
+    }
+
+    /// Sets the order of the transactions of the next mined block: the given transactions are
+    /// executed first and in the given order, followed by all other ready transactions. The
+    /// transactions of every sender are still executed in nonce order.
+    ///
+    /// Handler for RPC call: `anvil_setNextBlockTransactions`
+    pub async fn anvil_set_next_block_transactions(&self, hashes: Vec<TxHash>) -> Result<()> {
+        node_info!("anvil_setNextBlockTransactions");
+        self.backend.set_next_block_transactions(hashes);
+        Ok(())
+    }
+
     /// Snapshot the state of the blockchain at the current block.
     ///
     /// Handler for RPC call: `evm_snapshot`
@@ -2062,35 +2566,42 @@ impl EthApi {
     pub async fn txpool_content(&self) -> Result<TxpoolContent> {
         node_info!("txpool_content");
         let mut content = TxpoolContent::default();
-        fn convert(tx: Arc<PoolTransaction>) -> Transaction {
-            let from = *tx.pending_transaction.sender();
-            let mut tx = transaction_build(
-                Some(tx.hash()),
-                tx.pending_transaction.transaction.clone(),
-                None,
-                None,
-                None,
-            );
-
-            // we set the from field here explicitly to the set sender of the pending transaction,
-            // in case the transaction is impersonated.
-            tx.from = from;
-            tx.inner
-        }
 
         for pending in self.pool.ready_transactions() {
             let entry = content.pending.entry(*pending.pending_transaction.sender()).or_default();
             let key = pending.pending_transaction.nonce().to_string();
-            entry.insert(key, convert(pending));
+            entry.insert(key, pool_transaction_to_rpc(pending));
         }
         for queued in self.pool.pending_transactions() {
             let entry = content.pending.entry(*queued.pending_transaction.sender()).or_default();
             let key = queued.pending_transaction.nonce().to_string();
-            entry.insert(key, convert(queued));
+            entry.insert(key, pool_transaction_to_rpc(queued));
         }
 
         Ok(content)
     }
+
+    /// Returns the details of all transactions of the given sender that are currently pending for
+    /// inclusion in the next block(s), as well as the ones that are being scheduled for future
+    /// execution only.
+    ///
+    /// See [here](https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-txpool#txpool-contentfrom) for more details
+    ///
+    /// Handler for ETH RPC call: `txpool_contentFrom`
+    pub async fn txpool_content_from(&self, from: Address) -> Result<TxpoolContentFrom> {
+        node_info!("txpool_contentFrom");
+        let mut content = TxpoolContentFrom::default();
+        let (ready, queued) = self.pool.transactions_by_sender(from);
+        for tx in ready {
+            let key = tx.pending_transaction.nonce().to_string();
+            content.pending.insert(key, pool_transaction_to_rpc(tx));
+        }
+        for tx in queued {
+            let key = tx.pending_transaction.nonce().to_string();
+            content.queued.insert(key, pool_transaction_to_rpc(tx));
+        }
+        Ok(content)
+    }
 }
 
 // === impl EthApi utility functions ===
@@ -2147,14 +2658,15 @@ impl EthApi {
         &self,
         request: WithOtherFields<TransactionRequest>,
         block_number: Option<BlockId>,
-        overrides: Option<StateOverride>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
     ) -> Result<u128> {
         let block_request = self.block_request(block_number).await?;
         // check if the number predates the fork, if in fork mode
         if let BlockRequest::Number(number) = block_request {
             if let Some(fork) = self.get_fork() {
                 if fork.predates_fork(number) {
-                    if overrides.is_some() {
+                    if state_overrides.is_some() || block_overrides.is_some() {
                         return Err(BlockchainError::StateOverrideError(
                             "not available on past forked blocks".to_string(),
                         ));
@@ -2165,13 +2677,9 @@ impl EthApi {
         }
 
         self.backend
-            .with_database_at(Some(block_request), |mut state, block| {
-                if let Some(overrides) = overrides {
-                    state = Box::new(state::apply_state_override(
-                        overrides.into_iter().collect(),
-                        state,
-                    )?);
-                }
+            .with_database_at(Some(block_request), |state, mut block| {
+                let state =
+                    state::apply_overrides(state, &mut block, state_overrides, block_overrides)?;
                 self.do_estimate_gas_with_state(request, state, block)
             })
             .await?
@@ -2313,6 +2821,7 @@ impl EthApi {
     /// Updates the `TransactionOrder`
     pub fn set_transaction_order(&self, order: TransactionOrder) {
         *self.transaction_order.write() = order;
+        self.backend.set_block_builder(order.block_builder());
     }
 
     /// Returns the priority of the transaction based on the current `TransactionOrder`
@@ -2330,6 +2839,18 @@ impl EthApi {
         self.backend.get_fork()
     }
 
+    /// Returns the collected metrics, if enabled
+    pub fn metrics(&self) -> Option<&Arc<NodeMetrics>> {
+        self.backend.metrics()
+    }
+
+    /// Returns the metrics of the node in the Prometheus text format, if enabled
+    pub fn encode_metrics(&self) -> Option<String> {
+        let metrics = self.metrics()?;
+        let fork_stats = self.get_fork().map(|fork| fork.cache_stats());
+        Some(metrics.encode(self.pool.txpool_status(), fork_stats.as_deref()))
+    }
+
     /// Returns the current instance's ID.
     pub fn instance_id(&self) -> B256 {
         *self.instance_id.read()
@@ -2351,6 +2872,12 @@ impl EthApi {
         self.backend.new_block_notifications()
     }
 
+    /// Returns a new event stream that yields Notifications when a block was removed from the
+    /// chain
+    pub fn removed_block_notifications(&self) -> RemovedBlockNotifications {
+        self.backend.removed_block_notifications()
+    }
+
     /// Returns a new listeners for ready transactions
     pub fn new_ready_transactions(&self) -> Receiver<TxHash> {
         self.pool.add_ready_listener()
@@ -2366,6 +2893,42 @@ impl EthApi {
         self.backend.is_fork()
     }
 
+    /// Returns the number of the best block
+    pub fn best_number(&self) -> u64 {
+        self.backend.best_number()
+    }
+
+    /// Includes the given op-stack deposit transactions, relayed from L1, at the top of the next
+    /// block.
+    ///
+    /// If auto-mining is enabled, the next block is mined right away.
+    pub async fn relay_deposits(&self, deposits: Vec<DepositTransaction>) -> Result<()> {
+        if deposits.is_empty() {
+            return Ok(())
+        }
+        self.backend.add_deposits(deposits)?;
+        if self.miner.is_auto_mine() {
+            self.mine_one().await;
+        }
+        Ok(())
+    }
+
+    /// Removes the relayed op-stack deposits with the given source hashes, because the L1 blocks
+    /// that emitted them were reorged out.
+    ///
+    /// If one of them was already mined after the block with the number `since`, the chain is
+    /// rolled back to before it and the other transactions of the removed blocks are added back to
+    /// the pool.
+    pub async fn remove_relayed_deposits(
+        &self,
+        source_hashes: &HashSet<B256>,
+        since: u64,
+    ) -> Result<()> {
+        let unwound = self.backend.remove_deposits(source_hashes, since).await?;
+        self.add_unwound_transactions(unwound).await;
+        Ok(())
+    }
+
     /// Mines exactly one block
     pub async fn mine_one(&self) {
         let transactions = self.pool.ready_transactions().collect::<Vec<_>>();
@@ -2475,6 +3038,15 @@ impl EthApi {
                     }
                 })
             }
+            Some(TypedTransactionRequest::EIP7702(mut m)) => {
+                m.nonce = nonce;
+                m.chain_id = chain_id;
+                m.gas_limit = gas_limit;
+                if max_fee_per_gas.is_none() {
+                    m.max_fee_per_gas = self.backend.gas_price();
+                }
+                TypedTransactionRequest::EIP7702(m)
+            }
             Some(TypedTransactionRequest::Deposit(mut m)) => {
                 m.gas_limit = gas_limit;
                 TypedTransactionRequest::Deposit(m)
@@ -2548,12 +3120,28 @@ impl EthApi {
         self.backend.get_db().read().await.maybe_state_root()
     }
 
+    /// Decodes the raw signed transactions of a bundle
+    fn decode_bundle_transactions(&self, txs: Vec<Bytes>) -> Result<Vec<PendingTransaction>> {
+        if txs.is_empty() {
+            return Err(RpcError::invalid_params("bundle contains no transactions").into())
+        }
+        txs.iter()
+            .map(|tx| {
+                let transaction = TypedTransaction::decode_2718(&mut tx.as_ref())
+                    .map_err(|_| BlockchainError::FailedToDecodeSignedTransaction)?;
+                self.ensure_typed_transaction_supported(&transaction)?;
+                Ok(PendingTransaction::new(transaction)?)
+            })
+            .collect()
+    }
+
     /// additional validation against hardfork
     fn ensure_typed_transaction_supported(&self, tx: &TypedTransaction) -> Result<()> {
         match &tx {
             TypedTransaction::EIP2930(_) => self.backend.ensure_eip2930_active(),
             TypedTransaction::EIP1559(_) => self.backend.ensure_eip1559_active(),
             TypedTransaction::EIP4844(_) => self.backend.ensure_eip4844_active(),
+            TypedTransaction::EIP7702(_) => self.backend.ensure_eip7702_active(),
             TypedTransaction::Deposit(_) => self.backend.ensure_op_deposits_active(),
             TypedTransaction::Legacy(_) => Ok(()),
         }
@@ -2572,6 +3160,23 @@ fn required_marker(provided_nonce: u64, on_chain_nonce: u64, from: Address) -> V
     }
 }
 
+/// Converts a transaction of the pool into its RPC representation
+fn pool_transaction_to_rpc(tx: Arc<PoolTransaction>) -> Transaction {
+    let from = *tx.pending_transaction.sender();
+    let mut tx = transaction_build(
+        Some(tx.hash()),
+        tx.pending_transaction.transaction.clone(),
+        None,
+        None,
+        None,
+    );
+
+    // we set the from field here explicitly to the set sender of the pending transaction,
+    // in case the transaction is impersonated.
+    tx.from = from;
+    tx.inner
+}
+
 fn convert_transact_out(out: &Option<Output>) -> Bytes {
     match out {
         None => Default::default(),
@@ -2608,6 +3213,9 @@ fn determine_base_gas_by_kind(request: &WithOtherFields<TransactionRequest>) ->
                 TxKind::Create => MIN_CREATE_GAS,
             },
             TypedTransactionRequest::EIP4844(_) => MIN_TRANSACTION_GAS,
+            TypedTransactionRequest::EIP7702(req) => {
+                MIN_TRANSACTION_GAS + req.authorization_gas() as u128
+            }
             TypedTransactionRequest::Deposit(req) => match req.kind {
                 TxKind::Call(_) => MIN_TRANSACTION_GAS,
                 TxKind::Create => MIN_CREATE_GAS,
@@ -0,0 +1,175 @@
+//! Ordering of the transactions of new blocks
+
+use crate::eth::pool::transactions::PoolTransaction;
+use alloy_primitives::{keccak256, Address, TxHash};
+use std::{cmp::Reverse, collections::HashMap, fmt::Debug, sync::Arc};
+
+/// Determines the order in which the ready transactions of the pool are executed in a new block.
+///
+/// Custom implementations can be plugged in via
+/// [NodeConfig::with_block_builder](crate::NodeConfig::with_block_builder) when using `anvil` as a
+/// library.
+pub trait BlockBuilder: Send + Sync + Debug {
+    /// Returns the transactions in the order they should be executed in the block.
+    ///
+    /// The given transactions are in the order of the pool. The returned order does not need to
+    /// respect the nonces of the senders, the transactions of every sender are put in nonce order
+    /// afterwards, see [ensure_nonce_order].
+    fn order(&self, transactions: Vec<Arc<PoolTransaction>>) -> Vec<Arc<PoolTransaction>>;
+}
+
+/// Orders the transactions with the given builder while keeping the transactions of every sender
+/// in nonce order
+pub fn build_block_transactions(
+    builder: &dyn BlockBuilder,
+    transactions: Vec<Arc<PoolTransaction>>,
+) -> Vec<Arc<PoolTransaction>> {
+    ensure_nonce_order(builder.order(transactions))
+}
+
+/// Reorders the transactions of every sender by nonce, without changing the positions that are
+/// taken by the transactions of a sender.
+///
+/// For example `[A1, B0, A0]` becomes `[A0, B0, A1]`.
+pub fn ensure_nonce_order(transactions: Vec<Arc<PoolTransaction>>) -> Vec<Arc<PoolTransaction>> {
+    let mut by_sender: HashMap<Address, Vec<Arc<PoolTransaction>>> = HashMap::new();
+    for tx in &transactions {
+        by_sender.entry(*tx.pending_transaction.sender()).or_default().push(tx.clone());
+    }
+    // sorted descending so the lowest nonce can be popped
+    for txs in by_sender.values_mut() {
+        txs.sort_by_key(|tx| Reverse(tx.pending_transaction.nonce()));
+    }
+
+    transactions
+        .iter()
+        .filter_map(|tx| by_sender.get_mut(tx.pending_transaction.sender())?.pop())
+        .collect()
+}
+
+/// Keeps the order of the pool, which is determined by the
+/// [TransactionOrder](crate::eth::pool::transactions::TransactionOrder)
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PoolOrder;
+
+impl BlockBuilder for PoolOrder {
+    fn order(&self, transactions: Vec<Arc<PoolTransaction>>) -> Vec<Arc<PoolTransaction>> {
+        transactions
+    }
+}
+
+/// Orders the transactions by sender address, ascending
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SenderOrder;
+
+impl BlockBuilder for SenderOrder {
+    fn order(&self, mut transactions: Vec<Arc<PoolTransaction>>) -> Vec<Arc<PoolTransaction>> {
+        transactions
+            .sort_by_key(|tx| (*tx.pending_transaction.sender(), tx.pending_transaction.nonce()));
+        transactions
+    }
+}
+
+/// Shuffles the transactions deterministically: the order only depends on the seed and the
+/// hashes of the transactions, not on the order they arrived in.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RandomOrder {
+    pub seed: u64,
+}
+
+impl BlockBuilder for RandomOrder {
+    fn order(&self, mut transactions: Vec<Arc<PoolTransaction>>) -> Vec<Arc<PoolTransaction>> {
+        let seed = self.seed.to_be_bytes();
+        transactions.sort_by_cached_key(|tx| keccak256([&seed[..], &tx.hash()[..]].concat()));
+        transactions
+    }
+}
+
+/// Executes the given transactions first and in the given order, followed by all other
+/// transactions in the order of the pool.
+///
+/// Hashes of transactions that are not ready are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct FixedOrder {
+    pub hashes: Vec<TxHash>,
+}
+
+impl BlockBuilder for FixedOrder {
+    fn order(&self, mut transactions: Vec<Arc<PoolTransaction>>) -> Vec<Arc<PoolTransaction>> {
+        let positions: HashMap<_, _> =
+            self.hashes.iter().enumerate().map(|(pos, hash)| (*hash, pos)).collect();
+        // stable sort, so unlisted transactions keep the order of the pool
+        transactions.sort_by_key(|tx| positions.get(&tx.hash()).copied().unwrap_or(usize::MAX));
+        transactions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::pool::transactions::TransactionPriority;
+    use alloy_consensus::{Signed, TxLegacy};
+    use alloy_primitives::{TxKind, B256};
+    use anvil_core::eth::transaction::{
+        impersonated_signature, PendingTransaction, TypedTransaction,
+    };
+
+    fn tx(sender: u8, nonce: u64) -> Arc<PoolTransaction> {
+        let tx = TxLegacy { nonce, to: TxKind::Call(Address::ZERO), ..Default::default() };
+        let signed = Signed::new_unchecked(tx, impersonated_signature(), B256::ZERO);
+        let pending_transaction = PendingTransaction::with_impersonated(
+            TypedTransaction::Legacy(signed),
+            Address::with_last_byte(sender),
+        );
+        Arc::new(PoolTransaction {
+            pending_transaction,
+            requires: vec![],
+            provides: vec![],
+            priority: TransactionPriority::default(),
+        })
+    }
+
+    fn senders_and_nonces(txs: &[Arc<PoolTransaction>]) -> Vec<(u8, u64)> {
+        txs.iter()
+            .map(|tx| (tx.pending_transaction.sender()[19], tx.pending_transaction.nonce()))
+            .collect()
+    }
+
+    #[test]
+    fn test_ensure_nonce_order() {
+        let txs = vec![tx(1, 1), tx(2, 0), tx(1, 0), tx(2, 1)];
+        let ordered = ensure_nonce_order(txs);
+        assert_eq!(senders_and_nonces(&ordered), vec![(1, 0), (2, 0), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn test_sender_order() {
+        let txs = vec![tx(2, 0), tx(1, 0), tx(3, 0), tx(1, 1)];
+        let ordered = build_block_transactions(&SenderOrder, txs);
+        assert_eq!(senders_and_nonces(&ordered), vec![(1, 0), (1, 1), (2, 0), (3, 0)]);
+    }
+
+    #[test]
+    fn test_random_order_is_deterministic() {
+        let txs: Vec<_> = (1..=8).map(|sender| tx(sender, 0)).collect();
+        let mut reversed = txs.clone();
+        reversed.reverse();
+
+        let builder = RandomOrder { seed: 42 };
+        let a = build_block_transactions(&builder, txs.clone());
+        let b = build_block_transactions(&builder, reversed);
+        assert_eq!(senders_and_nonces(&a), senders_and_nonces(&b));
+
+        let c = build_block_transactions(&RandomOrder { seed: 43 }, txs);
+        assert_ne!(senders_and_nonces(&a), senders_and_nonces(&c));
+    }
+
+    #[test]
+    fn test_fixed_order() {
+        let txs = vec![tx(1, 0), tx(2, 0), tx(3, 0), tx(3, 1)];
+        let builder = FixedOrder { hashes: vec![txs[3].hash(), txs[1].hash(), B256::ZERO] };
+        let ordered = build_block_transactions(&builder, txs);
+        // the second transaction of sender 3 can't go before its first one
+        assert_eq!(senders_and_nonces(&ordered), vec![(3, 0), (2, 0), (1, 0), (3, 1)]);
+    }
+}
@@ -0,0 +1,105 @@
+//! Bundles of transactions that are included in a block as a whole or not at all
+
+use alloy_primitives::B256;
+use anvil_core::eth::transaction::PendingTransaction;
+
+/// A bundle submitted via `eth_sendBundle`
+#[derive(Clone, Debug)]
+pub struct Bundle {
+    pub hash: B256,
+    /// The transactions of the bundle, executed in this order at the top of the block
+    pub transactions: Vec<PendingTransaction>,
+    /// The block the bundle must be included in
+    pub block_number: u64,
+    pub min_timestamp: Option<u64>,
+    pub max_timestamp: Option<u64>,
+    /// The hashes of the transactions that are allowed to revert
+    pub reverting_tx_hashes: Vec<B256>,
+}
+
+// === impl Bundle ===
+
+impl Bundle {
+    /// Returns true if the bundle can be included in a block with the given timestamp
+    pub fn is_valid_at(&self, timestamp: u64) -> bool {
+        self.min_timestamp.map_or(true, |min| timestamp >= min) &&
+            self.max_timestamp.map_or(true, |max| timestamp <= max)
+    }
+
+    /// Returns true if the transaction with the given hash is allowed to revert
+    pub fn may_revert(&self, tx_hash: &B256) -> bool {
+        self.reverting_tx_hashes.contains(tx_hash)
+    }
+}
+
+/// All bundles that target upcoming blocks
+#[derive(Clone, Debug, Default)]
+pub struct BundlePool {
+    bundles: Vec<Bundle>,
+}
+
+// === impl BundlePool ===
+
+impl BundlePool {
+    /// Adds a new bundle
+    pub fn add(&mut self, bundle: Bundle) {
+        self.bundles.push(bundle);
+    }
+
+    /// Removes and returns the bundles that target the given block, in the order they were
+    /// submitted.
+    ///
+    /// Bundles that target earlier blocks can never be included anymore and are dropped.
+    pub fn take_for_block(&mut self, block_number: u64) -> Vec<Bundle> {
+        let (bundles, upcoming) = std::mem::take(&mut self.bundles)
+            .into_iter()
+            .filter(|bundle| bundle.block_number >= block_number)
+            .partition(|bundle| bundle.block_number == block_number);
+        self.bundles = upcoming;
+        bundles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle(block_number: u64) -> Bundle {
+        Bundle {
+            hash: B256::with_last_byte(block_number as u8),
+            transactions: vec![],
+            block_number,
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_tx_hashes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_take_for_block() {
+        let mut pool = BundlePool::default();
+        pool.add(bundle(1));
+        pool.add(bundle(2));
+        pool.add(bundle(3));
+        pool.add(bundle(2));
+
+        let bundles = pool.take_for_block(2);
+        assert_eq!(bundles.len(), 2);
+        assert!(bundles.iter().all(|bundle| bundle.block_number == 2));
+
+        // the bundle for block 1 was dropped
+        assert!(pool.take_for_block(1).is_empty());
+        assert_eq!(pool.take_for_block(3).len(), 1);
+    }
+
+    #[test]
+    fn test_timestamp_bounds() {
+        let mut bundle = bundle(1);
+        bundle.min_timestamp = Some(10);
+        bundle.max_timestamp = Some(20);
+        assert!(!bundle.is_valid_at(9));
+        assert!(bundle.is_valid_at(10));
+        assert!(bundle.is_valid_at(20));
+        assert!(!bundle.is_valid_at(21));
+    }
+}
@@ -1,12 +1,17 @@
 //! Helper types for working with [revm](foundry_evm::revm)
 
-use crate::revm::primitives::AccountInfo;
+use crate::{eth::backend::mem::storage::MinedTransaction, revm::primitives::AccountInfo};
+use alloy_consensus::Header;
 use alloy_primitives::{keccak256, Address, Bytes, B256, U256, U64};
 use alloy_rpc_types::BlockId;
+use anvil_core::eth::{
+    block::Block,
+    transaction::{MaybeImpersonatedTransaction, TransactionInfo, TypedReceipt},
+};
 use foundry_common::errors::FsPathError;
 use foundry_evm::{
     backend::{DatabaseError, DatabaseResult, MemDb, RevertSnapshotAction, StateSnapshot},
-    fork::BlockchainDb,
+    fork::{BlockchainDb, SharedBackend},
     revm::{
         db::{CacheDB, DatabaseRef, DbAccount},
         primitives::{BlockEnv, Bytecode, HashMap, KECCAK_EMPTY},
@@ -31,6 +36,12 @@ pub trait MaybeFullDatabase: DatabaseRef<Error = DatabaseError> {
 
     /// Reverses `clear_into_snapshot` by initializing the db's state with the snapshot
     fn init_from_snapshot(&mut self, snapshot: StateSnapshot);
+
+    /// Returns the state that was changed locally on top of the forked remote state, if this is
+    /// the state of a fork
+    fn maybe_as_fork_local_db(&self) -> Option<&CacheDB<SharedBackend>> {
+        None
+    }
 }
 
 impl<'a, T: 'a + MaybeFullDatabase + ?Sized> MaybeFullDatabase for &'a T
@@ -48,16 +59,23 @@ where
     fn clear(&mut self) {}
 
     fn init_from_snapshot(&mut self, _snapshot: StateSnapshot) {}
+
+    fn maybe_as_fork_local_db(&self) -> Option<&CacheDB<SharedBackend>> {
+        T::maybe_as_fork_local_db(self)
+    }
 }
 
 /// Helper trait to reset the DB if it's forked
 #[auto_impl::auto_impl(Box)]
 pub trait MaybeForkedDatabase {
-    fn maybe_reset(&mut self, _url: Option<String>, block_number: BlockId) -> Result<(), String>;
+    fn maybe_reset(&mut self, db: BlockchainDb, block_number: BlockId) -> Result<(), String>;
 
     fn maybe_flush_cache(&self) -> Result<(), String>;
 
     fn maybe_inner(&self) -> Result<&BlockchainDb, String>;
+
+    /// Replaces the state that was changed locally on top of the forked remote state with `local`
+    fn maybe_reset_local_db(&mut self, local: CacheDB<SharedBackend>) -> Result<(), String>;
 }
 
 /// This bundles all required revm traits
@@ -250,7 +268,7 @@ impl<T: DatabaseRef<Error = DatabaseError>> MaybeFullDatabase for CacheDB<T> {
 }
 
 impl<T: DatabaseRef<Error = DatabaseError>> MaybeForkedDatabase for CacheDB<T> {
-    fn maybe_reset(&mut self, _url: Option<String>, _block_number: BlockId) -> Result<(), String> {
+    fn maybe_reset(&mut self, _db: BlockchainDb, _block_number: BlockId) -> Result<(), String> {
         Err("not supported".to_string())
     }
 
@@ -261,6 +279,10 @@ impl<T: DatabaseRef<Error = DatabaseError>> MaybeForkedDatabase for CacheDB<T> {
     fn maybe_inner(&self) -> Result<&BlockchainDb, String> {
         Err("not supported".to_string())
     }
+
+    fn maybe_reset_local_db(&mut self, _local: CacheDB<SharedBackend>) -> Result<(), String> {
+        Err("not supported".to_string())
+    }
 }
 
 /// Represents a state at certain point
@@ -274,6 +296,12 @@ impl StateDb {
     }
 }
 
+impl fmt::Debug for StateDb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StateDb").finish_non_exhaustive()
+    }
+}
+
 impl DatabaseRef for StateDb {
     type Error = DatabaseError;
     fn basic_ref(&self, address: Address) -> DatabaseResult<Option<AccountInfo>> {
@@ -309,6 +337,10 @@ impl MaybeFullDatabase for StateDb {
     fn init_from_snapshot(&mut self, snapshot: StateSnapshot) {
         self.0.init_from_snapshot(snapshot)
     }
+
+    fn maybe_as_fork_local_db(&self) -> Option<&CacheDB<SharedBackend>> {
+        self.0.maybe_as_fork_local_db()
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -320,6 +352,12 @@ pub struct SerializableState {
     pub accounts: BTreeMap<Address, SerializableAccountRecord>,
     /// The best block number of the state, can be different from block number (Arbitrum chain).
     pub best_block_number: Option<U64>,
+    /// The blocks, transactions and receipts of the chain
+    ///
+    /// Note: This is an Option for backwards compatibility with state files that only contain
+    /// accounts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history: Option<SerializableHistory>,
 }
 
 // === impl SerializableState ===
@@ -341,10 +379,82 @@ impl SerializableState {
     }
 }
 
+/// The block history of a dumped chain
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializableHistory {
+    /// The version of the history format, see [SerializableHistory::VERSION]
+    pub version: u64,
+    /// The number of the best block of the chain
+    pub best_block_number: u64,
+    /// The total difficulty of the chain up to and including the best block
+    pub total_difficulty: U256,
+    /// All blocks, ordered by number
+    pub blocks: Vec<SerializableBlock>,
+    /// All transactions of the blocks and their receipts
+    pub transactions: Vec<SerializableTransaction>,
+}
+
+impl SerializableHistory {
+    /// The current version of the history format
+    pub const VERSION: u64 = 1;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SerializableAccountRecord {
     pub nonce: u64,
     pub balance: U256,
     pub code: Bytes,
     pub storage: BTreeMap<U256, U256>,
 }
+
+/// A mined block in a serializable form
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerializableBlock {
+    pub header: Header,
+    pub transactions: Vec<MaybeImpersonatedTransaction>,
+    pub ommers: Vec<Header>,
+}
+
+impl From<Block> for SerializableBlock {
+    fn from(block: Block) -> Self {
+        Self { header: block.header, transactions: block.transactions, ommers: block.ommers }
+    }
+}
+
+impl From<SerializableBlock> for Block {
+    fn from(block: SerializableBlock) -> Self {
+        Self { header: block.header, transactions: block.transactions, ommers: block.ommers }
+    }
+}
+
+/// A mined transaction and its receipt in a serializable form
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerializableTransaction {
+    pub info: TransactionInfo,
+    pub receipt: TypedReceipt,
+    pub block_hash: B256,
+    pub block_number: u64,
+}
+
+impl From<MinedTransaction> for SerializableTransaction {
+    fn from(tx: MinedTransaction) -> Self {
+        Self {
+            info: tx.info,
+            receipt: tx.receipt,
+            block_hash: tx.block_hash,
+            block_number: tx.block_number,
+        }
+    }
+}
+
+impl From<SerializableTransaction> for MinedTransaction {
+    fn from(tx: SerializableTransaction) -> Self {
+        Self {
+            info: tx.info,
+            receipt: tx.receipt,
+            block_hash: tx.block_hash,
+            block_number: tx.block_number,
+        }
+    }
+}
@@ -4,14 +4,16 @@ use crate::{
         error::InvalidTransactionError,
         pool::transactions::PoolTransaction,
     },
+    evm::inject_eip7702,
     inject_precompiles,
     mem::inspector::Inspector,
     PrecompileFactory,
 };
 use alloy_consensus::{Header, Receipt, ReceiptWithBloom};
-use alloy_primitives::{Bloom, BloomInput, Log, B256};
+use alloy_primitives::{Address, Bloom, BloomInput, Log, B256, U256};
 use anvil_core::eth::{
     block::{Block, BlockInfo, PartialHeader},
+    subscription::AccountDiff,
     transaction::{
         DepositReceipt, PendingTransaction, TransactionInfo, TypedReceipt, TypedTransaction,
     },
@@ -28,8 +30,8 @@ use foundry_evm::{
     },
     traces::CallTraceNode,
 };
-use revm::primitives::MAX_BLOB_GAS_PER_BLOCK;
-use std::sync::Arc;
+use revm::primitives::{AccountInfo, HashMap, ResultAndState, State, MAX_BLOB_GAS_PER_BLOCK};
+use std::{collections::BTreeMap, sync::Arc};
 
 /// Represents an executed transaction (transacted on the DB)
 #[derive(Debug)]
@@ -62,6 +64,7 @@ impl ExecutedTransaction {
             TypedTransaction::EIP2930(_) => TypedReceipt::EIP2930(receipt_with_bloom),
             TypedTransaction::EIP1559(_) => TypedReceipt::EIP1559(receipt_with_bloom),
             TypedTransaction::EIP4844(_) => TypedReceipt::EIP4844(receipt_with_bloom),
+            TypedTransaction::EIP7702(_) => TypedReceipt::EIP7702(receipt_with_bloom),
             TypedTransaction::Deposit(tx) => TypedReceipt::Deposit(DepositReceipt {
                 inner: receipt_with_bloom,
                 deposit_nonce: Some(tx.nonce),
@@ -81,6 +84,16 @@ pub struct ExecutedTransactions {
     /// All transactions that were invalid at the point of their execution and were not included in
     /// the block
     pub invalid: Vec<Arc<PoolTransaction>>,
+    /// The accounts changed by the `included` transactions
+    pub state_diff: BTreeMap<Address, AccountDiff>,
+}
+
+/// The state of an account before the block was executed
+#[derive(Debug, Default)]
+pub struct AccountPrestate {
+    info: AccountInfo,
+    /// The values of the storage slots that were changed
+    storage: HashMap<U256, U256>,
 }
 
 /// An executor for a series of transactions
@@ -102,6 +115,8 @@ pub struct TransactionExecutor<'a, Db: ?Sized, Validator: TransactionValidator>
     pub enable_steps_tracing: bool,
     /// Precompiles to inject to the EVM.
     pub precompile_factory: Option<Arc<dyn PrecompileFactory>>,
+    /// The state before the block of all accounts that were touched so far
+    pub prestate: HashMap<Address, AccountPrestate>,
 }
 
 impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'a, DB, Validator> {
@@ -225,7 +240,97 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
 
         let block = Block::new(partial_header, transactions.clone(), ommers);
         let block = BlockInfo { block, transactions: transaction_infos, receipts };
-        ExecutedTransactions { block, included, invalid }
+        let state_diff = self.state_diff();
+        ExecutedTransactions { block, included, invalid, state_diff }
+    }
+
+    /// Records the state of all accounts and storage slots changed by `state` that were not touched
+    /// before
+    ///
+    /// All storage slots of destroyed accounts are recorded, so that their removal is part of the
+    /// diff.
+    fn record_prestate(&mut self, state: &State) -> Result<(), DatabaseError> {
+        for (address, account) in state {
+            if !account.is_touched() {
+                continue
+            }
+            self.record_account(*address)?;
+            // the storage of a destroyed account is wiped, so all of its slots change
+            let wiped = if account.is_selfdestructed() {
+                self.db
+                    .maybe_as_full_db()
+                    .and_then(|accounts| accounts.get(address))
+                    .map(|account| account.storage.clone())
+                    .unwrap_or_default()
+            } else {
+                Default::default()
+            };
+            let prestate = self.prestate.get_mut(address).expect("was recorded");
+            for (slot, value) in wiped {
+                prestate.storage.entry(slot).or_insert(value);
+            }
+            for (slot, value) in &account.storage {
+                if value.is_changed() {
+                    prestate.storage.entry(*slot).or_insert(value.original_value);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Records the state of the account if it was not touched before
+    fn record_account(&mut self, address: Address) -> Result<(), DatabaseError> {
+        if !self.prestate.contains_key(&address) {
+            let info = self.db.basic(address)?.unwrap_or_default();
+            self.prestate.insert(address, AccountPrestate { info, storage: Default::default() });
+        }
+        Ok(())
+    }
+
+    /// Returns the changes of all touched accounts, compared to their state before the block
+    fn state_diff(&mut self) -> BTreeMap<Address, AccountDiff> {
+        let mut diff = BTreeMap::new();
+        for (address, prestate) in std::mem::take(&mut self.prestate) {
+            match self.account_diff(address, prestate) {
+                Ok(account) if !account.is_empty() => {
+                    diff.insert(address, account);
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    warn!(target: "backend", ?address, ?err, "failed to compute account diff");
+                }
+            }
+        }
+        diff
+    }
+
+    fn account_diff(
+        &mut self,
+        address: Address,
+        prestate: AccountPrestate,
+    ) -> Result<AccountDiff, DatabaseError> {
+        let info = self.db.basic(address)?.unwrap_or_default();
+        let mut diff = AccountDiff::default();
+        if info.balance != prestate.info.balance {
+            diff.balance = Some(info.balance);
+        }
+        if info.nonce != prestate.info.nonce {
+            diff.nonce = Some(info.nonce);
+        }
+        if info.code_hash != prestate.info.code_hash {
+            let code = match info.code {
+                Some(code) => code,
+                None => self.db.code_by_hash(info.code_hash)?,
+            };
+            diff.code = Some(code.original_bytes());
+        }
+        for (slot, value) in prestate.storage {
+            let present = self.db.storage(address, slot)?;
+            if present != value {
+                diff.storage.insert(slot.into(), present.into());
+            }
+        }
+        Ok(diff)
     }
 
     fn env_for(&self, tx: &PendingTransaction) -> EnvWithHandlerCfg {
@@ -306,10 +411,19 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
             if let Some(factory) = &self.precompile_factory {
                 inject_precompiles(&mut evm, factory.precompiles());
             }
+            inject_eip7702(
+                &mut evm,
+                transaction
+                    .pending_transaction
+                    .transaction
+                    .authorization_list()
+                    .unwrap_or_default()
+                    .to_vec(),
+            );
 
             trace!(target: "backend", "[{:?}] executing", transaction.hash());
-            // transact and commit the transaction
-            match evm.transact_commit() {
+            // transact the transaction, it's committed after its prestate was recorded
+            match evm.transact() {
                 Ok(exec_result) => exec_result,
                 Err(err) => {
                     warn!(target: "backend", "[{:?}] failed to execute: {:?}", transaction.hash(), err);
@@ -335,6 +449,12 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
         };
         inspector.print_logs();
 
+        let ResultAndState { result: exec_result, state } = exec_result;
+        if let Err(err) = self.record_prestate(&state) {
+            return Some(TransactionExecutionOutcome::DatabaseError(transaction, err))
+        }
+        self.db.commit(state);
+
         let (exit_reason, gas_used, out, logs) = match exec_result {
             ExecutionResult::Success { reason, gas_used, logs, output, .. } => {
                 (reason.into(), gas_used, Some(output), Some(logs))
@@ -376,6 +496,14 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
     }
 }
 
+/// Returns the price the sender pays per unit of gas
+pub fn effective_gas_price(env: &EnvWithHandlerCfg) -> U256 {
+    env.tx
+        .gas_priority_fee
+        .map(|tip| env.tx.gas_price.min(env.block.basefee.saturating_add(tip)))
+        .unwrap_or(env.tx.gas_price)
+}
+
 /// Inserts all logs into the bloom
 fn build_logs_bloom(logs: Vec<Log>, bloom: &mut Bloom) {
     for log in logs {
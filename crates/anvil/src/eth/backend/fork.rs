@@ -9,18 +9,31 @@ use alloy_rpc_types::{
     Filter, Log, Transaction, WithOtherFields,
 };
 use alloy_rpc_types_trace::{
-    geth::{GethDebugTracingOptions, GethTrace},
-    parity::LocalizedTransactionTrace as Trace,
+    filter::TraceFilter,
+    geth::{GethDebugTracingOptions, GethTrace, TraceResult},
+    parity::{
+        LocalizedTransactionTrace as Trace, TraceResults, TraceResultsWithTransactionHash,
+        TraceType,
+    },
 };
 use alloy_transport::TransportError;
-use anvil_core::eth::transaction::{convert_to_anvil_receipt, ReceiptResponse};
+use anvil_core::eth::{
+    simulate::{SimulatePayload, SimulatedBlock},
+    transaction::{convert_to_anvil_receipt, ReceiptResponse},
+};
 use foundry_common::provider::{ProviderBuilder, RetryProvider};
+use foundry_config::Config;
+use foundry_evm::fork::{BlockchainDb, BlockchainDbCache, BlockchainDbMeta, BlockchainDbStats};
 use parking_lot::{
     lock_api::{RwLockReadGuard, RwLockWriteGuard},
     RawRwLock, RwLock,
 };
 use revm::primitives::BlobExcessGasAndPrice;
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 use tokio::sync::RwLock as AsyncRwLock;
 
 /// Represents a fork of a remote client
@@ -48,19 +61,15 @@ impl ClientFork {
     }
 
     /// Reset the fork to a fresh forked state, and optionally update the fork config
+    ///
+    /// The state that was already fetched for the new fork block, earlier in this session or in a
+    /// previous one if storage caching is enabled, is reused.
     pub async fn reset(
         &self,
         url: Option<String>,
         block_number: impl Into<BlockId>,
     ) -> Result<(), BlockchainError> {
         let block_number = block_number.into();
-        {
-            self.database
-                .write()
-                .await
-                .maybe_reset(url.clone(), block_number)
-                .map_err(BlockchainError::Internal)?;
-        }
 
         if let Some(url) = url {
             self.config.write().update_url(url)?;
@@ -82,6 +91,21 @@ impl ClientFork {
         let total_difficulty = block.header.total_difficulty.unwrap_or_default();
 
         let number = block.header.number.ok_or(BlockchainError::BlockNotFound)?;
+
+        {
+            let mut database = self.database.write().await;
+            let mut meta =
+                database.maybe_inner().map_err(BlockchainError::Internal)?.meta().read().clone();
+            meta.cfg_env.chain_id = self.chain_id();
+            meta.block_env.number = U256::from(number);
+            meta.block_env.timestamp = U256::from(timestamp);
+            meta.block_env.difficulty = block.header.difficulty;
+            meta.block_env.prevrandao = Some(block.header.mix_hash.unwrap_or_default());
+
+            let db = self.config.read().block_db(meta, block_hash);
+            database.maybe_reset(db, number.into()).map_err(BlockchainError::Internal)?;
+        }
+
         self.config.write().update_block(number, block_hash, timestamp, base_fee, total_difficulty);
 
         self.clear_cached_storage();
@@ -138,6 +162,16 @@ impl ClientFork {
         self.config.read().provider.clone()
     }
 
+    /// Returns the counters of the fork cache and the state requests sent to the remote client
+    pub fn cache_stats(&self) -> Arc<BlockchainDbStats> {
+        self.config.read().block_dbs.stats().clone()
+    }
+
+    /// Returns the `(chain id, block number)` of all fork blocks whose state is cached
+    pub fn cached_blocks(&self) -> Vec<(u64, u64)> {
+        self.config.read().block_dbs.blocks()
+    }
+
     fn storage_read(&self) -> RwLockReadGuard<'_, RawRwLock, ForkedStorage> {
         self.storage.read()
     }
@@ -190,6 +224,16 @@ impl ClientFork {
         Ok(res)
     }
 
+    /// Sends `eth_simulateV1`
+    pub async fn simulate_v1(
+        &self,
+        request: &SimulatePayload,
+        block: Option<BlockNumber>,
+    ) -> Result<Vec<SimulatedBlock>, TransportError> {
+        let block = block.unwrap_or(BlockNumber::Latest);
+        self.provider().raw_request("eth_simulateV1".into(), (request.clone(), block)).await
+    }
+
     /// Sends `eth_createAccessList`
     pub async fn create_access_list(
         &self,
@@ -340,18 +384,42 @@ impl ClientFork {
         hash: B256,
         opts: GethDebugTracingOptions,
     ) -> Result<GethTrace, TransportError> {
-        if let Some(traces) = self.storage_read().geth_transaction_traces.get(&hash).cloned() {
-            return Ok(traces);
+        // only the default struct log trace is cached, other tracers produce different output for
+        // the same transaction
+        let cacheable = opts == GethDebugTracingOptions::default();
+        if cacheable {
+            if let Some(traces) = self.storage_read().geth_transaction_traces.get(&hash).cloned() {
+                return Ok(traces);
+            }
         }
 
         let trace = self.provider().debug_trace_transaction(hash, opts).await?;
 
-        let mut storage = self.storage_write();
-        storage.geth_transaction_traces.insert(hash, trace.clone());
+        if cacheable {
+            let mut storage = self.storage_write();
+            storage.geth_transaction_traces.insert(hash, trace.clone());
+        }
 
         Ok(trace)
     }
 
+    pub async fn debug_trace_block(
+        &self,
+        block: BlockId,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TraceResult>, TransportError> {
+        match block {
+            BlockId::Hash(hash) => {
+                self.provider()
+                    .raw_request("debug_traceBlockByHash".into(), (hash.block_hash, opts))
+                    .await
+            }
+            BlockId::Number(number) => {
+                self.provider().raw_request("debug_traceBlockByNumber".into(), (number, opts)).await
+            }
+        }
+    }
+
     pub async fn trace_block(&self, number: u64) -> Result<Vec<Trace>, TransportError> {
         if let Some(traces) = self.storage_read().block_traces.get(&number).cloned() {
             return Ok(traces);
@@ -366,6 +434,31 @@ impl ClientFork {
         Ok(traces)
     }
 
+    pub async fn trace_filter(&self, filter: TraceFilter) -> Result<Vec<Trace>, TransportError> {
+        self.provider().raw_request("trace_filter".into(), (filter,)).await
+    }
+
+    pub async fn trace_replay_transaction(
+        &self,
+        hash: B256,
+        trace_types: HashSet<TraceType>,
+    ) -> Result<TraceResults, TransportError> {
+        self.provider().raw_request("trace_replayTransaction".into(), (hash, trace_types)).await
+    }
+
+    pub async fn trace_replay_block_transactions(
+        &self,
+        number: u64,
+        trace_types: HashSet<TraceType>,
+    ) -> Result<Vec<TraceResultsWithTransactionHash>, TransportError> {
+        self.provider()
+            .raw_request(
+                "trace_replayBlockTransactions".into(),
+                (BlockNumber::Number(number), trace_types),
+            )
+            .await
+    }
+
     pub async fn transaction_receipt(
         &self,
         hash: B256,
@@ -597,6 +690,10 @@ pub struct ClientForkConfig {
     pub compute_units_per_second: u64,
     /// total difficulty of the chain until this block
     pub total_difficulty: U256,
+    /// The databases of all fork blocks used in this session
+    pub block_dbs: BlockchainDbCache,
+    /// Whether the state fetched for a fork block is cached on disk
+    pub storage_caching: bool,
 }
 
 // === impl ClientForkConfig ===
@@ -639,6 +736,24 @@ impl ClientForkConfig {
         self.total_difficulty = total_difficulty;
         trace!(target: "fork", "Updated block number={} hash={:?}", block_number, block_hash);
     }
+
+    /// Returns the database for the block with the given hash that `meta` points to, reusing it if
+    /// the block was used before
+    ///
+    /// If storage caching is enabled, the database is loaded from the block's cache file. The path
+    /// of the file already identifies the chain and the block, so the remaining metadata isn't
+    /// checked.
+    fn block_db(&self, meta: BlockchainDbMeta, block_hash: B256) -> BlockchainDb {
+        let chain_id = meta.cfg_env.chain_id;
+        let block_number = meta.block_env.number.saturating_to::<u64>();
+        self.block_dbs.get_or_insert_with(chain_id, block_number, block_hash, || {
+            let cache_path = self
+                .storage_caching
+                .then(|| Config::foundry_block_cache_file(chain_id, block_number))
+                .flatten();
+            BlockchainDb::new_skip_check(meta, cache_path)
+        })
+    }
 }
 
 /// Contains cached state fetched to serve EthApi requests
@@ -0,0 +1,270 @@
+//! Persistent on-disk storage of the chain
+
+use crate::eth::backend::db::{
+    Db, SerializableAccountRecord, SerializableBlock, SerializableTransaction,
+};
+use alloy_primitives::{Address, U256};
+use anvil_core::eth::subscription::AccountDiff;
+use foundry_common::{errors::FsPathError, fs};
+use foundry_evm::backend::DatabaseResult;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+/// The changed fields and storage slots of all changed accounts
+pub type StateChanges = BTreeMap<Address, AccountDiff>;
+
+/// Everything that is persisted for a single block
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PersistedBlock {
+    pub block: SerializableBlock,
+    pub transactions: Vec<SerializableTransaction>,
+    /// The total difficulty of the chain up to and including this block
+    pub total_difficulty: U256,
+    /// The changes that were made to the state after the previous block and before this block,
+    /// e.g. via `anvil_setBalance`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pending_changes: StateChanges,
+    /// The changes made by the transactions of this block
+    pub state_changes: StateChanges,
+}
+
+/// A chain that was read from disk
+#[derive(Clone, Debug)]
+pub struct PersistedChain {
+    /// All blocks, ordered by number
+    pub blocks: Vec<PersistedBlock>,
+    /// The changes that were made to the state after the last block
+    pub pending_changes: StateChanges,
+}
+
+/// On-disk storage of the chain
+///
+/// Every mined block is written to its own `blocks/<number>.json` file, together with its
+/// transactions, their receipts and the changes it made to the state, so existing files are never
+/// rewritten. State changes that happen between blocks, e.g. via `anvil_setBalance`, are written
+/// to `pending.json` as they happen and moved into the file of the next block.
+#[derive(Debug)]
+pub struct DiskChain {
+    /// The directory the chain is stored in
+    path: PathBuf,
+    /// The changes that were made to the state since the last block, as stored in `pending.json`
+    pending: StateChanges,
+    /// The pending changes at the time a snapshot was created, by snapshot id
+    snapshots: HashMap<U256, StateChanges>,
+}
+
+impl DiskChain {
+    /// Opens the chain stored in the given directory, creating the directory if it doesn't exist
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, FsPathError> {
+        let path = path.into();
+        fs::create_dir_all(path.join("blocks"))?;
+        Ok(Self { path, pending: Default::default(), snapshots: Default::default() })
+    }
+
+    /// Reads the stored chain, if any
+    pub fn load(&mut self) -> Result<Option<PersistedChain>, FsPathError> {
+        let blocks = self.read_blocks()?;
+        if blocks.is_empty() {
+            return Ok(None)
+        }
+
+        let pending = self.pending_file();
+        if pending.exists() {
+            self.pending = fs::read_json_file(&pending)?;
+        }
+
+        Ok(Some(PersistedChain { blocks, pending_changes: self.pending.clone() }))
+    }
+
+    /// Writes a new block with the changes its transactions made to the state, the pending
+    /// changes become part of the block
+    pub fn write_block(
+        &mut self,
+        block: SerializableBlock,
+        transactions: Vec<SerializableTransaction>,
+        total_difficulty: U256,
+        state_changes: StateChanges,
+    ) -> Result<(), FsPathError> {
+        let number = block.header.number;
+        let block = PersistedBlock {
+            block,
+            transactions,
+            total_difficulty,
+            pending_changes: std::mem::take(&mut self.pending),
+            state_changes,
+        };
+        fs::write_json_file(&self.block_file(number), &block)?;
+        trace!(target: "backend", number, "persisted block");
+
+        self.remove_pending()
+    }
+
+    /// Adds changes that were made to the state outside of a block
+    pub fn write_pending(&mut self, changes: StateChanges) -> Result<(), FsPathError> {
+        merge_changes(&mut self.pending, changes);
+        fs::write_json_file(&self.pending_file(), &self.pending)
+    }
+
+    /// Removes all blocks above the given block number and all pending changes
+    pub fn unwind_to(&mut self, number: u64) -> Result<(), FsPathError> {
+        for (block_number, file) in self.block_files()? {
+            if block_number > number {
+                fs::remove_file(file)?;
+            }
+        }
+        self.pending.clear();
+        self.remove_pending()
+    }
+
+    /// Records the pending changes for a snapshot with the given id
+    pub fn snapshot(&mut self, id: U256) {
+        self.snapshots.insert(id, self.pending.clone());
+    }
+
+    /// Restores the pending changes of the snapshot with the given id
+    pub fn revert_snapshot(&mut self, id: U256) -> Result<(), FsPathError> {
+        let Some(pending) = self.snapshots.remove(&id) else { return Ok(()) };
+        self.pending = pending;
+        if self.pending.is_empty() {
+            return self.remove_pending()
+        }
+        fs::write_json_file(&self.pending_file(), &self.pending)
+    }
+
+    /// Returns all block files and their block numbers
+    fn block_files(&self) -> Result<Vec<(u64, PathBuf)>, FsPathError> {
+        let dir = self.path.join("blocks");
+        let entries = std::fs::read_dir(&dir).map_err(|err| FsPathError::read(err, &dir))?;
+        let mut files = Vec::new();
+        for entry in entries {
+            let file = entry.map_err(|err| FsPathError::read(err, &dir))?.path();
+            if file.extension().map_or(true, |ext| ext != "json") {
+                continue
+            }
+            if let Some(number) =
+                file.file_stem().and_then(|stem| stem.to_str()).and_then(|s| s.parse().ok())
+            {
+                files.push((number, file));
+            }
+        }
+        files.sort_unstable_by_key(|(number, _)| *number);
+        Ok(files)
+    }
+
+    /// Reads all blocks, ordered by number
+    fn read_blocks(&self) -> Result<Vec<PersistedBlock>, FsPathError> {
+        self.block_files()?.into_iter().map(|(_, file)| fs::read_json_file(&file)).collect()
+    }
+
+    fn remove_pending(&self) -> Result<(), FsPathError> {
+        let pending = self.pending_file();
+        if pending.exists() {
+            fs::remove_file(pending)?;
+        }
+        Ok(())
+    }
+
+    fn block_file(&self, number: u64) -> PathBuf {
+        self.path.join("blocks").join(format!("{number}.json"))
+    }
+
+    fn pending_file(&self) -> PathBuf {
+        self.path.join("pending.json")
+    }
+}
+
+/// Returns the changes that set all fields and storage slots of an account to the given values
+pub fn account_changes(account: SerializableAccountRecord) -> AccountDiff {
+    AccountDiff {
+        balance: Some(account.balance),
+        nonce: Some(account.nonce),
+        code: Some(account.code),
+        storage: account
+            .storage
+            .into_iter()
+            .map(|(slot, value)| (slot.into(), value.into()))
+            .collect(),
+    }
+}
+
+/// Applies the given changes to the database
+pub fn apply_changes(db: &mut dyn Db, changes: StateChanges) -> DatabaseResult<()> {
+    for (address, account) in changes {
+        if let Some(balance) = account.balance {
+            db.set_balance(address, balance)?;
+        }
+        if let Some(nonce) = account.nonce {
+            db.set_nonce(address, nonce)?;
+        }
+        if let Some(code) = account.code {
+            db.set_code(address, code)?;
+        }
+        for (slot, value) in account.storage {
+            db.set_storage_at(address, U256::from_be_bytes(slot.0), U256::from_be_bytes(value.0))?;
+        }
+    }
+    Ok(())
+}
+
+/// Merges `changes` into `into`, the values of `changes` take precedence
+fn merge_changes(into: &mut StateChanges, changes: StateChanges) {
+    for (address, changes) in changes {
+        let account = into.entry(address).or_default();
+        if changes.balance.is_some() {
+            account.balance = changes.balance;
+        }
+        if changes.nonce.is_some() {
+            account.nonce = changes.nonce;
+        }
+        if changes.code.is_some() {
+            account.code = changes.code;
+        }
+        account.storage.extend(changes.storage);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+
+    #[test]
+    fn test_merge_changes() {
+        let a = Address::with_last_byte(1);
+        let b = Address::with_last_byte(2);
+        let slot = B256::with_last_byte(1);
+
+        let mut pending = StateChanges::from([(
+            a,
+            AccountDiff {
+                balance: Some(U256::from(1)),
+                nonce: Some(1),
+                storage: BTreeMap::from([(slot, B256::with_last_byte(1))]),
+                ..Default::default()
+            },
+        )]);
+        merge_changes(
+            &mut pending,
+            StateChanges::from([
+                (
+                    a,
+                    AccountDiff {
+                        balance: Some(U256::from(2)),
+                        storage: BTreeMap::from([(slot, B256::with_last_byte(2))]),
+                        ..Default::default()
+                    },
+                ),
+                (b, AccountDiff { nonce: Some(3), ..Default::default() }),
+            ]),
+        );
+
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[&a].balance, Some(U256::from(2)));
+        assert_eq!(pending[&a].nonce, Some(1));
+        assert_eq!(pending[&a].storage[&slot], B256::with_last_byte(2));
+        assert_eq!(pending[&b].nonce, Some(3));
+    }
+}
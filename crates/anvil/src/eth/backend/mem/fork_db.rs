@@ -9,8 +9,8 @@ use alloy_primitives::{Address, B256, U256, U64};
 use alloy_rpc_types::BlockId;
 use foundry_evm::{
     backend::{DatabaseResult, RevertSnapshotAction, StateSnapshot},
-    fork::{database::ForkDbSnapshot, BlockchainDb},
-    revm::Database,
+    fork::{database::ForkDbSnapshot, BlockchainDb, SharedBackend},
+    revm::{db::CacheDB, Database},
 };
 
 pub use foundry_evm::fork::database::ForkedDatabase;
@@ -64,6 +64,7 @@ impl Db for ForkedDatabase {
             block: Some(at),
             accounts,
             best_block_number: Some(best_number),
+            history: None,
         }))
     }
 
@@ -116,11 +117,15 @@ impl MaybeFullDatabase for ForkDbSnapshot {
     fn init_from_snapshot(&mut self, snapshot: StateSnapshot) {
         self.snapshot = snapshot;
     }
+
+    fn maybe_as_fork_local_db(&self) -> Option<&CacheDB<SharedBackend>> {
+        Some(&self.local)
+    }
 }
 
 impl MaybeForkedDatabase for ForkedDatabase {
-    fn maybe_reset(&mut self, url: Option<String>, block_number: BlockId) -> Result<(), String> {
-        self.reset(url, block_number)
+    fn maybe_reset(&mut self, db: BlockchainDb, block_number: BlockId) -> Result<(), String> {
+        self.reset_with_db(db, block_number)
     }
 
     fn maybe_flush_cache(&self) -> Result<(), String> {
@@ -131,4 +136,9 @@ impl MaybeForkedDatabase for ForkedDatabase {
     fn maybe_inner(&self) -> Result<&BlockchainDb, String> {
         Ok(self.inner())
     }
+
+    fn maybe_reset_local_db(&mut self, local: CacheDB<SharedBackend>) -> Result<(), String> {
+        *self.database_mut() = local;
+        Ok(())
+    }
 }
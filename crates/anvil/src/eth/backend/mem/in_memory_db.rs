@@ -6,13 +6,16 @@ use crate::{
         StateDb,
     },
     mem::state::state_root,
-    revm::{db::DbAccount, primitives::AccountInfo},
+    revm::{
+        db::{CacheDB, DbAccount},
+        primitives::AccountInfo,
+    },
 };
 use alloy_primitives::{Address, B256, U256, U64};
 use alloy_rpc_types::BlockId;
 use foundry_evm::{
     backend::{DatabaseResult, StateSnapshot},
-    fork::BlockchainDb,
+    fork::{BlockchainDb, SharedBackend},
     hashbrown::HashMap,
 };
 
@@ -65,6 +68,7 @@ impl Db for MemDb {
             block: Some(at),
             accounts,
             best_block_number: Some(best_number),
+            history: None,
         }))
     }
 
@@ -117,7 +121,7 @@ impl MaybeFullDatabase for MemDb {
 }
 
 impl MaybeForkedDatabase for MemDb {
-    fn maybe_reset(&mut self, _url: Option<String>, _block_number: BlockId) -> Result<(), String> {
+    fn maybe_reset(&mut self, _db: BlockchainDb, _block_number: BlockId) -> Result<(), String> {
         Err("not supported".to_string())
     }
 
@@ -128,6 +132,10 @@ impl MaybeForkedDatabase for MemDb {
     fn maybe_inner(&self) -> Result<&BlockchainDb, String> {
         Err("not supported".to_string())
     }
+
+    fn maybe_reset_local_db(&mut self, _local: CacheDB<SharedBackend>) -> Result<(), String> {
+        Err("not supported".to_string())
+    }
 }
 
 #[cfg(test)]
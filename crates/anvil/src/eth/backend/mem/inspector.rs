@@ -1,13 +1,13 @@
 //! Anvil specific [`revm::Inspector`] implementation
 
 use crate::{eth::macros::node_info, revm::Database};
-use alloy_primitives::{Address, Log};
+use alloy_primitives::{address, b256, Address, Log, B256};
 use foundry_evm::{
     call_inspectors,
     decode::decode_console_logs,
     inspectors::{LogCollector, TracingInspector},
     revm::{
-        interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+        interpreter::{opcode, CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
         primitives::U256,
         EvmContext,
     },
@@ -39,6 +39,12 @@ impl Inspector {
         self
     }
 
+    /// Configures the `Tracer` [`revm::Inspector`] with the given config
+    pub fn with_tracing_config(mut self, config: TracingInspectorConfig) -> Self {
+        self.tracer = Some(TracingInspector::new(config));
+        self
+    }
+
     /// Enables steps recording for `Tracer`.
     pub fn with_steps_tracing(mut self) -> Self {
         self.tracer = Some(TracingInspector::new(TracingInspectorConfig::all()));
@@ -138,6 +144,156 @@ impl<DB: Database> revm::Inspector<DB> for Inspector {
 
 impl<DB: Database> InspectorExt<DB> for Inspector {}
 
+/// The address that emits the synthetic ETH transfer logs of `eth_simulateV1`
+pub const ETH_TRANSFER_LOG_ADDRESS: Address = address!("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee");
+
+/// `keccak256("Transfer(address,address,uint256)")`
+pub const TRANSFER_EVENT_SIGNATURE: B256 =
+    b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+
+/// A [`revm::Inspector`] that collects the logs of successful call frames in execution order.
+///
+/// If enabled, this also records an ERC20 style `Transfer` log emitted by
+/// [`ETH_TRANSFER_LOG_ADDRESS`] for every ETH value transfer, as `eth_simulateV1` does for
+/// `traceTransfers`.
+#[derive(Clone, Debug, Default)]
+pub struct SimulateInspector {
+    /// Whether to record ETH value transfers as logs
+    trace_transfers: bool,
+    /// All logs collected so far
+    pub logs: Vec<Log>,
+    /// The number of collected logs at the start of each active call frame
+    checkpoints: Vec<usize>,
+}
+
+// === impl SimulateInspector ===
+
+impl SimulateInspector {
+    pub fn new(trace_transfers: bool) -> Self {
+        Self { trace_transfers, ..Default::default() }
+    }
+
+    fn transfer_log(&self, from: Address, to: Address, value: U256) -> Option<Log> {
+        if !self.trace_transfers || value.is_zero() {
+            return None
+        }
+        Some(Log::new_unchecked(
+            ETH_TRANSFER_LOG_ADDRESS,
+            vec![TRANSFER_EVENT_SIGNATURE, from.into_word(), to.into_word()],
+            value.to_be_bytes_vec().into(),
+        ))
+    }
+
+    /// Discards all logs of the exited call frame if it failed
+    fn exit_frame(&mut self, success: bool) {
+        let checkpoint = self.checkpoints.pop().unwrap_or_default();
+        if !success {
+            self.logs.truncate(checkpoint);
+        }
+    }
+}
+
+impl<DB: Database> revm::Inspector<DB> for SimulateInspector {
+    #[inline]
+    fn log(&mut self, _ecx: &mut EvmContext<DB>, log: &Log) {
+        self.logs.push(log.clone());
+    }
+
+    #[inline]
+    fn call(&mut self, _ecx: &mut EvmContext<DB>, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.checkpoints.push(self.logs.len());
+        if let Some(value) = inputs.transfer_value() {
+            if let Some(log) = self.transfer_log(inputs.caller, inputs.target_address, value) {
+                self.logs.push(log);
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn call_end(
+        &mut self,
+        _ecx: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.exit_frame(outcome.result.is_ok());
+        outcome
+    }
+
+    #[inline]
+    fn create(
+        &mut self,
+        _ecx: &mut EvmContext<DB>,
+        _inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.checkpoints.push(self.logs.len());
+        None
+    }
+
+    #[inline]
+    fn create_end(
+        &mut self,
+        _ecx: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        // the address of the created contract is only known at this point, so the transfer log is
+        // inserted in front of the logs emitted by the constructor
+        if let Some(address) = outcome.address.filter(|_| outcome.result.is_ok()) {
+            if let Some(log) = self.transfer_log(inputs.caller, address, inputs.value) {
+                let checkpoint = self.checkpoints.last().copied().unwrap_or_default();
+                self.logs.insert(checkpoint, log);
+            }
+        }
+        self.exit_frame(outcome.result.is_ok());
+        outcome
+    }
+
+    #[inline]
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if let Some(log) = self.transfer_log(contract, target, value) {
+            self.logs.push(log);
+        }
+    }
+}
+
+impl<DB: Database> InspectorExt<DB> for SimulateInspector {}
+
+/// A [`revm::Inspector`] that records the storage slots of an account that are read via `SLOAD`,
+/// in the order they are read
+#[derive(Clone, Debug)]
+pub struct StorageReadInspector {
+    /// The account whose storage reads are recorded
+    address: Address,
+    /// All slots read so far
+    pub slots: Vec<U256>,
+}
+
+// === impl StorageReadInspector ===
+
+impl StorageReadInspector {
+    pub fn new(address: Address) -> Self {
+        Self { address, slots: Vec::new() }
+    }
+}
+
+impl<DB: Database> revm::Inspector<DB> for StorageReadInspector {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, _ecx: &mut EvmContext<DB>) {
+        if interp.current_opcode() != opcode::SLOAD ||
+            interp.contract.target_address != self.address
+        {
+            return
+        }
+        if let Ok(slot) = interp.stack.peek(0) {
+            self.slots.push(slot);
+        }
+    }
+}
+
+impl<DB: Database> InspectorExt<DB> for StorageReadInspector {}
+
 /// Prints all the logs
 #[inline]
 pub fn print_logs(logs: &[Log]) {
@@ -4,54 +4,79 @@ use crate::{
     config::PruneStateHistoryConfig,
     eth::{
         backend::{
+            block_builder::{build_block_transactions, BlockBuilder, FixedOrder},
+            bundle::{Bundle, BundlePool},
             cheats::CheatsManager,
-            db::{Db, MaybeFullDatabase, SerializableState},
-            executor::{ExecutedTransactions, TransactionExecutor},
+            db::{
+                Db, MaybeFullDatabase, SerializableHistory, SerializableState,
+                SerializableTransaction, StateDb,
+            },
+            executor::{effective_gas_price, ExecutedTransactions, TransactionExecutor},
             fork::ClientFork,
             genesis::GenesisConfig,
             mem::{
+                disk::{self, DiskChain, PersistedBlock, PersistedChain, StateChanges},
+                inspector::{SimulateInspector, StorageReadInspector},
                 state::{storage_root, trie_accounts},
                 storage::MinedTransactionReceipt,
             },
-            notifications::{NewBlockNotification, NewBlockNotifications},
+            notifications::{
+                NewBlockNotification, NewBlockNotifications, RemovedBlockNotification,
+                RemovedBlockNotifications,
+            },
             time::{utc_from_secs, TimeManager},
             validate::TransactionValidator,
         },
         error::{BlockchainError, ErrDetail, InvalidTransactionError},
         fees::{FeeDetails, FeeManager},
         macros::node_info,
-        pool::transactions::PoolTransaction,
+        pool::transactions::{to_marker, PoolTransaction},
         util::get_precompiles_for,
     },
+    evm::inject_eip7702,
     inject_precompiles,
     mem::{
         inspector::Inspector,
         storage::{BlockchainStorage, InMemoryBlockStates, MinedBlockOutcome},
     },
+    metrics::NodeMetrics,
     revm::{db::DatabaseRef, primitives::AccountInfo},
     NodeConfig, PrecompileFactory,
 };
-use alloy_consensus::{Header, Receipt, ReceiptWithBloom};
+use alloy_consensus::{Blob, Header, Receipt, ReceiptWithBloom};
 use alloy_eips::eip4844::MAX_BLOBS_PER_BLOCK;
-use alloy_primitives::{keccak256, Address, Bytes, TxHash, TxKind, B256, U256, U64};
+use alloy_primitives::{keccak256, Address, Bloom, Bytes, TxHash, TxKind, B256, U256, U64};
 use alloy_rpc_types::{
     request::TransactionRequest, serde_helpers::JsonStorageKey, state::StateOverride, AccessList,
-    Block as AlloyBlock, BlockId, BlockNumberOrTag as BlockNumber,
+    Block as AlloyBlock, BlockId, BlockNumberOrTag as BlockNumber, BlockOverrides,
     EIP1186AccountProofResponse as AccountProof, EIP1186StorageProof as StorageProof, Filter,
     FilteredParams, Header as AlloyHeader, Log, Transaction, TransactionReceipt, WithOtherFields,
 };
 use alloy_rpc_types_trace::{
-    geth::{DefaultFrame, GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace},
-    parity::LocalizedTransactionTrace,
+    filter::TraceFilter,
+    geth::{
+        FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
+        GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, NoopFrame, TraceResult,
+    },
+    parity::{
+        Action, LocalizedTransactionTrace, TraceOutput, TraceResults,
+        TraceResultsWithTransactionHash, TraceType, TransactionTrace,
+    },
 };
 use alloy_trie::{proof::ProofRetainer, HashBuilder, Nibbles};
 use anvil_core::{
     eth::{
-        block::{Block, BlockInfo},
+        beacon::{BeaconBlockHeader, BlobSidecar, SignedBeaconBlockHeader},
+        block::{Block, BlockInfo, PartialHeader},
+        bundle::{bundle_hash, CallBundleResponse, CallBundleResult},
+        simulate::{SimBlock, SimCallResult, SimulateError, SimulatePayload, SimulatedBlock},
+        subscription::{AccountDiff, StateDiff},
         transaction::{
-            DepositReceipt, MaybeImpersonatedTransaction, PendingTransaction, ReceiptResponse,
-            TransactionInfo, TypedReceipt, TypedTransaction,
+            eip7702::SignedAuthorization, optimism::DepositTransaction, DepositReceipt,
+            MaybeImpersonatedTransaction, PendingTransaction, ReceiptResponse, TransactionInfo,
+            TypedReceipt, TypedTransaction,
         },
+        trie,
         utils::meets_eip155,
     },
     types::{Forking, Index},
@@ -62,15 +87,16 @@ use foundry_evm::{
     backend::{DatabaseError, DatabaseResult, RevertSnapshotAction},
     constants::DEFAULT_CREATE2_DEPLOYER_RUNTIME_CODE,
     decode::RevertDecoder,
-    inspectors::AccessListInspector,
+    inspectors::{AccessListInspector, FourByteInspector},
     revm::{
-        db::CacheDB,
+        db::{AccountState, CacheDB},
         interpreter::InstructionResult,
         primitives::{
             BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, Output, SpecId,
             TransactTo, TxEnv, KECCAK_EMPTY,
         },
     },
+    traces::TracingInspectorConfig,
     utils::new_evm_with_inspector_ref,
     InspectorExt,
 };
@@ -81,10 +107,12 @@ use revm::{
     primitives::{
         calc_blob_gasprice, BlobExcessGasAndPrice, HashMap, OptimismFields, ResultAndState,
     },
+    DatabaseCommit,
 };
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashSet},
     io::{Read, Write},
+    path::PathBuf,
     sync::Arc,
     time::Duration,
 };
@@ -92,6 +120,7 @@ use storage::{Blockchain, MinedTransaction};
 use tokio::sync::RwLock as AsyncRwLock;
 
 pub mod cache;
+pub mod disk;
 pub mod fork_db;
 pub mod in_memory_db;
 pub mod inspector;
@@ -121,6 +150,30 @@ impl BlockRequest {
     }
 }
 
+/// The attributes of a block that is built via the engine API
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockAttributes {
+    pub timestamp: u64,
+    pub coinbase: Address,
+    pub prevrandao: B256,
+    pub parent_beacon_block_root: Option<B256>,
+}
+
+impl BlockAttributes {
+    /// Applies the attributes that affect the execution of the block
+    fn apply_to_env(&self, block: &mut BlockEnv) {
+        block.timestamp = U256::from(self.timestamp);
+        block.coinbase = self.coinbase;
+        block.prevrandao = Some(self.prevrandao);
+    }
+
+    /// Applies the attributes that are only recorded in the header of the block
+    fn apply_to_header(&self, header: &mut Header) {
+        header.mix_hash = self.prevrandao;
+        header.parent_beacon_block_root = self.parent_beacon_block_root;
+    }
+}
+
 /// Gives access to the [revm::Database]
 #[derive(Clone)]
 pub struct Backend {
@@ -161,6 +214,8 @@ pub struct Backend {
     genesis: GenesisConfig,
     /// listeners for new blocks that get notified when a new block was imported
     new_block_listeners: Arc<Mutex<Vec<UnboundedSender<NewBlockNotification>>>>,
+    /// listeners for removed blocks that get notified when blocks were removed from the chain
+    removed_block_listeners: Arc<Mutex<Vec<UnboundedSender<RemovedBlockNotification>>>>,
     /// keeps track of active snapshots at a specific block
     active_snapshots: Arc<Mutex<HashMap<U256, (u64, B256)>>>,
     enable_steps_tracing: bool,
@@ -173,6 +228,18 @@ pub struct Backend {
     slots_in_an_epoch: u64,
     /// Precompiles to inject to the EVM.
     precompile_factory: Option<Arc<dyn PrecompileFactory>>,
+    /// The on-disk chain every mined block is written to, if enabled
+    disk_chain: Arc<Mutex<Option<DiskChain>>>,
+    /// Determines the order of the transactions of new blocks
+    block_builder: Arc<RwLock<Arc<dyn BlockBuilder>>>,
+    /// The order of the transactions of the next mined block, overrides the `block_builder` once
+    next_block_transactions: Arc<Mutex<Option<FixedOrder>>>,
+    /// Bundles submitted via `eth_sendBundle` that target upcoming blocks
+    bundles: Arc<Mutex<BundlePool>>,
+    /// Relayed op-stack deposits that are included at the top of the next block
+    deposits: Arc<Mutex<Vec<PendingTransaction>>>,
+    /// Collected metrics, if enabled
+    metrics: Option<Arc<NodeMetrics>>,
 }
 
 impl Backend {
@@ -219,9 +286,16 @@ impl Backend {
             Default::default()
         };
 
-        let (slots_in_an_epoch, precompile_factory) = {
+        let (slots_in_an_epoch, precompile_factory, block_builder, metrics, time) = {
             let cfg = node_config.read().await;
-            (cfg.slots_in_an_epoch, cfg.precompile_factory.clone())
+            let block_builder =
+                cfg.block_builder.clone().unwrap_or_else(|| cfg.transaction_order.block_builder());
+            let metrics = cfg.enable_metrics.then(Default::default);
+            let time = match cfg.shared_clock.clone() {
+                Some(clock) => TimeManager::with_clock(start_timestamp, clock),
+                None => TimeManager::new(start_timestamp),
+            };
+            (cfg.slots_in_an_epoch, cfg.precompile_factory.clone(), block_builder, metrics, time)
         };
 
         let backend = Self {
@@ -230,9 +304,10 @@ impl Backend {
             states: Arc::new(RwLock::new(states)),
             env,
             fork,
-            time: TimeManager::new(start_timestamp),
+            time,
             cheats: Default::default(),
             new_block_listeners: Default::default(),
+            removed_block_listeners: Default::default(),
             fees,
             genesis,
             active_snapshots: Arc::new(Mutex::new(Default::default())),
@@ -242,6 +317,12 @@ impl Backend {
             node_config,
             slots_in_an_epoch,
             precompile_factory,
+            disk_chain: Default::default(),
+            block_builder: Arc::new(RwLock::new(block_builder)),
+            next_block_transactions: Default::default(),
+            bundles: Default::default(),
+            deposits: Default::default(),
+            metrics,
         };
 
         if let Some(interval_block_time) = automine_block_time {
@@ -352,6 +433,11 @@ impl Backend {
         self.fork.read().clone()
     }
 
+    /// Returns the collected metrics, if enabled
+    pub fn metrics(&self) -> Option<&Arc<NodeMetrics>> {
+        self.metrics.as_ref()
+    }
+
     /// Returns the database
     pub fn get_db(&self) -> &Arc<AsyncRwLock<Box<dyn Db>>> {
         &self.db
@@ -373,6 +459,13 @@ impl Backend {
 
     /// Resets the fork to a fresh state
     pub async fn reset_fork(&self, forking: Forking) -> Result<(), BlockchainError> {
+        if self.disk_chain.lock().is_some() {
+            return Err(RpcError::invalid_params(
+                "forking is not supported while the chain is persisted",
+            )
+            .into())
+        }
+
         if !self.is_fork() {
             if let Some(eth_rpc_url) = forking.clone().json_rpc_url {
                 let mut env = self.env.read().clone();
@@ -453,12 +546,10 @@ impl Backend {
             self.states.write().clear();
 
             // insert back all genesis accounts, by reusing cached `AccountInfo`s we don't need to
-            // fetch the data via RPC again
+            // fetch the data via RPC again, the database was already reset to a fresh state of the
+            // fork block by `ClientFork::reset`
             let mut db = self.db.write().await;
 
-            // clear database
-            db.clear();
-
             let fork_genesis_infos = self.genesis.fork_genesis_account_infos.lock();
             for (address, info) in
                 self.genesis.accounts.iter().copied().zip(fork_genesis_infos.iter().cloned())
@@ -542,17 +633,36 @@ impl Backend {
 
     /// Sets the nonce of the given address
     pub async fn set_nonce(&self, address: Address, nonce: U256) -> DatabaseResult<()> {
-        self.db.write().await.set_nonce(address, nonce.try_into().unwrap_or(u64::MAX))
+        let nonce = nonce.try_into().unwrap_or(u64::MAX);
+        self.db.write().await.set_nonce(address, nonce)?;
+        self.persist_pending_changes(|| {
+            StateChanges::from([(
+                address,
+                AccountDiff { nonce: Some(nonce), ..Default::default() },
+            )])
+        });
+        Ok(())
     }
 
     /// Sets the balance of the given address
     pub async fn set_balance(&self, address: Address, balance: U256) -> DatabaseResult<()> {
-        self.db.write().await.set_balance(address, balance)
+        self.db.write().await.set_balance(address, balance)?;
+        self.persist_pending_changes(|| {
+            StateChanges::from([(
+                address,
+                AccountDiff { balance: Some(balance), ..Default::default() },
+            )])
+        });
+        Ok(())
     }
 
     /// Sets the code of the given address
     pub async fn set_code(&self, address: Address, code: Bytes) -> DatabaseResult<()> {
-        self.db.write().await.set_code(address, code.0.into())
+        self.db.write().await.set_code(address, code.clone())?;
+        self.persist_pending_changes(|| {
+            StateChanges::from([(address, AccountDiff { code: Some(code), ..Default::default() })])
+        });
+        Ok(())
     }
 
     /// Sets the value for the given slot of the given address
@@ -562,7 +672,14 @@ impl Backend {
         slot: U256,
         val: B256,
     ) -> DatabaseResult<()> {
-        self.db.write().await.set_storage_at(address, slot, U256::from_be_bytes(val.0))
+        self.db.write().await.set_storage_at(address, slot, U256::from_be_bytes(val.0))?;
+        self.persist_pending_changes(|| {
+            StateChanges::from([(
+                address,
+                AccountDiff { storage: BTreeMap::from([(slot.into(), val)]), ..Default::default() },
+            )])
+        });
+        Ok(())
     }
 
     /// Returns the configured specid
@@ -590,6 +707,11 @@ impl Backend {
         (self.spec_id() as u8) >= (SpecId::CANCUN as u8)
     }
 
+    /// Returns true for post Prague
+    pub fn is_eip7702(&self) -> bool {
+        (self.spec_id() as u8) >= (SpecId::PRAGUE as u8)
+    }
+
     /// Returns true if op-stack deposits are active
     pub fn is_optimism(&self) -> bool {
         self.env.read().handler_cfg.is_optimism
@@ -618,6 +740,13 @@ impl Backend {
         Err(BlockchainError::EIP4844TransactionUnsupportedAtHardfork)
     }
 
+    pub fn ensure_eip7702_active(&self) -> Result<(), BlockchainError> {
+        if self.is_eip7702() {
+            return Ok(())
+        }
+        Err(BlockchainError::EIP7702TransactionUnsupportedAtHardfork)
+    }
+
     /// Returns an error if op-stack deposits are not active
     pub fn ensure_op_deposits_active(&self) -> Result<(), BlockchainError> {
         if self.is_optimism() {
@@ -686,6 +815,9 @@ impl Backend {
         let id = self.db.write().await.snapshot();
         trace!(target: "backend", "creating snapshot {} at {}", id, num);
         self.active_snapshots.lock().insert(id, (num, hash));
+        if let Some(disk) = self.disk_chain.lock().as_mut() {
+            disk.snapshot(id);
+        }
         id
     }
 
@@ -695,23 +827,13 @@ impl Backend {
         if let Some((num, hash)) = block {
             let best_block_hash = {
                 // revert the storage that's newer than the snapshot
-                let current_height = self.best_number();
-                let mut storage = self.blockchain.storage.write();
-
-                for n in ((num + 1)..=current_height).rev() {
-                    trace!(target: "backend", "reverting block {}", n);
-                    let n = U64::from(n);
-                    if let Some(hash) = storage.hashes.remove(&n) {
-                        if let Some(block) = storage.blocks.remove(&hash) {
-                            for tx in block.transactions {
-                                let _ = storage.transactions.remove(&tx.hash());
-                            }
-                        }
+                self.blockchain.storage.write().unwind_to(num, hash);
+                self.unwind_disk_chain(num);
+                if let Some(disk) = self.disk_chain.lock().as_mut() {
+                    if let Err(err) = disk.revert_snapshot(id) {
+                        error!(target: "backend", %err, "failed to persist state changes");
                     }
                 }
-
-                storage.best_number = U64::from(num);
-                storage.best_hash = hash;
                 hash
             };
             let block =
@@ -733,6 +855,9 @@ impl Backend {
                 basefee: env.block.basefee,
                 ..Default::default()
             };
+        } else {
+            // the snapshot is unknown or its block was removed from the chain
+            return Ok(false)
         }
         Ok(self.db.write().await.revert(id, RevertSnapshotAction::RevertRemove))
     }
@@ -741,8 +866,147 @@ impl Backend {
         self.active_snapshots.lock().clone().into_iter().collect()
     }
 
+    /// Removes the last `depth` blocks from the chain and restores the state of the block that
+    /// becomes the new best block.
+    ///
+    /// Listeners are notified about every removed block, so that the logs emitted in those blocks
+    /// can be reported as removed.
+    ///
+    /// Returns the transactions of the removed blocks, in the order they were mined.
+    pub async fn rollback(&self, depth: u64) -> Result<Vec<PendingTransaction>, BlockchainError> {
+        if depth == 0 {
+            return Ok(Vec::new())
+        }
+
+        let best_number = self.best_number();
+        let Some(number) = best_number.checked_sub(depth) else {
+            return Err(RpcError::invalid_params(format!(
+                "depth {depth} exceeds the current block height {best_number}"
+            ))
+            .into())
+        };
+        let fork_block_number = self.get_fork().map(|fork| fork.block_number());
+        if let Some(fork_block_number) = fork_block_number.filter(|n| number < *n) {
+            return Err(RpcError::invalid_params(format!(
+                "cannot roll back past the fork block {fork_block_number}"
+            ))
+            .into())
+        }
+        // the fork block isn't stored locally
+        let hash =
+            self.blockchain.hash(BlockId::number(number)).ok_or(BlockchainError::BlockNotFound)?;
+        let block = self.block_by_hash(hash).await?.ok_or(BlockchainError::BlockNotFound)?;
+
+        if self.is_fork() {
+            self.restore_fork_state(number, hash).await?;
+        } else {
+            self.restore_state(number, hash).await?;
+        }
+
+        let removed = self.blockchain.storage.write().unwind_to(number, hash);
+        self.unwind_disk_chain(number);
+        // snapshots of removed blocks can no longer be reverted to
+        self.active_snapshots.lock().retain(|_, (snapshot_number, _)| *snapshot_number <= number);
+
+        self.time.reset(block.header.timestamp);
+        {
+            let mut env = self.env.write();
+            env.block = BlockEnv {
+                number: U256::from(number),
+                timestamp: U256::from(block.header.timestamp),
+                difficulty: block.header.difficulty,
+                // ensures prevrandao is set
+                prevrandao: Some(block.header.mix_hash.unwrap_or_default()),
+                gas_limit: U256::from(block.header.gas_limit),
+                // Keep previous `coinbase` and `basefee` value
+                coinbase: env.block.coinbase,
+                basefee: env.block.basefee,
+                ..Default::default()
+            };
+        }
+
+        let mut transactions = Vec::new();
+        for (block, receipts) in removed.into_iter().rev() {
+            transactions.extend(
+                block
+                    .transactions
+                    .iter()
+                    .filter(|tx| !matches!(tx.transaction, TypedTransaction::Deposit(_)))
+                    .filter_map(|tx| PendingTransaction::from_maybe_impersonated(tx.clone()).ok()),
+            );
+            self.notify_on_removed_block(block, receipts);
+        }
+
+        Ok(transactions)
+    }
+
+    /// Replaces the current state with the state of the block `number`
+    async fn restore_state(&self, number: u64, hash: B256) -> Result<(), BlockchainError> {
+        // collect the state of the block we roll back to
+        let accounts = {
+            let mut states = self.states.write();
+            let state = states.get(&hash).ok_or(BlockchainError::DataUnavailable)?;
+            let accounts = state.maybe_as_full_db().ok_or(BlockchainError::DataUnavailable)?;
+            accounts
+                .iter()
+                .filter(|(_, account)| account.account_state != AccountState::NotExisting)
+                .map(|(address, account)| {
+                    let mut info = account.info.clone();
+                    if info.code.is_none() && info.code_hash != KECCAK_EMPTY {
+                        info.code = Some(state.code_by_hash_ref(info.code_hash)?);
+                    }
+                    Ok((*address, info, account.storage.clone()))
+                })
+                .collect::<DatabaseResult<Vec<_>>>()?
+        };
+
+        let mut db = self.db.write().await;
+        let block_hashes = db.clear_into_snapshot().block_hashes;
+        for (address, info, storage) in accounts {
+            db.insert_account(address, info);
+            for (slot, value) in storage {
+                db.set_storage_at(address, slot, value)?;
+            }
+        }
+        // only keep the hashes of the blocks that are still part of the chain
+        for (block_number, block_hash) in block_hashes {
+            if block_number <= U256::from(number) {
+                db.insert_block_hash(block_number, block_hash);
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the local changes on top of the forked state with the changes of the block
+    /// `number`
+    async fn restore_fork_state(&self, number: u64, hash: B256) -> Result<(), BlockchainError> {
+        let local = {
+            let mut states = self.states.write();
+            let state = states.get(&hash).ok_or(BlockchainError::DataUnavailable)?;
+            state.maybe_as_fork_local_db().ok_or(BlockchainError::DataUnavailable)?.clone()
+        };
+
+        let mut db = self.db.write().await;
+        {
+            let inner = db.maybe_inner().map_err(BlockchainError::Internal)?;
+            // only keep the hashes of the blocks that are still part of the chain
+            inner
+                .block_hashes()
+                .write()
+                .retain(|block_number, _| *block_number <= U256::from(number));
+        }
+        db.maybe_reset_local_db(local).map_err(BlockchainError::Internal)
+    }
+
     /// Get the current state.
     pub async fn serialized_state(&self) -> Result<SerializableState, BlockchainError> {
+        let mut state = self.serialized_accounts().await?;
+        state.history = Some(self.blockchain.storage.read().serialized_history());
+        Ok(state)
+    }
+
+    /// Returns the accounts of the current state, without the block history
+    async fn serialized_accounts(&self) -> Result<SerializableState, BlockchainError> {
         let at = self.env.read().block.clone();
         let best_number = self.blockchain.storage.read().best_number;
         let state = self.db.read().await.dump_state(at, best_number)?;
@@ -763,7 +1027,11 @@ impl Backend {
     }
 
     /// Apply [SerializableState] data to the backend storage.
-    pub async fn load_state(&self, state: SerializableState) -> Result<bool, BlockchainError> {
+    pub async fn load_state(&self, mut state: SerializableState) -> Result<bool, BlockchainError> {
+        if let Some(history) = state.history.take() {
+            self.load_history(history).await?;
+        }
+
         // reset the block env
         if let Some(block) = state.block.clone() {
             self.env.write().block = block.clone();
@@ -774,14 +1042,64 @@ impl Backend {
                 state.best_block_number.unwrap_or(block.number.to::<U64>());
         }
 
-        if !self.db.write().await.load_state(state)? {
-            Err(RpcError::invalid_params(
+        let mut changes = if self.disk_chain.lock().is_some() {
+            state
+                .accounts
+                .iter()
+                .map(|(address, account)| (*address, disk::account_changes(account.clone())))
+                .collect::<StateChanges>()
+        } else {
+            StateChanges::new()
+        };
+
+        let mut db = self.db.write().await;
+        if !db.load_state(state)? {
+            return Err(RpcError::invalid_params(
                 "Loading state not supported with the current configuration",
             )
             .into())
-        } else {
-            Ok(true)
         }
+        // the loaded nonce is the highest of the existing and the loaded one
+        for (address, account) in &mut changes {
+            account.nonce = Some(db.basic_ref(*address)?.unwrap_or_default().nonce);
+        }
+        drop(db);
+
+        self.persist_pending_changes(|| changes);
+        Ok(true)
+    }
+
+    /// Restores the blocks, transactions and receipts of a dumped chain
+    async fn load_history(&self, history: SerializableHistory) -> Result<(), BlockchainError> {
+        if history.version != SerializableHistory::VERSION {
+            return Err(RpcError::invalid_params(format!(
+                "unsupported state history version {}, expected {}",
+                history.version,
+                SerializableHistory::VERSION
+            ))
+            .into())
+        }
+
+        let hashes = history
+            .blocks
+            .iter()
+            .map(|block| (U256::from(block.header.number), block.header.hash_slow()))
+            .collect::<Vec<_>>();
+
+        {
+            let mut db = self.db.write().await;
+            for (number, hash) in hashes {
+                db.insert_block_hash(number, hash);
+            }
+        }
+
+        let mut storage = self.blockchain.storage.write();
+        storage.load_history(history);
+        // continue mining on top of the restored best block
+        if let Some(block) = storage.blocks.get(&storage.best_hash) {
+            self.time.reset(block.header.timestamp);
+        }
+        Ok(())
     }
 
     /// Deserialize and add all chain data to the backend storage
@@ -813,11 +1131,13 @@ impl Backend {
         env
     }
 
-    /// Creates an EVM instance with optionally injected precompiles.
+    /// Creates an EVM instance with optionally injected precompiles that applies the given EIP-7702
+    /// authorizations of the transaction.
     fn new_evm_with_inspector_ref<DB, I>(
         &self,
         db: DB,
         env: EnvWithHandlerCfg,
+        authorizations: &[SignedAuthorization],
         inspector: I,
     ) -> revm::Evm<'_, I, WrapDatabaseRef<DB>>
     where
@@ -828,6 +1148,7 @@ impl Backend {
         if let Some(ref factory) = self.precompile_factory {
             inject_precompiles(&mut evm, factory.precompiles());
         }
+        inject_eip7702(&mut evm, authorizations.to_vec());
         evm
     }
 
@@ -849,7 +1170,13 @@ impl Backend {
 
         let db = self.db.read().await;
         let mut inspector = Inspector::default();
-        let mut evm = self.new_evm_with_inspector_ref(&*db, env, &mut inspector);
+        let authorizations = tx.pending_transaction.transaction.authorization_list();
+        let mut evm = self.new_evm_with_inspector_ref(
+            &*db,
+            env,
+            authorizations.unwrap_or_default(),
+            &mut inspector,
+        );
         let ResultAndState { result, state } = evm.transact()?;
         let (exit_reason, gas_used, out, logs) = match result {
             ExecutionResult::Success { reason, gas_used, logs, output, .. } => {
@@ -876,7 +1203,8 @@ impl Backend {
 
     /// Creates the pending block
     ///
-    /// This will execute all transaction in the order they come but will not mine the block
+    /// This will execute all transaction in the order of the block builder but will not mine the
+    /// block
     pub async fn with_pending_block<F, T>(
         &self,
         pool_transactions: Vec<Arc<PoolTransaction>>,
@@ -885,6 +1213,7 @@ impl Backend {
     where
         F: FnOnce(Box<dyn MaybeFullDatabase + '_>, BlockInfo) -> T,
     {
+        let pool_transactions = self.order_block_transactions(pool_transactions, false);
         let db = self.db.read().await;
         let env = self.next_env();
 
@@ -904,6 +1233,7 @@ impl Backend {
             blob_gas_used: 0,
             enable_steps_tracing: self.enable_steps_tracing,
             precompile_factory: self.precompile_factory.clone(),
+            prestate: Default::default(),
         };
 
         // create a new pending block
@@ -913,22 +1243,189 @@ impl Backend {
 
     /// Mines a new block and stores it.
     ///
-    /// this will execute all transaction in the order they come in and return all the markers they
-    /// provide.
+    /// this will execute all transaction in the order of the block builder and return all the
+    /// markers they provide.
     pub async fn mine_block(
         &self,
         pool_transactions: Vec<Arc<PoolTransaction>>,
     ) -> MinedBlockOutcome {
-        self.do_mine_block(pool_transactions).await
+        let pool_transactions = self.order_block_transactions(pool_transactions, true);
+        self.do_mine_block(pool_transactions, None).await
+    }
+
+    /// Builds a block with the given attributes on top of the best block, without mining it.
+    ///
+    /// The transactions are executed in the given order.
+    pub async fn build_block(
+        &self,
+        transactions: Vec<Arc<PoolTransaction>>,
+        attributes: &BlockAttributes,
+    ) -> ExecutedTransactions {
+        let mut env = self.env.read().clone();
+        if env.block.basefee.is_zero() {
+            env.cfg.disable_base_fee = true;
+        }
+        env.block.number = env.block.number.saturating_add(U256::from(1));
+        env.block.basefee = U256::from(self.base_fee());
+        env.block.blob_excess_gas_and_price = self.excess_blob_gas_and_price();
+        attributes.apply_to_env(&mut env.block);
+
+        let parent_hash = self.blockchain.storage.read().best_hash;
+        let mut db = self.db.write().await;
+        // execute on the actual state, so that the state root matches the one of the mined block
+        let snapshot = db.snapshot();
+        let executor = TransactionExecutor {
+            db: &mut *db,
+            validator: self,
+            pending: transactions.into_iter(),
+            block_env: env.block.clone(),
+            cfg_env: CfgEnvWithHandlerCfg::new(env.cfg.clone(), env.handler_cfg),
+            parent_hash,
+            gas_used: 0,
+            blob_gas_used: 0,
+            enable_steps_tracing: self.enable_steps_tracing,
+            precompile_factory: self.precompile_factory.clone(),
+            prestate: Default::default(),
+        };
+        let mut executed = executor.execute();
+        db.revert(snapshot, RevertSnapshotAction::RevertRemove);
+
+        attributes.apply_to_header(&mut executed.block.block.header);
+        executed
+    }
+
+    /// Builds a block with the given attributes on top of the given parent block, without mining it
+    /// or changing the best block.
+    ///
+    /// The transactions are executed in the given order. Returns `None` if the parent block or its
+    /// state is not known.
+    pub async fn build_block_on(
+        &self,
+        parent_hash: B256,
+        transactions: Vec<Arc<PoolTransaction>>,
+        attributes: &BlockAttributes,
+    ) -> Option<ExecutedTransactions> {
+        if parent_hash == self.best_hash() {
+            return Some(self.build_block(transactions, attributes).await)
+        }
+        let parent = self.get_block_by_hash(parent_hash)?.header;
+
+        let mut env = self.env.read().clone();
+        if env.block.basefee.is_zero() {
+            env.cfg.disable_base_fee = true;
+        }
+        env.block.number = U256::from(parent.number.saturating_add(1));
+        env.block.basefee = U256::from(self.fees.get_next_block_base_fee_per_gas(
+            parent.gas_used,
+            parent.gas_limit,
+            parent.base_fee_per_gas.unwrap_or_default(),
+        ));
+        env.block.blob_excess_gas_and_price = self.excess_blob_gas_and_price().map(|_| {
+            BlobExcessGasAndPrice::new(self.fees.get_next_block_blob_excess_gas(
+                parent.excess_blob_gas.unwrap_or_default(),
+                parent.blob_gas_used.unwrap_or_default(),
+            ))
+        });
+        attributes.apply_to_env(&mut env.block);
+
+        let mut states = self.states.write();
+        let state = states.get(&parent_hash)?;
+        let mut cache_db = CacheDB::new(state);
+        let executor = TransactionExecutor {
+            db: &mut cache_db,
+            validator: self,
+            pending: transactions.into_iter(),
+            block_env: env.block.clone(),
+            cfg_env: CfgEnvWithHandlerCfg::new(env.cfg.clone(), env.handler_cfg),
+            parent_hash,
+            gas_used: 0,
+            blob_gas_used: 0,
+            enable_steps_tracing: self.enable_steps_tracing,
+            precompile_factory: self.precompile_factory.clone(),
+            prestate: Default::default(),
+        };
+        let mut executed = executor.execute();
+
+        // the cache only holds the accounts the block touched, the state root is computed from the
+        // full state of the parent with the changes of the block applied
+        if let Some(accounts) = state.maybe_as_full_db() {
+            let mut accounts = accounts.clone();
+            for (address, account) in cache_db.accounts {
+                match account.account_state {
+                    AccountState::NotExisting | AccountState::StorageCleared => {
+                        accounts.insert(address, account);
+                    }
+                    _ => {
+                        let entry = accounts.entry(address).or_default();
+                        entry.info = account.info;
+                        entry.storage.extend(account.storage);
+                    }
+                }
+            }
+            executed.block.block.header.state_root = state::state_root(&accounts);
+        }
+
+        attributes.apply_to_header(&mut executed.block.block.header);
+        Some(executed)
+    }
+
+    /// Mines a block with the given attributes that includes exactly the given transactions, in the
+    /// given order.
+    ///
+    /// Fails if the timestamp of the block is not greater than the timestamp of the best block.
+    pub async fn mine_block_with_attributes(
+        &self,
+        transactions: Vec<Arc<PoolTransaction>>,
+        attributes: &BlockAttributes,
+    ) -> Result<MinedBlockOutcome, BlockchainError> {
+        self.time.set_next_block_timestamp(attributes.timestamp)?;
+        Ok(self.do_mine_block(transactions, Some(attributes)).await)
     }
 
+    /// Orders the transactions of a new block, the transactions of every sender stay in nonce
+    /// order.
+    ///
+    /// If `is_mined` is true, the order set via [Self::set_next_block_transactions] is consumed.
+    pub fn order_block_transactions(
+        &self,
+        pool_transactions: Vec<Arc<PoolTransaction>>,
+        is_mined: bool,
+    ) -> Vec<Arc<PoolTransaction>> {
+        let next_block_transactions = if is_mined {
+            self.next_block_transactions.lock().take()
+        } else {
+            self.next_block_transactions.lock().clone()
+        };
+        if let Some(order) = next_block_transactions {
+            return build_block_transactions(&order, pool_transactions)
+        }
+        let builder = self.block_builder.read().clone();
+        build_block_transactions(&*builder, pool_transactions)
+    }
+
+    /// Sets the block builder that determines the order of the transactions of new blocks
+    pub fn set_block_builder(&self, builder: Arc<dyn BlockBuilder>) {
+        *self.block_builder.write() = builder;
+    }
+
+    /// Executes the given transactions first and in the given order in the next mined block,
+    /// followed by all other transactions
+    pub fn set_next_block_transactions(&self, hashes: Vec<TxHash>) {
+        *self.next_block_transactions.lock() = Some(FixedOrder { hashes });
+    }
+
+    /// Mines a new block with the given transactions.
+    ///
+    /// Blocks with attributes include exactly the given transactions, otherwise relayed deposits
+    /// and bundles are included as well.
     async fn do_mine_block(
         &self,
         pool_transactions: Vec<Arc<PoolTransaction>>,
+        attributes: Option<&BlockAttributes>,
     ) -> MinedBlockOutcome {
         trace!(target: "backend", "creating new block with {} transactions", pool_transactions.len());
 
-        let (outcome, header, block_hash) = {
+        let (outcome, header, block_hash, state_diff) = {
             let current_base_fee = self.base_fee();
             let current_excess_blob_gas_and_price = self.excess_blob_gas_and_price();
 
@@ -945,6 +1442,10 @@ impl Backend {
             env.block.basefee = U256::from(current_base_fee);
             env.block.blob_excess_gas_and_price = current_excess_blob_gas_and_price;
             env.block.timestamp = U256::from(self.time.next_timestamp());
+            let coinbase = env.block.coinbase;
+            if let Some(attributes) = attributes {
+                attributes.apply_to_env(&mut env.block);
+            }
 
             let best_hash = self.blockchain.storage.read().best_hash;
 
@@ -956,10 +1457,20 @@ impl Backend {
 
             let (executed_tx, block_hash) = {
                 let mut db = self.db.write().await;
+
+                // relayed deposits go first, followed by the bundles that target this block, each
+                // bundle as a whole or not at all
+                let mut transactions = Vec::new();
+                if attributes.is_none() {
+                    transactions.extend(self.deposit_transactions());
+                    transactions.extend(self.bundle_transactions(&*db, &env));
+                }
+                transactions.extend(pool_transactions);
+
                 let executor = TransactionExecutor {
                     db: &mut *db,
                     validator: self,
-                    pending: pool_transactions.into_iter(),
+                    pending: transactions.into_iter(),
                     block_env: env.block.clone(),
                     cfg_env: CfgEnvWithHandlerCfg::new(env.cfg.clone(), env.handler_cfg),
                     parent_hash: best_hash,
@@ -967,8 +1478,12 @@ impl Backend {
                     blob_gas_used: 0,
                     enable_steps_tracing: self.enable_steps_tracing,
                     precompile_factory: self.precompile_factory.clone(),
+                    prestate: Default::default(),
                 };
-                let executed_tx = executor.execute();
+                let mut executed_tx = executor.execute();
+                if let Some(attributes) = attributes {
+                    attributes.apply_to_header(&mut executed_tx.block.block.header);
+                }
 
                 // we also need to update the new blockhash in the db itself
                 let block_hash = executed_tx.block.block.header.hash_slow();
@@ -978,7 +1493,7 @@ impl Backend {
             };
 
             // create the new block with the current timestamp
-            let ExecutedTransactions { block, included, invalid } = executed_tx;
+            let ExecutedTransactions { block, included, invalid, state_diff } = executed_tx;
             let BlockInfo { block, transactions, receipts } = block;
 
             let mut storage = self.blockchain.storage.write();
@@ -1006,6 +1521,10 @@ impl Backend {
             storage.blocks.insert(block_hash, block);
             storage.hashes.insert(block_number, block_hash);
 
+            if let Some(metrics) = &self.metrics {
+                metrics.record_block(header.gas_used as u64);
+            }
+
             node_info!("");
             // insert all transactions
             for (info, receipt) in transactions.into_iter().zip(receipts) {
@@ -1045,6 +1564,8 @@ impl Backend {
 
             // we intentionally set the difficulty to `0` for newer blocks
             env.block.difficulty = U256::from(0);
+            // the fee recipient of a block with attributes only applies to that block
+            env.block.coinbase = coinbase;
 
             // update env with new values
             *self.env.write() = env;
@@ -1056,8 +1577,13 @@ impl Backend {
             node_info!("    Block Time: {:?}\n", timestamp.to_rfc2822());
 
             let outcome = MinedBlockOutcome { block_number, included, invalid };
+            let state_diff = StateDiff {
+                block_number: block_number.to::<u64>(),
+                block_hash,
+                accounts: state_diff,
+            };
 
-            (outcome, header, block_hash)
+            (outcome, header, block_hash, state_diff)
         };
         let next_block_base_fee = self.fees.get_next_block_base_fee_per_gas(
             header.gas_used,
@@ -1074,12 +1600,176 @@ impl Backend {
         self.fees
             .set_blob_excess_gas_and_price(BlobExcessGasAndPrice::new(next_block_excess_blob_gas));
 
+        self.persist_block(block_hash, &state_diff.accounts);
+
         // notify all listeners
-        self.notify_on_new_block(header, block_hash);
+        self.notify_on_new_block(header, block_hash, state_diff);
 
         outcome
     }
 
+    /// Persists the chain in the given directory, every mined block is written to it.
+    ///
+    /// If the directory already contains a chain, the backend is restored to that chain, otherwise
+    /// the current chain is written to it.
+    pub async fn open_disk_chain(&self, path: impl Into<PathBuf>) -> Result<(), BlockchainError> {
+        if self.is_fork() {
+            return Err(RpcError::invalid_params(
+                "persisting the chain is not supported in forking mode",
+            )
+            .into())
+        }
+
+        let mut disk =
+            DiskChain::open(path).map_err(|err| BlockchainError::Message(err.to_string()))?;
+        let chain = disk.load().map_err(|err| BlockchainError::Message(err.to_string()))?;
+        *self.disk_chain.lock() = Some(disk);
+
+        if let Some(chain) = chain {
+            return self.restore_chain(chain).await
+        }
+
+        let hashes = {
+            let storage = self.blockchain.storage.read();
+            let mut hashes = storage.hashes.iter().map(|(n, hash)| (*n, *hash)).collect::<Vec<_>>();
+            hashes.sort_unstable();
+            hashes
+        };
+        // the current state is written once, with the first block
+        let mut state_changes = self
+            .serialized_accounts()
+            .await?
+            .accounts
+            .into_iter()
+            .map(|(address, account)| (address, disk::account_changes(account)))
+            .collect::<StateChanges>();
+        for (_, hash) in hashes {
+            self.persist_block(hash, &std::mem::take(&mut state_changes));
+        }
+        Ok(())
+    }
+
+    /// Writes changes that were made to the state outside of a block to the on-disk chain, if
+    /// enabled
+    fn persist_pending_changes(&self, changes: impl FnOnce() -> StateChanges) {
+        if let Some(disk) = self.disk_chain.lock().as_mut() {
+            if let Err(err) = disk.write_pending(changes()) {
+                error!(target: "backend", %err, "failed to persist state changes");
+            }
+        }
+    }
+
+    /// Writes the given block, its transactions and the changes it made to the state to the
+    /// on-disk chain, if enabled
+    fn persist_block(&self, hash: B256, state_changes: &StateChanges) {
+        let mut disk_chain = self.disk_chain.lock();
+        let Some(disk) = disk_chain.as_mut() else { return };
+        let (block, transactions, total_difficulty) = {
+            let storage = self.blockchain.storage.read();
+            let Some(block) = storage.blocks.get(&hash).cloned() else { return };
+            let transactions = block
+                .transactions
+                .iter()
+                .filter_map(|tx| storage.transactions.get(&tx.hash()).cloned())
+                .map(SerializableTransaction::from)
+                .collect::<Vec<_>>();
+            (block, transactions, storage.total_difficulty)
+        };
+        if let Err(err) =
+            disk.write_block(block.into(), transactions, total_difficulty, state_changes.clone())
+        {
+            error!(target: "backend", %err, ?hash, "failed to persist block");
+        }
+    }
+
+    /// Removes all blocks above the given block number from the on-disk chain, if enabled
+    fn unwind_disk_chain(&self, number: u64) {
+        if let Some(disk) = self.disk_chain.lock().as_mut() {
+            if let Err(err) = disk.unwind_to(number) {
+                error!(target: "backend", %err, number, "failed to unwind persisted chain");
+            }
+        }
+    }
+
+    /// Replaces the chain and its state with the chain that was read from disk.
+    ///
+    /// The state is rebuilt by replaying the changes of all blocks, the state of every block is
+    /// kept for historical queries.
+    async fn restore_chain(&self, chain: PersistedChain) -> Result<(), BlockchainError> {
+        let PersistedChain { blocks, pending_changes } = chain;
+        let Some(last) = blocks.last().map(|block| block.block.header.clone()) else {
+            return Ok(())
+        };
+
+        {
+            let mut db = self.db.write().await;
+            let mut states = self.states.write();
+            let mut storage = self.blockchain.storage.write();
+            db.clear();
+            states.clear();
+            storage.blocks.clear();
+            storage.hashes.clear();
+            storage.transactions.clear();
+
+            for (idx, persisted) in blocks.into_iter().enumerate() {
+                let PersistedBlock {
+                    block,
+                    transactions,
+                    total_difficulty,
+                    pending_changes,
+                    state_changes,
+                } = persisted;
+                let block = Block::from(block);
+                let number = U64::from(block.header.number);
+                let hash = block.header.hash_slow();
+
+                // the state of the previous block includes the changes made before this block
+                disk::apply_changes(&mut **db, pending_changes)?;
+                if idx == 0 {
+                    storage.genesis_hash = hash;
+                } else if self.prune_state_history_config.is_state_history_supported() {
+                    states.insert(storage.best_hash, db.current_state());
+                }
+                disk::apply_changes(&mut **db, state_changes)?;
+                db.insert_block_hash(U256::from(number), hash);
+
+                for tx in transactions {
+                    storage.transactions.insert(tx.info.transaction_hash, tx.into());
+                }
+                storage.blocks.insert(hash, block);
+                storage.hashes.insert(number, hash);
+                storage.best_number = number;
+                storage.best_hash = hash;
+                storage.total_difficulty = total_difficulty;
+            }
+            disk::apply_changes(&mut **db, pending_changes)?;
+        }
+
+        self.time.reset(last.timestamp);
+        {
+            let mut env = self.env.write();
+            env.block.number = U256::from(last.number);
+            env.block.timestamp = U256::from(last.timestamp);
+            env.block.difficulty = last.difficulty;
+            env.block.prevrandao = Some(last.mix_hash);
+        }
+
+        let next_block_base_fee = self.fees.get_next_block_base_fee_per_gas(
+            last.gas_used,
+            last.gas_limit,
+            last.base_fee_per_gas.unwrap_or_default(),
+        );
+        let next_block_excess_blob_gas = self.fees.get_next_block_blob_excess_gas(
+            last.excess_blob_gas.unwrap_or_default(),
+            last.blob_gas_used.unwrap_or_default(),
+        );
+        self.fees.set_base_fee(next_block_base_fee);
+        self.fees
+            .set_blob_excess_gas_and_price(BlobExcessGasAndPrice::new(next_block_excess_blob_gas));
+
+        Ok(())
+    }
+
     /// Executes the [TransactionRequest] without writing to the DB
     ///
     /// # Errors
@@ -1090,28 +1780,36 @@ impl Backend {
         request: WithOtherFields<TransactionRequest>,
         fee_details: FeeDetails,
         block_request: Option<BlockRequest>,
-        overrides: Option<StateOverride>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
     ) -> Result<(InstructionResult, Option<Output>, u128, State), BlockchainError> {
-        self.with_database_at(block_request, |state, block| {
-            let block_number = block.number.to::<u64>();
-            let (exit, out, gas, state) = match overrides {
-                None => self.call_with_state(state, request, fee_details, block),
-                Some(overrides) => {
-                    let state = state::apply_state_override(overrides.into_iter().collect(), state)?;
-                    self.call_with_state(state, request, fee_details, block)
-                },
-            }?;
+        self.with_database_at(block_request, |state, mut block| {
+            let state =
+                state::apply_overrides(state, &mut block, state_overrides, block_overrides)?;
+            // the block number can be overridden with an arbitrary value
+            let block_number = block.number.saturating_to::<u64>();
+            let (exit, out, gas, state) =
+                self.call_with_state(state, request, fee_details, block)?;
             trace!(target: "backend", "call return {:?} out: {:?} gas {} on block {}", exit, out, gas, block_number);
             Ok((exit, out, gas, state))
-        }).await?
+        })
+        .await?
     }
 
+    /// Builds the env for a call with the given request, together with the EIP-7702
+    /// authorizations of the request
     fn build_call_env(
         &self,
         request: WithOtherFields<TransactionRequest>,
         fee_details: FeeDetails,
         block_env: BlockEnv,
-    ) -> EnvWithHandlerCfg {
+    ) -> (EnvWithHandlerCfg, Vec<SignedAuthorization>) {
+        // the authorization list is not part of the request type
+        let authorizations = request
+            .other
+            .get_deserialized::<Vec<SignedAuthorization>>("authorizationList")
+            .and_then(Result::ok)
+            .unwrap_or_default();
         let WithOtherFields::<TransactionRequest> {
             inner:
                 TransactionRequest {
@@ -1145,69 +1843,548 @@ impl Backend {
         // impls and providers <https://github.com/foundry-rs/foundry/issues/4388>
         env.cfg.disable_block_gas_limit = true;
 
-        if let Some(base) = max_fee_per_gas {
-            env.block.basefee = U256::from(base);
+        if let Some(base) = max_fee_per_gas {
+            env.block.basefee = U256::from(base);
+        }
+
+        let gas_price = gas_price.or(max_fee_per_gas).unwrap_or_else(|| self.gas_price());
+        let caller = from.unwrap_or_default();
+        let to = to.as_ref().and_then(TxKind::to);
+        env.tx = TxEnv {
+            caller,
+            gas_limit: gas_limit as u64,
+            gas_price: U256::from(gas_price),
+            gas_priority_fee: max_priority_fee_per_gas.map(U256::from),
+            max_fee_per_blob_gas: max_fee_per_blob_gas.map(U256::from),
+            transact_to: match to {
+                Some(addr) => TransactTo::Call(*addr),
+                None => TransactTo::Create,
+            },
+            value: value.unwrap_or_default(),
+            data: input.into_input().unwrap_or_default(),
+            chain_id: None,
+            nonce,
+            access_list: access_list.unwrap_or_default().flattened(),
+            blob_hashes: blob_versioned_hashes.unwrap_or_default(),
+            optimism: OptimismFields { enveloped_tx: Some(Bytes::new()), ..Default::default() },
+            ..Default::default()
+        };
+
+        if env.block.basefee == revm::primitives::U256::ZERO {
+            // this is an edge case because the evm fails if `tx.effective_gas_price < base_fee`
+            // 0 is only possible if it's manually set
+            env.cfg.disable_base_fee = true;
+        }
+
+        (env, authorizations)
+    }
+
+    pub fn call_with_state<D>(
+        &self,
+        state: D,
+        request: WithOtherFields<TransactionRequest>,
+        fee_details: FeeDetails,
+        block_env: BlockEnv,
+    ) -> Result<(InstructionResult, Option<Output>, u128, State), BlockchainError>
+    where
+        D: DatabaseRef<Error = DatabaseError>,
+    {
+        let mut inspector = Inspector::default();
+
+        let (env, authorizations) = self.build_call_env(request, fee_details, block_env);
+        let mut evm = self.new_evm_with_inspector_ref(state, env, &authorizations, &mut inspector);
+        let ResultAndState { result, state } = evm.transact()?;
+        let (exit_reason, gas_used, out) = match result {
+            ExecutionResult::Success { reason, gas_used, output, .. } => {
+                (reason.into(), gas_used, Some(output))
+            }
+            ExecutionResult::Revert { gas_used, output } => {
+                (InstructionResult::Revert, gas_used, Some(Output::Call(output)))
+            }
+            ExecutionResult::Halt { reason, gas_used } => (reason.into(), gas_used, None),
+        };
+        drop(evm);
+        inspector.print_logs();
+        Ok((exit_reason, out, gas_used as u128, state))
+    }
+
+    /// Finds the storage slot of `address` that holds the value returned by calling it with
+    /// `input`.
+    ///
+    /// Like forge-std's `stdstore`, this records the slots that are read during the call and
+    /// returns the last read slot that changes the returned value when it is overwritten.
+    pub async fn find_storage_slot(
+        &self,
+        address: Address,
+        input: Bytes,
+    ) -> Result<Option<U256>, BlockchainError> {
+        let request =
+            WithOtherFields::new(TransactionRequest::default().to(address).input(input.into()));
+        let returned_word =
+            |output: Option<&Bytes>| output.and_then(|out| out.get(..32)).map(U256::from_be_slice);
+
+        self.with_database_at(None, |state, block| {
+            let (env, authorizations) =
+                self.build_call_env(request.clone(), FeeDetails::zero(), block.clone());
+            let mut inspector = StorageReadInspector::new(address);
+            let mut evm =
+                self.new_evm_with_inspector_ref(&state, env, &authorizations, &mut inspector);
+            let ResultAndState { result, .. } = evm.transact()?;
+            drop(evm);
+            let Some(value) = returned_word(result.output()).filter(|_| result.is_success()) else {
+                return Ok(None)
+            };
+
+            let probe =
+                if value == U256::from(0x1337) { U256::from(0x7331) } else { U256::from(0x1337) };
+            for slot in inspector.slots.into_iter().rev() {
+                let mut cache_db = CacheDB::new(&state);
+                cache_db.insert_account_storage(address, slot, probe)?;
+                let (exit, out, ..) = self.call_with_state(
+                    cache_db,
+                    request.clone(),
+                    FeeDetails::zero(),
+                    block.clone(),
+                )?;
+                if exit.is_ok() && returned_word(out.as_ref().map(Output::data)) == Some(probe) {
+                    return Ok(Some(slot))
+                }
+            }
+            Ok::<_, BlockchainError>(None)
+        })
+        .await?
+    }
+
+    /// Simulates the given blocks of calls on top of the requested block, without changing the
+    /// chain.
+    ///
+    /// Each simulated block is built on top of the state and the header of the previous one.
+    pub async fn simulate(
+        &self,
+        request: SimulatePayload,
+        block_request: Option<BlockRequest>,
+    ) -> Result<Vec<SimulatedBlock>, BlockchainError> {
+        let parent_hash = match block_request {
+            Some(BlockRequest::Number(number)) => {
+                self.get_block(number).map(|block| block.header.hash_slow()).unwrap_or_default()
+            }
+            _ => self.best_hash(),
+        };
+
+        self.with_database_at(block_request, |state, mut block_env| {
+            let SimulatePayload { block_state_calls, trace_transfers, validation } = request;
+            let mut cache_db = CacheDB::new(state);
+            let mut parent_hash = parent_hash;
+            let mut blocks = Vec::with_capacity(block_state_calls.len());
+
+            for SimBlock { block_overrides, state_overrides, calls } in block_state_calls {
+                block_env.number = block_env.number.saturating_add(U256::from(1));
+                block_env.timestamp = block_env.timestamp.saturating_add(U256::from(1));
+                if let Some(overrides) = block_overrides {
+                    state::apply_block_overrides(overrides, &mut cache_db, &mut block_env);
+                }
+                if let Some(overrides) = state_overrides {
+                    state::apply_cached_db_state_override(overrides, &mut cache_db)?;
+                }
+
+                let block = self.simulate_block(
+                    &mut cache_db,
+                    &block_env,
+                    parent_hash,
+                    calls,
+                    trace_transfers,
+                    validation,
+                )?;
+                parent_hash = block.inner.header.hash.unwrap_or_default();
+                blocks.push(block);
+            }
+
+            Ok(blocks)
+        })
+        .await?
+    }
+
+    /// Adds a bundle that is included at the top of its target block if all of its transactions
+    /// are valid and none reverts that isn't allowed to
+    pub fn add_bundle(&self, bundle: Bundle) {
+        self.bundles.lock().add(bundle);
+    }
+
+    /// Queues relayed op-stack deposit transactions, they are included at the top of the next
+    /// block in the given order
+    pub fn add_deposits(&self, deposits: Vec<DepositTransaction>) -> Result<(), BlockchainError> {
+        self.ensure_op_deposits_active()?;
+        let deposits = deposits
+            .into_iter()
+            .map(|tx| PendingTransaction::new(TypedTransaction::Deposit(tx)))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.deposits.lock().extend(deposits);
+        Ok(())
+    }
+
+    /// Removes the relayed deposits with the given source hashes, because the L1 blocks that
+    /// emitted them were reorged out.
+    ///
+    /// Queued deposits are dropped. If one of the deposits was already mined in a block after the
+    /// block with the number `since`, the chain is rolled back to before the first such block and
+    /// the other deposits of the removed blocks are queued again.
+    ///
+    /// Returns the transactions of the removed blocks that are not deposits.
+    pub async fn remove_deposits(
+        &self,
+        source_hashes: &HashSet<B256>,
+        since: u64,
+    ) -> Result<Vec<PendingTransaction>, BlockchainError> {
+        let is_removed = |tx: &TypedTransaction| matches!(tx, TypedTransaction::Deposit(tx) if source_hashes.contains(&tx.source_hash));
+        self.deposits.lock().retain(|tx| !is_removed(&tx.transaction));
+
+        let best_number = self.best_number();
+        let mut first = None;
+        let mut requeued = Vec::new();
+        for number in since.saturating_add(1)..=best_number {
+            let Some(block) = self.get_block(BlockId::number(number)) else { continue };
+            if first.is_none() && block.transactions.iter().any(|tx| is_removed(&tx.transaction)) {
+                first = Some(number);
+            }
+            if first.is_some() {
+                requeued.extend(
+                    block
+                        .transactions
+                        .into_iter()
+                        .filter(|tx| {
+                            matches!(tx.transaction, TypedTransaction::Deposit(_)) &&
+                                !is_removed(&tx.transaction)
+                        })
+                        .filter_map(|tx| PendingTransaction::from_maybe_impersonated(tx).ok()),
+                );
+            }
+        }
+        let Some(first) = first else { return Ok(Vec::new()) };
+
+        trace!(target: "backend", first, "rolling back blocks with reorged out deposits");
+        let unwound = self.rollback(best_number - first + 1).await?;
+        self.deposits.lock().splice(0..0, requeued);
+        Ok(unwound)
+    }
+
+    /// Takes all queued deposit transactions
+    fn deposit_transactions(&self) -> Vec<Arc<PoolTransaction>> {
+        std::mem::take(&mut *self.deposits.lock())
+            .into_iter()
+            .map(|tx| {
+                Arc::new(PoolTransaction {
+                    pending_transaction: tx,
+                    requires: vec![],
+                    provides: vec![],
+                    priority: Default::default(),
+                })
+            })
+            .collect()
+    }
+
+    /// Simulates the transactions of a bundle in a block with the given number and timestamp, on
+    /// top of the state of the requested block
+    pub async fn call_bundle(
+        &self,
+        transactions: Vec<PendingTransaction>,
+        block_request: Option<BlockRequest>,
+        block_number: u64,
+        timestamp: Option<u64>,
+    ) -> Result<CallBundleResponse, BlockchainError> {
+        self.with_database_at(block_request, |state, block_env| {
+            let state_block_number = block_env.number.to::<u64>();
+            let mut env = self.env.read().clone();
+            env.block = block_env;
+            env.block.number = U256::from(block_number);
+            if let Some(timestamp) = timestamp {
+                env.block.timestamp = U256::from(timestamp);
+            }
+            if env.block.basefee.is_zero() {
+                env.cfg.disable_base_fee = true;
+            }
+
+            let mut cache_db = CacheDB::new(state);
+            let results = self.execute_bundle(&mut cache_db, &env, &transactions)?;
+
+            let total_gas_used = results.iter().map(|res| res.gas_used).sum::<u64>();
+            let sum = |f: fn(&CallBundleResult) -> U256| {
+                results.iter().fold(U256::ZERO, |acc, res| acc.saturating_add(f(res)))
+            };
+            let coinbase_diff = sum(|res| res.coinbase_diff);
+            let bundle_gas_price = coinbase_diff.checked_div(U256::from(total_gas_used));
+
+            Ok(CallBundleResponse {
+                bundle_hash: bundle_hash(transactions.iter().map(|tx| tx.hash())),
+                bundle_gas_price: bundle_gas_price.unwrap_or_default(),
+                coinbase_diff,
+                eth_sent_to_coinbase: sum(|res| res.eth_sent_to_coinbase),
+                gas_fees: sum(|res| res.gas_fees),
+                results,
+                state_block_number,
+                total_gas_used,
+            })
+        })
+        .await?
+    }
+
+    /// Executes the transactions of a bundle in order on top of `cache_db` and commits their
+    /// changes.
+    ///
+    /// Fails if any of the transactions is invalid, reverted transactions are part of the result.
+    fn execute_bundle<D>(
+        &self,
+        cache_db: &mut CacheDB<D>,
+        env: &EnvWithHandlerCfg,
+        transactions: &[PendingTransaction],
+    ) -> Result<Vec<CallBundleResult>, BlockchainError>
+    where
+        D: DatabaseRef<Error = DatabaseError>,
+    {
+        let coinbase = env.block.coinbase;
+        let mut results = Vec::with_capacity(transactions.len());
+        for tx in transactions {
+            let mut env = env.clone();
+            env.tx = tx.to_revm_tx_env();
+
+            let account = cache_db.basic_ref(*tx.sender())?.unwrap_or_default();
+            self.validate_pool_transaction_for(tx, &account, &env)?;
+
+            let coinbase_before = cache_db.basic_ref(coinbase)?.unwrap_or_default().balance;
+            let gas_price = effective_gas_price(&env);
+            let tip = gas_price.saturating_sub(env.block.basefee);
+
+            let mut inspector = Inspector::default();
+            let authorizations = tx.transaction.authorization_list().unwrap_or_default();
+            let ResultAndState { result, state } = self
+                .new_evm_with_inspector_ref(&*cache_db, env, authorizations, &mut inspector)
+                .transact()?;
+            cache_db.commit(state);
+            inspector.print_logs();
+
+            let (gas_used, value, error, revert) = match result {
+                ExecutionResult::Success { gas_used, output, .. } => {
+                    (gas_used, Some(output.into_data()), None, None)
+                }
+                ExecutionResult::Revert { gas_used, output } => {
+                    (gas_used, None, Some("execution reverted".to_string()), Some(output))
+                }
+                ExecutionResult::Halt { reason, gas_used } => {
+                    (gas_used, None, Some(format!("{reason:?}")), None)
+                }
+            };
+
+            let coinbase_after = cache_db.basic_ref(coinbase)?.unwrap_or_default().balance;
+            let coinbase_diff = coinbase_after.saturating_sub(coinbase_before);
+            let gas_fees = U256::from(gas_used).saturating_mul(tip);
+            results.push(CallBundleResult {
+                tx_hash: *tx.hash(),
+                from_address: *tx.sender(),
+                to_address: tx.transaction.to(),
+                gas_used,
+                gas_price,
+                gas_fees,
+                coinbase_diff,
+                eth_sent_to_coinbase: coinbase_diff.saturating_sub(gas_fees),
+                value,
+                error,
+                revert,
+            });
+        }
+        Ok(results)
+    }
+
+    /// Returns the transactions of all bundles that can be included in the block with the given
+    /// env, in order.
+    ///
+    /// Every bundle is simulated on top of the previously accepted bundles and is dropped as a
+    /// whole if one of its transactions is invalid, reverts without being allowed to or if the
+    /// bundle doesn't fit in the block anymore.
+    fn bundle_transactions<D>(&self, db: D, env: &EnvWithHandlerCfg) -> Vec<Arc<PoolTransaction>>
+    where
+        D: DatabaseRef<Error = DatabaseError> + Clone,
+    {
+        let bundles = self.bundles.lock().take_for_block(env.block.number.to::<u64>());
+        if bundles.is_empty() {
+            return Vec::new()
         }
 
-        let gas_price = gas_price.or(max_fee_per_gas).unwrap_or_else(|| self.gas_price());
-        let caller = from.unwrap_or_default();
-        let to = to.as_ref().and_then(TxKind::to);
-        env.tx = TxEnv {
-            caller,
-            gas_limit: gas_limit as u64,
-            gas_price: U256::from(gas_price),
-            gas_priority_fee: max_priority_fee_per_gas.map(U256::from),
-            max_fee_per_blob_gas: max_fee_per_blob_gas.map(U256::from),
-            transact_to: match to {
-                Some(addr) => TransactTo::Call(*addr),
-                None => TransactTo::Create,
-            },
-            value: value.unwrap_or_default(),
-            data: input.into_input().unwrap_or_default(),
-            chain_id: None,
-            nonce,
-            access_list: access_list.unwrap_or_default().flattened(),
-            blob_hashes: blob_versioned_hashes.unwrap_or_default(),
-            optimism: OptimismFields { enveloped_tx: Some(Bytes::new()), ..Default::default() },
-            ..Default::default()
-        };
+        let timestamp = env.block.timestamp.to::<u64>();
+        let gas_limit = env.block.gas_limit.to::<u128>();
+        let mut gas_reserved = 0u128;
+        let mut cache_db = CacheDB::new(db);
+        let mut transactions = Vec::new();
+        for bundle in bundles {
+            if !bundle.is_valid_at(timestamp) {
+                trace!(target: "backend", hash=?bundle.hash, timestamp, "dropping bundle outside of its timestamp range");
+                continue
+            }
+            let bundle_gas =
+                bundle.transactions.iter().map(|tx| tx.transaction.gas_limit()).sum::<u128>();
+            if gas_reserved + bundle_gas > gas_limit {
+                trace!(target: "backend", hash=?bundle.hash, "dropping bundle that exceeds the block gas limit");
+                continue
+            }
 
-        if env.block.basefee == revm::primitives::U256::ZERO {
-            // this is an edge case because the evm fails if `tx.effective_gas_price < base_fee`
-            // 0 is only possible if it's manually set
-            env.cfg.disable_base_fee = true;
+            let mut bundle_db = cache_db.clone();
+            match self.execute_bundle(&mut bundle_db, env, &bundle.transactions) {
+                Ok(results)
+                    if results
+                        .iter()
+                        .all(|res| res.is_success() || bundle.may_revert(&res.tx_hash)) =>
+                {
+                    trace!(target: "backend", hash=?bundle.hash, "including bundle");
+                    cache_db = bundle_db;
+                    gas_reserved += bundle_gas;
+                    transactions.extend(bundle.transactions.into_iter().map(|tx| {
+                        let provides = vec![to_marker(tx.nonce(), *tx.sender())];
+                        Arc::new(PoolTransaction {
+                            pending_transaction: tx,
+                            requires: vec![],
+                            provides,
+                            priority: Default::default(),
+                        })
+                    }));
+                }
+                Ok(_) => {
+                    trace!(target: "backend", hash=?bundle.hash, "dropping bundle with reverted transaction");
+                }
+                Err(err) => {
+                    trace!(target: "backend", hash=?bundle.hash, ?err, "dropping invalid bundle");
+                }
+            }
         }
-
-        env
+        transactions
     }
 
-    pub fn call_with_state<D>(
+    /// Executes the calls of a single simulated block on top of `cache_db` and commits their
+    /// changes.
+    ///
+    /// Note: the state root of a simulated block is not computed.
+    fn simulate_block<D>(
         &self,
-        state: D,
-        request: WithOtherFields<TransactionRequest>,
-        fee_details: FeeDetails,
-        block_env: BlockEnv,
-    ) -> Result<(InstructionResult, Option<Output>, u128, State), BlockchainError>
+        cache_db: &mut CacheDB<D>,
+        block_env: &BlockEnv,
+        parent_hash: B256,
+        calls: Vec<WithOtherFields<TransactionRequest>>,
+        trace_transfers: bool,
+        validation: bool,
+    ) -> Result<SimulatedBlock, BlockchainError>
     where
         D: DatabaseRef<Error = DatabaseError>,
     {
-        let mut inspector = Inspector::default();
-
-        let env = self.build_call_env(request, fee_details, block_env);
-        let mut evm = self.new_evm_with_inspector_ref(state, env, &mut inspector);
-        let ResultAndState { result, state } = evm.transact()?;
-        let (exit_reason, gas_used, out) = match result {
-            ExecutionResult::Success { reason, gas_used, output, .. } => {
-                (reason.into(), gas_used, Some(output))
-            }
-            ExecutionResult::Revert { gas_used, output } => {
-                (InstructionResult::Revert, gas_used, Some(Output::Call(output)))
+        let mut receipts = Vec::with_capacity(calls.len());
+        let mut results = Vec::with_capacity(calls.len());
+        let mut logs_bloom = Bloom::default();
+        let mut cumulative_gas_used = 0u128;
+
+        for request in calls {
+            let fees = FeeDetails::new(
+                request.gas_price,
+                request.max_fee_per_gas,
+                request.max_priority_fee_per_gas,
+                request.max_fee_per_blob_gas,
+            )?
+            .or_zero_fees();
+            let (mut env, authorizations) = self.build_call_env(request, fees, block_env.clone());
+            // the calls are executed against the (overridden) base fee of the simulated block
+            env.block.basefee = block_env.basefee;
+            env.cfg.disable_base_fee = !validation || block_env.basefee.is_zero();
+            if validation && env.tx.nonce.is_none() {
+                env.tx.nonce = Some(
+                    cache_db.basic_ref(env.tx.caller)?.map(|acc| acc.nonce).unwrap_or_default(),
+                );
             }
-            ExecutionResult::Halt { reason, gas_used } => (reason.into(), gas_used, None),
+
+            let mut inspector = SimulateInspector::new(trace_transfers);
+            let ResultAndState { result, state } = self
+                .new_evm_with_inspector_ref(&*cache_db, env, &authorizations, &mut inspector)
+                .transact()?;
+            cache_db.commit(state);
+
+            let (success, gas_used, return_data, error) = match result {
+                ExecutionResult::Success { gas_used, output, .. } => {
+                    (true, gas_used, output.into_data(), None)
+                }
+                ExecutionResult::Revert { gas_used, output } => {
+                    // this mimics geth revert error
+                    let mut message = "execution reverted".to_string();
+                    if let Some(reason) = RevertDecoder::new().maybe_decode(&output, None) {
+                        message = format!("{message}: {reason}");
+                    }
+                    let error = SimulateError { code: SimulateError::REVERTED, message };
+                    (false, gas_used, output, Some(error))
+                }
+                ExecutionResult::Halt { reason, gas_used } => {
+                    let error = SimulateError {
+                        code: SimulateError::VM_ERROR,
+                        message: format!("{reason:?}"),
+                    };
+                    (false, gas_used, Bytes::new(), Some(error))
+                }
+            };
+
+            cumulative_gas_used = cumulative_gas_used.saturating_add(gas_used as u128);
+            let receipt: ReceiptWithBloom =
+                Receipt { status: success, cumulative_gas_used, logs: inspector.logs.clone() }
+                    .into();
+            logs_bloom.accrue_bloom(&receipt.logs_bloom);
+            receipts.push(TypedReceipt::EIP1559(receipt));
+            results.push((success, gas_used, return_data, error, inspector.logs));
+        }
+
+        let partial_header = PartialHeader {
+            parent_hash,
+            beneficiary: block_env.coinbase,
+            receipts_root: trie::ordered_trie_root(receipts.iter().map(alloy_rlp::encode)),
+            logs_bloom,
+            difficulty: block_env.difficulty,
+            // the block env can be overridden with arbitrary values
+            number: block_env.number.saturating_to::<u64>(),
+            gas_limit: block_env.gas_limit.saturating_to::<u128>(),
+            gas_used: cumulative_gas_used,
+            timestamp: block_env.timestamp.saturating_to::<u64>(),
+            mix_hash: block_env.prevrandao.unwrap_or_default(),
+            base_fee: self.is_eip1559().then(|| block_env.basefee.saturating_to::<u128>()),
+            ..Default::default()
         };
-        drop(evm);
-        inspector.print_logs();
-        Ok((exit_reason, out, gas_used as u128, state))
+        let block = Block::new(partial_header, Vec::<MaybeImpersonatedTransaction>::new(), vec![]);
+        let block_hash = block.header.hash_slow();
+        let block_number = block.header.number;
+        let block_timestamp = block.header.timestamp;
+
+        let mut log_index = 0u64;
+        let calls = results
+            .into_iter()
+            .enumerate()
+            .map(|(transaction_index, (success, gas_used, return_data, error, logs))| {
+                let logs = logs
+                    .into_iter()
+                    .map(|inner| {
+                        let log = Log {
+                            inner,
+                            block_hash: Some(block_hash),
+                            block_number: Some(block_number),
+                            block_timestamp: Some(block_timestamp),
+                            transaction_hash: None,
+                            transaction_index: Some(transaction_index as u64),
+                            log_index: Some(log_index),
+                            removed: false,
+                        };
+                        log_index += 1;
+                        log
+                    })
+                    .collect();
+                SimCallResult {
+                    return_data,
+                    logs,
+                    gas_used: U64::from(gas_used),
+                    status: U64::from(success as u8),
+                    error,
+                }
+            })
+            .collect();
+
+        Ok(SimulatedBlock { inner: self.convert_block(block), calls })
     }
 
     pub async fn call_with_tracing(
@@ -1215,36 +2392,126 @@ impl Backend {
         request: WithOtherFields<TransactionRequest>,
         fee_details: FeeDetails,
         block_request: Option<BlockRequest>,
-        opts: GethDefaultTracingOptions,
-    ) -> Result<DefaultFrame, BlockchainError> {
-        self.with_database_at(block_request, |state, block| {
-            let mut inspector = Inspector::default().with_steps_tracing();
+        opts: GethDebugTracingCallOptions,
+    ) -> Result<GethTrace, BlockchainError> {
+        let GethDebugTracingCallOptions { tracing_options: opts, state_overrides, block_overrides } =
+            opts;
+        self.with_database_at(block_request, |state, mut block| {
+            let state =
+                state::apply_overrides(state, &mut block, state_overrides, block_overrides)?;
             let block_number = block.number;
 
-            let env = self.build_call_env(request, fee_details, block);
-            let mut evm = self.new_evm_with_inspector_ref(state, env, &mut inspector);
-            let ResultAndState { result, state: _ } = evm.transact()?;
-
-            let (exit_reason, gas_used, out) = match result {
-                ExecutionResult::Success { reason, gas_used, output, .. } => {
-                    (reason.into(), gas_used, Some(output))
-                }
-                ExecutionResult::Revert { gas_used, output } => {
-                    (InstructionResult::Revert, gas_used, Some(Output::Call(output)))
-                }
-                ExecutionResult::Halt { reason, gas_used } => (reason.into(), gas_used, None),
-            };
-
-            drop(evm);
-            let tracer = inspector.tracer.expect("tracer disappeared");
-            let return_value = out.as_ref().map(|o| o.data().clone()).unwrap_or_default();
-            let res = tracer.into_geth_builder().geth_traces(gas_used, return_value, opts);
-            trace!(target: "backend", ?exit_reason, ?out, %gas_used, %block_number, "trace call");
-            Ok(res)
+            let (env, authorizations) = self.build_call_env(request, fee_details, block);
+            let (trace, ResultAndState { result, state: _ }) =
+                self.geth_trace_with_state(state, env, &authorizations, opts)?;
+            trace!(target: "backend", ?result, %block_number, "trace call");
+            Ok(trace)
         })
         .await?
     }
 
+    /// Executes the given env on top of `state` and traces the execution with the geth tracer
+    /// configured in `opts`.
+    ///
+    /// Returns the trace together with the result of the execution, without committing it.
+    fn geth_trace_with_state<D>(
+        &self,
+        state: D,
+        env: EnvWithHandlerCfg,
+        authorizations: &[SignedAuthorization],
+        opts: GethDebugTracingOptions,
+    ) -> Result<(GethTrace, ResultAndState), BlockchainError>
+    where
+        D: DatabaseRef<Error = DatabaseError>,
+    {
+        let GethDebugTracingOptions { config, tracer, tracer_config, .. } = opts;
+
+        let tracer = match tracer {
+            Some(GethDebugTracerType::BuiltInTracer(tracer)) => tracer,
+            Some(GethDebugTracerType::JsTracer(_)) => {
+                return Err(RpcError::invalid_params("unsupported tracer type").into())
+            }
+            None => {
+                // defaults to the struct log tracer
+                let mut inspector = Inspector::default()
+                    .with_tracing_config(TracingInspectorConfig::from_geth_config(&config));
+                let res = self
+                    .new_evm_with_inspector_ref(&state, env, authorizations, &mut inspector)
+                    .transact()?;
+                inspector.print_logs();
+
+                let return_value = res.result.output().cloned().unwrap_or_default();
+                let frame = inspector
+                    .tracer
+                    .expect("tracer disappeared")
+                    .into_geth_builder()
+                    .geth_traces(res.result.gas_used(), return_value, config);
+                return Ok((frame.into(), res))
+            }
+        };
+
+        match tracer {
+            GethDebugBuiltInTracerType::CallTracer => {
+                let call_config = tracer_config
+                    .into_call_config()
+                    .map_err(|err| RpcError::invalid_params(err.to_string()))?;
+
+                let mut inspector = Inspector::default().with_tracing_config(
+                    TracingInspectorConfig::from_geth_call_config(&call_config),
+                );
+                let res = self
+                    .new_evm_with_inspector_ref(&state, env, authorizations, &mut inspector)
+                    .transact()?;
+                inspector.print_logs();
+
+                let frame = inspector
+                    .tracer
+                    .expect("tracer disappeared")
+                    .into_geth_builder()
+                    .geth_call_traces(call_config, res.result.gas_used());
+                Ok((frame.into(), res))
+            }
+            GethDebugBuiltInTracerType::PreStateTracer => {
+                let prestate_config = tracer_config
+                    .into_pre_state_config()
+                    .map_err(|err| RpcError::invalid_params(err.to_string()))?;
+
+                let mut inspector = Inspector::default().with_tracing_config(
+                    TracingInspectorConfig::from_geth_prestate_config(&prestate_config),
+                );
+                let res = self
+                    .new_evm_with_inspector_ref(&state, env, authorizations, &mut inspector)
+                    .transact()?;
+                inspector.print_logs();
+
+                let frame = inspector
+                    .tracer
+                    .expect("tracer disappeared")
+                    .into_geth_builder()
+                    .geth_prestate_traces(&res, prestate_config, &state)?;
+                Ok((frame.into(), res))
+            }
+            GethDebugBuiltInTracerType::FourByteTracer => {
+                let mut inspector = FourByteInspector::default();
+                let res = self
+                    .new_evm_with_inspector_ref(&state, env, authorizations, &mut inspector)
+                    .transact()?;
+                Ok((FourByteFrame::from(inspector).into(), res))
+            }
+            GethDebugBuiltInTracerType::NoopTracer => {
+                let mut inspector = Inspector::default();
+                let res = self
+                    .new_evm_with_inspector_ref(&state, env, authorizations, &mut inspector)
+                    .transact()?;
+                inspector.print_logs();
+                Ok((NoopFrame::default().into(), res))
+            }
+            GethDebugBuiltInTracerType::MuxTracer => {
+                Err(RpcError::invalid_params("unsupported tracer type").into())
+            }
+        }
+    }
+
     pub fn build_access_list_with_state<D>(
         &self,
         state: D,
@@ -1270,8 +2537,8 @@ impl Backend {
             self.precompiles(),
         );
 
-        let env = self.build_call_env(request, fee_details, block_env);
-        let mut evm = self.new_evm_with_inspector_ref(state, env, &mut inspector);
+        let (env, authorizations) = self.build_call_env(request, fee_details, block_env);
+        let mut evm = self.new_evm_with_inspector_ref(state, env, &authorizations, &mut inspector);
         let ResultAndState { result, state: _ } = evm.transact()?;
         let (exit_reason, gas_used, out) = match result {
             ExecutionResult::Success { reason, gas_used, output, .. } => {
@@ -1562,6 +2829,50 @@ impl Backend {
         self.blockchain.get_block_by_hash(&hash)
     }
 
+    /// Returns the blob sidecars of the given block in the format of the beacon API
+    ///
+    /// Returns `None` if the block is not known.
+    pub fn blob_sidecars(&self, id: impl Into<BlockId>) -> Option<Vec<BlobSidecar>> {
+        let block = self.get_block(id)?;
+        let header = &block.header;
+        let signed_block_header = SignedBeaconBlockHeader {
+            message: BeaconBlockHeader {
+                slot: header.number,
+                proposer_index: 0,
+                parent_root: header.parent_hash,
+                state_root: header.state_root,
+                body_root: header.transactions_root,
+            },
+            signature: vec![0; 96].into(),
+        };
+
+        let sidecars = block
+            .transactions
+            .iter()
+            .filter_map(|tx| tx.sidecar())
+            .flat_map(|sidecar| sidecar.blobs.iter().zip(&sidecar.commitments).zip(&sidecar.proofs))
+            .enumerate()
+            .map(|(index, ((blob, commitment), proof))| BlobSidecar {
+                index: index as u64,
+                blob: Box::new(*blob),
+                kzg_commitment: *commitment,
+                kzg_proof: *proof,
+                signed_block_header: signed_block_header.clone(),
+                kzg_commitment_inclusion_proof: Vec::new(),
+            })
+            .collect();
+        Some(sidecars)
+    }
+
+    /// Returns the blobs of the given mined EIP-4844 transaction
+    pub fn blobs_by_transaction_hash(&self, hash: TxHash) -> Option<Vec<Blob>> {
+        let storage = self.blockchain.storage.read();
+        let block_hash = storage.transactions.get(&hash)?.block_hash;
+        let tx =
+            storage.blocks.get(&block_hash)?.transactions.iter().find(|tx| tx.hash() == hash)?;
+        tx.sidecar().map(|sidecar| sidecar.blobs.clone())
+    }
+
     pub fn mined_block_by_number(&self, number: BlockNumber) -> Option<AlloyBlock> {
         let block = self.get_block(number)?;
         let mut block = self.convert_block(block);
@@ -1922,8 +3233,8 @@ impl Backend {
         hash: B256,
         opts: GethDebugTracingOptions,
     ) -> Result<GethTrace, BlockchainError> {
-        if let Some(traces) = self.mined_geth_trace_transaction(hash, opts.clone()) {
-            return Ok(GethTrace::Default(traces));
+        if let Some(trace) = self.mined_geth_trace_transaction(hash, opts.clone()) {
+            return trace
         }
 
         if let Some(fork) = self.get_fork() {
@@ -1937,8 +3248,208 @@ impl Backend {
         &self,
         hash: B256,
         opts: GethDebugTracingOptions,
-    ) -> Option<DefaultFrame> {
-        self.blockchain.storage.read().transactions.get(&hash).map(|tx| tx.geth_trace(opts.config))
+    ) -> Option<Result<GethTrace, BlockchainError>> {
+        let tx = self.mined_transaction(hash)?;
+        let trace = match opts.tracer {
+            None => Ok(tx.geth_trace(opts.config).into()),
+            Some(GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::CallTracer)) => {
+                match opts.tracer_config.into_call_config() {
+                    Ok(call_config) => Ok(tx.geth_call_trace(call_config).into()),
+                    Err(err) => Err(RpcError::invalid_params(err.to_string()).into()),
+                }
+            }
+            Some(GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::NoopTracer)) => {
+                Ok(NoopFrame::default().into())
+            }
+            // all other tracers require the state the transaction was executed on
+            Some(_) => self.replay_geth_trace_transaction(&tx, opts),
+        };
+        Some(trace)
+    }
+
+    /// Returns the geth traces of all transactions in the given block
+    ///
+    /// Mined blocks are re-executed on top of the state of their parent block.
+    pub async fn debug_trace_block(
+        &self,
+        block_id: BlockId,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TraceResult>, BlockchainError> {
+        if let Some(block) = self.get_block(block_id) {
+            return self.mined_geth_trace_block(&block, opts)
+        }
+
+        if let Some(fork) = self.get_fork() {
+            let predates_fork = match block_id {
+                BlockId::Hash(_) => true,
+                BlockId::Number(number) => {
+                    fork.predates_fork_inclusive(self.convert_block_number(Some(number)))
+                }
+            };
+            if predates_fork {
+                return Ok(fork.debug_trace_block(block_id, opts).await?)
+            }
+        }
+
+        Err(BlockchainError::BlockNotFound)
+    }
+
+    fn mined_geth_trace_block(
+        &self,
+        block: &Block,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TraceResult>, BlockchainError> {
+        let traces = self.replay_block_with_geth_tracer(block, None, &opts)?;
+        Ok(traces
+            .into_iter()
+            .map(|(tx_hash, result)| TraceResult::Success { result, tx_hash: Some(tx_hash) })
+            .collect())
+    }
+
+    /// Re-executes the given mined transaction on top of the state it was originally executed on
+    /// and traces it with the configured geth tracer.
+    fn replay_geth_trace_transaction(
+        &self,
+        tx: &MinedTransaction,
+        opts: GethDebugTracingOptions,
+    ) -> Result<GethTrace, BlockchainError> {
+        let block = self.get_block_by_hash(tx.block_hash).ok_or(BlockchainError::BlockNotFound)?;
+        let hash = tx.info.transaction_hash;
+        self.replay_block_with_geth_tracer(&block, Some(hash), &opts)?
+            .into_iter()
+            .find_map(|(tx_hash, trace)| (tx_hash == hash).then_some(trace))
+            .ok_or(BlockchainError::DataUnavailable)
+    }
+
+    /// Re-executes the transactions of the given mined block on top of the state of its parent
+    /// block and traces them with the configured geth tracer.
+    ///
+    /// If a `target` transaction is provided, only that transaction is traced and the replay stops
+    /// right after it.
+    fn replay_block_with_geth_tracer(
+        &self,
+        block: &Block,
+        target: Option<B256>,
+        opts: &GethDebugTracingOptions,
+    ) -> Result<Vec<(B256, GethTrace)>, BlockchainError> {
+        self.replay_block(block, target, |db, env, authorizations| {
+            self.geth_trace_with_state(db, env, authorizations, opts.clone())
+        })
+    }
+
+    /// Re-executes the transactions of the given mined block on top of the state of its parent
+    /// block and traces them with the parity tracer for the given trace types.
+    ///
+    /// If a `target` transaction is provided, only that transaction is traced and the replay stops
+    /// right after it.
+    fn replay_block_with_parity_tracer(
+        &self,
+        block: &Block,
+        target: Option<B256>,
+        trace_types: &HashSet<TraceType>,
+    ) -> Result<Vec<TraceResultsWithTransactionHash>, BlockchainError> {
+        let traces = self.replay_block(block, target, |db, env, authorizations| {
+            let mut inspector = Inspector::default()
+                .with_tracing_config(TracingInspectorConfig::from_parity_config(trace_types));
+            let res = self
+                .new_evm_with_inspector_ref(db, env, authorizations, &mut inspector)
+                .transact()?;
+            let trace = inspector
+                .tracer
+                .expect("tracer disappeared")
+                .into_parity_builder()
+                .into_trace_results_with_state(&res, trace_types, db)?;
+            Ok((trace, res))
+        })?;
+        Ok(traces
+            .into_iter()
+            .map(|(transaction_hash, full_trace)| TraceResultsWithTransactionHash {
+                full_trace,
+                transaction_hash,
+            })
+            .collect())
+    }
+
+    /// Re-executes the transactions of the given mined block on top of the state of its parent
+    /// block, the transactions to trace are executed with the given `trace` function.
+    ///
+    /// If a `target` transaction is provided, only that transaction is traced and the replay stops
+    /// right after it.
+    fn replay_block<T>(
+        &self,
+        block: &Block,
+        target: Option<B256>,
+        mut trace: impl FnMut(
+            &CacheDB<&StateDb>,
+            EnvWithHandlerCfg,
+            &[SignedAuthorization],
+        ) -> Result<(T, ResultAndState), BlockchainError>,
+    ) -> Result<Vec<(B256, T)>, BlockchainError> {
+        if block.transactions.is_empty() {
+            return Ok(Vec::new())
+        }
+
+        let mut states = self.states.write();
+        let parent_state = states.get(&block.header.parent_hash).ok_or_else(|| {
+            warn!(target: "backend", "Not historic state found for block={}", block.header.number);
+            BlockchainError::DataUnavailable
+        })?;
+        let mut cache_db = CacheDB::new(parent_state);
+
+        let mut env = self.env.read().clone();
+        env.block = BlockEnv {
+            number: U256::from(block.header.number),
+            coinbase: block.header.beneficiary,
+            timestamp: U256::from(block.header.timestamp),
+            difficulty: block.header.difficulty,
+            prevrandao: Some(block.header.mix_hash),
+            basefee: U256::from(block.header.base_fee_per_gas.unwrap_or_default()),
+            gas_limit: U256::from(block.header.gas_limit),
+            blob_excess_gas_and_price: block
+                .header
+                .excess_blob_gas
+                .map(|excess_blob_gas| BlobExcessGasAndPrice::new(excess_blob_gas as u64)),
+            ..Default::default()
+        };
+        if env.block.basefee.is_zero() {
+            // mirrors the block building, see `do_mine_block`
+            env.cfg.disable_base_fee = true;
+        }
+
+        let mut traces = Vec::new();
+        for tx in &block.transactions {
+            let pending = PendingTransaction::from_maybe_impersonated(tx.clone())?;
+            let hash = *pending.hash();
+            env.tx = pending.to_revm_tx_env();
+            if env.handler_cfg.is_optimism {
+                env.tx.optimism.enveloped_tx =
+                    Some(alloy_rlp::encode(&pending.transaction.transaction).into());
+            }
+
+            let authorizations = pending.transaction.authorization_list().unwrap_or_default();
+            let state = if target.map_or(true, |target| target == hash) {
+                let (trace, ResultAndState { state, .. }) =
+                    trace(&cache_db, env.clone(), authorizations)?;
+                traces.push((hash, trace));
+                if target.is_some() {
+                    break
+                }
+                state
+            } else {
+                let mut inspector = Inspector::default();
+                self.new_evm_with_inspector_ref(
+                    &cache_db,
+                    env.clone(),
+                    authorizations,
+                    &mut inspector,
+                )
+                .transact()?
+                .state
+            };
+            cache_db.commit(state);
+        }
+
+        Ok(traces)
     }
 
     /// Returns the traces for the given block
@@ -1960,6 +3471,96 @@ impl Backend {
         Ok(vec![])
     }
 
+    /// Returns the traces of the blocks in the range of the filter whose addresses match the
+    /// filter
+    ///
+    /// If no start block is given, the range starts at the fork block or genesis.
+    pub async fn trace_filter(
+        &self,
+        filter: TraceFilter,
+    ) -> Result<Vec<LocalizedTransactionTrace>, BlockchainError> {
+        let fork = self.get_fork();
+        let best_number = self.best_number();
+        let from = filter
+            .from_block
+            .unwrap_or_else(|| fork.as_ref().map(|fork| fork.block_number()).unwrap_or_default());
+        let to = filter.to_block.unwrap_or(best_number);
+        if from > to || to > best_number {
+            return Err(BlockchainError::BlockOutOfRange(best_number, to.max(from)))
+        }
+
+        let mut traces = vec![];
+        let mut next = from;
+        if let Some(fork) = fork.filter(|fork| fork.predates_fork(from)) {
+            let fork_to = to.min(fork.block_number() - 1);
+            let fork_filter = TraceFilter {
+                from_block: Some(from),
+                to_block: Some(fork_to),
+                after: None,
+                count: None,
+                ..filter.clone()
+            };
+            traces.extend(fork.trace_filter(fork_filter).await?);
+            next = fork_to + 1;
+        }
+
+        let matcher = filter.matcher();
+        for number in next..=to {
+            let Some(block_traces) = self.mined_parity_trace_block(number) else { continue };
+            traces.extend(block_traces.into_iter().filter(|trace| {
+                let (from, to) = trace_addresses(&trace.trace);
+                matcher.matches(from, to)
+            }));
+        }
+
+        let after = filter.after.unwrap_or_default() as usize;
+        let count = filter.count.map_or(usize::MAX, |count| count as usize);
+        Ok(traces.into_iter().skip(after).take(count).collect())
+    }
+
+    /// Re-executes the given transaction and returns the requested parity traces
+    pub async fn trace_replay_transaction(
+        &self,
+        hash: B256,
+        trace_types: HashSet<TraceType>,
+    ) -> Result<TraceResults, BlockchainError> {
+        if let Some(tx) = self.mined_transaction(hash) {
+            let block =
+                self.get_block_by_hash(tx.block_hash).ok_or(BlockchainError::BlockNotFound)?;
+            return self
+                .replay_block_with_parity_tracer(&block, Some(hash), &trace_types)?
+                .pop()
+                .map(|trace| trace.full_trace)
+                .ok_or(BlockchainError::DataUnavailable)
+        }
+
+        if let Some(fork) = self.get_fork() {
+            return Ok(fork.trace_replay_transaction(hash, trace_types).await?)
+        }
+
+        Err(BlockchainError::BlockNotFound)
+    }
+
+    /// Re-executes all transactions of the given block and returns the requested parity traces
+    pub async fn trace_replay_block_transactions(
+        &self,
+        block: BlockNumber,
+        trace_types: HashSet<TraceType>,
+    ) -> Result<Vec<TraceResultsWithTransactionHash>, BlockchainError> {
+        let number = self.convert_block_number(Some(block));
+        if let Some(block) = self.get_block(number) {
+            return self.replay_block_with_parity_tracer(&block, None, &trace_types)
+        }
+
+        if let Some(fork) = self.get_fork() {
+            if fork.predates_fork(number) {
+                return Ok(fork.trace_replay_block_transactions(number, trace_types).await?)
+            }
+        }
+
+        Err(BlockchainError::BlockNotFound)
+    }
+
     pub async fn transaction_receipt(
         &self,
         hash: B256,
@@ -2034,6 +3635,11 @@ impl Backend {
                 .base_fee_per_gas
                 .unwrap_or_else(|| self.base_fee())
                 .saturating_add(t.tx().tx().max_priority_fee_per_gas),
+            TypedTransaction::EIP7702(t) => block
+                .header
+                .base_fee_per_gas
+                .unwrap_or_else(|| self.base_fee())
+                .saturating_add(t.tx().max_priority_fee_per_gas),
             TypedTransaction::Deposit(_) => 0_u128,
         };
 
@@ -2068,6 +3674,7 @@ impl Backend {
             TypedReceipt::Legacy(_) => TypedReceipt::Legacy(receipt_with_bloom),
             TypedReceipt::EIP2930(_) => TypedReceipt::EIP2930(receipt_with_bloom),
             TypedReceipt::EIP4844(_) => TypedReceipt::EIP4844(receipt_with_bloom),
+            TypedReceipt::EIP7702(_) => TypedReceipt::EIP7702(receipt_with_bloom),
             TypedReceipt::Deposit(r) => TypedReceipt::Deposit(DepositReceipt {
                 inner: receipt_with_bloom,
                 deposit_nonce: r.deposit_nonce,
@@ -2268,18 +3875,45 @@ impl Backend {
         rx
     }
 
+    /// Returns a new stream of blocks that were removed from the chain
+    pub fn removed_block_notifications(&self) -> RemovedBlockNotifications {
+        let (tx, rx) = unbounded();
+        self.removed_block_listeners.lock().push(tx);
+        trace!(target: "backed", "added removed block listener");
+        rx
+    }
+
     /// Notifies all `new_block_listeners` about the new block
-    fn notify_on_new_block(&self, header: Header, hash: B256) {
+    fn notify_on_new_block(&self, header: Header, hash: B256, state_diff: StateDiff) {
         // cleanup closed notification streams first, if the channel is closed we can remove the
         // sender half for the set
         self.new_block_listeners.lock().retain(|tx| !tx.is_closed());
 
-        let notification = NewBlockNotification { hash, header: Arc::new(header) };
+        let notification = NewBlockNotification {
+            hash,
+            header: Arc::new(header),
+            state_diff: Arc::new(state_diff),
+        };
 
         self.new_block_listeners
             .lock()
             .retain(|tx| tx.unbounded_send(notification.clone()).is_ok());
     }
+
+    /// Notifies all `removed_block_listeners` about the removed block
+    fn notify_on_removed_block(&self, block: Block, receipts: Vec<TypedReceipt>) {
+        self.removed_block_listeners.lock().retain(|tx| !tx.is_closed());
+
+        let notification = RemovedBlockNotification {
+            hash: block.header.hash_slow(),
+            block: Arc::new(block),
+            receipts: Arc::new(receipts),
+        };
+
+        self.removed_block_listeners
+            .lock()
+            .retain(|tx| tx.unbounded_send(notification.clone()).is_ok());
+    }
 }
 
 /// Get max nonce from transaction pool by address
@@ -2411,6 +4045,20 @@ impl TransactionValidator for Backend {
             }
         }
 
+        // EIP-7702 Prague hard fork validation steps
+        if let TypedTransaction::EIP7702(tx) = &tx.transaction {
+            if tx.tx().authorization_list.is_empty() {
+                return Err(InvalidTransactionError::EmptyAuthorizationList)
+            }
+
+            // the gas limit must also cover the intrinsic gas of the authorizations
+            let authorization_gas = tx.tx().authorization_gas() as u128;
+            if tx.tx().gas_limit < MIN_TRANSACTION_GAS + authorization_gas {
+                warn!(target: "backend", "[{:?}] gas too low for authorization list", tx.hash());
+                return Err(InvalidTransactionError::GasTooLow)
+            }
+        }
+
         let max_cost = tx.max_cost();
         let value = tx.value();
         // check sufficient funds: `gas * price + value`
@@ -2531,3 +4179,21 @@ pub fn prove_storage(storage: &HashMap<U256, U256>, keys: &[B256]) -> Vec<Vec<By
 
     proofs
 }
+
+/// Returns the `from` and `to` address of the trace, as matched by `trace_filter`
+fn trace_addresses(trace: &TransactionTrace) -> (Address, Option<Address>) {
+    match &trace.action {
+        Action::Call(call) => (call.from, Some(call.to)),
+        Action::Create(create) => {
+            let created = match &trace.result {
+                Some(TraceOutput::Create(output)) => Some(output.address),
+                _ => None,
+            };
+            (create.from, created)
+        }
+        Action::Selfdestruct(selfdestruct) => {
+            (selfdestruct.address, Some(selfdestruct.refund_address))
+        }
+        Action::Reward(reward) => (reward.author, None),
+    }
+}
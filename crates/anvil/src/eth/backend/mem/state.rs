@@ -3,13 +3,13 @@
 use crate::eth::error::BlockchainError;
 use alloy_primitives::{keccak256, Address, B256, U256};
 use alloy_rlp::Encodable;
-use alloy_rpc_types::state::StateOverride;
+use alloy_rpc_types::{state::StateOverride, BlockOverrides};
 use alloy_trie::{HashBuilder, Nibbles};
 use foundry_evm::{
     backend::DatabaseError,
     revm::{
         db::{CacheDB, DatabaseRef, DbAccount},
-        primitives::{AccountInfo, Bytecode, HashMap},
+        primitives::{AccountInfo, BlockEnv, Bytecode, HashMap},
     },
 };
 
@@ -70,15 +70,35 @@ pub fn trie_account_rlp(info: &AccountInfo, storage: &HashMap<U256, U256>) -> Ve
     out
 }
 
-/// Applies the given state overrides to the state, returning a new CacheDB state
-pub fn apply_state_override<D>(
-    overrides: StateOverride,
+/// Applies the given state and block overrides to a copy of the state and to the block env,
+/// returning the overridden state
+pub fn apply_overrides<D>(
     state: D,
+    block_env: &mut BlockEnv,
+    state_overrides: Option<StateOverride>,
+    block_overrides: Option<BlockOverrides>,
 ) -> Result<CacheDB<D>, BlockchainError>
 where
     D: DatabaseRef<Error = DatabaseError>,
 {
     let mut cache_db = CacheDB::new(state);
+    if let Some(overrides) = state_overrides {
+        apply_cached_db_state_override(overrides, &mut cache_db)?;
+    }
+    if let Some(overrides) = block_overrides {
+        apply_block_overrides(overrides, &mut cache_db, block_env);
+    }
+    Ok(cache_db)
+}
+
+/// Applies the given state overrides to the given CacheDB
+pub fn apply_cached_db_state_override<D>(
+    overrides: StateOverride,
+    cache_db: &mut CacheDB<D>,
+) -> Result<(), BlockchainError>
+where
+    D: DatabaseRef<Error = DatabaseError>,
+{
     for (account, account_overrides) in overrides.iter() {
         let mut account_info = cache_db.basic_ref(*account)?.unwrap_or_default();
 
@@ -120,5 +140,52 @@ where
             }
         };
     }
-    Ok(cache_db)
+    Ok(())
+}
+
+/// Applies the given block overrides to the block env and the block hashes of the CacheDB
+pub fn apply_block_overrides<D>(
+    overrides: BlockOverrides,
+    cache_db: &mut CacheDB<D>,
+    env: &mut BlockEnv,
+) {
+    let BlockOverrides {
+        number,
+        difficulty,
+        time,
+        gas_limit,
+        coinbase,
+        random,
+        base_fee,
+        block_hash,
+    } = overrides;
+
+    if let Some(block_hashes) = block_hash {
+        // override block hashes
+        cache_db
+            .block_hashes
+            .extend(block_hashes.into_iter().map(|(num, hash)| (U256::from(num), hash)))
+    }
+
+    if let Some(number) = number {
+        env.number = number;
+    }
+    if let Some(difficulty) = difficulty {
+        env.difficulty = difficulty;
+    }
+    if let Some(time) = time {
+        env.timestamp = U256::from(time);
+    }
+    if let Some(gas_limit) = gas_limit {
+        env.gas_limit = U256::from(gas_limit);
+    }
+    if let Some(coinbase) = coinbase {
+        env.coinbase = coinbase;
+    }
+    if let Some(random) = random {
+        env.prevrandao = Some(random);
+    }
+    if let Some(base_fee) = base_fee {
+        env.basefee = base_fee;
+    }
 }
@@ -1,7 +1,7 @@
 //! In-memory blockchain storage
 use crate::eth::{
     backend::{
-        db::{MaybeFullDatabase, StateDb},
+        db::{MaybeFullDatabase, SerializableHistory, StateDb},
         mem::cache::DiskStateCache,
     },
     pool::transactions::PoolTransaction,
@@ -9,7 +9,7 @@ use crate::eth::{
 use alloy_primitives::{Bytes, TxHash, B256, U256, U64};
 use alloy_rpc_types::{BlockId, BlockNumberOrTag, TransactionInfo as RethTransactionInfo};
 use alloy_rpc_types_trace::{
-    geth::{DefaultFrame, GethDefaultTracingOptions},
+    geth::{CallConfig, CallFrame, DefaultFrame, GethDefaultTracingOptions},
     parity::LocalizedTransactionTrace,
 };
 use anvil_core::eth::{
@@ -294,6 +294,91 @@ impl BlockchainStorage {
             block.transactions.clear();
         }
     }
+
+    /// Removes all blocks above the given block, together with their transactions, and makes it
+    /// the new best block.
+    ///
+    /// Returns the removed blocks and their receipts, starting with the highest block.
+    pub fn unwind_to(
+        &mut self,
+        block_number: u64,
+        block_hash: B256,
+    ) -> Vec<(Block, Vec<TypedReceipt>)> {
+        let mut removed = Vec::new();
+        for n in ((block_number + 1)..=self.best_number.to::<u64>()).rev() {
+            trace!(target: "backend", "unwinding block {}", n);
+            if let Some(hash) = self.hashes.remove(&U64::from(n)) {
+                if let Some(block) = self.blocks.remove(&hash) {
+                    let receipts = block
+                        .transactions
+                        .iter()
+                        .filter_map(|tx| self.transactions.remove(&tx.hash()))
+                        .map(|tx| tx.receipt)
+                        .collect();
+                    self.total_difficulty =
+                        self.total_difficulty.saturating_sub(block.header.difficulty);
+                    removed.push((block, receipts));
+                }
+            }
+        }
+
+        self.best_number = U64::from(block_number);
+        self.best_hash = block_hash;
+        removed
+    }
+
+    /// Returns all blocks and transactions of the chain in a serializable form
+    pub fn serialized_history(&self) -> SerializableHistory {
+        let mut blocks = self.blocks.values().cloned().collect::<Vec<_>>();
+        blocks.sort_unstable_by_key(|block| block.header.number);
+        let transactions = blocks
+            .iter()
+            .flat_map(|block| block.transactions.iter())
+            .filter_map(|tx| self.transactions.get(&tx.hash()).cloned())
+            .map(Into::into)
+            .collect();
+
+        SerializableHistory {
+            version: SerializableHistory::VERSION,
+            best_block_number: self.best_number.to::<u64>(),
+            total_difficulty: self.total_difficulty,
+            blocks: blocks.into_iter().map(Into::into).collect(),
+            transactions,
+        }
+    }
+
+    /// Inserts all blocks and transactions of the given history and makes its best block the best
+    /// block of the chain, existing blocks above it are removed.
+    pub fn load_history(&mut self, history: SerializableHistory) {
+        for block in history.blocks {
+            let block = Block::from(block);
+            let hash = block.header.hash_slow();
+            if block.header.number == 0 {
+                self.genesis_hash = hash;
+            }
+            // replace the block that was previously stored at this height
+            if let Some(replaced) = self.hashes.insert(U64::from(block.header.number), hash) {
+                if replaced != hash {
+                    self.remove_block_transactions(replaced);
+                    self.blocks.remove(&replaced);
+                }
+            }
+            self.blocks.insert(hash, block);
+        }
+        for tx in history.transactions {
+            self.transactions.insert(tx.info.transaction_hash, tx.into());
+        }
+
+        let best_number = history.best_block_number;
+        if let Some(best_hash) = self.hashes.get(&U64::from(best_number)).copied() {
+            if self.best_number > U64::from(best_number) {
+                self.unwind_to(best_number, best_hash);
+            }
+            self.best_number = U64::from(best_number);
+            self.best_hash = best_hash;
+        }
+        self.total_difficulty = history.total_difficulty;
+    }
 }
 
 // === impl BlockchainStorage ===
@@ -422,6 +507,12 @@ impl MinedTransaction {
                 opts,
             )
     }
+
+    /// Returns the `callTracer` frame of the transaction for `debug_traceTransaction`
+    pub fn geth_call_trace(&self, config: CallConfig) -> CallFrame {
+        GethTraceBuilder::new(self.info.traces.clone(), TracingInspectorConfig::default_parity())
+            .geth_call_traces(config, self.info.gas_used as u64)
+    }
 }
 
 /// Intermediary Anvil representation of a receipt
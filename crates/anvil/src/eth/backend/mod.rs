@@ -8,6 +8,8 @@ pub mod mem;
 pub mod cheats;
 pub mod time;
 
+pub mod block_builder;
+pub mod bundle;
 pub mod executor;
 pub mod fork;
 pub mod genesis;
@@ -2,6 +2,7 @@
 
 use alloy_consensus::Header;
 use alloy_primitives::B256;
+use anvil_core::eth::{block::Block, subscription::StateDiff, transaction::TypedReceipt};
 use futures::channel::mpsc::UnboundedReceiver;
 use std::sync::Arc;
 
@@ -12,7 +13,23 @@ pub struct NewBlockNotification {
     pub hash: B256,
     /// block header
     pub header: Arc<Header>,
+    /// The state changes of the block
+    pub state_diff: Arc<StateDiff>,
 }
 
 /// Type alias for a receiver that receives [NewBlockNotification]
 pub type NewBlockNotifications = UnboundedReceiver<NewBlockNotification>;
+
+/// A notification that's emitted when a block was removed from the chain, e.g. by a reorg
+#[derive(Clone, Debug)]
+pub struct RemovedBlockNotification {
+    /// Hash of the removed block
+    pub hash: B256,
+    /// the removed block
+    pub block: Arc<Block>,
+    /// receipts of the transactions of the removed block
+    pub receipts: Arc<Vec<TypedReceipt>>,
+}
+
+/// Type alias for a receiver that receives [RemovedBlockNotification]
+pub type RemovedBlockNotifications = UnboundedReceiver<RemovedBlockNotification>;
@@ -39,6 +39,17 @@ impl TimeManager {
         time_manager
     }
 
+    /// Creates a new instance that uses the given clock instead of starting at `start_timestamp`,
+    /// the timestamps of new blocks are still greater than `start_timestamp`
+    pub fn with_clock(start_timestamp: u64, clock: SharedClock) -> TimeManager {
+        TimeManager {
+            last_timestamp: Arc::new(RwLock::new(start_timestamp)),
+            offset: clock.offset,
+            next_exact_timestamp: Default::default(),
+            interval: Default::default(),
+        }
+    }
+
     /// Resets the current time manager to the given timestamp, resetting the offsets and
     /// next block timestamp option
     pub fn reset(&self, start_timestamp: u64) {
@@ -140,6 +151,25 @@ impl TimeManager {
     }
 }
 
+/// A clock that can be shared by several nodes, so that time moves the same way on all of them.
+///
+/// Changing the time of one node, for example via `evm_increaseTime` or `evm_setTime`, changes it
+/// for all nodes that share the clock.
+#[derive(Clone, Debug, Default)]
+pub struct SharedClock {
+    /// The offset to the system time, see [TimeManager::offset]
+    offset: Arc<RwLock<i128>>,
+}
+
+impl SharedClock {
+    /// Creates a clock that starts at the given timestamp, or at the current time if `None`
+    pub fn new(start_timestamp: Option<u64>) -> Self {
+        let offset = start_timestamp
+            .map_or(0, |start| (start as i128) - duration_since_unix_epoch().as_secs() as i128);
+        Self { offset: Arc::new(RwLock::new(offset)) }
+    }
+}
+
 /// Returns the current duration since unix epoch.
 pub fn duration_since_unix_epoch() -> Duration {
     use std::time::SystemTime;
@@ -0,0 +1,284 @@
+//! A subset of the engine API that lets an external client drive block production
+//!
+//! Anvil only keeps the canonical chain: a payload is validated against the state of its parent
+//! and only becomes the head if it is valid, setting the head to an older block via
+//! `engine_forkchoiceUpdatedV3` rolls the chain back to it.
+
+use crate::eth::{
+    backend::mem::BlockAttributes,
+    error::{BlockchainError, Result},
+    macros::node_info,
+    pool::transactions::{to_marker, PoolTransaction},
+    EthApi,
+};
+use alloy_consensus::{transaction::eip4844::TxEip4844Variant, Signed};
+use alloy_eips::eip2718::{Decodable2718, Encodable2718};
+use alloy_primitives::{keccak256, Bytes, B256, U256};
+use alloy_rpc_types_engine::{
+    BlobsBundleV1, ExecutionPayloadEnvelopeV3, ExecutionPayloadV1, ExecutionPayloadV2,
+    ExecutionPayloadV3, ForkchoiceState, ForkchoiceUpdated, PayloadAttributes, PayloadId,
+    PayloadStatus, PayloadStatusEnum,
+};
+use anvil_core::eth::{
+    block::{Block, BlockInfo},
+    transaction::{PendingTransaction, TypedTransaction},
+};
+use anvil_rpc::error::{ErrorCode, RpcError};
+use std::sync::Arc;
+
+/// Error code of an unknown payload id
+const UNKNOWN_PAYLOAD: i64 = -38001;
+/// Error code of invalid payload attributes
+const INVALID_PAYLOAD_ATTRIBUTES: i64 = -38003;
+
+impl EthApi {
+    /// Sets the head of the chain to the given block and starts building a payload on top of it if
+    /// attributes are given.
+    ///
+    /// The safe and finalized blocks are derived from the head, like for all other blocks.
+    ///
+    /// Handler for RPC call: `engine_forkchoiceUpdatedV3`
+    pub async fn engine_forkchoice_updated_v3(
+        &self,
+        state: ForkchoiceState,
+        attributes: Option<PayloadAttributes>,
+    ) -> Result<ForkchoiceUpdated> {
+        node_info!("engine_forkchoiceUpdatedV3");
+        let head = state.head_block_hash;
+        let Some(block) = self.backend.get_block_by_hash(head) else {
+            return Ok(ForkchoiceUpdated::new(PayloadStatus::from_status(
+                PayloadStatusEnum::Syncing,
+            )))
+        };
+        // all stored blocks are canonical, the blocks above the new head are removed
+        let unwound =
+            self.backend.rollback(self.backend.best_number() - block.header.number).await?;
+        self.add_unwound_transactions(unwound).await;
+
+        let status = PayloadStatus::new(PayloadStatusEnum::Valid, Some(head));
+        let Some(attributes) = attributes else { return Ok(ForkchoiceUpdated::new(status)) };
+
+        if attributes.timestamp <= block.header.timestamp {
+            return Err(engine_error(
+                INVALID_PAYLOAD_ATTRIBUTES,
+                "timestamp must be greater than the timestamp of the head",
+            ))
+        }
+        if attributes.withdrawals.as_ref().is_some_and(|withdrawals| !withdrawals.is_empty()) {
+            return Err(engine_error(INVALID_PAYLOAD_ATTRIBUTES, "withdrawals are not supported"))
+        }
+        let attributes = BlockAttributes {
+            timestamp: attributes.timestamp,
+            coinbase: attributes.suggested_fee_recipient,
+            prevrandao: attributes.prev_randao,
+            parent_beacon_block_root: attributes.parent_beacon_block_root,
+        };
+        let id = payload_id(head, &attributes);
+        self.payloads.lock().insert(id, (head, attributes));
+
+        Ok(ForkchoiceUpdated::new(status).with_payload_id(id))
+    }
+
+    /// Builds the payload that was started with `engine_forkchoiceUpdatedV3` from the ready
+    /// transactions of the pool.
+    ///
+    /// Handler for RPC call: `engine_getPayloadV3`
+    pub async fn engine_get_payload_v3(&self, id: PayloadId) -> Result<ExecutionPayloadEnvelopeV3> {
+        node_info!("engine_getPayloadV3");
+        let Some((parent, attributes)) = self.payloads.lock().get(&id).copied() else {
+            return Err(engine_error(UNKNOWN_PAYLOAD, "Unknown payload"))
+        };
+        if parent != self.backend.best_hash() {
+            return Err(engine_error(UNKNOWN_PAYLOAD, "the head changed since the payload started"))
+        }
+
+        let transactions = self.pool.ready_transactions().collect::<Vec<_>>();
+        let transactions = self.backend.order_block_transactions(transactions, false);
+        let BlockInfo { block, transactions, .. } =
+            self.backend.build_block(transactions, &attributes).await.block;
+
+        let base_fee = block.header.base_fee_per_gas.unwrap_or_default();
+        let block_value = block
+            .transactions
+            .iter()
+            .zip(&transactions)
+            .map(|(tx, info)| U256::from(priority_fee(tx, base_fee) * info.gas_used))
+            .sum();
+        let mut blobs_bundle = BlobsBundleV1 { commitments: vec![], proofs: vec![], blobs: vec![] };
+        for sidecar in block.transactions.iter().filter_map(|tx| tx.sidecar()) {
+            blobs_bundle.commitments.extend_from_slice(&sidecar.commitments);
+            blobs_bundle.proofs.extend_from_slice(&sidecar.proofs);
+            blobs_bundle.blobs.extend_from_slice(&sidecar.blobs);
+        }
+
+        Ok(ExecutionPayloadEnvelopeV3 {
+            execution_payload: execution_payload(&block),
+            block_value,
+            blobs_bundle,
+            should_override_builder: false,
+        })
+    }
+
+    /// Executes the payload on top of its parent and makes it the head of the chain if it is
+    /// valid.
+    ///
+    /// Blob transactions are included with their sidecars if they are in the pool.
+    ///
+    /// Handler for RPC call: `engine_newPayloadV3`
+    pub async fn engine_new_payload_v3(
+        &self,
+        payload: ExecutionPayloadV3,
+        versioned_hashes: Vec<B256>,
+        parent_beacon_block_root: B256,
+    ) -> Result<PayloadStatus> {
+        node_info!("engine_newPayloadV3");
+        let inner = &payload.payload_inner.payload_inner;
+        let block_hash = inner.block_hash;
+        if self.backend.get_block_by_hash(block_hash).is_some() {
+            return Ok(PayloadStatus::new(PayloadStatusEnum::Valid, Some(block_hash)))
+        }
+        let Some(parent) = self.backend.get_block_by_hash(inner.parent_hash) else {
+            return Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing))
+        };
+        let invalid = |validation_error: String| {
+            PayloadStatus::new(
+                PayloadStatusEnum::Invalid { validation_error },
+                Some(inner.parent_hash),
+            )
+        };
+        if !payload.payload_inner.withdrawals.is_empty() {
+            return Ok(invalid("withdrawals are not supported".to_string()))
+        }
+
+        let mut transactions = Vec::with_capacity(inner.transactions.len());
+        for encoded in &inner.transactions {
+            let tx = match TypedTransaction::decode_2718(&mut encoded.as_ref()) {
+                Ok(tx) => tx,
+                Err(err) => return Ok(invalid(format!("invalid transaction: {err}"))),
+            };
+            // payloads don't carry the sidecars of blob transactions
+            let pending = match self.pool.get_transaction(tx.hash()) {
+                Some(pending) => pending,
+                None => PendingTransaction::new(tx)?,
+            };
+            transactions.push(Arc::new(PoolTransaction {
+                requires: vec![],
+                provides: vec![to_marker(pending.nonce(), *pending.sender())],
+                pending_transaction: pending,
+                priority: Default::default(),
+            }));
+        }
+        let blob_hashes = transactions
+            .iter()
+            .filter_map(|tx| tx.pending_transaction.transaction.essentials().blob_versioned_hashes)
+            .flatten()
+            .collect::<Vec<_>>();
+        if blob_hashes != versioned_hashes {
+            return Ok(invalid("blob versioned hashes do not match".to_string()))
+        }
+
+        let attributes = BlockAttributes {
+            timestamp: inner.timestamp,
+            coinbase: inner.fee_recipient,
+            prevrandao: inner.prev_randao,
+            parent_beacon_block_root: Some(parent_beacon_block_root),
+        };
+        // validate the payload on top of the state of its parent before the chain is changed
+        let Some(executed) =
+            self.backend.build_block_on(inner.parent_hash, transactions.clone(), &attributes).await
+        else {
+            return Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing))
+        };
+        if executed.included.len() != transactions.len() {
+            return Ok(invalid("the payload contains invalid transactions".to_string()))
+        }
+        let hash = executed.block.block.header.hash_slow();
+        if hash != block_hash {
+            return Ok(invalid(format!("block hash mismatch: expected {block_hash}, got {hash}")))
+        }
+
+        // anvil keeps no side chains, the payload replaces the blocks above its parent
+        let unwound =
+            self.backend.rollback(self.backend.best_number() - parent.header.number).await?;
+        self.add_unwound_transactions(unwound).await;
+
+        let outcome = match self.backend.mine_block_with_attributes(transactions, &attributes).await
+        {
+            Ok(outcome) => outcome,
+            Err(err) => return Ok(invalid(err.to_string())),
+        };
+        self.pool.on_mined_block(outcome);
+
+        Ok(PayloadStatus::new(PayloadStatusEnum::Valid, Some(block_hash)))
+    }
+}
+
+/// Returns the id of the payload with the given parent and attributes
+fn payload_id(parent: B256, attributes: &BlockAttributes) -> PayloadId {
+    let mut buf = parent.to_vec();
+    buf.extend_from_slice(&attributes.timestamp.to_be_bytes());
+    buf.extend_from_slice(attributes.prevrandao.as_slice());
+    buf.extend_from_slice(attributes.coinbase.as_slice());
+    if let Some(root) = attributes.parent_beacon_block_root {
+        buf.extend_from_slice(root.as_slice());
+    }
+    let hash = keccak256(buf);
+    PayloadId::new(hash[..8].try_into().unwrap())
+}
+
+/// Returns the priority fee per gas the block producer receives for the transaction
+fn priority_fee(tx: &TypedTransaction, base_fee: u128) -> u128 {
+    let max_fee = tx.gas_price().saturating_sub(base_fee);
+    match tx.essentials().max_priority_fee_per_gas {
+        Some(max_priority_fee) => max_fee.min(max_priority_fee.to()),
+        None => max_fee,
+    }
+}
+
+/// Converts the block into an execution payload
+fn execution_payload(block: &Block) -> ExecutionPayloadV3 {
+    let header = &block.header;
+    ExecutionPayloadV3 {
+        payload_inner: ExecutionPayloadV2 {
+            payload_inner: ExecutionPayloadV1 {
+                parent_hash: header.parent_hash,
+                fee_recipient: header.beneficiary,
+                state_root: header.state_root,
+                receipts_root: header.receipts_root,
+                logs_bloom: header.logs_bloom,
+                prev_randao: header.mix_hash,
+                block_number: header.number,
+                gas_limit: header.gas_limit as u64,
+                gas_used: header.gas_used as u64,
+                timestamp: header.timestamp,
+                extra_data: header.extra_data.clone(),
+                base_fee_per_gas: U256::from(header.base_fee_per_gas.unwrap_or_default()),
+                block_hash: header.hash_slow(),
+                transactions: block.transactions.iter().map(|tx| payload_transaction(tx)).collect(),
+            },
+            withdrawals: vec![],
+        },
+        blob_gas_used: header.blob_gas_used.unwrap_or_default() as u64,
+        excess_blob_gas: header.excess_blob_gas.unwrap_or_default() as u64,
+    }
+}
+
+/// Encodes the transaction for an execution payload, blob transactions without their sidecar
+fn payload_transaction(tx: &TypedTransaction) -> Bytes {
+    if let TypedTransaction::EIP4844(signed) = tx {
+        if let TxEip4844Variant::TxEip4844WithSidecar(with_sidecar) = signed.tx() {
+            let tx = Signed::new_unchecked(
+                TxEip4844Variant::TxEip4844(with_sidecar.tx().clone()),
+                *signed.signature(),
+                *signed.hash(),
+            );
+            return TypedTransaction::EIP4844(tx).encoded_2718().into()
+        }
+    }
+    tx.encoded_2718().into()
+}
+
+fn engine_error(code: i64, message: impl Into<String>) -> BlockchainError {
+    RpcError { code: ErrorCode::ServerError(code), message: message.into().into(), data: None }
+        .into()
+}
@@ -85,6 +85,8 @@ pub enum BlockchainError {
     EIP2930TransactionUnsupportedAtHardfork,
     #[error("EIP-4844 fields received but is not supported by the current hardfork.\n\nYou can use it by running anvil with '--hardfork cancun' or later.")]
     EIP4844TransactionUnsupportedAtHardfork,
+    #[error("EIP-7702 authorization list received but is not supported by the current hardfork.\n\nYou can use it by running anvil with '--hardfork prague' or later.")]
+    EIP7702TransactionUnsupportedAtHardfork,
     #[error("op-stack deposit tx received but is not supported.\n\nYou can use it by running anvil with '--optimism'.")]
     DepositTransactionUnsupported,
     #[error("Excess blob gas not set.")]
@@ -126,6 +128,12 @@ pub enum PoolError {
     ReplacementUnderpriced(Box<PoolTransaction>),
     #[error("Tx: [{0:?}] already Imported")]
     AlreadyImported(Box<PoolTransaction>),
+    /// Thrown if the sender already has the maximum number of transactions in the pool
+    #[error("Tx: [{0:?}] exceeds the account slots of the sender")]
+    AccountSlotsExceeded(Box<PoolTransaction>),
+    /// Thrown if the pool is full and the transaction is too cheap to evict another one
+    #[error("Tx: [{0:?}] pool is full")]
+    PoolFull(Box<PoolTransaction>),
 }
 
 /// Errors that can occur with `eth_feeHistory`
@@ -228,6 +236,9 @@ pub enum InvalidTransactionError {
     /// Thrown when there are no `blob_hashes` in the transaction.
     #[error("There should be at least one blob in a Blob transaction.")]
     EmptyBlobs,
+    /// Thrown when an EIP-7702 transaction has an empty authorization list.
+    #[error("EIP-7702 transaction must have at least one authorization")]
+    EmptyAuthorizationList,
 }
 
 impl From<revm::primitives::InvalidTransaction> for InvalidTransactionError {
@@ -324,6 +335,10 @@ impl<T: Serialize> ToRpcResponseResult for Result<T> {
                         PoolError::AlreadyImported(_) => {
                             RpcError::transaction_rejected("transaction already imported")
                         }
+                        PoolError::AccountSlotsExceeded(_) => {
+                            RpcError::transaction_rejected("account limit exceeded")
+                        }
+                        PoolError::PoolFull(_) => RpcError::transaction_rejected("txpool is full"),
                     }
                 }
                 BlockchainError::NoSignerAvailable => {
@@ -443,6 +458,9 @@ impl<T: Serialize> ToRpcResponseResult for Result<T> {
                 err @ BlockchainError::EIP4844TransactionUnsupportedAtHardfork => {
                     RpcError::invalid_params(err.to_string())
                 }
+                err @ BlockchainError::EIP7702TransactionUnsupportedAtHardfork => {
+                    RpcError::invalid_params(err.to_string())
+                }
                 err @ BlockchainError::DepositTransactionUnsupported => {
                     RpcError::invalid_params(err.to_string())
                 }
@@ -41,10 +41,30 @@ use alloy_rpc_types::txpool::TxpoolStatus;
 use anvil_core::eth::transaction::PendingTransaction;
 use futures::channel::mpsc::{channel, Receiver, Sender};
 use parking_lot::{Mutex, RwLock};
-use std::{collections::VecDeque, fmt, sync::Arc};
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 pub mod transactions;
 
+/// The replacement rules and limits of the pool
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// The minimum increase in percent of both the max fee and the priority fee to replace a
+    /// transaction with the same nonce
+    pub price_bump: u64,
+    /// The maximum number of transactions per account
+    pub account_slots: Option<usize>,
+    /// The maximum number of transactions in the pool, the cheapest transactions are evicted if
+    /// the pool is full
+    pub global_slots: Option<usize>,
+    /// How long a transaction that is not ready can stay in the pool
+    pub queue_lifetime: Option<Duration>,
+}
+
 /// Transaction pool that performs validation.
 #[derive(Default)]
 pub struct Pool {
@@ -57,6 +77,19 @@ pub struct Pool {
 // == impl Pool ==
 
 impl Pool {
+    /// Creates a new pool with the given replacement rules and limits
+    pub fn new(config: PoolConfig) -> Self {
+        Self {
+            inner: RwLock::new(PoolInner { config, ..Default::default() }),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the replacement rules and limits of the pool
+    pub fn config(&self) -> PoolConfig {
+        self.inner.read().config
+    }
+
     /// Returns an iterator that yields all transactions that are currently ready
     pub fn ready_transactions(&self) -> TransactionsIterator {
         self.inner.read().ready_transactions()
@@ -67,6 +100,19 @@ impl Pool {
         self.inner.read().pending_transactions.transactions().collect()
     }
 
+    /// Returns all transactions of the given sender, split into those that are ready and those
+    /// that are not ready yet
+    pub fn transactions_by_sender(
+        &self,
+        sender: Address,
+    ) -> (Vec<Arc<PoolTransaction>>, Vec<Arc<PoolTransaction>>) {
+        let inner = self.inner.read();
+        let is_sender = |tx: &Arc<PoolTransaction>| *tx.pending_transaction.sender() == sender;
+        let ready = inner.ready_transactions().filter(is_sender).collect();
+        let pending = inner.pending_transactions.transactions().filter(is_sender).collect();
+        (ready, pending)
+    }
+
     /// Returns the _pending_ transaction for that `hash` if it exists in the mempool
     pub fn get_transaction(&self, hash: TxHash) -> Option<PendingTransaction> {
         self.inner.read().get_transaction(hash)
@@ -86,8 +132,9 @@ impl Pool {
     pub fn on_mined_block(&self, outcome: MinedBlockOutcome) -> PruneResult {
         let MinedBlockOutcome { block_number, included, invalid } = outcome;
 
-        // remove invalid transactions from the pool
+        // remove invalid and expired transactions from the pool
         self.remove_invalid(invalid.into_iter().map(|tx| tx.hash()).collect());
+        self.inner.write().remove_expired(Instant::now());
 
         // prune all the markers the mined transactions provide
         let res = self
@@ -111,7 +158,7 @@ impl Pool {
 
     /// Adds a new transaction to the pool
     pub fn add_transaction(&self, tx: PoolTransaction) -> Result<AddedTransaction, PoolError> {
-        let added = self.inner.write().add_transaction(tx)?;
+        let added = self.add_transaction_silently(tx)?;
         if let AddedTransaction::Ready(ref ready) = added {
             self.notify_listener(ready.hash);
             // also notify promoted transactions
@@ -122,6 +169,17 @@ impl Pool {
         Ok(added)
     }
 
+    /// Adds a transaction to the pool without notifying the ready listeners, so that it does not
+    /// trigger mining, e.g. a transaction of a block that was removed from the chain
+    pub fn add_transaction_silently(
+        &self,
+        tx: PoolTransaction,
+    ) -> Result<AddedTransaction, PoolError> {
+        let mut inner = self.inner.write();
+        inner.remove_expired(Instant::now());
+        inner.add_transaction(tx)
+    }
+
     /// Adds a new transaction listener to the pool that gets notified about every new ready
     /// transaction
     pub fn add_ready_listener(&self) -> Receiver<TxHash> {
@@ -207,6 +265,7 @@ impl Pool {
 struct PoolInner {
     ready_transactions: ReadyTransactions,
     pending_transactions: PendingTransactions,
+    config: PoolConfig,
 }
 
 // == impl PoolInner ==
@@ -264,18 +323,94 @@ impl PoolInner {
             return Err(PoolError::AlreadyImported(Box::new(tx)))
         }
 
+        // replacements don't take up another slot
+        if !self.replaces(&tx) {
+            self.ensure_slot(&tx)?;
+        }
+
         let tx = PendingPoolTransaction::new(tx, self.ready_transactions.provided_markers());
         trace!(target: "txpool", "[{:?}] {:?}", tx.transaction.hash(), tx);
 
         // If all markers are not satisfied import to future
         if !tx.is_ready() {
             let hash = tx.transaction.hash();
-            self.pending_transactions.add_transaction(tx)?;
+            self.pending_transactions.add_transaction(tx, self.config.price_bump)?;
             return Ok(AddedTransaction::Pending { hash })
         }
         self.add_ready_transaction(tx)
     }
 
+    /// Returns true if the transaction replaces a transaction in the pool
+    fn replaces(&self, tx: &PoolTransaction) -> bool {
+        self.pending_transactions.provides(&tx.provides) ||
+            tx.provides
+                .iter()
+                .any(|marker| self.ready_transactions.provided_markers().contains_key(marker))
+    }
+
+    /// Makes sure there's a free slot for the new transaction.
+    ///
+    /// If the pool is full, the cheapest transaction is evicted together with the transactions
+    /// that depend on it, if it's cheaper than the new transaction.
+    fn ensure_slot(&mut self, tx: &PoolTransaction) -> Result<(), PoolError> {
+        if let Some(account_slots) = self.config.account_slots {
+            let sender = *tx.pending_transaction.sender();
+            if self.transactions_by_sender(sender).count() >= account_slots {
+                warn!(target: "txpool", "[{:?}] account slots of {:?} exceeded", tx.hash(), sender);
+                return Err(PoolError::AccountSlotsExceeded(Box::new(tx.clone())))
+            }
+        }
+
+        if let Some(global_slots) = self.config.global_slots {
+            let len = self.ready_transactions.len() + self.pending_transactions.len();
+            if len >= global_slots {
+                // prefer evicting transactions that are not ready yet
+                let cheapest = self
+                    .pending_transactions
+                    .transactions()
+                    .map(|tx| (false, tx))
+                    .chain(self.ready_transactions().map(|tx| (true, tx)))
+                    .min_by_key(|(is_ready, tx)| (tx.gas_price(), *is_ready));
+                match cheapest {
+                    Some((_, cheapest)) if cheapest.gas_price() < tx.gas_price() => {
+                        trace!(target: "txpool", "pool is full, evicting [{:?}] for [{:?}]", cheapest.hash(), tx.hash());
+                        // the later transactions of the sender depend on the evicted one
+                        let sender = *cheapest.pending_transaction.sender();
+                        let nonce = cheapest.pending_transaction.nonce();
+                        let evicted = self
+                            .transactions_by_sender(sender)
+                            .filter(|tx| tx.pending_transaction.nonce() >= nonce)
+                            .map(|tx| tx.hash())
+                            .collect();
+                        self.remove_invalid(evicted);
+                    }
+                    _ => {
+                        warn!(target: "txpool", "[{:?}] pool is full", tx.hash());
+                        return Err(PoolError::PoolFull(Box::new(tx.clone())))
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes the transactions that are not ready and have been in the pool longer than the
+    /// queue lifetime
+    fn remove_expired(&mut self, now: Instant) -> Vec<Arc<PoolTransaction>> {
+        let Some(deadline) =
+            self.config.queue_lifetime.and_then(|lifetime| now.checked_sub(lifetime))
+        else {
+            return vec![]
+        };
+        let expired = self.pending_transactions.added_before(deadline);
+        if expired.is_empty() {
+            return vec![]
+        }
+        trace!(target: "txpool", "Removing expired transactions: {:?}", expired);
+        self.pending_transactions.remove(expired)
+    }
+
     /// Adds the transaction to the ready queue
     fn add_ready_transaction(
         &mut self,
@@ -298,7 +433,7 @@ impl PoolInner {
 
             let current_hash = current_tx.transaction.hash();
             // try to add the transaction to the ready pool
-            match self.ready_transactions.add_transaction(current_tx) {
+            match self.ready_transactions.add_transaction(current_tx, self.config.price_bump) {
                 Ok(replaced_transactions) => {
                     if !is_new_tx {
                         ready.promoted.push(current_hash);
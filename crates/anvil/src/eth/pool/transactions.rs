@@ -1,4 +1,8 @@
-use crate::eth::{error::PoolError, util::hex_fmt_many};
+use crate::eth::{
+    backend::block_builder::{BlockBuilder, PoolOrder, RandomOrder, SenderOrder},
+    error::PoolError,
+    util::hex_fmt_many,
+};
 use alloy_primitives::{Address, TxHash};
 use anvil_core::eth::transaction::{PendingTransaction, TypedTransaction};
 use parking_lot::RwLock;
@@ -22,9 +26,10 @@ pub fn to_marker(nonce: u64, from: Address) -> TxMarker {
     data.to_vec()
 }
 
-/// Modes that determine the transaction ordering of the mempool
+/// Modes that determine the transaction ordering of the mempool and of new blocks
 ///
-/// This type controls the transaction order via the priority metric of a transaction
+/// This type controls the transaction order via the priority metric of a transaction and the
+/// [BlockBuilder] that orders the transactions of new blocks
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum TransactionOrder {
     /// Keep the pool transaction transactions sorted in the order they arrive.
@@ -35,6 +40,10 @@ pub enum TransactionOrder {
     /// This means that it prioritizes transactions based on the fees paid to the miner.
     #[default]
     Fees,
+    /// Builds blocks with the transactions sorted by sender address.
+    Sender,
+    /// Builds blocks with the transactions in a random order that is determined by the seed.
+    Random(u64),
 }
 
 // === impl TransactionOrder ===
@@ -43,8 +52,19 @@ impl TransactionOrder {
     /// Returns the priority of the transactions
     pub fn priority(&self, tx: &TypedTransaction) -> TransactionPriority {
         match self {
-            TransactionOrder::Fifo => TransactionPriority::default(),
             TransactionOrder::Fees => TransactionPriority(tx.gas_price()),
+            TransactionOrder::Fifo | TransactionOrder::Sender | TransactionOrder::Random(_) => {
+                TransactionPriority::default()
+            }
+        }
+    }
+
+    /// Returns the [BlockBuilder] that orders the transactions of new blocks
+    pub fn block_builder(&self) -> Arc<dyn BlockBuilder> {
+        match *self {
+            TransactionOrder::Fifo | TransactionOrder::Fees => Arc::new(PoolOrder),
+            TransactionOrder::Sender => Arc::new(SenderOrder),
+            TransactionOrder::Random(seed) => Arc::new(RandomOrder { seed }),
         }
     }
 }
@@ -57,12 +77,30 @@ impl FromStr for TransactionOrder {
         let order = match s.as_str() {
             "fees" => TransactionOrder::Fees,
             "fifo" => TransactionOrder::Fifo,
-            _ => return Err(format!("Unknown TransactionOrder: `{s}`")),
+            "sender" => TransactionOrder::Sender,
+            "random" => TransactionOrder::Random(0),
+            _ => match s.strip_prefix("random:") {
+                Some(seed) => TransactionOrder::Random(
+                    seed.parse().map_err(|_| format!("Invalid random order seed: `{seed}`"))?,
+                ),
+                None => return Err(format!("Unknown TransactionOrder: `{s}`")),
+            },
         };
         Ok(order)
     }
 }
 
+impl fmt::Display for TransactionOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionOrder::Fifo => f.write_str("fifo"),
+            TransactionOrder::Fees => f.write_str("fees"),
+            TransactionOrder::Sender => f.write_str("sender"),
+            TransactionOrder::Random(seed) => write!(f, "random:{seed}"),
+        }
+    }
+}
+
 /// Metric value for the priority of a transaction.
 ///
 /// The `TransactionPriority` determines the ordering of two transactions that have all their
@@ -95,6 +133,11 @@ impl PoolTransaction {
     pub fn gas_price(&self) -> u128 {
         self.pending_transaction.transaction.gas_price()
     }
+
+    /// Returns the max priority fee per gas of this transaction
+    pub fn max_priority_fee_per_gas(&self) -> u128 {
+        self.pending_transaction.transaction.max_priority_fee_per_gas()
+    }
 }
 
 impl fmt::Debug for PoolTransaction {
@@ -147,25 +190,34 @@ impl PendingTransactions {
     }
 
     /// Adds a transaction to Pending queue of transactions
-    pub fn add_transaction(&mut self, tx: PendingPoolTransaction) -> Result<(), PoolError> {
+    ///
+    /// A transaction that provides the same markers as a pending transaction replaces it if its
+    /// max fee and priority fee are both higher by at least `price_bump` percent.
+    pub fn add_transaction(
+        &mut self,
+        tx: PendingPoolTransaction,
+        price_bump: u64,
+    ) -> Result<(), PoolError> {
         assert!(!tx.is_ready(), "transaction must not be ready");
         assert!(
             !self.waiting_queue.contains_key(&tx.transaction.hash()),
             "transaction is already added"
         );
 
-        if let Some(replace) = self
-            .waiting_markers
-            .get(&tx.transaction.provides)
-            .and_then(|hash| self.waiting_queue.get(hash))
-        {
+        if let Some(replace) = self.waiting_markers.get(&tx.transaction.provides).copied() {
             // check if underpriced
-            if tx.transaction.gas_price() < replace.transaction.gas_price() {
+            if is_underpriced(
+                &tx.transaction,
+                &self.waiting_queue[&replace].transaction,
+                price_bump,
+            ) {
                 warn!(target: "txpool", "pending replacement transaction underpriced [{:?}]", tx.transaction.hash());
                 return Err(PoolError::ReplacementUnderpriced(Box::new(
                     tx.transaction.as_ref().clone(),
                 )))
             }
+            trace!(target: "txpool", "replacing pending transaction [{:?}] with higher gas price [{:?}]", replace, tx.transaction.hash());
+            self.remove(vec![replace]);
         }
 
         // add all missing markers
@@ -186,6 +238,20 @@ impl PendingTransactions {
         self.waiting_queue.contains_key(hash)
     }
 
+    /// Returns true if a transaction that provides the given markers is part of the queue
+    pub fn provides(&self, markers: &[TxMarker]) -> bool {
+        self.waiting_markers.contains_key(markers)
+    }
+
+    /// Returns the hashes of all transactions that were added before `deadline`
+    pub fn added_before(&self, deadline: Instant) -> Vec<TxHash> {
+        self.waiting_queue
+            .iter()
+            .filter(|(_, tx)| tx.added_at < deadline)
+            .map(|(hash, _)| *hash)
+            .collect()
+    }
+
     /// Returns the transaction for the hash if it's pending
     pub fn get(&self, hash: &TxHash) -> Option<&PendingPoolTransaction> {
         self.waiting_queue.get(hash)
@@ -391,6 +457,15 @@ impl ReadyTransactions {
         self.independent_transactions.clear();
     }
 
+    /// Returns the number of ready transactions
+    pub fn len(&self) -> usize {
+        self.ready_tx.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ready_tx.read().is_empty()
+    }
+
     /// Returns true if the transaction is part of the queue.
     pub fn contains(&self, hash: &TxHash) -> bool {
         self.ready_tx.read().contains_key(hash)
@@ -420,6 +495,7 @@ impl ReadyTransactions {
     pub fn add_transaction(
         &mut self,
         tx: PendingPoolTransaction,
+        price_bump: u64,
     ) -> Result<Vec<Arc<PoolTransaction>>, PoolError> {
         assert!(tx.is_ready(), "transaction must be ready",);
         assert!(
@@ -427,7 +503,7 @@ impl ReadyTransactions {
             "transaction already included"
         );
 
-        let (replaced_tx, unlocks) = self.replaced_transactions(&tx.transaction)?;
+        let (replaced_tx, unlocks) = self.replaced_transactions(&tx.transaction, price_bump)?;
 
         let id = self.next_id();
         let hash = tx.transaction.hash();
@@ -470,6 +546,7 @@ impl ReadyTransactions {
     fn replaced_transactions(
         &mut self,
         tx: &PoolTransaction,
+        price_bump: u64,
     ) -> Result<(Vec<Arc<PoolTransaction>>, Vec<TxHash>), PoolError> {
         // check if we are replacing transactions
         let remove_hashes: HashSet<_> =
@@ -492,7 +569,7 @@ impl ReadyTransactions {
                 // (addr + nonce) then we check for gas price
                 if to_remove.provides() == tx.provides {
                     // check if underpriced
-                    if tx.pending_transaction.transaction.gas_price() <= to_remove.gas_price() {
+                    if is_underpriced(tx, &to_remove.transaction.transaction, price_bump) {
                         warn!(target: "txpool", "ready replacement transaction underpriced [{:?}]", tx.hash());
                         return Err(PoolError::ReplacementUnderpriced(Box::new(tx.clone())))
                     } else {
@@ -644,6 +721,24 @@ impl ReadyTransactions {
     }
 }
 
+/// Returns true if the `new` transaction can't replace the `old` one because its max fee or its
+/// priority fee isn't higher by at least `price_bump` percent.
+///
+/// Without a bump, the max fee still has to increase.
+pub fn is_underpriced(new: &PoolTransaction, old: &PoolTransaction, price_bump: u64) -> bool {
+    if price_bump == 0 && new.gas_price() <= old.gas_price() {
+        return true
+    }
+    is_below_bump(new.gas_price(), old.gas_price(), price_bump) ||
+        is_below_bump(new.max_priority_fee_per_gas(), old.max_priority_fee_per_gas(), price_bump)
+}
+
+/// Returns true if `new_price` isn't higher than `old_price` by at least `price_bump` percent
+fn is_below_bump(new_price: u128, old_price: u128, price_bump: u64) -> bool {
+    let min_price = old_price.saturating_mul(100 + price_bump as u128).div_ceil(100);
+    new_price < min_price
+}
+
 /// A reference to a transaction in the pool
 #[derive(Clone, Debug)]
 pub struct PoolTransactionRef {
@@ -708,4 +803,15 @@ mod tests {
         assert_eq!(to_marker(1, addr), to_marker(1, addr));
         assert_ne!(to_marker(2, addr), to_marker(1, addr));
     }
+
+    #[test]
+    fn can_check_price_bump() {
+        assert!(!is_below_bump(100, 100, 0));
+        assert!(is_below_bump(99, 100, 0));
+        assert!(is_below_bump(109, 100, 10));
+        assert!(!is_below_bump(110, 100, 10));
+        assert!(is_below_bump(5, 5, 10));
+        assert!(!is_below_bump(6, 5, 10));
+        assert!(!is_below_bump(1, 0, 10));
+    }
 }
@@ -106,6 +106,7 @@ impl Signer for DevSigner {
             TypedTransactionRequest::EIP2930(mut tx) => Ok(signer.sign_transaction_sync(&mut tx)?),
             TypedTransactionRequest::EIP1559(mut tx) => Ok(signer.sign_transaction_sync(&mut tx)?),
             TypedTransactionRequest::EIP4844(mut tx) => Ok(signer.sign_transaction_sync(&mut tx)?),
+            TypedTransactionRequest::EIP7702(mut tx) => Ok(signer.sign_transaction_sync(&mut tx)?),
             TypedTransactionRequest::Deposit(mut tx) => Ok(signer.sign_transaction_sync(&mut tx)?),
         }
     }
@@ -137,6 +138,10 @@ pub fn build_typed_transaction(
             let sighash = tx.signature_hash();
             TypedTransaction::EIP4844(Signed::new_unchecked(tx, signature, sighash))
         }
+        TypedTransactionRequest::EIP7702(tx) => {
+            let sighash = tx.signature_hash();
+            TypedTransaction::EIP7702(Signed::new_unchecked(tx, signature, sighash))
+        }
         TypedTransactionRequest::Deposit(tx) => {
             let DepositTransactionRequest {
                 from,
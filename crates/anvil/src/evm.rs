@@ -1,6 +1,15 @@
 use alloy_primitives::Address;
-use foundry_evm::revm::{precompile::Precompile, ContextPrecompile, ContextPrecompiles};
-use std::{fmt::Debug, sync::Arc};
+use anvil_core::eth::transaction::eip7702::{
+    delegated_address, delegation_designator, SignedAuthorization, PER_AUTH_BASE_COST,
+    PER_EMPTY_ACCOUNT_COST,
+};
+use foundry_evm::revm::{
+    interpreter::CallInputs,
+    precompile::Precompile,
+    primitives::{Bytecode, EVMError, InvalidTransaction, SpecId},
+    ContextPrecompile, ContextPrecompiles, InnerEvmContext,
+};
+use std::{cell::Cell, fmt::Debug, rc::Rc, sync::Arc};
 
 /// Object-safe trait that enables injecting extra precompiles when using
 /// `anvil` as a library.
@@ -37,6 +46,117 @@ pub fn inject_precompiles<DB, I>(
     }));
 }
 
+/// Appends a handler register to `evm` that adds support for EIP-7702, which revm doesn't support
+/// natively.
+///
+/// The given `authorizations` of the transaction are applied to the journaled state before the
+/// transaction is executed, so they are only committed together with the transaction. Their
+/// intrinsic gas is part of the transaction's intrinsic gas and accounts that already existed are
+/// refunded. Calls to accounts with a delegation designator execute the code of the delegate.
+pub(crate) fn inject_eip7702<DB, I>(
+    evm: &mut revm::Evm<'_, I, DB>,
+    authorizations: Vec<SignedAuthorization>,
+) where
+    DB: revm::Database,
+{
+    evm.handler.append_handler_register_box(Box::new(move |handler| {
+        let authorization_gas = authorizations.len() as u64 * PER_EMPTY_ACCOUNT_COST;
+        let old_handle = handler.validation.initial_tx_gas.clone();
+        handler.validation.initial_tx_gas = Arc::new(move |env| {
+            let initial_gas = old_handle(env)?.saturating_add(authorization_gas);
+            if initial_gas > env.tx.gas_limit {
+                return Err(InvalidTransaction::CallGasCostMoreThanGasLimit.into())
+            }
+            Ok(initial_gas)
+        });
+
+        let refund = Rc::new(Cell::new(0));
+
+        let authorizations = authorizations.clone();
+        let refund_inner = refund.clone();
+        let old_handle = handler.pre_execution.load_accounts.clone();
+        handler.pre_execution.load_accounts = Arc::new(move |ctx| {
+            old_handle(ctx)?;
+            let refund = apply_authorizations(&mut ctx.evm.inner, &authorizations)?;
+            refund_inner.set(refund);
+            Ok(())
+        });
+
+        let old_handle = handler.execution.last_frame_return.clone();
+        handler.execution.last_frame_return = Arc::new(move |ctx, frame_result| {
+            old_handle(ctx, frame_result)?;
+            frame_result.gas_mut().record_refund(refund.get() as i64);
+            Ok(())
+        });
+
+        let old_handle = handler.execution.call.clone();
+        handler.execution.call = Arc::new(move |ctx, mut inputs| {
+            resolve_delegation(&mut ctx.evm.inner, &mut inputs)?;
+            old_handle(ctx, inputs)
+        });
+    }));
+}
+
+/// Applies the authorizations of an EIP-7702 transaction, invalid authorizations are skipped.
+///
+/// Returns the gas refunded for authorities that already existed.
+fn apply_authorizations<DB: revm::Database>(
+    ecx: &mut InnerEvmContext<DB>,
+    authorizations: &[SignedAuthorization],
+) -> Result<u64, EVMError<DB::Error>> {
+    let caller = ecx.env.tx.caller;
+    let chain_id = ecx.env.cfg.chain_id;
+    let mut refund = 0;
+    for authorization in authorizations {
+        if authorization.chain_id != 0 && authorization.chain_id != chain_id {
+            continue
+        }
+        let Ok(authority) = authorization.recover_authority() else { continue };
+
+        let (account, _) = ecx.journaled_state.load_code(authority, &mut ecx.db)?;
+        // the nonce of the sender is bumped before the authorizations are processed, but the evm
+        // only bumps it after they were applied
+        let nonce = if authority == caller { account.info.nonce + 1 } else { account.info.nonce };
+        if authorization.nonce != nonce {
+            continue
+        }
+
+        // only accounts without code or that already delegate can be delegated
+        let code = account.info.code.as_ref().map(|code| code.original_bytes()).unwrap_or_default();
+        if !code.is_empty() && delegated_address(&code).is_none() {
+            continue
+        }
+
+        if !account.info.is_empty() {
+            refund += PER_EMPTY_ACCOUNT_COST - PER_AUTH_BASE_COST;
+        }
+
+        trace!(target: "backend", ?authority, address=?authorization.address, "applying authorization");
+        ecx.journaled_state
+            .set_code(authority, Bytecode::new_raw(delegation_designator(authorization.address)));
+        ecx.journaled_state.inc_nonce(authority);
+    }
+    Ok(refund)
+}
+
+/// Executes the code of the delegate if the called account delegates to another account via an
+/// EIP-7702 delegation designator.
+fn resolve_delegation<DB: revm::Database>(
+    ecx: &mut InnerEvmContext<DB>,
+    inputs: &mut CallInputs,
+) -> Result<(), EVMError<DB::Error>> {
+    if ecx.spec_id() < SpecId::PRAGUE {
+        return Ok(())
+    }
+    let (account, _) = ecx.journaled_state.load_code(inputs.bytecode_address, &mut ecx.db)?;
+    let delegate =
+        account.info.code.as_ref().and_then(|code| delegated_address(&code.original_bytes()));
+    if let Some(delegate) = delegate {
+        inputs.bytecode_address = delegate;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{evm::inject_precompiles, PrecompileFactory};
@@ -21,6 +21,7 @@ pub enum Hardfork {
     Paris,
     Shanghai,
     Cancun,
+    Prague,
     #[default]
     Latest,
 }
@@ -45,6 +46,8 @@ impl Hardfork {
             Hardfork::Paris => 15537394,
             Hardfork::Shanghai => 17034870,
             Hardfork::Cancun | Hardfork::Latest => 19426587,
+            // not yet scheduled on mainnet
+            Hardfork::Prague => u64::MAX,
         }
     }
 }
@@ -72,6 +75,7 @@ impl FromStr for Hardfork {
             "paris" | "merge" | "15" => Hardfork::Paris,
             "shanghai" | "16" => Hardfork::Shanghai,
             "cancun" | "17" => Hardfork::Cancun,
+            "prague" | "18" => Hardfork::Prague,
             "latest" => Hardfork::Latest,
             _ => return Err(format!("Unknown hardfork {s}")),
         };
@@ -99,6 +103,7 @@ impl From<Hardfork> for SpecId {
             Hardfork::Paris => SpecId::MERGE,
             Hardfork::Shanghai => SpecId::SHANGHAI,
             Hardfork::Cancun | Hardfork::Latest => SpecId::CANCUN,
+            Hardfork::Prague => SpecId::PRAGUE,
         }
     }
 }
@@ -146,4 +151,11 @@ mod tests {
         let hf: Hardfork = 12244000u64.into();
         assert_eq!(hf, Hardfork::Berlin);
     }
+
+    #[test]
+    fn test_parse_prague() {
+        let hf: Hardfork = "prague".parse().unwrap();
+        assert_eq!(hf, Hardfork::Prague);
+        assert_eq!(hf.fork_block(), u64::MAX);
+    }
 }
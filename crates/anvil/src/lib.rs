@@ -17,7 +17,8 @@ use crate::{
     shutdown::Signal,
     tasks::TaskManager,
 };
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{hex, Address, B256, U256};
+use alloy_rpc_types_engine::JwtSecret;
 use alloy_signer_wallet::LocalWallet;
 use eth::backend::fork::ClientFork;
 use foundry_common::provider::{ProviderBuilder, RetryProvider};
@@ -27,8 +28,9 @@ use parking_lot::Mutex;
 use server::try_spawn_ipc;
 use std::{
     future::Future,
-    io,
+    io::{self, Write},
     net::SocketAddr,
+    path::Path,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -56,6 +58,10 @@ pub use evm::{inject_precompiles, PrecompileFactory};
 pub mod filter;
 /// commandline output
 pub mod logging;
+/// prometheus metrics
+pub mod metrics;
+/// running several chains in one process
+pub mod multichain;
 /// types for subscriptions
 pub mod pubsub;
 /// axum RPC server implementations
@@ -138,11 +144,12 @@ pub async fn try_spawn(mut config: NodeConfig) -> io::Result<(EthApi, NodeHandle
         server_config,
         no_mining,
         transaction_order,
+        pool_config,
         genesis,
         ..
     } = config.clone();
 
-    let pool = Arc::new(Pool::default());
+    let pool = Arc::new(Pool::new(pool_config));
 
     let mode = if let Some(block_time) = block_time {
         MiningMode::interval(block_time)
@@ -218,10 +225,38 @@ pub async fn try_spawn(mut config: NodeConfig) -> io::Result<(EthApi, NodeHandle
         servers.push(tokio::task::spawn(srv.map_err(Into::into)));
     }
 
+    let mut auth_addresses = Vec::new();
+    if let Some(auth_port) = config.auth_port {
+        let secret = match (config.jwt_secret, &config.jwt_secret_path) {
+            (Some(secret), _) => secret,
+            (None, Some(path)) => read_jwt_secret(path)?,
+            (None, None) => B256::random(),
+        };
+        config.jwt_secret = Some(secret);
+        let secret = JwtSecret::from_hex(hex::encode(secret))
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err.to_string()))?;
+        for addr in &config.host {
+            let tcp_listener =
+                tokio::net::TcpListener::bind(SocketAddr::new(*addr, auth_port)).await?;
+            auth_addresses.push(tcp_listener.local_addr()?);
+
+            let srv =
+                server::serve_auth_on(tcp_listener, api.clone(), server_config.clone(), secret);
+            servers.push(tokio::task::spawn(srv.map_err(Into::into)));
+        }
+    }
+
     let tokio_handle = Handle::current();
     let (signal, on_shutdown) = shutdown::signal();
     let task_manager = TaskManager::new(tokio_handle, on_shutdown);
 
+    if let (Some(l1_url), Some(portal)) = (&config.optimism_l1_url, config.optimism_portal) {
+        let provider = ProviderBuilder::new(l1_url)
+            .build()
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+        task_manager.spawn_deposit_relayer(provider, api.clone(), portal);
+    }
+
     let ipc_task =
         config.get_ipc_path().map(|path| try_spawn_ipc(api.clone(), path)).transpose()?;
 
@@ -231,6 +266,7 @@ pub async fn try_spawn(mut config: NodeConfig) -> io::Result<(EthApi, NodeHandle
         servers,
         ipc_task,
         addresses,
+        auth_addresses,
         _signal: Some(signal),
         task_manager,
     };
@@ -240,6 +276,26 @@ pub async fn try_spawn(mut config: NodeConfig) -> io::Result<(EthApi, NodeHandle
     Ok((api, handle))
 }
 
+/// Reads the hex encoded JWT secret from the file or writes a new random one to it if it does not
+/// exist
+///
+/// A new file is only readable by the current user.
+fn read_jwt_secret(path: &Path) -> io::Result<B256> {
+    if !path.exists() {
+        let secret = B256::random();
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options.open(path)?.write_all(hex::encode(secret).as_bytes())?;
+        return Ok(secret)
+    }
+    let content = std::fs::read_to_string(path)?;
+    content.trim().parse().map_err(|err| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("invalid JWT secret: {err}"))
+    })
+}
+
 type IpcTask = JoinHandle<()>;
 
 /// A handle to the spawned node and server tasks
@@ -249,6 +305,8 @@ pub struct NodeHandle {
     config: NodeConfig,
     /// The address of the running rpc server
     addresses: Vec<SocketAddr>,
+    /// The addresses of the authenticated servers that serve the engine API, if enabled
+    auth_addresses: Vec<SocketAddr>,
     /// Join handle for the Node Service
     pub node_service: JoinHandle<Result<(), NodeError>>,
     /// Join handles (one per socket) for the Anvil server.
@@ -274,6 +332,9 @@ impl NodeHandle {
             if let Some(ipc_path) = self.ipc_path() {
                 println!("IPC path: {}", ipc_path);
             }
+            if self.addresses.is_empty() {
+                return
+            }
             println!(
                 "Listening on {}",
                 self.addresses
@@ -282,6 +343,12 @@ impl NodeHandle {
                     .collect::<Vec<String>>()
                     .join(", ")
             );
+            if let Some(auth_endpoint) = self.auth_endpoint() {
+                println!("Engine API listening on {auth_endpoint}");
+                if let Some(path) = &self.config.jwt_secret_path {
+                    println!("JWT secret: {}", path.display());
+                }
+            }
         }
     }
 
@@ -303,6 +370,16 @@ impl NodeHandle {
         format!("ws://{}", self.socket_address())
     }
 
+    /// Returns the http endpoint of the authenticated server that serves the engine API, if enabled
+    pub fn auth_endpoint(&self) -> Option<String> {
+        self.auth_addresses.first().map(|addr| format!("http://{addr}"))
+    }
+
+    /// Returns the secret the JWTs of the engine API clients must be signed with, if enabled
+    pub fn jwt_secret(&self) -> Option<B256> {
+        self.config.jwt_secret.filter(|_| self.config.auth_port.is_some())
+    }
+
     /// Returns the path of the launched ipc server, if any
     pub fn ipc_path(&self) -> Option<String> {
         self.config.get_ipc_path()
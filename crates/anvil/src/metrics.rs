@@ -0,0 +1,239 @@
+//! Metrics of the node in the Prometheus text format, served at `/metrics` if enabled
+
+use alloy_rpc_types::txpool::TxpoolStatus;
+use foundry_evm::fork::BlockchainDbStats;
+use parking_lot::Mutex;
+use std::{collections::BTreeMap, fmt::Write, time::Duration};
+
+/// The upper bounds of the buckets of the RPC latency histogram, in seconds
+const LATENCY_BUCKETS: &[f64] = &[0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.5, 1.0];
+
+/// The upper bounds of the buckets of the gas used per block histogram
+const GAS_BUCKETS: &[f64] =
+    &[21_000.0, 100_000.0, 500_000.0, 1_000_000.0, 5_000_000.0, 10_000_000.0, 30_000_000.0];
+
+/// Collects metrics about the RPC calls served and the blocks mined by the node
+#[derive(Debug)]
+pub struct NodeMetrics {
+    /// Metrics of every RPC method that was called, by method name
+    rpc: Mutex<BTreeMap<String, RpcMethodMetrics>>,
+    /// The gas used by every mined block
+    block_gas: Mutex<Histogram>,
+}
+
+// === impl NodeMetrics ===
+
+impl NodeMetrics {
+    /// Records a handled RPC call
+    pub fn record_call(&self, method: &str, elapsed: Duration, is_error: bool) {
+        let mut rpc = self.rpc.lock();
+        let metrics = rpc.entry(method.to_string()).or_default();
+        metrics.latency.observe(elapsed.as_secs_f64());
+        if is_error {
+            metrics.errors += 1;
+        }
+    }
+
+    /// Records a mined block
+    pub fn record_block(&self, gas_used: u64) {
+        self.block_gas.lock().observe(gas_used as f64);
+    }
+
+    /// Encodes all metrics in the Prometheus text format.
+    ///
+    /// The state of the pool and the fork cache is not tracked by the metrics and must be passed
+    /// in.
+    pub fn encode(&self, pool: TxpoolStatus, fork: Option<&BlockchainDbStats>) -> String {
+        let mut out = String::new();
+
+        {
+            let rpc = self.rpc.lock();
+            header(&mut out, "anvil_rpc_requests_total", "counter", "Number of RPC calls");
+            for (method, metrics) in rpc.iter() {
+                let labels = format!("method=\"{}\"", escape(method));
+                sample(&mut out, "anvil_rpc_requests_total", &labels, metrics.latency.count);
+            }
+            header(&mut out, "anvil_rpc_errors_total", "counter", "Number of failed RPC calls");
+            for (method, metrics) in rpc.iter() {
+                let labels = format!("method=\"{}\"", escape(method));
+                sample(&mut out, "anvil_rpc_errors_total", &labels, metrics.errors);
+            }
+            header(
+                &mut out,
+                "anvil_rpc_request_duration_seconds",
+                "histogram",
+                "Time it took to handle RPC calls",
+            );
+            for (method, metrics) in rpc.iter() {
+                let labels = format!("method=\"{}\"", escape(method));
+                metrics.latency.encode(&mut out, "anvil_rpc_request_duration_seconds", &labels);
+            }
+        }
+
+        header(
+            &mut out,
+            "anvil_txpool_transactions",
+            "gauge",
+            "Number of transactions in the pool",
+        );
+        sample(&mut out, "anvil_txpool_transactions", "status=\"pending\"", pool.pending);
+        sample(&mut out, "anvil_txpool_transactions", "status=\"queued\"", pool.queued);
+
+        {
+            let block_gas = self.block_gas.lock();
+            header(&mut out, "anvil_blocks_mined_total", "counter", "Number of mined blocks");
+            sample(&mut out, "anvil_blocks_mined_total", "", block_gas.count);
+            header(&mut out, "anvil_block_gas_used", "histogram", "Gas used by mined blocks");
+            block_gas.encode(&mut out, "anvil_block_gas_used", "");
+        }
+
+        if let Some(fork) = fork {
+            header(
+                &mut out,
+                "anvil_fork_cache_hits_total",
+                "counter",
+                "Number of fork state lookups served from the cache",
+            );
+            sample(&mut out, "anvil_fork_cache_hits_total", "", fork.hits());
+            header(
+                &mut out,
+                "anvil_fork_cache_misses_total",
+                "counter",
+                "Number of fork state lookups fetched from the remote client",
+            );
+            sample(&mut out, "anvil_fork_cache_misses_total", "", fork.misses());
+            header(
+                &mut out,
+                "anvil_fork_rpc_requests_total",
+                "counter",
+                "Number of state requests sent to the remote client",
+            );
+            sample(&mut out, "anvil_fork_rpc_requests_total", "", fork.requests());
+        }
+
+        out
+    }
+}
+
+impl Default for NodeMetrics {
+    fn default() -> Self {
+        Self { rpc: Default::default(), block_gas: Mutex::new(Histogram::new(GAS_BUCKETS)) }
+    }
+}
+
+/// Metrics of a single RPC method
+#[derive(Debug)]
+struct RpcMethodMetrics {
+    /// Number of calls that returned an error
+    errors: u64,
+    /// Latency of all calls, also counts the calls
+    latency: Histogram,
+}
+
+impl Default for RpcMethodMetrics {
+    fn default() -> Self {
+        Self { errors: 0, latency: Histogram::new(LATENCY_BUCKETS) }
+    }
+}
+
+/// A cumulative histogram with fixed buckets
+#[derive(Debug)]
+struct Histogram {
+    /// The upper bounds of the buckets, ascending
+    bounds: &'static [f64],
+    /// The number of observations per bucket, not cumulative
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self { bounds, buckets: vec![0; bounds.len()], sum: 0.0, count: 0 }
+    }
+
+    fn observe(&mut self, value: f64) {
+        if let Some(idx) = self.bounds.iter().position(|bound| value <= *bound) {
+            self.buckets[idx] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn encode(&self, out: &mut String, name: &str, labels: &str) {
+        let sep = if labels.is_empty() { "" } else { "," };
+        let mut cumulative = 0;
+        for (bound, count) in self.bounds.iter().zip(&self.buckets) {
+            cumulative += count;
+            let labels = format!("{labels}{sep}le=\"{bound}\"");
+            sample(out, &format!("{name}_bucket"), &labels, cumulative);
+        }
+        sample(out, &format!("{name}_bucket"), &format!("{labels}{sep}le=\"+Inf\""), self.count);
+        sample(out, &format!("{name}_sum"), labels, self.sum);
+        sample(out, &format!("{name}_count"), labels, self.count);
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn sample(out: &mut String, name: &str, labels: &str, value: impl std::fmt::Display) {
+    if labels.is_empty() {
+        let _ = writeln!(out, "{name} {value}");
+    } else {
+        let _ = writeln!(out, "{name}{{{labels}}} {value}");
+    }
+}
+
+/// Escapes a label value
+fn escape(value: &str) -> String {
+    value.replace('\\', r"\\").replace('"', r#"\""#).replace('\n', r"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_histogram() {
+        let mut histogram = Histogram::new(&[1.0, 10.0]);
+        histogram.observe(0.5);
+        histogram.observe(5.0);
+        histogram.observe(50.0);
+
+        let mut out = String::new();
+        histogram.encode(&mut out, "gas", "");
+        assert_eq!(
+            out,
+            "gas_bucket{le=\"1\"} 1\ngas_bucket{le=\"10\"} 2\ngas_bucket{le=\"+Inf\"} 3\ngas_sum 55.5\ngas_count 3\n"
+        );
+    }
+
+    #[test]
+    fn test_encode_metrics() {
+        let metrics = NodeMetrics::default();
+        metrics.record_call("eth_call", Duration::from_millis(2), false);
+        metrics.record_call("eth_call", Duration::from_millis(3), true);
+        metrics.record_block(21_000);
+
+        let stats = BlockchainDbStats::default();
+        stats.record_hit();
+        stats.record_requests(3);
+
+        let out = metrics.encode(TxpoolStatus { pending: 2, queued: 1 }, Some(&stats));
+        assert!(out.contains("anvil_rpc_requests_total{method=\"eth_call\"} 2\n"));
+        assert!(out.contains("anvil_rpc_errors_total{method=\"eth_call\"} 1\n"));
+        assert!(out.contains(
+            "anvil_rpc_request_duration_seconds_bucket{method=\"eth_call\",le=\"0.0025\"} 1\n"
+        ));
+        assert!(out.contains("anvil_txpool_transactions{status=\"pending\"} 2\n"));
+        assert!(out.contains("anvil_txpool_transactions{status=\"queued\"} 1\n"));
+        assert!(out.contains("anvil_blocks_mined_total 1\n"));
+        assert!(out.contains("anvil_block_gas_used_bucket{le=\"21000\"} 1\n"));
+        assert!(out.contains("anvil_fork_cache_hits_total 1\n"));
+        assert!(out.contains("anvil_fork_cache_misses_total 0\n"));
+        assert!(out.contains("anvil_fork_rpc_requests_total 3\n"));
+    }
+}
@@ -0,0 +1,439 @@
+//! Runs several independent chains in one process
+
+use crate::{
+    eth::{backend::time::SharedClock, EthApi},
+    server::error::NodeResult,
+    Hardfork, NodeConfig, NodeHandle,
+};
+use alloy_primitives::Address;
+use axum::Router;
+use eyre::{ensure, Context as _};
+use foundry_common::provider::ProviderBuilder;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    future::Future,
+    net::SocketAddr,
+    panic::AssertUnwindSafe,
+    path::Path,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::task::{JoinError, JoinHandle};
+
+/// The configuration of a set of chains that run in one process, loaded from a JSON file.
+///
+/// ```json
+/// {
+///   "sharedClock": true,
+///   "chains": [
+///     { "name": "l1", "chainId": 1, "port": 8545 },
+///     { "name": "l2", "chainId": 10, "hardfork": "cancun", "port": 8545, "path": "/l2" }
+///   ]
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct MultiChainConfig {
+    /// Whether all chains share one clock, so that moving the time of one chain moves it for all
+    #[serde(default)]
+    pub shared_clock: bool,
+    /// The chains to launch
+    pub chains: Vec<ChainConfig>,
+}
+
+/// The configuration of a single chain of a [MultiChainConfig]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ChainConfig {
+    /// The unique name of the chain
+    pub name: String,
+    /// The chain id, defaults to the one of the fork or anvil's default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u64>,
+    /// The hardfork, defaults to the latest one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hardfork: Option<String>,
+    /// The URL of the chain to fork
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork_url: Option<String>,
+    /// The block to fork at, defaults to the latest block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork_block_number: Option<u64>,
+    /// The port to serve the chain on, defaults to the port of the base configuration.
+    ///
+    /// Several chains can share a port if they are served at different paths.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// The URL path the chain is served at, defaults to `/`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Whether the chain is an Optimism chain
+    #[serde(default)]
+    pub optimism: bool,
+    /// The name of the L1 chain whose OptimismPortal deposits are relayed to this chain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deposits_from: Option<String>,
+    /// The address of the OptimismPortal on the `deposits_from` chain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimism_portal: Option<Address>,
+}
+
+// === impl MultiChainConfig ===
+
+impl MultiChainConfig {
+    /// Loads the configuration from the given JSON file, it is validated when the chains are
+    /// launched
+    pub fn load(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read chains config {}", path.display()))?;
+        serde_json::from_str(&content)
+            .wrap_err_with(|| format!("failed to parse chains config {}", path.display()))
+    }
+
+    /// Checks that the names of the chains are unique, that their hardforks are known and that
+    /// no two chains are served at the same port and path, chains without a port are served at
+    /// `default_port`
+    pub fn validate(&self, default_port: u16) -> eyre::Result<()> {
+        ensure!(!self.chains.is_empty(), "no chains configured");
+        let mut names = HashSet::new();
+        let mut endpoints = HashSet::new();
+        for chain in &self.chains {
+            ensure!(names.insert(&chain.name), "duplicate chain name `{}`", chain.name);
+            chain.hardfork()?;
+            if let Some(path) = &chain.path {
+                ensure!(
+                    path.starts_with('/'),
+                    "path `{path}` of chain `{}` must start with `/`",
+                    chain.name
+                );
+            }
+            ensure!(
+                endpoints.insert((chain.port(default_port), chain.path())),
+                "chain `{}` is served at the same port and path as another chain",
+                chain.name
+            );
+        }
+        for chain in &self.chains {
+            let Some(l1) = &chain.deposits_from else { continue };
+            ensure!(
+                l1 != &chain.name && names.contains(l1),
+                "chain `{}` relays deposits from unknown chain `{l1}`",
+                chain.name
+            );
+            ensure!(
+                chain.optimism && chain.optimism_portal.is_some(),
+                "chain `{}` relays deposits but is not an Optimism chain with a portal",
+                chain.name
+            );
+        }
+        Ok(())
+    }
+}
+
+// === impl ChainConfig ===
+
+impl ChainConfig {
+    /// Returns the parsed hardfork, if set
+    pub fn hardfork(&self) -> eyre::Result<Option<Hardfork>> {
+        self.hardfork
+            .as_deref()
+            .map(|hardfork| hardfork.parse::<Hardfork>().map_err(|err| eyre::eyre!(err)))
+            .transpose()
+            .wrap_err_with(|| format!("invalid hardfork of chain `{}`", self.name))
+    }
+
+    /// Returns the port the chain is served at, `default` if not configured
+    fn port(&self, default: u16) -> u16 {
+        self.port.unwrap_or(default)
+    }
+
+    /// Returns the path the chain is served at, without a trailing slash, empty for the root
+    fn path(&self) -> &str {
+        self.path.as_deref().unwrap_or_default().trim_end_matches('/')
+    }
+
+    /// Returns the configuration of the node of this chain, derived from the given base
+    /// configuration.
+    ///
+    /// The node doesn't serve RPC calls on its own, they are served by [try_spawn_chains].
+    pub fn node_config(&self, base: &NodeConfig, clock: Option<SharedClock>) -> NodeConfig {
+        let mut config = base
+            .clone()
+            .with_hardfork(self.hardfork().ok().flatten().or(base.hardfork))
+            .with_shared_clock(clock)
+            .with_optimism(base.enable_optimism || self.optimism)
+            .with_ipc(None);
+        if self.chain_id.is_some() {
+            config = config.with_chain_id(self.chain_id);
+        }
+        if self.fork_url.is_some() {
+            config = config
+                .with_eth_rpc_url(self.fork_url.clone())
+                .with_fork_block_number(self.fork_block_number);
+        }
+        config.host.clear();
+        config
+    }
+}
+
+/// Launches all chains of the given configuration, the chains use the given base configuration
+/// for everything that isn't configured per chain.
+///
+/// Chains that share a port are served by the same server, at their paths.
+pub async fn try_spawn_chains(
+    config: MultiChainConfig,
+    base: NodeConfig,
+) -> eyre::Result<MultiChainHandle> {
+    config.validate(base.port)?;
+    let clock = config.shared_clock.then(|| SharedClock::new(base.genesis_timestamp));
+
+    let mut chains = Vec::with_capacity(config.chains.len());
+    let mut relays = Vec::new();
+    let mut routers: BTreeMap<u16, Router> = BTreeMap::new();
+    for chain in config.chains {
+        if let (Some(l1), Some(portal)) = (&chain.deposits_from, chain.optimism_portal) {
+            relays.push((chain.name.clone(), l1.clone(), portal));
+        }
+
+        let (api, handle) = crate::try_spawn(chain.node_config(&base, clock.clone()))
+            .await
+            .wrap_err_with(|| format!("failed to launch chain `{}`", chain.name))?;
+
+        let port = chain.port(base.port);
+        let router = crate::server::router(api.clone(), base.server_config.clone());
+        let path = chain.path().to_string();
+        let routes = routers.remove(&port).unwrap_or_default();
+        let routes = add_routes(routes, &path, router)
+            .wrap_err_with(|| format!("failed to serve chain `{}`", chain.name))?;
+        routers.insert(port, routes);
+
+        chains.push(Chain { name: chain.name, port, path, api, handle, addresses: Vec::new() });
+    }
+
+    let mut servers = Vec::new();
+    for (port, router) in routers {
+        let mut addresses = Vec::with_capacity(base.host.len());
+        for host in &base.host {
+            let tcp_listener = tokio::net::TcpListener::bind(SocketAddr::new(*host, port)).await?;
+            addresses.push(tcp_listener.local_addr()?);
+            let router = router.clone();
+            servers.push(tokio::task::spawn(async move {
+                axum::serve(tcp_listener, router.into_make_service()).await.map_err(Into::into)
+            }));
+        }
+        for chain in chains.iter_mut().filter(|chain| chain.port == port) {
+            chain.addresses.clone_from(&addresses);
+        }
+    }
+
+    // the endpoints of the L1 chains are only known once their servers are bound
+    for (l2, l1, portal) in relays {
+        let l1 = chains.iter().find(|chain| chain.name == l1).expect("validated");
+        let provider = ProviderBuilder::new(&l1.http_endpoint()).build()?;
+        let l2 = chains.iter().find(|chain| chain.name == l2).expect("validated");
+        l2.handle.task_manager().spawn_deposit_relayer(provider, l2.api.clone(), portal);
+    }
+
+    let handle = MultiChainHandle { chains, servers };
+    if !base.silent {
+        handle.print();
+    }
+    Ok(handle)
+}
+
+/// Adds the routes of a chain served at `path` to the routes of its port.
+///
+/// Axum panics on conflicting routes, e.g. for a chain served at `/metrics` next to a chain with
+/// metrics at the root of the same port, so the panic is turned into an error.
+fn add_routes(routes: Router, path: &str, router: Router) -> eyre::Result<Router> {
+    std::panic::catch_unwind(AssertUnwindSafe(|| {
+        if path.is_empty() {
+            routes.merge(router)
+        } else {
+            routes.nest(path, router)
+        }
+    }))
+    .map_err(|_| {
+        eyre::eyre!("the routes at path `{path}` conflict with the routes of another chain")
+    })
+}
+
+/// A chain launched by [try_spawn_chains]
+pub struct Chain {
+    /// The name of the chain
+    pub name: String,
+    /// The port from the configuration
+    port: u16,
+    /// The path the chain is served at, empty for the root
+    path: String,
+    /// The API of the chain's node
+    pub api: EthApi,
+    /// The handle of the chain's node, which doesn't serve RPC calls itself
+    pub handle: NodeHandle,
+    /// The addresses the chain is served at
+    addresses: Vec<SocketAddr>,
+}
+
+impl Chain {
+    /// Returns the http endpoint of the chain
+    pub fn http_endpoint(&self) -> String {
+        format!("http://{}{}", self.addresses[0], self.path)
+    }
+
+    /// Returns the websocket endpoint of the chain
+    pub fn ws_endpoint(&self) -> String {
+        format!("ws://{}{}", self.addresses[0], self.path)
+    }
+}
+
+/// A handle to all chains launched by [try_spawn_chains] and the servers that serve them.
+///
+/// This future resolves if any node or server task resolves or fails. Dropping the handle shuts
+/// down all chains.
+pub struct MultiChainHandle {
+    chains: Vec<Chain>,
+    servers: Vec<JoinHandle<NodeResult<()>>>,
+}
+
+impl MultiChainHandle {
+    /// Returns all chains in the order of the configuration
+    pub fn chains(&self) -> &[Chain] {
+        &self.chains
+    }
+
+    /// Returns mutable access to all chains
+    pub fn chains_mut(&mut self) -> &mut [Chain] {
+        &mut self.chains
+    }
+
+    /// Returns the chain with the given name
+    pub fn chain(&self, name: &str) -> Option<&Chain> {
+        self.chains.iter().find(|chain| chain.name == name)
+    }
+
+    /// Prints the endpoints of all chains
+    fn print(&self) {
+        for chain in &self.chains {
+            println!(
+                "Chain `{}` (id {}) listening on {}",
+                chain.name,
+                chain.api.chain_id(),
+                chain.http_endpoint()
+            );
+        }
+    }
+}
+
+impl Future for MultiChainHandle {
+    type Output = Result<NodeResult<()>, JoinError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let pin = self.get_mut();
+
+        for chain in pin.chains.iter_mut() {
+            if let Poll::Ready(res) = chain.handle.poll_unpin(cx) {
+                return Poll::Ready(res)
+            }
+        }
+
+        for server in pin.servers.iter_mut() {
+            if let Poll::Ready(res) = server.poll_unpin(cx) {
+                return Poll::Ready(res)
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for MultiChainHandle {
+    fn drop(&mut self) {
+        for server in &self.servers {
+            server.abort();
+        }
+        for chain in &self.chains {
+            chain.handle.node_service.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let s = r#"{
+            "sharedClock": true,
+            "chains": [
+                { "name": "l1", "chainId": 1, "port": 8545 },
+                { "name": "l2", "chainId": 10, "hardfork": "cancun", "port": 8545, "path": "/l2" }
+            ]
+        }"#;
+        let config: MultiChainConfig = serde_json::from_str(s).unwrap();
+        config.validate(8545).unwrap();
+        assert!(config.shared_clock);
+        assert_eq!(config.chains.len(), 2);
+        assert_eq!(config.chains[1].hardfork().unwrap(), Some(Hardfork::Cancun));
+        assert_eq!(config.chains[1].path(), "/l2");
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let chain = |name: &str, path: Option<&str>| ChainConfig {
+            name: name.to_string(),
+            port: Some(8545),
+            path: path.map(str::to_string),
+            ..Default::default()
+        };
+
+        let config = MultiChainConfig {
+            chains: vec![chain("a", None), chain("a", Some("/b"))],
+            ..Default::default()
+        };
+        assert!(config.validate(8545).is_err());
+
+        let config = MultiChainConfig {
+            chains: vec![chain("a", None), chain("b", Some("/"))],
+            ..Default::default()
+        };
+        assert!(config.validate(8545).is_err());
+
+        // chains without a port are served at the default port
+        let mut default_port = chain("b", None);
+        default_port.port = None;
+        let config =
+            MultiChainConfig { chains: vec![chain("a", None), default_port], ..Default::default() };
+        assert!(config.validate(8545).is_err());
+        config.validate(8546).unwrap();
+
+        let config = MultiChainConfig { chains: vec![chain("a", Some("b"))], ..Default::default() };
+        assert!(config.validate(8545).is_err());
+
+        let mut invalid = chain("a", None);
+        invalid.hardfork = Some("nope".to_string());
+        let config = MultiChainConfig { chains: vec![invalid], ..Default::default() };
+        assert!(config.validate(8545).is_err());
+
+        assert!(MultiChainConfig::default().validate(8545).is_err());
+
+        let mut l2 = chain("l2", Some("/l2"));
+        l2.deposits_from = Some("l1".to_string());
+        l2.optimism_portal = Some(Address::random());
+        let config =
+            MultiChainConfig { chains: vec![chain("l1", None), l2.clone()], ..Default::default() };
+        assert!(config.validate(8545).is_err());
+
+        l2.optimism = true;
+        let config =
+            MultiChainConfig { chains: vec![chain("l1", None), l2.clone()], ..Default::default() };
+        config.validate(8545).unwrap();
+
+        l2.deposits_from = Some("l3".to_string());
+        let config = MultiChainConfig { chains: vec![chain("l1", None), l2], ..Default::default() };
+        assert!(config.validate(8545).is_err());
+    }
+}
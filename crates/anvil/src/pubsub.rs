@@ -1,10 +1,17 @@
 use crate::{
-    eth::{backend::notifications::NewBlockNotifications, error::to_rpc_result},
+    eth::{
+        backend::notifications::{NewBlockNotifications, RemovedBlockNotifications},
+        error::to_rpc_result,
+    },
     StorageInfo,
 };
 use alloy_primitives::{TxHash, B256};
 use alloy_rpc_types::{pubsub::SubscriptionResult, FilteredParams, Log};
-use anvil_core::eth::{block::Block, subscription::SubscriptionId, transaction::TypedReceipt};
+use anvil_core::eth::{
+    block::Block,
+    subscription::{StateDiffFilter, SubscriptionId},
+    transaction::TypedReceipt,
+};
 use anvil_rpc::{request::Version, response::ResponseResult};
 use futures::{channel::mpsc::Receiver, ready, Stream, StreamExt};
 use serde::Serialize;
@@ -15,9 +22,12 @@ use std::{
 };
 
 /// Listens for new blocks and matching logs emitted in that block
+///
+/// Logs of blocks that are removed from the chain are emitted again with `removed: true`
 #[derive(Debug)]
 pub struct LogsSubscription {
     pub blocks: NewBlockNotifications,
+    pub removed_blocks: RemovedBlockNotifications,
     pub storage: StorageInfo,
     pub filter: FilteredParams,
     pub queued: VecDeque<Log>,
@@ -37,6 +47,20 @@ impl LogsSubscription {
                 return Poll::Ready(Some(EthSubscriptionResponse::new(params)));
             }
 
+            // blocks are removed before the blocks replacing them are mined, so the removed logs
+            // are always drained first
+            if let Poll::Ready(Some(removed)) = self.removed_blocks.poll_next_unpin(cx) {
+                let block = Block::clone(&removed.block);
+                let receipts = Vec::clone(&removed.receipts);
+                self.queued.extend(filter_logs(block, receipts, &self.filter).into_iter().map(
+                    |mut log| {
+                        log.removed = true;
+                        log
+                    },
+                ));
+                continue;
+            }
+
             if let Some(block) = ready!(self.blocks.poll_next_unpin(cx)) {
                 let b = self.storage.block(block.hash);
                 let receipts = self.storage.receipts(block.hash);
@@ -90,6 +114,8 @@ pub enum EthSubscription {
     Logs(Box<LogsSubscription>),
     Header(NewBlockNotifications, StorageInfo, SubscriptionId),
     PendingTransactions(Receiver<TxHash>, SubscriptionId),
+    /// The state changes of every mined block, see `anvil_subscribe`
+    StateDiff(NewBlockNotifications, StateDiffFilter, SubscriptionId),
 }
 
 // === impl EthSubscription ===
@@ -116,6 +142,20 @@ impl EthSubscription {
                     }
                 }
             }
+            EthSubscription::StateDiff(blocks, filter, id) => loop {
+                // blocks without matching changes are skipped
+                if let Some(block) = ready!(blocks.poll_next_unpin(cx)) {
+                    if let Some(diff) = filter.filter(&block.state_diff) {
+                        let params = EthSubscriptionParams {
+                            subscription: id.clone(),
+                            result: to_rpc_result(diff),
+                        };
+                        return Poll::Ready(Some(EthSubscriptionResponse::new(params)));
+                    }
+                } else {
+                    return Poll::Ready(None);
+                }
+            },
             EthSubscription::PendingTransactions(tx, id) => {
                 let res = ready!(tx.poll_next_unpin(cx))
                     .map(SubscriptionResult::TransactionHash)
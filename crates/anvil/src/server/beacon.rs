@@ -0,0 +1,70 @@
+//! A minimal subset of the beacon node API
+
+use crate::EthApi;
+use anvil_core::eth::beacon::{BeaconBlockId, BlobSidecars};
+use axum::{
+    extract::{Path, RawQuery, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+/// Handler for `/eth/v1/beacon/blob_sidecars/{block_id}`
+///
+/// The optional `indices` query parameter can be repeated or comma separated.
+pub async fn blob_sidecars(
+    State(api): State<EthApi>,
+    Path(block_id): Path<String>,
+    RawQuery(query): RawQuery,
+) -> Response {
+    let block_id = match block_id.parse::<BeaconBlockId>() {
+        Ok(block_id) => block_id,
+        Err(err) => return error(StatusCode::BAD_REQUEST, err),
+    };
+    let indices = match parse_indices(query.as_deref().unwrap_or_default()) {
+        Ok(indices) => indices,
+        Err(err) => return error(StatusCode::BAD_REQUEST, err),
+    };
+    let Some(mut data) = api.beacon_blob_sidecars(block_id) else {
+        return error(StatusCode::NOT_FOUND, format!("block not found: {block_id}"))
+    };
+    if let Some(indices) = indices {
+        data.retain(|sidecar| indices.contains(&sidecar.index));
+    }
+    Json(BlobSidecars { data }).into_response()
+}
+
+/// Parses the `indices` of the query, `None` if the query does not filter by index
+fn parse_indices(query: &str) -> Result<Option<Vec<u64>>, String> {
+    let mut indices = None;
+    for pair in query.split('&') {
+        let Some(values) = pair.strip_prefix("indices=") else { continue };
+        let values = values.replace("%2C", ",").replace("%2c", ",");
+        for index in values.split(',').filter(|index| !index.is_empty()) {
+            let index = index.parse().map_err(|_| format!("invalid blob index: {index}"))?;
+            indices.get_or_insert_with(Vec::new).push(index);
+        }
+    }
+    Ok(indices)
+}
+
+/// Returns an error in the format of the beacon API
+fn error(code: StatusCode, message: String) -> Response {
+    let body = serde_json::json!({ "code": code.as_u16(), "message": message });
+    (code, Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_indices() {
+        assert_eq!(parse_indices("").unwrap(), None);
+        assert_eq!(parse_indices("foo=bar").unwrap(), None);
+        assert_eq!(parse_indices("indices=0,2").unwrap(), Some(vec![0, 2]));
+        assert_eq!(parse_indices("indices=0&indices=2").unwrap(), Some(vec![0, 2]));
+        assert_eq!(parse_indices("indices=1%2C3").unwrap(), Some(vec![1, 3]));
+        assert!(parse_indices("indices=a").is_err());
+    }
+}
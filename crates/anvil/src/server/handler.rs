@@ -8,15 +8,24 @@ use alloy_rpc_types::{
     pubsub::{Params, SubscriptionKind},
     FilteredParams,
 };
-use anvil_core::eth::{subscription::SubscriptionId, EthPubSub, EthRequest, EthRpcCall};
-use anvil_rpc::{error::RpcError, response::ResponseResult};
+use anvil_core::eth::{
+    subscription::{AnvilSubscriptionKind, SubscriptionId},
+    EthPubSub, EthRequest, EthRpcCall,
+};
+use anvil_rpc::{
+    error::{ErrorCode, RpcError},
+    response::{ResponseResult, RpcResponse},
+};
 use anvil_server::{PubSubContext, PubSubRpcHandler, RpcHandler};
+use std::time::Duration;
 
 /// A `RpcHandler` that expects `EthRequest` rpc calls via http
 #[derive(Clone)]
 pub struct HttpEthRpcHandler {
     /// Access to the node
     api: EthApi,
+    /// Whether `engine_` calls are served
+    engine: bool,
 }
 
 // === impl WsEthRpcHandler ===
@@ -24,7 +33,13 @@ pub struct HttpEthRpcHandler {
 impl HttpEthRpcHandler {
     /// Creates a new instance of the handler using the given `EthApi`
     pub fn new(api: EthApi) -> Self {
-        Self { api }
+        Self { api, engine: false }
+    }
+
+    /// Also serves the `engine_` calls, only used by the authenticated server
+    pub fn with_engine_api(mut self) -> Self {
+        self.engine = true;
+        self
     }
 }
 
@@ -33,8 +48,15 @@ impl RpcHandler for HttpEthRpcHandler {
     type Request = EthRequest;
 
     async fn on_request(&self, request: Self::Request) -> ResponseResult {
+        if request.is_engine() && !self.engine {
+            return RpcError::method_not_found().into()
+        }
         self.api.execute(request).await
     }
+
+    fn on_call_handled(&self, method: &str, elapsed: Duration, response: &RpcResponse) {
+        record_call(&self.api, method, elapsed, response)
+    }
 }
 
 /// A `RpcHandler` that expects `EthRequest` rpc calls and `EthPubSub` via pubsub connection
@@ -42,12 +64,20 @@ impl RpcHandler for HttpEthRpcHandler {
 pub struct PubSubEthRpcHandler {
     /// Access to the node
     api: EthApi,
+    /// Whether `engine_` calls are served
+    engine: bool,
 }
 
 impl PubSubEthRpcHandler {
     /// Creates a new instance of the handler using the given `EthApi`
     pub fn new(api: EthApi) -> Self {
-        Self { api }
+        Self { api, engine: false }
+    }
+
+    /// Also serves the `engine_` calls, only used by the authenticated server
+    pub fn with_engine_api(mut self) -> Self {
+        self.engine = true;
+        self
     }
 
     /// Invoked for an ethereum pubsub rpc call
@@ -60,6 +90,20 @@ impl PubSubEthRpcHandler {
                 let canceled = cx.remove_subscription(&id).is_some();
                 ResponseResult::Success(canceled.into())
             }
+            EthPubSub::AnvilSubscribe(kind, filter) => {
+                let subscription = match kind {
+                    AnvilSubscriptionKind::StateDiff => {
+                        trace!(target: "rpc::ws", "received state diff subscription {:?}", filter);
+                        let blocks = self.api.new_block_notifications();
+                        EthSubscription::StateDiff(blocks, filter, id.clone())
+                    }
+                };
+
+                cx.add_subscription(id.clone(), subscription);
+
+                trace!(target: "rpc::ws", "created new subscription: {:?}", id);
+                to_rpc_result(id)
+            }
             EthPubSub::EthSubscribe(kind, params) => {
                 let filter = match *params {
                     Params::None => None,
@@ -76,9 +120,11 @@ impl PubSubEthRpcHandler {
                     SubscriptionKind::Logs => {
                         trace!(target: "rpc::ws", "received logs subscription {:?}", params);
                         let blocks = self.api.new_block_notifications();
+                        let removed_blocks = self.api.removed_block_notifications();
                         let storage = self.api.storage_info();
                         EthSubscription::Logs(Box::new(LogsSubscription {
                             blocks,
+                            removed_blocks,
                             storage,
                             filter: params,
                             queued: Default::default(),
@@ -121,8 +167,27 @@ impl PubSubRpcHandler for PubSubEthRpcHandler {
     async fn on_request(&self, request: Self::Request, cx: PubSubContext<Self>) -> ResponseResult {
         trace!(target: "rpc", "received pubsub request {:?}", request);
         match request {
+            EthRpcCall::Request(request) if request.is_engine() && !self.engine => {
+                RpcError::method_not_found().into()
+            }
             EthRpcCall::Request(request) => self.api.execute(*request).await,
             EthRpcCall::PubSub(pubsub) => self.on_pub_sub(pubsub, cx).await,
         }
     }
+
+    fn on_call_handled(&self, method: &str, elapsed: Duration, response: &RpcResponse) {
+        record_call(&self.api, method, elapsed, response)
+    }
+}
+
+/// Records a handled call in the metrics of the node, if enabled
+fn record_call(api: &EthApi, method: &str, elapsed: Duration, response: &RpcResponse) {
+    let Some(metrics) = api.metrics() else { return };
+    let (method, is_error) = match response.result() {
+        ResponseResult::Success(_) => (method, false),
+        // don't create a series for every unknown method that is called
+        ResponseResult::Error(err) if err.code == ErrorCode::MethodNotFound => ("unknown", true),
+        ResponseResult::Error(_) => (method, true),
+    };
+    metrics.record_call(method, elapsed, is_error);
 }
@@ -1,13 +1,22 @@
 //! Contains the code to launch an Ethereum RPC server.
 
 use crate::{EthApi, IpcTask};
+use alloy_rpc_types_engine::JwtSecret;
 use anvil_server::{ipc::IpcEndpoint, ServerConfig};
-use axum::Router;
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
 use futures::StreamExt;
 use handler::{HttpEthRpcHandler, PubSubEthRpcHandler};
 use std::{future::Future, io, net::SocketAddr, pin::pin};
 use tokio::net::TcpListener;
 
+mod beacon;
 pub mod error;
 mod handler;
 
@@ -34,10 +43,64 @@ pub async fn serve_on(
 }
 
 /// Configures an [`axum::Router`] that handles [`EthApi`] related JSON-RPC calls via HTTP and WS.
+///
+/// The blob sidecars of mined blocks are served at `/eth/v1/beacon/blob_sidecars/{block_id}`, if
+/// metrics are enabled they are served at `/metrics`.
 pub fn router(api: EthApi, config: ServerConfig) -> Router {
     let http = HttpEthRpcHandler::new(api.clone());
-    let ws = PubSubEthRpcHandler::new(api);
+    let ws = PubSubEthRpcHandler::new(api.clone());
+    let router = anvil_server::http_ws_router(config, http, ws).route(
+        "/eth/v1/beacon/blob_sidecars/:block_id",
+        get(beacon::blob_sidecars).with_state(api.clone()),
+    );
+    if api.metrics().is_some() {
+        return router.route("/metrics", get(metrics).with_state(api))
+    }
+    router
+}
+
+/// Configures a server that handles [`EthApi`] related JSON-RPC calls, including the `engine_`
+/// calls, via HTTP and WS for clients that authenticate with a JWT signed by the given secret.
+pub async fn serve_auth_on(
+    tcp_listener: TcpListener,
+    api: EthApi,
+    config: ServerConfig,
+    secret: JwtSecret,
+) -> io::Result<()> {
+    axum::serve(tcp_listener, auth_router(api, config, secret).into_make_service()).await
+}
+
+/// Configures an [`axum::Router`] that handles [`EthApi`] related JSON-RPC calls, including the
+/// `engine_` calls, and rejects requests without a valid `Authorization: Bearer <jwt>` header.
+pub fn auth_router(api: EthApi, config: ServerConfig, secret: JwtSecret) -> Router {
+    let http = HttpEthRpcHandler::new(api.clone()).with_engine_api();
+    let ws = PubSubEthRpcHandler::new(api).with_engine_api();
     anvil_server::http_ws_router(config, http, ws)
+        .layer(middleware::from_fn_with_state(secret, authenticate))
+}
+
+/// Rejects the request if it is not authenticated with a JWT signed by the secret
+async fn authenticate(State(secret): State<JwtSecret>, request: Request, next: Next) -> Response {
+    let jwt = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match jwt.map(|jwt| secret.validate(jwt)) {
+        Some(Ok(())) => next.run(request).await,
+        Some(Err(err)) => (StatusCode::UNAUTHORIZED, err.to_string()).into_response(),
+        None => (StatusCode::UNAUTHORIZED, "missing JWT").into_response(),
+    }
+}
+
+/// Serves the metrics of the node in the Prometheus text format
+async fn metrics(State(api): State<EthApi>) -> Response {
+    match api.encode_metrics() {
+        Some(metrics) => {
+            ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], metrics).into_response()
+        }
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
 }
 
 /// Launches an ipc server at the given path in a new task
@@ -0,0 +1,135 @@
+//! Relays the deposits of an OptimismPortal on L1
+
+use crate::EthApi;
+use alloy_network::AnyNetwork;
+use alloy_primitives::{Address, B256};
+use alloy_provider::Provider;
+use alloy_rpc_types::Filter;
+use alloy_transport::Transport;
+use anvil_core::eth::transaction::optimism::{DepositTransaction, TRANSACTION_DEPOSITED_TOPIC};
+use std::collections::{BTreeMap, HashSet};
+
+/// How many of the most recent relayed L1 blocks are tracked to detect reorgs
+const MAX_TRACKED_BLOCKS: usize = 64;
+
+/// An L1 block whose deposits were relayed
+#[derive(Debug)]
+struct RelayedBlock {
+    hash: B256,
+    /// The best block number of this chain before the deposits were relayed
+    since: u64,
+    /// The source hashes of the relayed deposits
+    deposits: Vec<B256>,
+}
+
+/// Relays the deposits of the OptimismPortal at `portal` in new L1 blocks to the node.
+///
+/// The relayed L1 blocks are tracked, so deposits of blocks that were missed are relayed as well
+/// and the deposits of blocks that were reorged out are removed again.
+pub struct DepositRelayer<P> {
+    provider: P,
+    api: EthApi,
+    portal: Address,
+    /// The most recent relayed L1 blocks, by number
+    blocks: BTreeMap<u64, RelayedBlock>,
+}
+
+impl<P> DepositRelayer<P> {
+    /// Creates a new relayer for the deposits of the given portal
+    pub fn new(provider: P, api: EthApi, portal: Address) -> Self {
+        Self { provider, api, portal, blocks: Default::default() }
+    }
+
+    /// Relays the deposits of the new L1 block with the given hash and of all of its ancestors
+    /// that weren't relayed yet.
+    ///
+    /// If the block isn't a descendant of the last relayed block, the deposits of the relayed
+    /// blocks that were reorged out are removed first.
+    pub async fn on_new_block<T>(&mut self, hash: B256) -> eyre::Result<()>
+    where
+        P: Provider<T, AnyNetwork>,
+        T: Transport + Clone,
+    {
+        if self.blocks.values().any(|block| block.hash == hash) {
+            return Ok(())
+        }
+
+        // walk back to the last relayed ancestor
+        let (mut number, mut parent) = self.header(hash).await?;
+        let mut new_blocks = vec![(number, hash)];
+        let last = self.blocks.keys().next_back().copied();
+        while let Some(parent_number) = number.checked_sub(1) {
+            let reorged = self.blocks.get(&parent_number).is_some_and(|block| block.hash != parent);
+            let missed = !self.blocks.contains_key(&parent_number) &&
+                last.is_some_and(|last| parent_number > last);
+            if !reorged && !missed {
+                break
+            }
+            let (_, grandparent) = self.header(parent).await?;
+            new_blocks.push((parent_number, parent));
+            number = parent_number;
+            parent = grandparent;
+        }
+
+        let removed = self.blocks.split_off(&number);
+        if let Some(since) = removed.values().next().map(|block| block.since) {
+            let deposits = removed.into_values().flat_map(|block| block.deposits).collect();
+            debug!(target: "node", l1_block=number, "removing deposits of reorged out L1 blocks");
+            self.api.remove_relayed_deposits(&deposits, since).await?;
+        }
+
+        for (number, hash) in new_blocks.into_iter().rev() {
+            let deposits = self.deposits(hash).await?;
+            trace!(target: "node", block=?hash, deposits=deposits.len(), "relaying L1 deposits");
+            let since = self.api.best_number();
+            let source_hashes = deposits.iter().map(|tx| tx.source_hash).collect();
+            // tracked before the deposits are queued, so they're never relayed twice
+            self.blocks.insert(number, RelayedBlock { hash, since, deposits: source_hashes });
+            self.api.relay_deposits(deposits).await?;
+        }
+
+        while self.blocks.len() > MAX_TRACKED_BLOCKS {
+            self.blocks.pop_first();
+        }
+        Ok(())
+    }
+
+    /// Returns the number and the parent hash of the L1 block with the given hash
+    async fn header<T>(&self, hash: B256) -> eyre::Result<(u64, B256)>
+    where
+        P: Provider<T, AnyNetwork>,
+        T: Transport + Clone,
+    {
+        let block = self
+            .provider
+            .get_block(hash.into(), false)
+            .await?
+            .ok_or_else(|| eyre::eyre!("L1 block {hash} not found"))?;
+        Ok((block.header.number.unwrap_or_default(), block.header.parent_hash))
+    }
+
+    /// Returns the deposits of the portal in the L1 block with the given hash
+    async fn deposits<T>(&self, hash: B256) -> eyre::Result<Vec<DepositTransaction>>
+    where
+        P: Provider<T, AnyNetwork>,
+        T: Transport + Clone,
+    {
+        let filter = Filter::new()
+            .at_block_hash(hash)
+            .address(self.portal)
+            .event_signature(TRANSACTION_DEPOSITED_TOPIC);
+        let logs = self.provider.get_logs(&filter).await?;
+        Ok(logs
+            .iter()
+            // logs of a block that was reorged out while it was queried
+            .filter(|log| !log.removed)
+            .filter_map(|log| {
+                DepositTransaction::from_deposit_log(
+                    &log.inner,
+                    log.block_hash.unwrap_or(hash),
+                    log.log_index?,
+                )
+            })
+            .collect())
+    }
+}
@@ -1,17 +1,22 @@
 //! Task management support
 
-use crate::{shutdown::Shutdown, tasks::block_listener::BlockListener, EthApi};
+use crate::{
+    shutdown::Shutdown,
+    tasks::{block_listener::BlockListener, deposit_relayer::DepositRelayer},
+    EthApi,
+};
 use alloy_network::AnyNetwork;
-use alloy_primitives::B256;
+use alloy_primitives::{Address, B256};
 use alloy_provider::Provider;
 use alloy_rpc_types::Block;
 use alloy_transport::Transport;
 use anvil_core::types::Forking;
 use futures::StreamExt;
-use std::{fmt, future::Future};
-use tokio::{runtime::Handle, task::JoinHandle};
+use std::{fmt, future::Future, sync::Arc};
+use tokio::{runtime::Handle, sync::Mutex, task::JoinHandle};
 
 pub mod block_listener;
+pub mod deposit_relayer;
 
 /// A helper struct for managing additional tokio tasks.
 #[derive(Clone)]
@@ -86,6 +91,41 @@ impl TaskManager {
         })
     }
 
+    /// Spawns a new task that listens for new blocks of an L1 and relays the deposits of the
+    /// OptimismPortal at `portal` in every new block to the node.
+    ///
+    /// The deposits are included at the top of the next block, see [EthApi::relay_deposits].
+    /// Deposits of L1 blocks that are reorged out are removed again, see [DepositRelayer].
+    ///
+    /// ```
+    /// use anvil::{spawn, NodeConfig};
+    /// use foundry_common::provider::ProviderBuilder;
+    ///
+    /// # async fn t() {
+    /// let (api, handle) = spawn(NodeConfig::default().with_optimism(true)).await;
+    ///
+    /// let l1 = ProviderBuilder::new("http://....").build().unwrap();
+    /// let portal = "0xbEb5Fc579115071764c7423A4f12eDde41f106Ed".parse().unwrap();
+    ///
+    /// handle.task_manager().spawn_deposit_relayer(l1, api, portal);
+    /// # }
+    /// ```
+    pub fn spawn_deposit_relayer<P, T>(&self, provider: P, api: EthApi, portal: Address)
+    where
+        P: Provider<T, AnyNetwork> + Clone + Unpin + 'static,
+        T: Transport + Clone,
+    {
+        let relayer = Arc::new(Mutex::new(DepositRelayer::new(provider.clone(), api, portal)));
+        self.spawn_block_poll_listener(provider, move |hash| {
+            let relayer = relayer.clone();
+            async move {
+                if let Err(err) = relayer.lock().await.on_new_block(hash).await {
+                    error!(target: "node", ?err, block=?hash, "failed to relay L1 deposits");
+                }
+            }
+        })
+    }
+
     /// Spawns a new [`BlockListener`] task that listens for new blocks (poll-based) See also
     /// [`Provider::watch_blocks`] and executes the future the `task_factory` returns for the new
     /// block hash
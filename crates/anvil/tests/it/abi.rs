@@ -27,6 +27,15 @@ sol!(
     }
 );
 
+sol!(
+    #[sol(rpc)]
+    interface ERC20 {
+        function balanceOf(address owner) external view returns (uint256);
+        function allowance(address owner, address spender) external view returns (uint256);
+        function totalSupply() external view returns (uint256);
+    }
+);
+
 sol!(
     #[sol(rpc)]
     interface ERC721 {
@@ -73,3 +73,29 @@ async fn test_can_use_default_genesis_timestamp() {
 
     assert_ne!(0u64, provider.get_block(0.into(), false).await.unwrap().unwrap().header.timestamp);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_collect_metrics() {
+    let (api, handle) = spawn(NodeConfig::test().with_metrics(true)).await;
+    let provider = handle.http_provider();
+
+    provider.get_block_number().await.unwrap();
+    provider.get_block_number().await.unwrap();
+    provider.raw_request::<_, serde_json::Value>("eth_doesNotExist".into(), ()).await.unwrap_err();
+    api.mine_one().await;
+
+    let metrics = api.encode_metrics().unwrap();
+    assert!(metrics.contains("anvil_rpc_requests_total{method=\"eth_blockNumber\"} 2\n"));
+    assert!(metrics.contains("anvil_rpc_errors_total{method=\"unknown\"} 1\n"));
+    assert!(metrics.contains("anvil_txpool_transactions{status=\"pending\"} 0\n"));
+    assert!(metrics.contains("anvil_blocks_mined_total 1\n"));
+    // not forking
+    assert!(!metrics.contains("anvil_fork_cache_hits_total"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn metrics_are_disabled_by_default() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+    assert!(api.metrics().is_none());
+    assert!(api.encode_metrics().is_none());
+}
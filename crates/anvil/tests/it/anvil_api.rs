@@ -1,7 +1,7 @@
 //! tests for custom anvil endpoints
 
 use crate::{
-    abi::{Greeter, MulticallContract, BUSD},
+    abi::{Greeter, MulticallContract, BUSD, ERC20},
     fork::fork_config,
     utils::http_provider_with_signer,
 };
@@ -12,7 +12,10 @@ use alloy_rpc_types::{BlockId, BlockNumberOrTag, TransactionRequest, WithOtherFi
 use anvil::{eth::api::CLIENT_VERSION, spawn, Hardfork, NodeConfig};
 use anvil_core::{
     eth::EthRequest,
-    types::{AnvilMetadata, ForkedNetwork, Forking, NodeEnvironment, NodeForkConfig, NodeInfo},
+    types::{
+        AnvilMetadata, ForkedNetwork, Forking, NodeEnvironment, NodeForkConfig, NodeInfo,
+        ReorgOptions, TransactionData,
+    },
 };
 use foundry_evm::revm::primitives::SpecId;
 use std::{
@@ -420,6 +423,52 @@ async fn test_can_set_storage_bsc_fork() {
     assert_eq!(balance, U256::from(12345u64));
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_deal_erc20() {
+    let (api, handle) = spawn(fork_config()).await;
+    let provider = handle.http_provider();
+
+    let account = Address::random();
+    let amount = U256::from(1_000_000_000u64);
+    // USDC is a proxy, DAI is not
+    for token in [
+        address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+        address!("6B175474E89094C44Da98b954EedeAC495271d0F"),
+    ] {
+        let token = ERC20::new(token, &provider);
+        let supply = token.totalSupply().call().await.unwrap()._0;
+
+        api.anvil_deal_erc20(*token.address(), account, amount, Some(true)).await.unwrap();
+        assert_eq!(token.balanceOf(account).call().await.unwrap()._0, amount);
+        assert_eq!(token.totalSupply().call().await.unwrap()._0, supply + amount);
+
+        api.anvil_deal_erc20(*token.address(), account, U256::from(1), None).await.unwrap();
+        assert_eq!(token.balanceOf(account).call().await.unwrap()._0, U256::from(1));
+        assert_eq!(token.totalSupply().call().await.unwrap()._0, supply + amount);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_set_erc20_allowance() {
+    let (api, handle) = spawn(fork_config()).await;
+    let provider = handle.http_provider();
+
+    let token = ERC20::new(address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"), &provider);
+    let (owner, spender) = (Address::random(), Address::random());
+    let amount = U256::from(1337);
+
+    api.anvil_set_erc20_allowance(*token.address(), owner, spender, amount).await.unwrap();
+    assert_eq!(token.allowance(owner, spender).call().await.unwrap()._0, amount);
+    assert_eq!(token.allowance(spender, owner).call().await.unwrap()._0, U256::ZERO);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn deal_erc20_fails_for_non_token() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+    let err = api.anvil_deal_erc20(Address::random(), Address::random(), U256::from(1), None).await;
+    assert!(err.is_err());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_get_node_info() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -648,3 +697,140 @@ async fn can_remove_pool_transactions() {
     let final_txs = provider.txpool_inspect().await.unwrap();
     assert_eq!(final_txs.pending.len(), 0);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_rollback() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let accounts = handle.dev_wallets().collect::<Vec<_>>();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+    let balance_before = provider.get_balance(to).await.unwrap();
+
+    let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(U256::from(100));
+    let tx = WithOtherFields::new(tx);
+    let first = provider.send_transaction(tx.clone()).await.unwrap().get_receipt().await.unwrap();
+    let second = provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+    assert_eq!(api.block_number().unwrap(), U256::from(2));
+
+    // keep the unwound transaction in the pool
+    api.anvil_set_auto_mine(false).await.unwrap();
+    api.anvil_rollback(1).await.unwrap();
+
+    assert_eq!(api.block_number().unwrap(), U256::from(1));
+    assert_eq!(provider.get_balance(to).await.unwrap(), balance_before + U256::from(100));
+    assert_eq!(provider.get_transaction_count(from).await.unwrap(), 1);
+    assert!(api.transaction_receipt(first.transaction_hash).await.unwrap().is_some());
+    assert!(api.transaction_receipt(second.transaction_hash).await.unwrap().is_none());
+
+    // the transaction of the removed block is back in the pool
+    let content = api.txpool_content().await.unwrap();
+    let pending = content.pending.get(&from).unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending.values().next().unwrap().hash, second.transaction_hash);
+
+    // the chain continues from the new best block
+    api.mine_one().await;
+    let block = api.block_by_number(BlockNumberOrTag::Latest).await.unwrap().unwrap();
+    assert_eq!(block.header.number, Some(2));
+    assert_eq!(block.header.parent_hash, first.block_hash.unwrap());
+    assert!(api.transaction_receipt(second.transaction_hash).await.unwrap().is_some());
+
+    // can't roll back past genesis
+    assert!(api.anvil_rollback(3).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_reorg() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let accounts = handle.dev_wallets().collect::<Vec<_>>();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+    let balance_before = provider.get_balance(to).await.unwrap();
+
+    let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(U256::from(100));
+    let tx = WithOtherFields::new(tx);
+    let mut replaced = Vec::new();
+    for _ in 0..3 {
+        let receipt =
+            provider.send_transaction(tx.clone()).await.unwrap().get_receipt().await.unwrap();
+        replaced.push(receipt);
+    }
+    let common_block = api.block_by_number(BlockNumberOrTag::Number(1)).await.unwrap().unwrap();
+
+    let replacement = |value: u64| {
+        let tx =
+            TransactionRequest::default().with_from(from).with_to(to).with_value(U256::from(value));
+        TransactionData::Json(WithOtherFields::new(tx))
+    };
+    api.anvil_reorg(ReorgOptions {
+        depth: 2,
+        tx_block_pairs: vec![
+            (replacement(1000), 0),
+            (replacement(2000), 0),
+            (replacement(3000), 1),
+        ],
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(api.block_number().unwrap(), U256::from(3));
+    assert_eq!(
+        provider.get_balance(to).await.unwrap(),
+        balance_before + U256::from(100 + 1000 + 2000 + 3000)
+    );
+    assert_eq!(provider.get_transaction_count(from).await.unwrap(), 4);
+
+    // the transactions of the removed blocks are gone
+    assert!(api.transaction_receipt(replaced[0].transaction_hash).await.unwrap().is_some());
+    assert!(api.transaction_receipt(replaced[1].transaction_hash).await.unwrap().is_none());
+    assert!(api.transaction_receipt(replaced[2].transaction_hash).await.unwrap().is_none());
+
+    let block = api.block_by_number(BlockNumberOrTag::Number(2)).await.unwrap().unwrap();
+    assert_eq!(block.header.parent_hash, common_block.header.hash.unwrap());
+    assert_eq!(block.transactions.len(), 2);
+    let block = api.block_by_number(BlockNumberOrTag::Number(3)).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 1);
+
+    // transactions can only be placed in the new blocks
+    let err =
+        api.anvil_reorg(ReorgOptions { depth: 1, tx_block_pairs: vec![(replacement(1), 1)] }).await;
+    assert!(err.is_err());
+    assert_eq!(api.block_number().unwrap(), U256::from(3));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_reorg_without_mining_unwound_transactions() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let accounts = handle.dev_wallets().collect::<Vec<_>>();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+
+    let snapshot = api.evm_snapshot().await.unwrap();
+    let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(U256::from(100));
+    let tx = WithOtherFields::new(tx);
+    provider.send_transaction(tx.clone()).await.unwrap().get_receipt().await.unwrap();
+    let unwound = provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+
+    api.anvil_reorg(ReorgOptions { depth: 1, tx_block_pairs: vec![] }).await.unwrap();
+
+    // the unwound transaction is back in the pool, but automine doesn't mine an extra block
+    assert_eq!(api.block_number().unwrap(), U256::from(2));
+    let block = api.block_by_number(BlockNumberOrTag::Number(2)).await.unwrap().unwrap();
+    assert!(block.transactions.is_empty());
+    assert!(api.transaction_receipt(unwound.transaction_hash).await.unwrap().is_none());
+    assert_eq!(api.txpool_status().await.unwrap().pending, 1);
+
+    // snapshots of the removed blocks are gone, older ones can still be reverted to
+    let snapshot_above_tip = api.evm_snapshot().await.unwrap();
+    api.anvil_rollback(1).await.unwrap();
+    assert!(!api.evm_revert(snapshot_above_tip).await.unwrap());
+    assert_eq!(api.block_number().unwrap(), U256::from(1));
+    assert!(api.evm_revert(snapshot).await.unwrap());
+    assert_eq!(api.block_number().unwrap(), U256::ZERO);
+}
@@ -5,14 +5,24 @@ use crate::{
     utils::{connect_pubsub_with_signer, http_provider_with_signer},
 };
 use alloy_network::{EthereumSigner, TransactionBuilder};
-use alloy_primitives::{Address, ChainId, B256, U256};
+use alloy_primitives::{Address, Bytes, ChainId, B256, U256, U64};
 use alloy_provider::Provider;
 use alloy_rpc_types::{
-    request::TransactionRequest, state::AccountOverride, BlockId, BlockNumberOrTag,
+    request::TransactionRequest, state::AccountOverride, BlockId, BlockNumberOrTag, BlockOverrides,
     BlockTransactions, WithOtherFields,
 };
-use anvil::{eth::api::CLIENT_VERSION, spawn, NodeConfig, CHAIN_ID};
-use std::{collections::HashMap, time::Duration};
+use anvil::{
+    eth::{
+        api::CLIENT_VERSION,
+        backend::mem::inspector::{ETH_TRANSFER_LOG_ADDRESS, TRANSFER_EVENT_SIGNATURE},
+    },
+    spawn, NodeConfig, CHAIN_ID,
+};
+use anvil_core::eth::simulate::{SimBlock, SimulatePayload};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn can_get_block_number() {
@@ -312,3 +322,180 @@ async fn can_call_with_state_override() {
     // `value` *is* changed with state
     assert_eq!(value, "");
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_simulate_v1() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let wallet = handle.dev_wallets().next().unwrap();
+    let signer: EthereumSigner = wallet.clone().into();
+
+    let provider = http_provider_with_signer(&handle.http_endpoint(), signer);
+
+    let multicall_contract = MulticallContract::deploy(&provider).await.unwrap();
+    let best_number = api.block_number().unwrap().to::<u64>();
+
+    let from = Address::random();
+    let to = Address::random();
+    let value = U256::from(100u64);
+
+    let transfer = WithOtherFields::new(
+        TransactionRequest::default().with_from(from).with_to(to).with_value(value),
+    );
+    let get_balance = WithOtherFields::new(
+        TransactionRequest::default()
+            .with_to(*multicall_contract.address())
+            .with_input(multicall_contract.getEthBalance(to).calldata().to_owned()),
+    );
+
+    let payload = SimulatePayload {
+        block_state_calls: vec![
+            SimBlock {
+                block_overrides: Some(BlockOverrides {
+                    time: Some(U64::from(4_000_000_000u64)),
+                    ..Default::default()
+                }),
+                state_overrides: Some(HashMap::from([(
+                    from,
+                    AccountOverride { balance: Some(U256::from(1000u64)), ..Default::default() },
+                )])),
+                calls: vec![transfer],
+            },
+            SimBlock { calls: vec![get_balance], ..Default::default() },
+        ],
+        trace_transfers: true,
+        validation: false,
+    };
+
+    let blocks = api.simulate_v1(payload, None).await.unwrap();
+    assert_eq!(blocks.len(), 2);
+
+    let first = &blocks[0];
+    assert_eq!(first.inner.header.number, Some(best_number + 1));
+    assert_eq!(first.inner.header.timestamp, 4_000_000_000);
+    assert_eq!(first.calls.len(), 1);
+    let call = &first.calls[0];
+    assert_eq!(call.status, U64::from(1));
+    assert_eq!(call.gas_used, U64::from(21_000));
+    assert!(call.error.is_none());
+    // the value transfer is reported as a log
+    assert_eq!(call.logs.len(), 1);
+    let log = &call.logs[0];
+    assert_eq!(log.address(), ETH_TRANSFER_LOG_ADDRESS);
+    assert_eq!(log.topics(), &[TRANSFER_EVENT_SIGNATURE, from.into_word(), to.into_word()]);
+    assert_eq!(U256::from_be_slice(&log.data().data), value);
+    assert_eq!(log.block_hash, first.inner.header.hash);
+
+    // the second block builds on top of the first one
+    let second = &blocks[1];
+    assert_eq!(second.inner.header.number, Some(best_number + 2));
+    assert_eq!(second.inner.header.parent_hash, first.inner.header.hash.unwrap());
+    assert_eq!(U256::from_be_slice(&second.calls[0].return_data), value);
+    assert!(second.calls[0].logs.is_empty());
+
+    // nothing was committed to the chain
+    assert_eq!(api.block_number().unwrap().to::<u64>(), best_number);
+    assert_eq!(api.balance(to, None).await.unwrap(), U256::ZERO);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_call_with_block_overrides() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let wallet = handle.dev_wallets().next().unwrap();
+    let signer: EthereumSigner = wallet.clone().into();
+
+    let provider = http_provider_with_signer(&handle.http_endpoint(), signer);
+
+    let multicall_contract = MulticallContract::deploy(&provider).await.unwrap();
+    let call = |input: Bytes| {
+        WithOtherFields::new(
+            TransactionRequest::default().with_to(*multicall_contract.address()).with_input(input),
+        )
+    };
+
+    let coinbase = Address::random();
+    let block_hash = B256::random();
+    let overrides = BlockOverrides {
+        time: Some(U64::from(1337u64)),
+        coinbase: Some(coinbase),
+        block_hash: Some(BTreeMap::from([(1u64, block_hash)])),
+        ..Default::default()
+    };
+
+    let out = api
+        .call(
+            call(multicall_contract.getCurrentBlockTimestamp().calldata().to_owned()),
+            None,
+            None,
+            Some(overrides.clone()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(U256::from_be_slice(&out), U256::from(1337u64));
+
+    let out = api
+        .call(
+            call(multicall_contract.getCurrentBlockCoinbase().calldata().to_owned()),
+            None,
+            None,
+            Some(overrides.clone()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(Address::from_word(B256::from_slice(&out)), coinbase);
+
+    let out = api
+        .call(
+            call(multicall_contract.getBlockHash(U256::from(1)).calldata().to_owned()),
+            None,
+            None,
+            Some(overrides),
+        )
+        .await
+        .unwrap();
+    assert_eq!(B256::from_slice(&out), block_hash);
+
+    // the overrides are not persisted
+    let out = api
+        .call(
+            call(multicall_contract.getCurrentBlockCoinbase().calldata().to_owned()),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_ne!(Address::from_word(B256::from_slice(&out)), coinbase);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_estimate_gas_and_create_access_list_with_overrides() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let wallet = handle.dev_wallets().next().unwrap();
+    let signer: EthereumSigner = wallet.clone().into();
+
+    let provider = http_provider_with_signer(&handle.http_endpoint(), signer);
+
+    let simple_storage_contract =
+        SimpleStorage::deploy(&provider, "init".to_string()).await.unwrap();
+
+    // replace the code of an empty account with the code of the storage contract
+    let target = Address::random();
+    let code = provider.get_code_at(*simple_storage_contract.address()).await.unwrap();
+    let overrides =
+        HashMap::from([(target, AccountOverride { code: Some(code), ..Default::default() })]);
+
+    let tx = WithOtherFields::new(
+        TransactionRequest::default().with_from(wallet.address()).with_to(target).with_input(
+            simple_storage_contract.setValue("hello".to_string()).calldata().to_owned(),
+        ),
+    );
+
+    // without the override no code is executed
+    let plain_gas = api.estimate_gas(tx.clone(), None, None, None).await.unwrap();
+
+    let gas = api.estimate_gas(tx.clone(), None, Some(overrides.clone()), None).await.unwrap();
+    assert!(gas > plain_gas);
+
+    let access_list = api.create_access_list(tx, None, Some(overrides), None).await.unwrap();
+    assert!(access_list.gas_used > plain_gas);
+}
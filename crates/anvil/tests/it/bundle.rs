@@ -0,0 +1,244 @@
+//! tests for `eth_callBundle` and `eth_sendBundle`
+
+use alloy_eips::eip2718::Encodable2718;
+use alloy_network::{EthereumSigner, TransactionBuilder, TxSignerSync};
+use alloy_primitives::{bytes, Address, Bytes, U256, U64};
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockId, BlockNumberOrTag, BlockTransactions, TransactionRequest};
+use alloy_signer::SignerSync;
+use alloy_signer_wallet::LocalWallet;
+use anvil::{spawn, Hardfork, NodeConfig};
+use anvil_core::eth::{
+    bundle::{bundle_hash, CallBundleRequest, SendBundleRequest},
+    transaction::{
+        eip7702::{SignedAuthorization, TxEip7702},
+        TypedTransaction,
+    },
+};
+
+/// Returns a signed and encoded EIP-1559 transaction
+async fn signed_tx(wallet: &LocalWallet, nonce: u64, to: Address, value: U256) -> Bytes {
+    let signer: EthereumSigner = wallet.clone().into();
+    let tx = TransactionRequest::default()
+        .with_chain_id(31337)
+        .with_nonce(nonce)
+        .with_from(wallet.address())
+        .with_to(to)
+        .with_value(value)
+        .with_gas_limit(100_000)
+        .with_max_fee_per_gas(20_000_000_000)
+        .with_max_priority_fee_per_gas(1_000_000_000);
+    tx.build(&signer).await.unwrap().encoded_2718().into()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_call_bundle() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let from = wallets[0].address();
+    let to = wallets[1].address();
+
+    let txs = vec![
+        signed_tx(&wallets[0], 0, to, U256::from(1)).await,
+        signed_tx(&wallets[0], 1, to, U256::from(2)).await,
+    ];
+    let response = api
+        .call_bundle(CallBundleRequest {
+            txs,
+            block_number: U64::from(1),
+            state_block_number: BlockNumberOrTag::Latest,
+            timestamp: None,
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(response.state_block_number, 0);
+    assert_eq!(response.total_gas_used, 42_000);
+    assert_eq!(response.results.len(), 2);
+    assert!(response.results.iter().all(|res| res.is_success() && res.from_address == from));
+    assert_eq!(response.gas_fees, response.coinbase_diff);
+    assert_eq!(response.eth_sent_to_coinbase, U256::ZERO);
+    assert_eq!(response.bundle_hash, bundle_hash(response.results.iter().map(|res| &res.tx_hash)));
+
+    // the bundle is only simulated
+    assert_eq!(provider.get_transaction_count(from).await.unwrap(), 0);
+    assert_eq!(provider.get_block_number().await.unwrap(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_call_bundle_with_authorizations() {
+    let node_config = NodeConfig::test().with_hardfork(Some(Hardfork::Prague));
+    let (api, handle) = spawn(node_config).await;
+    let provider = handle.http_provider();
+
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let authority = wallets[1].clone();
+
+    // ADDRESS PUSH0 MSTORE PUSH1 0x20 PUSH0 RETURN
+    let delegate = Address::random();
+    api.anvil_set_code(delegate, bytes!("305f5260205ff3")).await.unwrap();
+
+    let hash = SignedAuthorization::signature_hash(31337, delegate, 0);
+    let authorization =
+        SignedAuthorization::new(31337, delegate, 0, authority.sign_hash_sync(&hash).unwrap());
+    let mut tx = TxEip7702 {
+        chain_id: 31337,
+        gas_limit: 100_000,
+        max_fee_per_gas: 20_000_000_000,
+        max_priority_fee_per_gas: 1_000_000_000,
+        to: authority.address(),
+        authorization_list: vec![authorization],
+        ..Default::default()
+    };
+    let signature = wallets[0].sign_transaction_sync(&mut tx).unwrap();
+    let tx =
+        TypedTransaction::EIP7702(alloy_consensus::SignableTransaction::into_signed(tx, signature));
+
+    let response = api
+        .call_bundle(CallBundleRequest {
+            txs: vec![tx.encoded_2718().into()],
+            block_number: U64::from(1),
+            state_block_number: BlockNumberOrTag::Latest,
+            timestamp: None,
+        })
+        .await
+        .unwrap();
+
+    // the call to the authority executed the code of the delegate
+    let value = response.results[0].value.clone().unwrap();
+    assert_eq!(&value[12..], authority.address().as_slice());
+    assert!(provider.get_code_at(authority.address()).await.unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_bundle() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let to = Address::random();
+
+    // a regular transaction that was submitted first
+    let pool_tx = signed_tx(&wallets[2], 0, to, U256::from(1)).await;
+    let pool_tx = provider.send_raw_transaction(&pool_tx).await.unwrap();
+
+    let txs = vec![
+        signed_tx(&wallets[0], 0, to, U256::from(1)).await,
+        signed_tx(&wallets[1], 0, to, U256::from(1)).await,
+    ];
+    let response = api
+        .send_bundle(SendBundleRequest { txs, block_number: U64::from(1), ..Default::default() })
+        .await
+        .unwrap();
+
+    // bundles for mined blocks are rejected
+    let err = api
+        .send_bundle(SendBundleRequest {
+            txs: vec![signed_tx(&wallets[3], 0, to, U256::from(1)).await],
+            block_number: U64::from(0),
+            ..Default::default()
+        })
+        .await;
+    assert!(err.is_err());
+
+    api.mine_one().await;
+
+    let block = provider.get_block(BlockId::latest(), false).await.unwrap().unwrap();
+    let BlockTransactions::Hashes(hashes) = block.transactions else { unreachable!() };
+    assert_eq!(hashes.len(), 3);
+    assert_eq!(bundle_hash(&hashes[..2]), response.bundle_hash);
+    assert_eq!(hashes[2], *pool_tx.tx_hash());
+    assert_eq!(provider.get_balance(to).await.unwrap(), U256::from(3));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_auto_mine_bundle() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let to = Address::random();
+
+    // a bundle for a later block waits for its block
+    let later = api
+        .send_bundle(SendBundleRequest {
+            txs: vec![signed_tx(&wallets[1], 0, to, U256::from(1)).await],
+            block_number: U64::from(2),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(provider.get_block_number().await.unwrap(), 0);
+
+    let next = api
+        .send_bundle(SendBundleRequest {
+            txs: vec![signed_tx(&wallets[0], 0, to, U256::from(1)).await],
+            block_number: U64::from(1),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(provider.get_block_number().await.unwrap(), 1);
+
+    let block = provider.get_block(BlockId::latest(), false).await.unwrap().unwrap();
+    let BlockTransactions::Hashes(hashes) = block.transactions else { unreachable!() };
+    assert_eq!(bundle_hash(&hashes), next.bundle_hash);
+    assert_ne!(next.bundle_hash, later.bundle_hash);
+    assert_eq!(provider.get_balance(to).await.unwrap(), U256::from(1));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn drops_reverting_bundle() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    // PUSH0 PUSH0 REVERT
+    let reverter = Address::random();
+    api.anvil_set_code(reverter, bytes!("5f5ffd")).await.unwrap();
+
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let txs = vec![
+        signed_tx(&wallets[0], 0, Address::random(), U256::from(1)).await,
+        signed_tx(&wallets[1], 0, reverter, U256::ZERO).await,
+    ];
+
+    api.send_bundle(SendBundleRequest {
+        txs: txs.clone(),
+        block_number: U64::from(1),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    api.mine_one().await;
+    let block = provider.get_block(BlockId::latest(), false).await.unwrap().unwrap();
+    assert!(block.transactions.is_empty());
+
+    // the same bundle is included if the transaction is allowed to revert
+    let call = api
+        .call_bundle(CallBundleRequest {
+            txs: txs.clone(),
+            block_number: U64::from(2),
+            state_block_number: BlockNumberOrTag::Latest,
+            timestamp: None,
+        })
+        .await
+        .unwrap();
+    assert!(call.results[0].is_success());
+    assert!(!call.results[1].is_success());
+
+    api.send_bundle(SendBundleRequest {
+        txs,
+        block_number: U64::from(2),
+        reverting_tx_hashes: vec![call.results[1].tx_hash],
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    api.mine_one().await;
+    let block = provider.get_block(BlockId::latest(), false).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 2);
+}
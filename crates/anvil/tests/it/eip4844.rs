@@ -2,10 +2,11 @@ use crate::utils::http_provider;
 use alloy_consensus::{SidecarBuilder, SimpleCoder};
 use alloy_eips::eip4844::{DATA_GAS_PER_BLOB, MAX_DATA_GAS_PER_BLOCK};
 use alloy_network::TransactionBuilder;
-use alloy_primitives::U256;
+use alloy_primitives::{B256, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::{BlockId, TransactionRequest, WithOtherFields};
 use anvil::{spawn, Hardfork, NodeConfig};
+use anvil_core::eth::beacon::BeaconBlockId;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn can_send_eip4844_transaction() {
@@ -203,3 +204,64 @@ async fn can_check_blob_fields_on_genesis() {
     assert_eq!(block.header.blob_gas_used, Some(0));
     assert_eq!(block.header.excess_blob_gas, Some(0));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_get_blob_sidecars() {
+    let node_config = NodeConfig::test().with_hardfork(Some(Hardfork::Cancun));
+    let (api, handle) = spawn(node_config).await;
+
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let from = wallets[0].address();
+    let to = wallets[1].address();
+    let provider = http_provider(&handle.http_endpoint());
+
+    let eip1559_est = provider.estimate_eip1559_fees(None).await.unwrap();
+    let gas_price = provider.get_gas_price().await.unwrap();
+
+    let data = vec![1u8; DATA_GAS_PER_BLOB as usize * 2];
+    let sidecar: SidecarBuilder<SimpleCoder> = SidecarBuilder::from_slice(&data);
+    let sidecar = sidecar.build().unwrap();
+
+    let tx = TransactionRequest::default()
+        .with_from(from)
+        .with_to(to)
+        .with_nonce(0)
+        .with_max_fee_per_blob_gas(gas_price + 1)
+        .with_max_fee_per_gas(eip1559_est.max_fee_per_gas)
+        .with_max_priority_fee_per_gas(eip1559_est.max_priority_fee_per_gas)
+        .with_blob_sidecar(sidecar.clone());
+    let mut tx = WithOtherFields::new(tx);
+
+    tx.populate_blob_hashes();
+
+    let receipt = provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+    let block_number = receipt.block_number.unwrap();
+
+    let blobs = api.anvil_get_blobs_by_transaction_hash(receipt.transaction_hash).await.unwrap();
+    assert_eq!(blobs, Some(sidecar.blobs.clone()));
+
+    let sidecars = api.beacon_blob_sidecars(BeaconBlockId::Slot(block_number)).unwrap();
+    assert_eq!(sidecars, api.beacon_blob_sidecars(BeaconBlockId::Head).unwrap());
+    assert_eq!(sidecars.len(), sidecar.blobs.len());
+    for (index, blob_sidecar) in sidecars.iter().enumerate() {
+        assert_eq!(blob_sidecar.index, index as u64);
+        assert_eq!(*blob_sidecar.blob, sidecar.blobs[index]);
+        assert_eq!(blob_sidecar.kzg_commitment, sidecar.commitments[index]);
+        assert_eq!(blob_sidecar.kzg_proof, sidecar.proofs[index]);
+        assert_eq!(blob_sidecar.signed_block_header.message.slot, block_number);
+    }
+
+    // blocks without blobs have no sidecars, unknown blocks are not found
+    assert_eq!(api.beacon_blob_sidecars(BeaconBlockId::Genesis), Some(vec![]));
+    assert_eq!(api.beacon_blob_sidecars(BeaconBlockId::Slot(block_number + 1)), None);
+    assert_eq!(api.anvil_get_blobs_by_transaction_hash(B256::random()).await.unwrap(), None);
+
+    // the sidecars are part of the stored blocks, so they're available after a reload
+    let state = api.serialized_state().await.unwrap();
+    let node_config =
+        NodeConfig::test().with_hardfork(Some(Hardfork::Cancun)).with_init_state(Some(state));
+    let (api, _handle) = spawn(node_config).await;
+    let blobs = api.anvil_get_blobs_by_transaction_hash(receipt.transaction_hash).await.unwrap();
+    assert_eq!(blobs, Some(sidecar.blobs.clone()));
+    assert_eq!(api.beacon_blob_sidecars(BeaconBlockId::Slot(block_number)).unwrap(), sidecars);
+}
@@ -0,0 +1,128 @@
+use crate::utils::http_provider;
+use alloy_eips::eip2718::Encodable2718;
+use alloy_network::{TransactionBuilder, TxSignerSync};
+use alloy_primitives::{bytes, Address, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{other::OtherFields, TransactionRequest, WithOtherFields};
+use alloy_signer::SignerSync;
+use anvil::{spawn, Hardfork, NodeConfig};
+use anvil_core::eth::transaction::{
+    eip7702::{delegation_designator, SignedAuthorization, TxEip7702},
+    TypedTransaction,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_eip7702_tx() {
+    let node_config = NodeConfig::test().with_hardfork(Some(Hardfork::Prague));
+    let (api, handle) = spawn(node_config).await;
+    let provider = http_provider(&handle.http_endpoint());
+
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let sender = wallets[0].clone();
+    let authority = wallets[1].clone();
+
+    // ADDRESS PUSH0 MSTORE PUSH1 0x20 PUSH0 RETURN
+    let delegate = Address::random();
+    api.anvil_set_code(delegate, bytes!("305f5260205ff3")).await.unwrap();
+
+    let chain_id = provider.get_chain_id().await.unwrap();
+    let hash = SignedAuthorization::signature_hash(chain_id, delegate, 0);
+    let signature = authority.sign_hash_sync(&hash).unwrap();
+    let authorization = SignedAuthorization::new(chain_id, delegate, 0, signature);
+    assert_eq!(authorization.recover_authority().unwrap(), authority.address());
+
+    let eip1559_est = provider.estimate_eip1559_fees(None).await.unwrap();
+    let mut tx = TxEip7702 {
+        chain_id,
+        nonce: 0,
+        gas_limit: 100_000,
+        max_fee_per_gas: eip1559_est.max_fee_per_gas,
+        max_priority_fee_per_gas: eip1559_est.max_priority_fee_per_gas,
+        to: authority.address(),
+        authorization_list: vec![authorization],
+        ..Default::default()
+    };
+    let signature = sender.sign_transaction_sync(&mut tx).unwrap();
+    let tx =
+        TypedTransaction::EIP7702(alloy_consensus::SignableTransaction::into_signed(tx, signature));
+
+    let receipt = provider
+        .send_raw_transaction(&tx.encoded_2718())
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    assert!(receipt.inner.inner.inner.receipt.status);
+    assert_eq!(receipt.inner.inner.r#type, 4);
+    // the delegate's code costs 15 gas and the refund for the existing authority is capped at a
+    // fifth of the gas used
+    let gas = 21_000 + 25_000 + 15;
+    assert_eq!(receipt.gas_used, gas - gas / 5);
+
+    let code = provider.get_code_at(authority.address()).await.unwrap();
+    assert_eq!(code, delegation_designator(delegate));
+    let nonce = provider.get_transaction_count(authority.address()).await.unwrap();
+    assert_eq!(nonce, 1);
+
+    // calls to the authority execute the code of the delegate in the context of the authority
+    let call = TransactionRequest::default().with_to(authority.address());
+    let out = provider.call(&WithOtherFields::new(call)).await.unwrap();
+    assert_eq!(U256::from_be_slice(&out), U256::from_be_slice(authority.address().as_slice()));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_call_with_authorization_list() {
+    let node_config = NodeConfig::test().with_hardfork(Some(Hardfork::Prague));
+    let (api, handle) = spawn(node_config).await;
+    let provider = http_provider(&handle.http_endpoint());
+
+    let authority = handle.dev_wallets().nth(1).unwrap();
+
+    // ADDRESS PUSH0 MSTORE PUSH1 0x20 PUSH0 RETURN
+    let delegate = Address::random();
+    api.anvil_set_code(delegate, bytes!("305f5260205ff3")).await.unwrap();
+
+    let chain_id = provider.get_chain_id().await.unwrap();
+    let hash = SignedAuthorization::signature_hash(chain_id, delegate, 0);
+    let authorization =
+        SignedAuthorization::new(chain_id, delegate, 0, authority.sign_hash_sync(&hash).unwrap());
+
+    let call = WithOtherFields {
+        inner: TransactionRequest::default().with_to(authority.address()),
+        other: OtherFields::from_iter([(
+            "authorizationList".to_string(),
+            serde_json::to_value(vec![authorization]).unwrap(),
+        )]),
+    };
+    let out = provider.call(&call).await.unwrap();
+    assert_eq!(U256::from_be_slice(&out), U256::from_be_slice(authority.address().as_slice()));
+
+    let gas = provider.estimate_gas(&call).await.unwrap();
+    assert!(gas > 21_000 + 12_500, "{gas}");
+
+    // the authorization is not applied outside of the call
+    let code = provider.get_code_at(authority.address()).await.unwrap();
+    assert!(code.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_eip7702_tx_before_prague() {
+    let (_api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::Cancun))).await;
+    let provider = http_provider(&handle.http_endpoint());
+
+    let sender = handle.dev_wallets().next().unwrap();
+    let mut tx = TxEip7702 {
+        chain_id: provider.get_chain_id().await.unwrap(),
+        gas_limit: 100_000,
+        max_fee_per_gas: 10_000_000_000,
+        to: Address::random(),
+        ..Default::default()
+    };
+    let signature = sender.sign_transaction_sync(&mut tx).unwrap();
+    let tx =
+        TypedTransaction::EIP7702(alloy_consensus::SignableTransaction::into_signed(tx, signature));
+
+    let err = provider.send_raw_transaction(&tx.encoded_2718()).await.unwrap_err();
+    assert!(err.to_string().contains("prague"), "{err}");
+}
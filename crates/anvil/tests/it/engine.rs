@@ -0,0 +1,157 @@
+//! engine API tests
+
+use crate::utils::http_provider;
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{hex, Address, B256, U256};
+use alloy_provider::{ext::TxPoolApi, Provider};
+use alloy_rpc_types::{BlockId, BlockNumberOrTag, TransactionRequest, WithOtherFields};
+use alloy_rpc_types_engine::{
+    ExecutionPayloadEnvelopeV3, ForkchoiceState, ForkchoiceUpdated, PayloadAttributes,
+    PayloadStatus, PayloadStatusEnum,
+};
+use anvil::{spawn, NodeConfig};
+use foundry_common::provider::ProviderBuilder;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_build_block_via_engine_api() {
+    let (api, handle) =
+        spawn(NodeConfig::test().with_no_mining(true).with_engine_api(Some(0), None)).await;
+    let provider = handle.http_provider();
+    let engine = ProviderBuilder::new(&handle.auth_endpoint().unwrap())
+        .jwt(hex::encode(handle.jwt_secret().unwrap()))
+        .build()
+        .unwrap();
+
+    let accounts = provider.get_accounts().await.unwrap();
+    let tx = TransactionRequest::default().with_from(accounts[0]).with_to(accounts[1]);
+    let tx_hash = *provider.send_transaction(WithOtherFields::new(tx)).await.unwrap().tx_hash();
+
+    let head = api.block_by_number(BlockNumberOrTag::Latest).await.unwrap().unwrap();
+    let head_hash = head.header.hash.unwrap();
+    let state = |head_block_hash| ForkchoiceState {
+        head_block_hash,
+        safe_block_hash: head_block_hash,
+        finalized_block_hash: head_block_hash,
+    };
+    let fee_recipient = Address::random();
+    let attributes = PayloadAttributes {
+        timestamp: head.header.timestamp + 12,
+        prev_randao: B256::random(),
+        suggested_fee_recipient: fee_recipient,
+        withdrawals: Some(vec![]),
+        parent_beacon_block_root: Some(B256::ZERO),
+    };
+
+    let updated: ForkchoiceUpdated = engine
+        .raw_request("engine_forkchoiceUpdatedV3".into(), (state(head_hash), Some(attributes)))
+        .await
+        .unwrap();
+    assert_eq!(updated.payload_status.status, PayloadStatusEnum::Valid);
+    let payload_id = updated.payload_id.unwrap();
+
+    let envelope: ExecutionPayloadEnvelopeV3 =
+        engine.raw_request("engine_getPayloadV3".into(), (payload_id,)).await.unwrap();
+    let payload = envelope.execution_payload;
+    let inner = &payload.payload_inner.payload_inner;
+    assert_eq!(inner.block_number, 1);
+    assert_eq!(inner.fee_recipient, fee_recipient);
+    assert_eq!(inner.transactions.len(), 1);
+    assert!(envelope.block_value > U256::ZERO);
+    // building the payload does not mine it
+    assert_eq!(provider.get_block_number().await.unwrap(), 0);
+
+    let block_hash = inner.block_hash;
+    let mut invalid_payload = payload.clone();
+    let status: PayloadStatus = engine
+        .raw_request("engine_newPayloadV3".into(), (payload, Vec::<B256>::new(), B256::ZERO))
+        .await
+        .unwrap();
+    assert_eq!(status.status, PayloadStatusEnum::Valid);
+    assert_eq!(status.latest_valid_hash, Some(block_hash));
+
+    let updated: ForkchoiceUpdated = engine
+        .raw_request("engine_forkchoiceUpdatedV3".into(), (state(block_hash), None::<()>))
+        .await
+        .unwrap();
+    assert_eq!(updated.payload_status.status, PayloadStatusEnum::Valid);
+
+    let block = provider.get_block(BlockId::number(1), false).await.unwrap().unwrap();
+    assert_eq!(block.header.hash, Some(block_hash));
+    assert_eq!(block.header.miner, fee_recipient);
+    let receipt = provider.get_transaction_receipt(tx_hash).await.unwrap().unwrap();
+    assert_eq!(receipt.block_hash, Some(block_hash));
+    assert_eq!(provider.txpool_status().await.unwrap().pending, 0);
+
+    // an invalid payload on top of an older block is rejected without changing the head
+    invalid_payload.payload_inner.payload_inner.block_hash = B256::random();
+    let status: PayloadStatus = engine
+        .raw_request(
+            "engine_newPayloadV3".into(),
+            (invalid_payload, Vec::<B256>::new(), B256::ZERO),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(status.status, PayloadStatusEnum::Invalid { .. }), "{status:?}");
+    let head = api.block_by_number(BlockNumberOrTag::Latest).await.unwrap().unwrap();
+    assert_eq!(head.header.hash, Some(block_hash));
+    let receipt = provider.get_transaction_receipt(tx_hash).await.unwrap().unwrap();
+    assert_eq!(receipt.block_hash, Some(block_hash));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn engine_api_requires_authentication() {
+    let (_api, handle) = spawn(NodeConfig::test().with_engine_api(Some(0), None)).await;
+    let state = ForkchoiceState {
+        head_block_hash: B256::ZERO,
+        safe_block_hash: B256::ZERO,
+        finalized_block_hash: B256::ZERO,
+    };
+
+    // the engine API is not served on the regular endpoint
+    let err = handle
+        .http_provider()
+        .raw_request::<_, ForkchoiceUpdated>(
+            "engine_forkchoiceUpdatedV3".into(),
+            (state, None::<()>),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Method not found"), "{err}");
+
+    let unauthenticated = http_provider(&handle.auth_endpoint().unwrap());
+    unauthenticated
+        .raw_request::<_, ForkchoiceUpdated>(
+            "engine_forkchoiceUpdatedV3".into(),
+            (state, None::<()>),
+        )
+        .await
+        .unwrap_err();
+
+    // all other methods are served on the authenticated endpoint as well
+    let engine = ProviderBuilder::new(&handle.auth_endpoint().unwrap())
+        .jwt(hex::encode(handle.jwt_secret().unwrap()))
+        .build()
+        .unwrap();
+    assert_eq!(engine.get_block_number().await.unwrap(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_create_jwt_secret_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("jwt.hex");
+    let (_api, handle) = spawn(
+        NodeConfig::test().with_engine_api(Some(0), None).with_jwt_secret_path(Some(path.clone())),
+    )
+    .await;
+
+    let secret = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(secret, hex::encode(handle.jwt_secret().unwrap()));
+
+    // the secret is only readable by the current user
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -80,6 +80,44 @@ async fn test_fork_eth_get_balance() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_cache_metrics() {
+    let (api, _handle) = spawn(fork_config().with_metrics(true)).await;
+    let addr = Address::random();
+    api.balance(addr, None).await.unwrap();
+    api.balance(addr, None).await.unwrap();
+
+    let stats = api.get_fork().unwrap().cache_stats();
+    assert!(stats.misses() >= 1);
+    assert!(stats.hits() >= 1);
+    assert!(stats.requests() >= 3);
+    assert!(api.encode_metrics().unwrap().contains("anvil_fork_cache_hits_total"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_reset_reuses_block_cache() {
+    let (api, _handle) = spawn(fork_config()).await;
+    let addr = Address::random();
+    api.balance(addr, None).await.unwrap();
+
+    api.anvil_reset(Some(Forking { json_rpc_url: None, block_number: Some(BLOCK_NUMBER - 1) }))
+        .await
+        .unwrap();
+    api.balance(addr, None).await.unwrap();
+
+    // switching back to a block that was already used doesn't fetch the account again
+    api.anvil_reset(Some(Forking { json_rpc_url: None, block_number: Some(BLOCK_NUMBER) }))
+        .await
+        .unwrap();
+    let misses = api.anvil_fork_cache_stats().await.unwrap().misses;
+    api.balance(addr, None).await.unwrap();
+
+    let stats = api.anvil_fork_cache_stats().await.unwrap();
+    assert_eq!(stats.misses, misses);
+    let blocks = stats.cached_blocks.iter().map(|block| block.block_number).collect::<Vec<_>>();
+    assert_eq!(blocks, vec![BLOCK_NUMBER - 1, BLOCK_NUMBER]);
+}
+
 // <https://github.com/foundry-rs/foundry/issues/4082>
 #[tokio::test(flavor = "multi_thread")]
 async fn test_fork_eth_get_balance_after_mine() {
@@ -385,6 +423,44 @@ async fn test_fork_snapshotting_blocks() {
     assert_eq!(block_number_after, block_number + 1);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_rollback() {
+    let fork = LocalFork::new().await;
+    let api = &fork.fork_api;
+    let provider = fork.fork_handle.http_provider();
+
+    let accounts: Vec<_> = fork.fork_handle.dev_wallets().collect();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+    let fork_block_number = provider.get_block_number().await.unwrap();
+    let balance_before = provider.get_balance(to).await.unwrap();
+
+    let tx = TransactionRequest::default().to(to).value(U256::from(100)).from(from);
+    let tx = WithOtherFields::new(tx);
+    for _ in 0..2 {
+        provider.send_transaction(tx.clone()).await.unwrap().get_receipt().await.unwrap();
+    }
+    assert_eq!(provider.get_block_number().await.unwrap(), fork_block_number + 2);
+
+    // keep the unwound transactions in the pool
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    api.anvil_rollback(1).await.unwrap();
+    assert_eq!(provider.get_block_number().await.unwrap(), fork_block_number + 1);
+    assert_eq!(provider.get_balance(to).await.unwrap(), balance_before + U256::from(100));
+    assert_eq!(provider.get_transaction_count(from).await.unwrap(), 1);
+
+    // rolling back to the fork block restores the fresh forked state
+    api.anvil_rollback(1).await.unwrap();
+    assert_eq!(provider.get_block_number().await.unwrap(), fork_block_number);
+    assert_eq!(provider.get_balance(to).await.unwrap(), balance_before);
+    assert_eq!(provider.get_transaction_count(from).await.unwrap(), 0);
+    assert_eq!(api.txpool_status().await.unwrap().pending, 2);
+
+    // can't roll back past the fork block
+    assert!(api.anvil_rollback(1).await.is_err());
+}
+
 /// tests that the remote state and local state are kept separate.
 /// changes don't make into the read only Database that holds the remote state, which is flushed to
 /// a cache file.
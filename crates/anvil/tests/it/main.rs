@@ -2,12 +2,16 @@ mod abi;
 mod anvil;
 mod anvil_api;
 mod api;
+mod bundle;
 mod eip4844;
+mod eip7702;
+mod engine;
 mod fork;
 mod gas;
 mod genesis;
 mod ipc;
 mod logs;
+mod multichain;
 mod optimism;
 mod otterscan;
 mod proof;
@@ -0,0 +1,74 @@
+//! tests for running several chains in one process
+
+use crate::utils::http_provider;
+use alloy_primitives::U256;
+use alloy_provider::Provider;
+use alloy_rpc_types::BlockId;
+use anvil::{
+    multichain::{try_spawn_chains, ChainConfig, MultiChainConfig},
+    NodeConfig,
+};
+
+fn chain(name: &str, chain_id: u64, path: &str) -> ChainConfig {
+    ChainConfig {
+        name: name.to_string(),
+        chain_id: Some(chain_id),
+        path: Some(path.to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_serve_chains_at_paths() {
+    let config = MultiChainConfig {
+        shared_clock: false,
+        chains: vec![chain("l1", 1, "/l1"), chain("l2", 10, "/l2")],
+    };
+    let handle = try_spawn_chains(config, NodeConfig::test()).await.unwrap();
+
+    let l1 = handle.chain("l1").unwrap();
+    let l2 = handle.chain("l2").unwrap();
+    assert_eq!(
+        l1.http_endpoint().trim_end_matches("/l1"),
+        l2.http_endpoint().trim_end_matches("/l2")
+    );
+
+    assert_eq!(http_provider(&l1.http_endpoint()).get_chain_id().await.unwrap(), 1);
+    assert_eq!(http_provider(&l2.http_endpoint()).get_chain_id().await.unwrap(), 10);
+
+    // the chains are independent
+    l1.api.mine_one().await;
+    assert_eq!(http_provider(&l1.http_endpoint()).get_block_number().await.unwrap(), 1);
+    assert_eq!(http_provider(&l2.http_endpoint()).get_block_number().await.unwrap(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_share_clock() {
+    let config = MultiChainConfig {
+        shared_clock: true,
+        chains: vec![chain("l1", 1, "/l1"), chain("l2", 10, "/l2")],
+    };
+    let handle = try_spawn_chains(config, NodeConfig::test()).await.unwrap();
+    let l1 = handle.chain("l1").unwrap();
+    let l2 = handle.chain("l2").unwrap();
+
+    let day = 24 * 60 * 60;
+    l1.api.evm_increase_time(U256::from(day)).await.unwrap();
+
+    l2.api.mine_one().await;
+    let provider = http_provider(&l2.http_endpoint());
+    let genesis = provider.get_block(BlockId::number(0), false).await.unwrap().unwrap();
+    let block = provider.get_block(BlockId::latest(), false).await.unwrap().unwrap();
+    assert!(block.header.timestamp >= genesis.header.timestamp + day);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_conflicting_routes() {
+    // the chain at `/metrics` conflicts with the metrics endpoint of the chain at the root
+    let config = MultiChainConfig {
+        shared_clock: false,
+        chains: vec![chain("l1", 1, "/"), chain("l2", 10, "/metrics")],
+    };
+    let err = try_spawn_chains(config, NodeConfig::test().with_metrics(true)).await.unwrap_err();
+    assert!(err.to_string().contains("failed to serve chain `l2`"), "{err:?}");
+}
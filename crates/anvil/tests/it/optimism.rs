@@ -3,10 +3,12 @@
 use crate::utils::http_provider_with_signer;
 use alloy_eips::eip2718::Encodable2718;
 use alloy_network::{EthereumSigner, TransactionBuilder};
-use alloy_primitives::{b256, U128, U256};
+use alloy_primitives::{b256, Address, Bytes, U128, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::{optimism::OptimismTransactionFields, TransactionRequest, WithOtherFields};
 use anvil::{spawn, Hardfork, NodeConfig};
+use anvil_core::{eth::transaction::optimism::TRANSACTION_DEPOSITED_TOPIC, types::ReorgOptions};
+use std::time::Duration;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_deposits_not_supported_if_optimism_disabled() {
@@ -143,3 +145,74 @@ async fn test_send_value_raw_deposit_transaction() {
     let after_balance_to = provider.get_balance(to).await.unwrap();
     assert_eq!(after_balance_to, before_balance_to + send_value);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_relay_deposits() {
+    let (l1_api, l1_handle) = spawn(NodeConfig::test()).await;
+
+    // a portal that emits `TransactionDeposited(caller, caller, 0, calldata)`:
+    // CALLDATASIZE PUSH0 PUSH0 CALLDATACOPY PUSH0 CALLER CALLER PUSH32 <topic>
+    // CALLDATASIZE PUSH0 LOG4 STOP
+    let portal = Address::random();
+    let mut code = vec![0x36, 0x5f, 0x5f, 0x37, 0x5f, 0x33, 0x33, 0x7f];
+    code.extend_from_slice(TRANSACTION_DEPOSITED_TOPIC.as_slice());
+    code.extend_from_slice(&[0x36, 0x5f, 0xa4, 0x00]);
+    l1_api.anvil_set_code(portal, code.into()).await.unwrap();
+
+    let (l2_api, l2_handle) = spawn(
+        NodeConfig::test()
+            .with_optimism(true)
+            .with_hardfork(Some(Hardfork::Paris))
+            .with_deposit_relay(Some(l1_handle.http_endpoint()), Some(portal)),
+    )
+    .await;
+    let l2 = l2_handle.http_provider();
+
+    // abi.encode(abi.encodePacked(mint, value, gasLimit, isCreation, data))
+    let mint = U256::from(1_000_000_000_000_000_000u128);
+    let mut opaque = Vec::new();
+    opaque.extend_from_slice(&mint.to_be_bytes::<32>());
+    opaque.extend_from_slice(&U256::ZERO.to_be_bytes::<32>());
+    opaque.extend_from_slice(&100_000u64.to_be_bytes());
+    opaque.push(0);
+    let mut input = Vec::new();
+    input.extend_from_slice(&U256::from(32).to_be_bytes::<32>());
+    input.extend_from_slice(&U256::from(opaque.len()).to_be_bytes::<32>());
+    input.extend_from_slice(&opaque);
+    input.resize(128, 0);
+
+    let depositor = Address::random();
+    l1_api.anvil_impersonate_account(depositor).await.unwrap();
+    l1_api.anvil_set_balance(depositor, U256::from(1e18 as u64)).await.unwrap();
+    let tx = TransactionRequest::default()
+        .with_from(depositor)
+        .with_to(portal)
+        .with_input(Bytes::from(input))
+        .with_gas_limit(100_000);
+    l1_api.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+
+    // the deposit is relayed once the relayer sees the new L1 block
+    let mut balance = U256::ZERO;
+    for _ in 0..100 {
+        balance = l2.get_balance(depositor).await.unwrap();
+        if !balance.is_zero() {
+            break
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(balance, mint);
+    assert_eq!(l2_api.block_number().unwrap(), U256::from(1));
+
+    // the deposit is removed again once the L1 block that emitted it is reorged out
+    l1_api.anvil_set_auto_mine(false).await.unwrap();
+    l1_api.anvil_reorg(ReorgOptions { depth: 1, tx_block_pairs: vec![] }).await.unwrap();
+    for _ in 0..100 {
+        balance = l2.get_balance(depositor).await.unwrap();
+        if balance.is_zero() {
+            break
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(balance, U256::ZERO);
+    assert_eq!(l2_api.block_number().unwrap(), U256::ZERO);
+}
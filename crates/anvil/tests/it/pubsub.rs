@@ -1,6 +1,9 @@
 //! tests for subscriptions
 
-use crate::utils::{connect_pubsub, connect_pubsub_with_signer};
+use crate::{
+    abi::SimpleStorage,
+    utils::{connect_pubsub, connect_pubsub_with_signer, http_provider_with_signer},
+};
 use alloy_network::{EthereumSigner, TransactionBuilder};
 use alloy_primitives::{Address, U256};
 use alloy_provider::Provider;
@@ -8,6 +11,7 @@ use alloy_pubsub::Subscription;
 use alloy_rpc_types::{Block as AlloyBlock, Filter, TransactionRequest, WithOtherFields};
 use alloy_sol_types::sol;
 use anvil::{spawn, NodeConfig};
+use anvil_core::eth::subscription::{StateDiff, StateDiffFilter};
 use futures::StreamExt;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -112,6 +116,46 @@ async fn test_sub_logs() {
     assert_eq!(receipt.inner.logs()[0], log);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sub_logs_removed_on_rollback() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let wallet = handle.dev_wallets().next().unwrap();
+    let provider = connect_pubsub(&handle.ws_endpoint()).await;
+
+    let contract_addr = EmitLogs::deploy_builder(provider.clone(), "First Message".to_string())
+        .from(wallet.address())
+        .deploy()
+        .await
+        .unwrap();
+    let contract = EmitLogs::new(contract_addr, provider.clone());
+
+    let filter = Filter::new().address(contract.address().to_owned());
+    let logs_sub = provider.subscribe_logs(&filter).await.unwrap();
+
+    let receipt = contract
+        .setValue("Next Message".to_string())
+        .send()
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+
+    let mut logs_sub = logs_sub.into_stream();
+    let log = logs_sub.next().await.unwrap();
+    assert_eq!(receipt.inner.logs()[0], log);
+    assert!(!log.removed);
+
+    // removing the block emits its logs again, marked as removed
+    api.anvil_rollback(1).await.unwrap();
+
+    let removed = logs_sub.next().await.unwrap();
+    assert!(removed.removed);
+    assert_eq!(removed.transaction_hash, log.transaction_hash);
+    assert_eq!(removed.block_hash, log.block_hash);
+    assert_eq!(removed.inner, log.inner);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_sub_logs_impersonated() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -246,6 +290,48 @@ async fn test_subscriptions() {
     assert_eq!(blocks, vec![1, 2, 3])
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sub_state_diff() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let wallet = handle.dev_wallets().next().unwrap();
+    let from = wallet.address();
+    let signer: EthereumSigner = wallet.into();
+    let provider = http_provider_with_signer(&handle.http_endpoint(), signer);
+    let ws = connect_pubsub(&handle.ws_endpoint()).await;
+
+    let simple_storage =
+        SimpleStorage::deploy(&provider, "initial value".to_string()).await.unwrap();
+    let address = *simple_storage.address();
+
+    let sub_id: U256 = ws.raw_request("anvil_subscribe".into(), ["stateDiff"]).await.unwrap();
+    let mut all = ws.get_subscription::<StateDiff>(sub_id).await.unwrap().into_stream();
+    let filter = StateDiffFilter { addresses: vec![address] };
+    let sub_id: U256 =
+        ws.raw_request("anvil_subscribe".into(), ("stateDiff", filter)).await.unwrap();
+    let mut filtered = ws.get_subscription::<StateDiff>(sub_id).await.unwrap().into_stream();
+
+    let to = Address::random();
+    let value = U256::from(1337);
+    let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(value);
+    provider.send_transaction(WithOtherFields::new(tx)).await.unwrap().get_receipt().await.unwrap();
+
+    let diff = all.next().await.unwrap();
+    assert_eq!(diff.block_number, 2);
+    assert_eq!(diff.accounts[&to].balance, Some(value));
+    assert_eq!(diff.accounts[&from].nonce, Some(2));
+    assert!(!diff.accounts.contains_key(&address));
+
+    simple_storage.setValue("hello".to_string()).send().await.unwrap().get_receipt().await.unwrap();
+
+    // the transfer didn't touch the contract
+    let diff = filtered.next().await.unwrap();
+    assert_eq!(diff.block_number, 3);
+    assert_eq!(diff.accounts.keys().collect::<Vec<_>>(), [&address]);
+    let account = &diff.accounts[&address];
+    assert_eq!((account.balance, account.nonce, account.code.as_ref()), (None, None, None));
+    assert!(!account.storage.is_empty());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_sub_new_heads_fast() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -1,6 +1,11 @@
 //! general eth api tests
 
-use anvil::{spawn, NodeConfig};
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{bytes, Address, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockId, TransactionRequest, WithOtherFields};
+use anvil::{spawn, Hardfork, NodeConfig};
+use anvil_core::types::Forking;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn can_load_state() {
@@ -21,3 +26,132 @@ async fn can_load_state() {
     let num2 = api.block_number().unwrap();
     assert_eq!(num, num2);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_load_state_with_history() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let accounts = handle.dev_wallets().collect::<Vec<_>>();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+
+    let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(U256::from(100));
+    let tx = WithOtherFields::new(tx);
+    let receipt = provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+    api.mine_one().await;
+
+    let num = api.block_number().unwrap();
+    let best = api.block_by_number(num.to::<u64>().into()).await.unwrap().unwrap();
+    let state = api.serialized_state().await.unwrap();
+    assert_eq!(state.history.as_ref().unwrap().blocks.len(), 3);
+
+    let (api, _handle) = spawn(NodeConfig::test().with_init_state(Some(state))).await;
+
+    assert_eq!(api.block_number().unwrap(), num);
+    let restored = api.block_by_number(num.to::<u64>().into()).await.unwrap().unwrap();
+    assert_eq!(restored.header.hash, best.header.hash);
+    let restored = api.transaction_receipt(receipt.transaction_hash).await.unwrap().unwrap();
+    assert_eq!(restored.block_hash, receipt.block_hash);
+
+    // the chain continues on top of the restored block
+    api.mine_one().await;
+    let block = api.block_by_number((num.to::<u64>() + 1).into()).await.unwrap().unwrap();
+    assert_eq!(block.header.parent_hash, best.header.hash.unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_persist_chain() {
+    let tmp = tempfile::tempdir().unwrap();
+    let db_path = tmp.path().join("chain");
+
+    let (api, handle) = spawn(NodeConfig::test().with_db_path(Some(db_path.clone()))).await;
+    let provider = handle.http_provider();
+
+    let accounts = handle.dev_wallets().collect::<Vec<_>>();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+    let balance_before = provider.get_balance(to).await.unwrap();
+
+    let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(U256::from(100));
+    let tx = WithOtherFields::new(tx);
+    let receipt = provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+    api.mine_one().await;
+
+    // changes made after the last block are persisted as they happen
+    let other = Address::random();
+    api.anvil_set_balance(other, U256::from(1337)).await.unwrap();
+
+    let num = api.block_number().unwrap();
+    let best = api.block_by_number(num.to::<u64>().into()).await.unwrap().unwrap();
+    let balance = provider.get_balance(to).await.unwrap();
+    drop(handle);
+
+    let (api, handle) = spawn(NodeConfig::test().with_db_path(Some(db_path))).await;
+    let provider = handle.http_provider();
+
+    assert_eq!(api.block_number().unwrap(), num);
+    let restored = api.block_by_number(num.to::<u64>().into()).await.unwrap().unwrap();
+    assert_eq!(restored.header.hash, best.header.hash);
+    let restored = api.transaction_receipt(receipt.transaction_hash).await.unwrap().unwrap();
+    assert_eq!(restored.block_hash, receipt.block_hash);
+    assert_eq!(provider.get_balance(to).await.unwrap(), balance);
+    assert_eq!(provider.get_balance(other).await.unwrap(), U256::from(1337));
+    assert_eq!(provider.get_transaction_count(from).await.unwrap(), 1);
+
+    // the state of past blocks is restored as well
+    let parent = BlockId::number(receipt.block_number.unwrap() - 1);
+    assert_eq!(api.balance(to, Some(parent)).await.unwrap(), balance_before);
+
+    // the chain continues on top of the restored block
+    api.mine_one().await;
+    let block = api.block_by_number((num.to::<u64>() + 1).into()).await.unwrap().unwrap();
+    assert_eq!(block.header.parent_hash, best.header.hash.unwrap());
+
+    // the persisted chain can't be switched to a fork
+    let forking =
+        Forking { json_rpc_url: Some("http://localhost:8545".to_string()), block_number: None };
+    assert!(api.anvil_reset(Some(forking)).await.is_err());
+    assert!(!api.is_fork());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_persist_selfdestruct() {
+    let tmp = tempfile::tempdir().unwrap();
+    let db_path = tmp.path().join("chain");
+    // before Cancun, `SELFDESTRUCT` wipes the storage of existing contracts
+    let config = || {
+        NodeConfig::test()
+            .with_hardfork(Some(Hardfork::Shanghai))
+            .with_db_path(Some(db_path.clone()))
+    };
+
+    let (api, handle) = spawn(config()).await;
+    let provider = handle.http_provider();
+    let from = handle.dev_accounts().next().unwrap();
+
+    // stores 1 in slot 0 and deploys `CALLER SELFDESTRUCT`
+    let code = bytes!("60016000556133ff6000526002601ef3");
+    let tx = TransactionRequest::default().with_from(from).with_deploy_code(code);
+    let receipt = provider
+        .send_transaction(WithOtherFields::new(tx))
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    let contract = receipt.contract_address.unwrap();
+    assert_eq!(api.storage_at(contract, U256::ZERO, None).await.unwrap(), B256::with_last_byte(1));
+
+    let tx = TransactionRequest::default().with_from(from).with_to(contract);
+    provider.send_transaction(WithOtherFields::new(tx)).await.unwrap().get_receipt().await.unwrap();
+    assert_eq!(api.storage_at(contract, U256::ZERO, None).await.unwrap(), B256::ZERO);
+    assert!(provider.get_code_at(contract).await.unwrap().is_empty());
+    drop(handle);
+
+    // the restored chain doesn't bring the destroyed contract back
+    let (api, handle) = spawn(config()).await;
+    let provider = handle.http_provider();
+    assert_eq!(api.storage_at(contract, U256::ZERO, None).await.unwrap(), B256::ZERO);
+    assert!(provider.get_code_at(contract).await.unwrap().is_empty());
+}
@@ -2,13 +2,20 @@ use crate::{fork::fork_config, utils::http_provider_with_signer};
 use alloy_network::{EthereumSigner, TransactionBuilder};
 use alloy_primitives::{hex, Address, Bytes, U256};
 use alloy_provider::{ext::DebugApi, Provider};
-use alloy_rpc_types::{BlockNumberOrTag, TransactionRequest, WithOtherFields};
+use alloy_rpc_types::{
+    state::AccountOverride, BlockId, BlockNumberOrTag, TransactionRequest, WithOtherFields,
+};
 use alloy_rpc_types_trace::{
-    geth::{GethDebugTracingCallOptions, GethTrace},
-    parity::{Action, LocalizedTransactionTrace},
+    filter::{TraceFilter, TraceFilterMode},
+    geth::{
+        CallConfig, GethDebugBuiltInTracerType, GethDebugTracingCallOptions,
+        GethDebugTracingOptions, GethTrace, PreStateConfig, PreStateFrame, TraceResult,
+    },
+    parity::{Action, Delta, LocalizedTransactionTrace, TraceType},
 };
 use alloy_sol_types::sol;
 use anvil::{spawn, Hardfork, NodeConfig};
+use std::collections::{HashMap, HashSet};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_get_transfer_parity_traces() {
@@ -132,6 +139,352 @@ async fn test_transfer_debug_trace_call() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_tracer_debug_trace_call() {
+    let (_api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::Shanghai))).await;
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let deployer: EthereumSigner = wallets[0].clone().into();
+    let provider = http_provider_with_signer(&handle.http_endpoint(), deployer);
+
+    let contract_addr = DebugTraceContract::deploy_builder(provider.clone())
+        .from(wallets[0].clone().address())
+        .deploy()
+        .await
+        .unwrap();
+    let contract = DebugTraceContract::new(contract_addr, provider.clone());
+    let calldata = contract.goodbye().calldata().to_owned();
+
+    let tx = TransactionRequest::default()
+        .from(wallets[1].address())
+        .to(contract_addr)
+        .with_input(calldata.clone());
+
+    let opts = GethDebugTracingCallOptions {
+        tracing_options: GethDebugTracingOptions::default()
+            .with_tracer(GethDebugBuiltInTracerType::CallTracer.into())
+            .with_call_config(CallConfig::default().with_log()),
+        ..Default::default()
+    };
+    let GethTrace::CallTracer(frame) = handle
+        .http_provider()
+        .debug_trace_call(tx.clone(), BlockNumberOrTag::Latest, opts)
+        .await
+        .unwrap()
+    else {
+        unreachable!("unexpected trace")
+    };
+
+    assert_eq!(frame.from, wallets[1].address());
+    assert_eq!(frame.to, Some(contract_addr));
+    assert_eq!(frame.input, calldata);
+    assert_eq!(frame.calls.len(), 1);
+    assert_eq!(frame.calls[0].typ, "SELFDESTRUCT");
+
+    let opts = GethDebugTracingCallOptions {
+        tracing_options: GethDebugTracingOptions::default()
+            .with_tracer(GethDebugBuiltInTracerType::CallTracer.into())
+            .with_call_config(CallConfig::default().only_top_call()),
+        ..Default::default()
+    };
+    let GethTrace::CallTracer(frame) =
+        handle.http_provider().debug_trace_call(tx, BlockNumberOrTag::Latest, opts).await.unwrap()
+    else {
+        unreachable!("unexpected trace")
+    };
+    assert!(frame.calls.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debug_trace_call_with_state_overrides() {
+    let (_api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::Shanghai))).await;
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let deployer: EthereumSigner = wallets[0].clone().into();
+    let provider = http_provider_with_signer(&handle.http_endpoint(), deployer);
+
+    let contract_addr = DebugTraceContract::deploy_builder(provider.clone())
+        .from(wallets[0].clone().address())
+        .deploy()
+        .await
+        .unwrap();
+    let contract = DebugTraceContract::new(contract_addr, provider.clone());
+    let code = provider.get_code_at(contract_addr).await.unwrap();
+
+    // call an empty account that only has code with the override
+    let target = Address::random();
+    let tx = TransactionRequest::default()
+        .from(wallets[1].address())
+        .to(target)
+        .with_input(contract.goodbye().calldata().to_owned());
+
+    let tracing_options = GethDebugTracingOptions::default()
+        .with_tracer(GethDebugBuiltInTracerType::CallTracer.into());
+
+    let opts = GethDebugTracingCallOptions {
+        tracing_options: tracing_options.clone(),
+        ..Default::default()
+    };
+    let GethTrace::CallTracer(frame) = handle
+        .http_provider()
+        .debug_trace_call(tx.clone(), BlockNumberOrTag::Latest, opts)
+        .await
+        .unwrap()
+    else {
+        unreachable!("unexpected trace")
+    };
+    assert!(frame.calls.is_empty());
+
+    let opts = GethDebugTracingCallOptions {
+        tracing_options,
+        state_overrides: Some(HashMap::from([(
+            target,
+            AccountOverride { code: Some(code), ..Default::default() },
+        )])),
+        ..Default::default()
+    };
+    let GethTrace::CallTracer(frame) =
+        handle.http_provider().debug_trace_call(tx, BlockNumberOrTag::Latest, opts).await.unwrap()
+    else {
+        unreachable!("unexpected trace")
+    };
+    assert_eq!(frame.to, Some(target));
+    assert_eq!(frame.calls.len(), 1);
+    assert_eq!(frame.calls[0].typ, "SELFDESTRUCT");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_builtin_tracers_debug_trace_transaction() {
+    let (_api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::Shanghai))).await;
+    let provider = handle.http_provider();
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let owner = wallets[0].address();
+    let destructor = wallets[1].address();
+
+    let contract_addr =
+        DebugTraceContract::deploy_builder(provider.clone()).from(owner).deploy().await.unwrap();
+    let contract = DebugTraceContract::new(contract_addr, provider.clone());
+    let receipt =
+        contract.goodbye().from(destructor).send().await.unwrap().get_receipt().await.unwrap();
+    let tx_hash = receipt.transaction_hash;
+
+    // callTracer
+    let opts = GethDebugTracingOptions::default()
+        .with_tracer(GethDebugBuiltInTracerType::CallTracer.into());
+    let GethTrace::CallTracer(frame) =
+        provider.debug_trace_transaction(tx_hash, opts).await.unwrap()
+    else {
+        unreachable!("unexpected trace")
+    };
+    assert_eq!(frame.from, destructor);
+    assert_eq!(frame.to, Some(contract_addr));
+    assert_eq!(frame.gas_used, U256::from(receipt.gas_used));
+    assert_eq!(frame.calls.len(), 1);
+    assert_eq!(frame.calls[0].typ, "SELFDESTRUCT");
+
+    // 4byteTracer
+    let opts = GethDebugTracingOptions::default()
+        .with_tracer(GethDebugBuiltInTracerType::FourByteTracer.into());
+    let GethTrace::FourByteTracer(frame) =
+        provider.debug_trace_transaction(tx_hash, opts).await.unwrap()
+    else {
+        unreachable!("unexpected trace")
+    };
+    assert_eq!(frame.0.get("0x75fc8e3c-0"), Some(&1));
+
+    // prestateTracer
+    let opts = GethDebugTracingOptions::default()
+        .with_tracer(GethDebugBuiltInTracerType::PreStateTracer.into());
+    let GethTrace::PreStateTracer(frame) =
+        provider.debug_trace_transaction(tx_hash, opts).await.unwrap()
+    else {
+        unreachable!("unexpected trace")
+    };
+    match frame {
+        PreStateFrame::Default(prestate) => {
+            assert!(prestate.0.contains_key(&destructor));
+            assert!(prestate.0[&contract_addr].code.is_some());
+        }
+        _ => unreachable!("unexpected prestate mode"),
+    }
+
+    // prestateTracer in diff mode
+    let opts = GethDebugTracingOptions::default()
+        .with_tracer(GethDebugBuiltInTracerType::PreStateTracer.into())
+        .with_prestate_config(PreStateConfig { diff_mode: Some(true) });
+    let GethTrace::PreStateTracer(frame) =
+        provider.debug_trace_transaction(tx_hash, opts).await.unwrap()
+    else {
+        unreachable!("unexpected trace")
+    };
+    match frame {
+        PreStateFrame::Diff(diff) => {
+            let pre = diff.pre[&destructor].nonce.unwrap();
+            let post = diff.post[&destructor].nonce.unwrap();
+            assert_eq!(post, pre + 1);
+            // the contract selfdestructed
+            assert!(diff.pre.contains_key(&contract_addr));
+            assert!(!diff.post.contains_key(&contract_addr));
+        }
+        _ => unreachable!("unexpected prestate mode"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debug_trace_block() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let accounts = handle.dev_wallets().collect::<Vec<_>>();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let mut hashes = Vec::new();
+    for nonce in 0..2u64 {
+        let tx =
+            TransactionRequest::default().to(to).value(U256::from(100)).from(from).nonce(nonce);
+        let tx = WithOtherFields::new(tx);
+        let pending = provider.send_transaction(tx).await.unwrap();
+        hashes.push(*pending.tx_hash());
+    }
+    api.evm_mine(None).await.unwrap();
+
+    let opts = GethDebugTracingOptions::default()
+        .with_tracer(GethDebugBuiltInTracerType::CallTracer.into());
+    let traces =
+        api.debug_trace_block_by_number(BlockNumberOrTag::Latest, opts.clone()).await.unwrap();
+    assert_eq!(traces.len(), 2);
+    for (trace, hash) in traces.iter().zip(&hashes) {
+        let TraceResult::Success { result, tx_hash } = trace else {
+            unreachable!("unexpected trace result")
+        };
+        assert_eq!(*tx_hash, Some(*hash));
+        let expected = api.debug_trace_transaction(*hash, opts.clone()).await.unwrap();
+        assert_eq!(*result, expected);
+    }
+
+    let block = provider.get_block(BlockId::latest(), false).await.unwrap().unwrap();
+    let block_hash = block.header.hash.unwrap();
+    let traces_by_hash = api.debug_trace_block_by_hash(block_hash, opts).await.unwrap();
+    assert_eq!(traces, traces_by_hash);
+
+    // the second transaction is replayed on top of the state changes of the first one
+    let opts = GethDebugTracingOptions::default()
+        .with_tracer(GethDebugBuiltInTracerType::PreStateTracer.into())
+        .with_prestate_config(PreStateConfig { diff_mode: Some(true) });
+    let traces = api.debug_trace_block_by_hash(block_hash, opts).await.unwrap();
+    assert_eq!(traces.len(), 2);
+    for (nonce, trace) in traces.into_iter().enumerate() {
+        match trace {
+            TraceResult::Success {
+                result: GethTrace::PreStateTracer(PreStateFrame::Diff(diff)),
+                ..
+            } => {
+                assert_eq!(diff.pre[&from].nonce, Some(nonce as u64));
+                assert_eq!(diff.post[&from].nonce, Some(nonce as u64 + 1));
+            }
+            _ => unreachable!("unexpected trace result"),
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trace_filter() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let accounts = handle.dev_accounts().collect::<Vec<_>>();
+    for (from, to) in
+        [(accounts[0], accounts[1]), (accounts[0], accounts[2]), (accounts[1], accounts[2])]
+    {
+        let tx = TransactionRequest::default().to(to).value(U256::from(100)).from(from);
+        provider
+            .send_transaction(WithOtherFields::new(tx))
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap();
+    }
+
+    let traces = api.trace_filter(TraceFilter::default()).await.unwrap();
+    assert_eq!(traces.len(), 3);
+
+    let traces =
+        api.trace_filter(TraceFilter::default().from_address(vec![accounts[0]])).await.unwrap();
+    assert_eq!(traces.len(), 2);
+
+    let filter = TraceFilter::default()
+        .from_address(vec![accounts[1]])
+        .to_address(vec![accounts[2]])
+        .mode(TraceFilterMode::Intersection);
+    let traces = api.trace_filter(filter).await.unwrap();
+    assert_eq!(traces.len(), 1);
+    assert_eq!(traces[0].block_number, Some(3));
+
+    let traces = api
+        .trace_filter(TraceFilter::default().from_block(2).to_block(3).after(1).count(5))
+        .await
+        .unwrap();
+    assert_eq!(traces.len(), 1);
+    assert_eq!(traces[0].block_number, Some(3));
+
+    api.trace_filter(TraceFilter::default().from_block(2).to_block(4)).await.unwrap_err();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trace_replay() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    let accounts = handle.dev_wallets().collect::<Vec<_>>();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let mut hashes = Vec::new();
+    for nonce in 0..2u64 {
+        let tx =
+            TransactionRequest::default().to(to).value(U256::from(100)).from(from).nonce(nonce);
+        let pending = provider.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+        hashes.push(*pending.tx_hash());
+    }
+    api.evm_mine(None).await.unwrap();
+
+    let trace_types = HashSet::from([TraceType::Trace, TraceType::StateDiff, TraceType::VmTrace]);
+    let replayed = api.trace_replay_transaction(hashes[1], trace_types.clone()).await.unwrap();
+    let traces = api.trace_transaction(hashes[1]).await.unwrap();
+    assert_eq!(replayed.trace.len(), traces.len());
+    assert_eq!(replayed.trace[0].action, traces[0].trace.action);
+    assert!(replayed.vm_trace.is_some());
+
+    // the second transaction is replayed on top of the state changes of the first one
+    let state_diff = replayed.state_diff.unwrap();
+    match &state_diff[&from].nonce {
+        Delta::Changed(nonce) => {
+            assert_eq!(nonce.from.to::<u64>(), 1);
+            assert_eq!(nonce.to.to::<u64>(), 2);
+        }
+        delta => unreachable!("unexpected nonce diff {delta:?}"),
+    }
+
+    let block = api
+        .trace_replay_block_transactions(
+            BlockNumberOrTag::Latest,
+            HashSet::from([TraceType::Trace]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(block.len(), 2);
+    for (trace, hash) in block.iter().zip(&hashes) {
+        assert_eq!(trace.transaction_hash, *hash);
+        assert!(!trace.full_trace.trace.is_empty());
+        assert!(trace.full_trace.state_diff.is_none());
+        assert!(trace.full_trace.vm_trace.is_none());
+    }
+}
+
 // <https://github.com/foundry-rs/foundry/issues/2656>
 #[tokio::test(flavor = "multi_thread")]
 async fn test_trace_address_fork() {
@@ -10,7 +10,7 @@ use alloy_rpc_types::{
     AccessList, AccessListItem, BlockId, BlockNumberOrTag, BlockTransactions, TransactionRequest,
     WithOtherFields,
 };
-use anvil::{spawn, Hardfork, NodeConfig};
+use anvil::{eth::pool::transactions::TransactionOrder, spawn, Hardfork, NodeConfig};
 use eyre::Ok;
 use futures::{future::join_all, FutureExt, StreamExt};
 use std::{collections::HashSet, str::FromStr, time::Duration};
@@ -95,6 +95,65 @@ async fn can_order_transactions() {
     assert_eq!(block.transactions, BlockTransactions::Hashes(vec![higher_price, lower_price]))
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_set_next_block_transactions() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let accounts = handle.dev_wallets().collect::<Vec<_>>();
+    let to = accounts[4].address();
+
+    let mut hashes = Vec::new();
+    for account in &accounts[..3] {
+        let tx = TransactionRequest::default().to(to).from(account.address()).value(U256::from(1));
+        let tx = provider.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+        hashes.push(*tx.tx_hash());
+    }
+
+    let order = vec![hashes[2], hashes[0], hashes[1]];
+    api.anvil_set_next_block_transactions(order.clone()).await.unwrap();
+    api.mine_one().await;
+
+    let block = provider.get_block(BlockId::latest(), false).await.unwrap().unwrap();
+    assert_eq!(block.transactions, BlockTransactions::Hashes(order));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_order_transactions_by_sender() {
+    let (api, handle) =
+        spawn(NodeConfig::test().with_transaction_order(TransactionOrder::Sender)).await;
+    let provider = handle.http_provider();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let accounts = handle.dev_wallets().collect::<Vec<_>>();
+    let to = accounts[4].address();
+
+    let mut sent = Vec::new();
+    for account in accounts[..4].iter().rev() {
+        let from = account.address();
+        // the higher fee must not move a transaction in front of the lower nonce
+        for gas_price in [1_000_000_000u128, 2_000_000_000] {
+            let tx = TransactionRequest::default()
+                .to(to)
+                .from(from)
+                .value(U256::from(1))
+                .with_gas_price(gas_price);
+            let tx = provider.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+            sent.push((from, *tx.tx_hash()));
+        }
+    }
+    api.mine_one().await;
+
+    // stable sort keeps the nonce order of every sender
+    sent.sort_by_key(|(from, _)| *from);
+    let expected = sent.into_iter().map(|(_, hash)| hash).collect();
+    let block = provider.get_block(BlockId::latest(), false).await.unwrap().unwrap();
+    assert_eq!(block.transactions, BlockTransactions::Hashes(expected));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_respect_nonces() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -259,6 +318,12 @@ async fn can_reject_underpriced_replacement() {
     let replacement_err = lower_priced_pending_tx.unwrap_err();
     assert!(replacement_err.to_string().contains("replacement transaction underpriced"));
 
+    // a different transaction with the same gas price doesn't replace it either
+    tx.set_gas_price(gas_price + 1);
+    tx.set_value(amount - U256::from(1));
+    let replacement_err = provider.send_transaction(tx).await.unwrap_err();
+    assert!(replacement_err.to_string().contains("replacement transaction underpriced"));
+
     // mine exactly one block
     api.mine_one().await;
     let higher_priced_receipt = higher_priced_pending_tx.get_receipt().await.unwrap();
@@ -1053,7 +1118,7 @@ async fn estimates_gas_on_pending_by_default() {
         .to(sender)
         .value(U256::from(1e10))
         .input(Bytes::from(vec![0x42]).into());
-    api.estimate_gas(WithOtherFields::new(tx), None, None).await.unwrap();
+    api.estimate_gas(WithOtherFields::new(tx), None, None, None).await.unwrap();
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -1070,7 +1135,7 @@ async fn test_estimate_gas() {
         .value(U256::from(1e10))
         .input(Bytes::from(vec![0x42]).into());
     // Expect the gas estimation to fail due to insufficient funds.
-    let error_result = api.estimate_gas(WithOtherFields::new(tx.clone()), None, None).await;
+    let error_result = api.estimate_gas(WithOtherFields::new(tx.clone()), None, None, None).await;
 
     assert!(error_result.is_err(), "Expected an error due to insufficient funds");
     let error_message = error_result.unwrap_err().to_string();
@@ -1089,7 +1154,7 @@ async fn test_estimate_gas() {
 
     // Estimate gas with state override implying sufficient funds.
     let gas_estimate = api
-        .estimate_gas(WithOtherFields::new(tx), None, Some(state_override))
+        .estimate_gas(WithOtherFields::new(tx), None, Some(state_override), None)
         .await
         .expect("Failed to estimate gas with state override");
 
@@ -4,7 +4,8 @@ use alloy_network::TransactionBuilder;
 use alloy_primitives::U256;
 use alloy_provider::{ext::TxPoolApi, Provider};
 use alloy_rpc_types::{TransactionRequest, WithOtherFields};
-use anvil::{spawn, NodeConfig};
+use anvil::{eth::pool::PoolConfig, spawn, NodeConfig};
+use std::time::Duration;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn geth_txpool() {
@@ -55,3 +56,192 @@ async fn geth_txpool() {
         assert!(content.contains_key(&nonce.to_string()));
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn geth_txpool_content_from() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let accounts = provider.get_accounts().await.unwrap();
+    let (from, other) = (accounts[0], accounts[1]);
+
+    let tx = |from, nonce| {
+        WithOtherFields::new(
+            TransactionRequest::default().with_from(from).with_to(from).with_nonce(nonce),
+        )
+    };
+    provider.send_transaction(tx(from, 0)).await.unwrap();
+    provider.send_transaction(tx(from, 1)).await.unwrap();
+    // nonce gap
+    provider.send_transaction(tx(from, 3)).await.unwrap();
+    provider.send_transaction(tx(other, 0)).await.unwrap();
+
+    let content = api.txpool_content_from(from).await.unwrap();
+    assert_eq!(content.pending.keys().collect::<Vec<_>>(), ["0", "1"]);
+    assert_eq!(content.queued.keys().collect::<Vec<_>>(), ["3"]);
+    assert!(content.pending.values().chain(content.queued.values()).all(|tx| tx.from == from));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_requires_price_bump() {
+    let config = PoolConfig { price_bump: 10, ..Default::default() };
+    let (api, handle) = spawn(NodeConfig::test().with_pool_config(config)).await;
+    let provider = handle.http_provider();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let account = provider.get_accounts().await.unwrap().remove(0);
+    let gas_price = 10_000_000_000u128;
+    let tx = |gas_price| {
+        WithOtherFields::new(
+            TransactionRequest::default()
+                .with_from(account)
+                .with_to(account)
+                .with_nonce(0)
+                .with_gas_price(gas_price),
+        )
+    };
+
+    provider.send_transaction(tx(gas_price)).await.unwrap();
+
+    let err = provider.send_transaction(tx(gas_price * 105 / 100)).await.unwrap_err();
+    assert!(err.to_string().contains("replacement transaction underpriced"), "{err}");
+
+    let replacement = provider.send_transaction(tx(gas_price * 110 / 100)).await.unwrap();
+    let content = api.txpool_content_from(account).await.unwrap();
+    assert_eq!(content.pending.len(), 1);
+    assert_eq!(content.pending["0"].hash, *replacement.tx_hash());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_requires_price_bump_of_both_fees() {
+    let config = PoolConfig { price_bump: 10, ..Default::default() };
+    let (api, handle) = spawn(NodeConfig::test().with_pool_config(config)).await;
+    let provider = handle.http_provider();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let account = provider.get_accounts().await.unwrap().remove(0);
+    let tx = |max_fee: u128, priority_fee: u128| {
+        WithOtherFields::new(
+            TransactionRequest::default()
+                .with_from(account)
+                .with_to(account)
+                .with_nonce(0)
+                .with_max_fee_per_gas(max_fee)
+                .with_max_priority_fee_per_gas(priority_fee),
+        )
+    };
+    let (max_fee, priority_fee) = (20_000_000_000u128, 1_000_000_000u128);
+
+    provider.send_transaction(tx(max_fee, priority_fee)).await.unwrap();
+
+    let err = provider.send_transaction(tx(max_fee * 2, priority_fee)).await.unwrap_err();
+    assert!(err.to_string().contains("replacement transaction underpriced"), "{err}");
+
+    let replacement =
+        provider.send_transaction(tx(max_fee * 110 / 100, priority_fee * 110 / 100)).await.unwrap();
+    let content = api.txpool_content_from(account).await.unwrap();
+    assert_eq!(content.pending["0"].hash, *replacement.tx_hash());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_enforces_slot_limits() {
+    let config = PoolConfig { account_slots: Some(2), global_slots: Some(3), ..Default::default() };
+    let (api, handle) = spawn(NodeConfig::test().with_pool_config(config)).await;
+    let provider = handle.http_provider();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let accounts = provider.get_accounts().await.unwrap();
+    let tx = |from, nonce, gas_price| {
+        WithOtherFields::new(
+            TransactionRequest::default()
+                .with_from(from)
+                .with_to(from)
+                .with_nonce(nonce)
+                .with_gas_price(gas_price),
+        )
+    };
+    let gas_price = 10_000_000_000u128;
+
+    provider.send_transaction(tx(accounts[0], 0, gas_price)).await.unwrap();
+    provider.send_transaction(tx(accounts[0], 1, gas_price)).await.unwrap();
+    let err = provider.send_transaction(tx(accounts[0], 2, gas_price)).await.unwrap_err();
+    assert!(err.to_string().contains("account limit exceeded"), "{err}");
+
+    let cheap = provider.send_transaction(tx(accounts[1], 0, gas_price - 1)).await.unwrap();
+    assert_eq!(provider.txpool_status().await.unwrap().pending, 3);
+
+    // the pool is full, a more expensive transaction evicts the cheapest one
+    provider.send_transaction(tx(accounts[2], 0, gas_price + 1)).await.unwrap();
+    assert_eq!(provider.txpool_status().await.unwrap().pending, 3);
+    assert!(api.txpool_content_from(accounts[1]).await.unwrap().pending.is_empty());
+    assert!(provider.get_transaction_by_hash(*cheap.tx_hash()).await.unwrap().is_none());
+
+    // a transaction that is not more expensive than the cheapest one is rejected
+    let err = provider.send_transaction(tx(accounts[3], 0, gas_price)).await.unwrap_err();
+    assert!(err.to_string().contains("txpool is full"), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_evicts_dependent_transactions() {
+    let config = PoolConfig { global_slots: Some(3), ..Default::default() };
+    let (api, handle) = spawn(NodeConfig::test().with_pool_config(config)).await;
+    let provider = handle.http_provider();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let accounts = provider.get_accounts().await.unwrap();
+    let tx = |from, nonce, gas_price| {
+        WithOtherFields::new(
+            TransactionRequest::default()
+                .with_from(from)
+                .with_to(from)
+                .with_nonce(nonce)
+                .with_gas_price(gas_price),
+        )
+    };
+    let gas_price = 10_000_000_000u128;
+
+    provider.send_transaction(tx(accounts[0], 0, gas_price)).await.unwrap();
+    provider.send_transaction(tx(accounts[1], 0, gas_price - 1)).await.unwrap();
+    provider.send_transaction(tx(accounts[1], 1, gas_price * 2)).await.unwrap();
+
+    // evicting the cheapest transaction also evicts the transaction that depends on it
+    provider.send_transaction(tx(accounts[2], 0, gas_price + 1)).await.unwrap();
+    assert_eq!(provider.txpool_status().await.unwrap().pending, 2);
+    let content = api.txpool_content_from(accounts[1]).await.unwrap();
+    assert!(content.pending.is_empty());
+    assert!(content.queued.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_drops_expired_queued_transactions() {
+    let config = PoolConfig { queue_lifetime: Some(Duration::from_secs(1)), ..Default::default() };
+    let (api, handle) = spawn(NodeConfig::test().with_pool_config(config)).await;
+    let provider = handle.http_provider();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let account = provider.get_accounts().await.unwrap().remove(0);
+    let tx = |nonce| {
+        WithOtherFields::new(
+            TransactionRequest::default().with_from(account).with_to(account).with_nonce(nonce),
+        )
+    };
+
+    // nonce gap
+    provider.send_transaction(tx(1)).await.unwrap();
+    assert_eq!(provider.txpool_status().await.unwrap().queued, 1);
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+
+    // the queued transaction expired
+    provider.send_transaction(tx(0)).await.unwrap();
+    let status = provider.txpool_status().await.unwrap();
+    assert_eq!(status.pending, 1);
+    assert_eq!(status.queued, 0);
+}
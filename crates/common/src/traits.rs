@@ -34,6 +34,10 @@ pub trait TestFunctionExt {
     /// Returns whether this function is a `setUp` function.
     fn is_setup(&self) -> bool;
 
+    /// Returns whether this function is the `afterInvariant` function, which is matched by its
+    /// exact name and takes no inputs.
+    fn is_after_invariant(&self) -> bool;
+
     /// Returns whether this function is a fixture function.
     fn is_fixture(&self) -> bool;
 }
@@ -60,6 +64,10 @@ impl TestFunctionExt for Function {
         self.name.is_setup()
     }
 
+    fn is_after_invariant(&self) -> bool {
+        self.name.is_after_invariant() && self.inputs.is_empty()
+    }
+
     fn is_fixture(&self) -> bool {
         self.name.is_fixture()
     }
@@ -86,6 +94,10 @@ impl TestFunctionExt for String {
         self.as_str().is_setup()
     }
 
+    fn is_after_invariant(&self) -> bool {
+        self.as_str().is_after_invariant()
+    }
+
     fn is_fixture(&self) -> bool {
         self.as_str().is_fixture()
     }
@@ -112,6 +124,10 @@ impl TestFunctionExt for str {
         self.eq_ignore_ascii_case("setup")
     }
 
+    fn is_after_invariant(&self) -> bool {
+        self == "afterInvariant"
+    }
+
     fn is_fixture(&self) -> bool {
         self.starts_with("fixture")
     }
@@ -1,7 +1,8 @@
 //! Configuration for fuzz testing.
 
 use crate::inline::{
-    parse_config_u32, InlineConfigParser, InlineConfigParserError, INLINE_CONFIG_FUZZ_KEY,
+    parse_config_bool, parse_config_u32, InlineConfigParser, InlineConfigParserError,
+    INLINE_CONFIG_FUZZ_KEY,
 };
 use alloy_primitives::U256;
 use serde::{Deserialize, Serialize};
@@ -25,6 +26,12 @@ pub struct FuzzConfig {
     pub dictionary: FuzzDictionaryConfig,
     /// Number of runs to execute and include in the gas report.
     pub gas_report_samples: u32,
+    /// Whether to keep the inputs that reach new branches in a corpus and mutate them into new
+    /// inputs.
+    pub coverage_guided: bool,
+    /// Path where the inputs that reach new branches are saved per test, and from which up to half
+    /// of the runs of later campaigns are seeded. Setting it enables coverage-guided fuzzing.
+    pub corpus_dir: Option<PathBuf>,
     /// Path where fuzz failures are recorded and replayed.
     pub failure_persist_dir: Option<PathBuf>,
     /// Name of the file to record fuzz failures, defaults to `failures`.
@@ -39,6 +46,8 @@ impl Default for FuzzConfig {
             seed: None,
             dictionary: FuzzDictionaryConfig::default(),
             gas_report_samples: 256,
+            coverage_guided: false,
+            corpus_dir: None,
             failure_persist_dir: None,
             failure_persist_file: None,
         }
@@ -54,10 +63,24 @@ impl FuzzConfig {
             seed: None,
             dictionary: FuzzDictionaryConfig::default(),
             gas_report_samples: 256,
+            coverage_guided: false,
+            corpus_dir: None,
             failure_persist_dir: Some(cache_dir),
             failure_persist_file: Some("failures".to_string()),
         }
     }
+
+    /// Returns whether the inputs that reach new branches are kept in a corpus.
+    pub fn is_coverage_guided(&self) -> bool {
+        self.coverage_guided || self.corpus_dir.is_some()
+    }
+
+    /// Returns the path of the corpus file of the given test, if a corpus directory is set.
+    pub fn corpus_file(&self, contract_name: &str, test_name: &str) -> Option<PathBuf> {
+        self.corpus_dir.as_ref().map(|dir| {
+            dir.join(contract_name.split(':').last().unwrap()).join(format!("{test_name}.json"))
+        })
+    }
 }
 
 impl InlineConfigParser for FuzzConfig {
@@ -83,6 +106,8 @@ impl InlineConfigParser for FuzzConfig {
                 "dictionary-weight" => {
                     conf_clone.dictionary.dictionary_weight = parse_config_u32(key, value)?
                 }
+                "coverage-guided" => conf_clone.coverage_guided = parse_config_bool(key, value)?,
+                "corpus-dir" => conf_clone.corpus_dir = Some(PathBuf::from(value)),
                 "failure-persist-file" => conf_clone.failure_persist_file = Some(value),
                 _ => Err(InlineConfigParserError::InvalidConfigProperty(key))?,
             }
@@ -148,12 +173,25 @@ mod tests {
             "forge-config: default.fuzz.runs = 42424242".to_string(),
             "forge-config: default.fuzz.dictionary-weight = 42".to_string(),
             "forge-config: default.fuzz.failure-persist-file = fuzz-failure".to_string(),
+            "forge-config: default.fuzz.coverage-guided = true".to_string(),
         ];
         let base_config = FuzzConfig::default();
         let merged: FuzzConfig = base_config.try_merge(configs).expect("No errors").unwrap();
         assert_eq!(merged.runs, 42424242);
         assert_eq!(merged.dictionary.dictionary_weight, 42);
         assert_eq!(merged.failure_persist_file, Some("fuzz-failure".to_string()));
+        assert!(merged.coverage_guided);
+    }
+
+    #[test]
+    fn corpus_file_per_test() {
+        let config = FuzzConfig { corpus_dir: Some("corpus".into()), ..Default::default() };
+        assert!(config.is_coverage_guided());
+        assert_eq!(
+            config.corpus_file("test/Counter.t.sol:CounterTest", "testFuzz_Increment"),
+            Some("corpus/CounterTest/testFuzz_Increment.json".into())
+        );
+        assert_eq!(FuzzConfig::default().corpus_file("CounterTest", "testFuzz_Increment"), None);
     }
 
     #[test]
@@ -32,6 +32,13 @@ pub struct InvariantConfig {
     pub max_assume_rejects: u32,
     /// Number of runs to execute and include in the gas report.
     pub gas_report_samples: u32,
+    /// Whether to keep the call sequences that reach new branches in a corpus and mutate them into
+    /// new sequences.
+    pub coverage_guided: bool,
+    /// Path where the call sequences that reach new branches are saved per test, and from which up
+    /// to half of the runs of later campaigns are seeded. Setting it enables coverage-guided
+    /// fuzzing.
+    pub corpus_dir: Option<PathBuf>,
     /// Path where invariant failures are recorded and replayed.
     pub failure_persist_dir: Option<PathBuf>,
 }
@@ -47,6 +54,8 @@ impl Default for InvariantConfig {
             shrink_run_limit: 2usize.pow(18_u32),
             max_assume_rejects: 65536,
             gas_report_samples: 256,
+            coverage_guided: false,
+            corpus_dir: None,
             failure_persist_dir: None,
         }
     }
@@ -64,6 +73,8 @@ impl InvariantConfig {
             shrink_run_limit: 2usize.pow(18_u32),
             max_assume_rejects: 65536,
             gas_report_samples: 256,
+            coverage_guided: false,
+            corpus_dir: None,
             failure_persist_dir: Some(cache_dir),
         }
     }
@@ -75,6 +86,18 @@ impl InvariantConfig {
             .join("failures")
             .join(contract_name.split(':').last().unwrap())
     }
+
+    /// Returns whether the call sequences that reach new branches are kept in a corpus.
+    pub fn is_coverage_guided(&self) -> bool {
+        self.coverage_guided || self.corpus_dir.is_some()
+    }
+
+    /// Returns the path of the corpus file of the given test, if a corpus directory is set.
+    pub fn corpus_file(&self, contract_name: &str, test_name: &str) -> Option<PathBuf> {
+        self.corpus_dir.as_ref().map(|dir| {
+            dir.join(contract_name.split(':').last().unwrap()).join(format!("{test_name}.json"))
+        })
+    }
 }
 
 impl InlineConfigParser for InvariantConfig {
@@ -99,6 +122,8 @@ impl InlineConfigParser for InvariantConfig {
                 "depth" => conf_clone.depth = parse_config_u32(key, value)?,
                 "fail-on-revert" => conf_clone.fail_on_revert = parse_config_bool(key, value)?,
                 "call-override" => conf_clone.call_override = parse_config_bool(key, value)?,
+                "coverage-guided" => conf_clone.coverage_guided = parse_config_bool(key, value)?,
+                "corpus-dir" => conf_clone.corpus_dir = Some(PathBuf::from(value)),
                 "failure-persist-dir" => {
                     conf_clone.failure_persist_dir = Some(PathBuf::from(value))
                 }
@@ -126,10 +151,14 @@ mod tests {
 
     #[test]
     fn successful_merge() {
-        let configs = &["forge-config: default.invariant.runs = 42424242".to_string()];
+        let configs = &[
+            "forge-config: default.invariant.runs = 42424242".to_string(),
+            "forge-config: default.invariant.coverage-guided = true".to_string(),
+        ];
         let base_config = InvariantConfig::default();
         let merged: InvariantConfig = base_config.try_merge(configs).expect("No errors").unwrap();
         assert_eq!(merged.runs, 42424242);
+        assert!(merged.coverage_guided);
     }
 
     #[test]
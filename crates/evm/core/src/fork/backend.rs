@@ -76,6 +76,8 @@ enum BackendRequest {
     Transaction(B256, TransactionSender),
     /// Sets the pinned block to fetch data from
     SetPinnedBlock(BlockId),
+    /// Sets the pinned block and the database that stores its data
+    UpdateBlock(BlockId, BlockchainDb),
 }
 
 /// Handles an internal provider and listens for requests.
@@ -142,16 +144,20 @@ where
                 trace!(target: "backendhandler", "received request basic address={:?}", addr);
                 let acc = self.db.accounts().read().get(&addr).cloned();
                 if let Some(basic) = acc {
+                    self.db.stats().record_hit();
                     let _ = sender.send(Ok(basic));
                 } else {
+                    self.db.stats().record_miss();
                     self.request_account(addr, sender);
                 }
             }
             BackendRequest::BlockHash(number, sender) => {
                 let hash = self.db.block_hashes().read().get(&U256::from(number)).cloned();
                 if let Some(hash) = hash {
+                    self.db.stats().record_hit();
                     let _ = sender.send(Ok(hash));
                 } else {
+                    self.db.stats().record_miss();
                     self.request_hash(number, sender);
                 }
             }
@@ -166,15 +172,21 @@ where
                 let value =
                     self.db.storage().read().get(&addr).and_then(|acc| acc.get(&idx).copied());
                 if let Some(value) = value {
+                    self.db.stats().record_hit();
                     let _ = sender.send(Ok(value));
                 } else {
                     // account present but not storage -> fetch storage
+                    self.db.stats().record_miss();
                     self.request_account_storage(addr, idx, sender);
                 }
             }
             BackendRequest::SetPinnedBlock(block_id) => {
                 self.block_id = Some(block_id);
             }
+            BackendRequest::UpdateBlock(block_id, db) => {
+                self.block_id = Some(block_id);
+                self.db = db;
+            }
         }
     }
 
@@ -197,6 +209,7 @@ where
                         .map_err(Into::into);
                     (storage, address, idx)
                 });
+                self.db.stats().record_requests(1);
                 self.pending_requests.push(ProviderRequest::Storage(fut));
             }
         }
@@ -225,6 +238,8 @@ where
             }
             Entry::Vacant(entry) => {
                 entry.insert(vec![listener]);
+                // balance, nonce and code are fetched separately
+                self.db.stats().record_requests(3);
                 self.pending_requests.push(self.get_account_req(address));
             }
         }
@@ -239,6 +254,7 @@ where
             (sender, block, number)
         });
 
+        self.db.stats().record_requests(1);
         self.pending_requests.push(ProviderRequest::FullBlock(fut));
     }
 
@@ -256,6 +272,7 @@ where
             (sender, block, tx)
         });
 
+        self.db.stats().record_requests(1);
         self.pending_requests.push(ProviderRequest::Transaction(fut));
     }
 
@@ -293,6 +310,7 @@ where
                     };
                     (block_hash, number)
                 });
+                self.db.stats().record_requests(1);
                 self.pending_requests.push(ProviderRequest::BlockHash(fut));
             }
         }
@@ -598,6 +616,12 @@ impl SharedBackend {
         self.backend.clone().try_send(req).map_err(|e| eyre::eyre!("{:?}", e))
     }
 
+    /// Updates the pinned block to fetch data from and the database that stores the fetched data
+    pub fn update_block(&self, block: impl Into<BlockId>, db: BlockchainDb) -> eyre::Result<()> {
+        let req = BackendRequest::UpdateBlock(block.into(), db);
+        self.backend.clone().try_send(req).map_err(|e| eyre::eyre!("{:?}", e))
+    }
+
     /// Returns the full block for the given block identifier
     pub fn get_full_block(&self, block: impl Into<BlockId>) -> DatabaseResult<Block> {
         tokio::task::block_in_place(|| {
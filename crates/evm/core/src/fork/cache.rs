@@ -8,11 +8,14 @@ use revm::{
 };
 use serde::{ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     fs,
     io::{BufWriter, Write},
     path::PathBuf,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
 };
 use url::Url;
 
@@ -27,6 +30,8 @@ pub struct BlockchainDb {
     meta: Arc<RwLock<BlockchainDbMeta>>,
     /// the cache that can be flushed
     cache: Arc<JsonBlockCacheDB>,
+    /// counters of the requests served by this database
+    stats: Arc<BlockchainDbStats>,
 }
 
 impl BlockchainDb {
@@ -81,7 +86,12 @@ impl BlockchainDb {
             })
             .unwrap_or_else(|| JsonBlockCacheDB::new(Arc::new(RwLock::new(meta)), cache_path));
 
-        Self { db: Arc::clone(cache.db()), meta: Arc::clone(cache.meta()), cache: Arc::new(cache) }
+        Self {
+            db: Arc::clone(cache.db()),
+            meta: Arc::clone(cache.meta()),
+            cache: Arc::new(cache),
+            stats: Default::default(),
+        }
     }
 
     /// Returns the map that holds the account related info
@@ -113,6 +123,124 @@ impl BlockchainDb {
     pub fn db(&self) -> &Arc<MemDb> {
         &self.db
     }
+
+    /// Returns the counters of the requests served by this database
+    pub fn stats(&self) -> &Arc<BlockchainDbStats> {
+        &self.stats
+    }
+
+    /// Sets the counters that record the requests served by this database
+    pub fn with_stats(mut self, stats: Arc<BlockchainDbStats>) -> Self {
+        self.stats = stats;
+        self
+    }
+}
+
+/// The [BlockchainDb]s of all fork blocks that were used in a session, keyed by chain id, block
+/// number and block hash
+///
+/// The hash tells apart blocks of different networks that share a chain id, e.g. a fork of a
+/// fork.
+///
+/// The state of a fork block never changes, so switching back to a block that was used before can
+/// reuse everything that was already fetched for it. All databases share the same
+/// [BlockchainDbStats].
+#[derive(Clone, Debug, Default)]
+pub struct BlockchainDbCache {
+    dbs: Arc<RwLock<BTreeMap<(u64, u64, B256), BlockchainDb>>>,
+    stats: Arc<BlockchainDbStats>,
+}
+
+impl BlockchainDbCache {
+    /// Adds the database of the block with the given hash that its [BlockchainDbMeta] points to
+    /// and returns it
+    ///
+    /// This replaces any database that is already stored for the same block.
+    pub fn insert(&self, block_hash: B256, db: BlockchainDb) -> BlockchainDb {
+        let db = db.with_stats(Arc::clone(&self.stats));
+        let (chain_id, block_number) = Self::key(&db.meta().read());
+        self.dbs.write().insert((chain_id, block_number, block_hash), db.clone());
+        db
+    }
+
+    /// Returns the database of the given block, creating it with `f` if the block wasn't used yet
+    pub fn get_or_insert_with(
+        &self,
+        chain_id: u64,
+        block_number: u64,
+        block_hash: B256,
+        f: impl FnOnce() -> BlockchainDb,
+    ) -> BlockchainDb {
+        if let Some(db) = self.get(chain_id, block_number, block_hash) {
+            trace!(target: "fork::cache", chain_id, block_number, "reusing cached block");
+            return db
+        }
+        self.insert(block_hash, f())
+    }
+
+    /// Returns the database of the given block if it was used before
+    pub fn get(&self, chain_id: u64, block_number: u64, block_hash: B256) -> Option<BlockchainDb> {
+        self.dbs.read().get(&(chain_id, block_number, block_hash)).cloned()
+    }
+
+    /// Returns the `(chain id, block number)` of all blocks that have a database
+    pub fn blocks(&self) -> Vec<(u64, u64)> {
+        self.dbs
+            .read()
+            .keys()
+            .map(|(chain_id, block_number, _)| (*chain_id, *block_number))
+            .collect()
+    }
+
+    /// Returns the counters shared by all databases
+    pub fn stats(&self) -> &Arc<BlockchainDbStats> {
+        &self.stats
+    }
+
+    fn key(meta: &BlockchainDbMeta) -> (u64, u64) {
+        (meta.cfg_env.chain_id, meta.block_env.number.saturating_to::<u64>())
+    }
+}
+
+/// Counters of the lookups served by a [BlockchainDb] and of the requests sent to the remote
+/// client
+#[derive(Debug, Default)]
+pub struct BlockchainDbStats {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    requests: AtomicU64,
+}
+
+impl BlockchainDbStats {
+    /// Records a lookup that was served from the cache
+    pub fn record_hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a lookup that had to be fetched from the remote client
+    pub fn record_miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records requests that were sent to the remote client
+    pub fn record_requests(&self, count: u64) {
+        self.requests.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Returns the number of lookups that were served from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of lookups that had to be fetched from the remote client
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of requests that were sent to the remote client
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
 }
 
 /// relevant identifying markers in the context of [BlockchainDb]
@@ -621,4 +749,32 @@ mod tests {
 
         let _s = serde_json::to_string(&cache).unwrap();
     }
+
+    #[test]
+    fn can_reuse_block_dbs() {
+        let meta = |number: u64| {
+            let mut env = revm::primitives::Env::default();
+            env.cfg.chain_id = 1;
+            env.block.number = U256::from(number);
+            BlockchainDbMeta::new(env, "http://localhost:8545".to_string())
+        };
+        let cache = BlockchainDbCache::default();
+
+        let hash = |n: u8| B256::with_last_byte(n);
+        let cache = BlockchainDbCache::default();
+
+        let first = cache.get_or_insert_with(1, 1, hash(1), || BlockchainDb::new(meta(1), None));
+        first.accounts().write().insert(Address::ZERO, AccountInfo::default());
+        let second = cache.get_or_insert_with(1, 2, hash(2), || BlockchainDb::new(meta(2), None));
+        assert!(second.accounts().read().is_empty());
+
+        let reused = cache.get_or_insert_with(1, 1, hash(1), || unreachable!());
+        assert!(reused.accounts().read().contains_key(&Address::ZERO));
+        assert!(Arc::ptr_eq(reused.stats(), second.stats()));
+        assert_eq!(cache.blocks(), vec![(1, 1), (1, 2)]);
+
+        // the same block number of another network with the same chain id isn't reused
+        let other = cache.get_or_insert_with(1, 1, hash(3), || BlockchainDb::new(meta(1), None));
+        assert!(other.accounts().read().is_empty());
+    }
 }
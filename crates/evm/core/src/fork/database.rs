@@ -82,6 +82,28 @@ impl ForkedDatabase {
         Ok(())
     }
 
+    /// Reset the fork to a fresh forked state at the given block, using `db` to store its state
+    ///
+    /// Unlike [Self::reset] this keeps the data that was already fetched, if `db` belongs to a
+    /// block that was used before it is reused as is.
+    pub fn reset_with_db(
+        &mut self,
+        db: BlockchainDb,
+        block_number: impl Into<BlockId>,
+    ) -> Result<(), String> {
+        self.backend.update_block(block_number, db.clone()).map_err(|err| err.to_string())?;
+
+        // persist the state of the previous block before switching
+        if !Arc::ptr_eq(self.db.db(), db.db()) {
+            self.flush_cache();
+        }
+        self.db = db;
+        // create a fresh `CacheDB`, effectively wiping modified state
+        self.cache_db = CacheDB::new(self.backend.clone());
+        trace!(target: "backend::forkdb", "Switched database");
+        Ok(())
+    }
+
     /// Flushes the cache to disk if configured
     pub fn flush_cache(&self) {
         self.db.cache().flush()
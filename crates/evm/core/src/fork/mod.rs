@@ -8,7 +8,9 @@ mod init;
 pub use init::environment;
 
 mod cache;
-pub use cache::{BlockchainDb, BlockchainDbMeta, JsonBlockCacheDB, MemDb};
+pub use cache::{
+    BlockchainDb, BlockchainDbCache, BlockchainDbMeta, BlockchainDbStats, JsonBlockCacheDB, MemDb,
+};
 
 pub mod database;
 
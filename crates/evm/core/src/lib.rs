@@ -6,7 +6,7 @@
 
 use auto_impl::auto_impl;
 use revm::{inspectors::NoOpInspector, interpreter::CreateInputs, Database, EvmContext, Inspector};
-use revm_inspectors::access_list::AccessListInspector;
+use revm_inspectors::{access_list::AccessListInspector, tracing::FourByteInspector};
 
 #[macro_use]
 extern crate tracing;
@@ -43,3 +43,4 @@ pub trait InspectorExt<DB: Database>: Inspector<DB> {
 
 impl<DB: Database> InspectorExt<DB> for NoOpInspector {}
 impl<DB: Database> InspectorExt<DB> for AccessListInspector {}
+impl<DB: Database> InspectorExt<DB> for FourByteInspector {}
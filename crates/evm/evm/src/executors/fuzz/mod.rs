@@ -10,12 +10,18 @@ use foundry_evm_core::{
 };
 use foundry_evm_coverage::HitMaps;
 use foundry_evm_fuzz::{
+    corpus::{max_seed_runs, shrink_calldata, Corpus, MAX_CORPUS_SHRINK_RUNS},
     strategies::{fuzz_calldata, fuzz_calldata_from_state, EvmFuzzState},
-    BaseCounterExample, CounterExample, FuzzCase, FuzzError, FuzzFixtures, FuzzTestResult,
+    BaseCounterExample, CounterExample, EdgeCoverage, FuzzCase, FuzzError, FuzzFixtures,
+    FuzzTestResult,
 };
 use foundry_evm_traces::CallTraceArena;
 use proptest::test_runner::{TestCaseError, TestError, TestRunner};
-use std::{borrow::Cow, cell::RefCell};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    path::PathBuf,
+};
 
 mod types;
 pub use types::{CaseOutcome, CounterExampleOutcome, FuzzOutcome};
@@ -34,17 +40,26 @@ pub struct FuzzedExecutor {
     sender: Address,
     /// The fuzz configuration
     config: FuzzConfig,
+    /// The file the corpus of the fuzzed function is loaded from and saved to
+    corpus_file: Option<PathBuf>,
 }
 
 impl FuzzedExecutor {
     /// Instantiates a fuzzed executor given a testrunner
     pub fn new(
-        executor: Executor,
+        mut executor: Executor,
         runner: TestRunner,
         sender: Address,
         config: FuzzConfig,
     ) -> Self {
-        Self { executor, runner, sender, config }
+        executor.set_edge_coverage(config.is_coverage_guided());
+        Self { executor, runner, sender, config, corpus_file: None }
+    }
+
+    /// Sets the file the corpus of the fuzzed function is loaded from and saved to.
+    pub fn with_corpus_file(mut self, corpus_file: Option<PathBuf>) -> Self {
+        self.corpus_file = corpus_file;
+        self
     }
 
     /// Fuzzes the provided function, assuming it is available at the contract at `address`
@@ -87,12 +102,57 @@ impl FuzzedExecutor {
             dictionary_weight => fuzz_calldata_from_state(func.clone(), &state),
         ];
 
+        // Inputs that reached new branches, mutated into new inputs if the fuzzing is
+        // coverage-guided.
+        let corpus: RefCell<Corpus<Bytes>> = RefCell::default();
+        // The saved corpus is replayed first, up to a share of the runs and until a
+        // counterexample is found. Inputs that still reach new branches are kept.
+        let seeds = self
+            .corpus_file
+            .as_deref()
+            .map(|path| Corpus::load_inputs(path, func))
+            .unwrap_or_default();
+        let replayed_seeds = Cell::new(0);
+        let max_seeds = max_seed_runs(self.config.runs);
+        let edge_coverage: RefCell<EdgeCoverage> = RefCell::default();
+        let mutation_runner = RefCell::new(self.runner.clone());
+
+        // Once a counterexample is found, inputs are no longer mutated.
+        let found_counterexample = Cell::new(false);
+        // Whether the counterexample is a seed or a mutation rather than a generated input, such
+        // counterexamples are shrunk after the run.
+        let derived_counterexample = Cell::new(false);
+
         debug!(func=?func.name, should_fail, "fuzzing");
-        let run_result = self.runner.clone().run(&strat, |calldata| {
+        let mut run_result = self.runner.clone().run(&strat, |generated| {
+            // Proptest isn't told about derived counterexamples, the remaining runs are skipped.
+            if derived_counterexample.get() {
+                return Ok(())
+            }
+
+            let seed = (!found_counterexample.get() && replayed_seeds.get() < max_seeds)
+                .then(|| seeds.entries().get(replayed_seeds.get()).cloned())
+                .flatten();
+            let calldata = if let Some(seed) = seed {
+                replayed_seeds.set(replayed_seeds.get() + 1);
+                seed
+            } else if self.config.is_coverage_guided() && !found_counterexample.get() {
+                let mut rng = mutation_runner.borrow_mut();
+                corpus.borrow().next_input(func, generated.clone(), rng.rng())
+            } else {
+                generated.clone()
+            };
+            let derived = calldata != generated;
             let fuzz_res = self.single_fuzz(address, should_fail, calldata)?;
 
             match fuzz_res {
                 FuzzOutcome::Case(case) => {
+                    if let Some(hits) = &case.edge_coverage {
+                        if edge_coverage.borrow_mut().merge(hits) {
+                            corpus.borrow_mut().push(case.case.calldata.clone());
+                        }
+                    }
+
                     let mut first_case = first_case.borrow_mut();
                     gas_by_case.borrow_mut().push((case.case.gas, case.case.stipend));
                     if first_case.is_none() {
@@ -120,6 +180,7 @@ impl FuzzedExecutor {
                     counterexample: _counterexample,
                     ..
                 }) => {
+                    found_counterexample.set(true);
                     let status = exit_reason;
                     // We cannot use the calldata returned by the test runner in `TestError::Fail`,
                     // since that input represents the last run case, which may not correspond with
@@ -128,6 +189,12 @@ impl FuzzedExecutor {
                     // case.
                     let call_res = _counterexample.1.result.clone();
                     *counterexample.borrow_mut() = _counterexample;
+                    // Proptest would shrink and persist the generated input, which isn't the one
+                    // that failed, so the failure of a seed or mutation is reported after the run.
+                    if derived {
+                        derived_counterexample.set(true);
+                        return Ok(())
+                    }
                     // HACK: we have to use an empty string here to denote `None`
                     let reason = rd.maybe_decode(&call_res, Some(status));
                     Err(TestCaseError::fail(reason.unwrap_or_default()))
@@ -135,7 +202,26 @@ impl FuzzedExecutor {
             }
         });
 
-        let (calldata, call) = counterexample.into_inner();
+        let (mut calldata, mut call) = counterexample.into_inner();
+        if derived_counterexample.get() {
+            (calldata, call) = self.shrink(func, address, should_fail, calldata, call);
+            let reason = rd.maybe_decode(&call.result, Some(call.exit_reason));
+            run_result = Err(TestError::Fail(reason.unwrap_or_default().into(), calldata.clone()));
+        }
+
+        if let Some(path) = &self.corpus_file {
+            let mut corpus = corpus.into_inner();
+            for seed in &seeds.entries()[replayed_seeds.get()..] {
+                corpus.push(seed.clone());
+            }
+            // The failing input of a derived counterexample is replayed first by the next run.
+            if derived_counterexample.get() {
+                corpus.push_front(calldata.clone());
+            }
+            if let Err(err) = corpus.save_inputs(path, func) {
+                error!(%err, "Failed to save fuzz corpus");
+            }
+        }
 
         let mut traces = traces.into_inner();
         let last_run_traces = if run_result.is_ok() { traces.pop() } else { call.traces.clone() };
@@ -186,6 +272,35 @@ impl FuzzedExecutor {
         result
     }
 
+    /// Shrinks a counterexample that was not generated by the strategy, by greedily simplifying
+    /// its arguments as long as the call still fails.
+    fn shrink(
+        &self,
+        func: &Function,
+        address: Address,
+        should_fail: bool,
+        mut calldata: Bytes,
+        mut call: RawCallResult,
+    ) -> (Bytes, RawCallResult) {
+        let mut runs = 0;
+        'shrink: while runs < MAX_CORPUS_SHRINK_RUNS {
+            for candidate in shrink_calldata(func, &calldata) {
+                if runs == MAX_CORPUS_SHRINK_RUNS {
+                    break 'shrink
+                }
+                runs += 1;
+                if let Ok(FuzzOutcome::CounterExample(outcome)) =
+                    self.single_fuzz(address, should_fail, candidate)
+                {
+                    (calldata, call) = outcome.counterexample;
+                    continue 'shrink
+                }
+            }
+            break
+        }
+        (calldata, call)
+    }
+
     /// Granular and single-step function that runs only one fuzz and returns either a `CaseOutcome`
     /// or a `CounterExampleOutcome`
     pub fn single_fuzz(
@@ -222,6 +337,7 @@ impl FuzzedExecutor {
                 case: FuzzCase { calldata, gas: call.gas_used, stipend: call.stipend },
                 traces: call.traces,
                 coverage: call.coverage,
+                edge_coverage: call.edge_coverage,
                 debug: call.debug,
                 breakpoints,
             }))
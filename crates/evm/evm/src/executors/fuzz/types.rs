@@ -6,6 +6,7 @@ use foundry_evm_coverage::HitMaps;
 use foundry_evm_fuzz::FuzzCase;
 use foundry_evm_traces::CallTraceArena;
 use revm::interpreter::InstructionResult;
+use std::collections::HashMap;
 
 /// Returned by a single fuzz in the case of a successful run
 #[derive(Debug)]
@@ -16,6 +17,8 @@ pub struct CaseOutcome {
     pub traces: Option<CallTraceArena>,
     /// The coverage info collected during the call
     pub coverage: Option<HitMaps>,
+    /// The hit counters of the branches taken during the call
+    pub edge_coverage: Option<HashMap<usize, u8>>,
     /// The debug nodes of the call
     pub debug: Option<DebugArena>,
     /// Breakpoints char pc map
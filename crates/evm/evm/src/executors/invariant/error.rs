@@ -1,22 +1,23 @@
-use super::{BasicTxDetails, InvariantContract};
+use super::{BasicTxDetails, IInvariantTest, InvariantContract};
 use crate::executors::RawCallResult;
+use alloy_json_abi::Function;
 use alloy_primitives::{Address, Bytes};
+use alloy_sol_types::SolCall;
 use foundry_config::InvariantConfig;
 use foundry_evm_core::decode::RevertDecoder;
 use foundry_evm_fuzz::{invariant::FuzzRunIdentifiedContracts, Reason};
 use proptest::test_runner::TestError;
+use std::collections::BTreeMap;
 
 /// Stores information about failures and reverts of the invariant tests.
 #[derive(Clone, Default)]
 pub struct InvariantFailures {
     /// Total number of reverts.
     pub reverts: usize,
-    /// How many different invariants have been broken.
-    pub broken_invariants_count: usize,
     /// The latest revert reason of a run.
     pub revert_reason: Option<String>,
-    /// Maps a broken invariant to its specific error.
-    pub error: Option<InvariantFuzzError>,
+    /// Maps the name of a broken invariant to its specific error.
+    pub errors: BTreeMap<String, InvariantFuzzError>,
 }
 
 impl InvariantFailures {
@@ -24,8 +25,31 @@ impl InvariantFailures {
         Self::default()
     }
 
-    pub fn into_inner(self) -> (usize, Option<InvariantFuzzError>) {
-        (self.reverts, self.error)
+    pub fn into_inner(self) -> (usize, BTreeMap<String, InvariantFuzzError>) {
+        (self.reverts, self.errors)
+    }
+
+    /// Returns whether the given invariant has been broken.
+    pub fn is_broken(&self, invariant_function: &Function) -> bool {
+        self.errors.contains_key(&invariant_function.name)
+    }
+
+    /// Returns whether all invariants of the campaign have been broken.
+    pub fn all_broken(&self, invariant_contract: &InvariantContract<'_>) -> bool {
+        invariant_contract.invariant_functions.iter().all(|func| self.is_broken(func))
+    }
+
+    /// Records an error for every invariant of the campaign that is not broken yet.
+    pub fn fail_unbroken(
+        &mut self,
+        invariant_contract: &InvariantContract<'_>,
+        mut error: impl FnMut(&Function) -> InvariantFuzzError,
+    ) {
+        for &func in &invariant_contract.invariant_functions {
+            if !self.is_broken(func) {
+                self.errors.insert(func.name.clone(), error(func));
+            }
+        }
     }
 }
 
@@ -59,7 +83,7 @@ pub struct FailedInvariantCaseData {
     pub revert_reason: String,
     /// Address of the invariant asserter.
     pub addr: Address,
-    /// Function data for invariant check.
+    /// Function data of the failed check, the invariant or `afterInvariant`.
     pub func: Bytes,
     /// Inner fuzzing Sequence coming from overriding calls.
     pub inner_sequence: Vec<Option<BasicTxDetails>>,
@@ -72,10 +96,11 @@ pub struct FailedInvariantCaseData {
 impl FailedInvariantCaseData {
     pub fn new(
         invariant_contract: &InvariantContract<'_>,
+        func: &Function,
         invariant_config: &InvariantConfig,
         targeted_contracts: &FuzzRunIdentifiedContracts,
         calldata: &[BasicTxDetails],
-        call_result: RawCallResult,
+        call_result: &RawCallResult,
         inner_sequence: &[Option<BasicTxDetails>],
     ) -> Self {
         // Collect abis of fuzzed and invariant contracts to decode custom error.
@@ -89,7 +114,6 @@ impl FailedInvariantCaseData {
             .with_abis(abis)
             .decode(call_result.result.as_ref(), Some(call_result.exit_reason));
 
-        let func = invariant_contract.invariant_function;
         let origin = func.name.as_str();
         Self {
             test_error: proptest::test_runner::TestError::Fail(
@@ -105,4 +129,14 @@ impl FailedInvariantCaseData {
             fail_on_revert: invariant_config.fail_on_revert,
         }
     }
+
+    /// Records `afterInvariant` as the failed check instead of the invariant, so that only
+    /// `afterInvariant` is checked when shrinking.
+    pub fn with_after_invariant_failure(mut self) -> Self {
+        self.func = IInvariantTest::afterInvariantCall {}.abi_encode().into();
+        if let TestError::Fail(reason, _) = &mut self.test_error {
+            *reason = format!("afterInvariant, reason: {}", self.revert_reason).into();
+        }
+        self
+    }
 }
@@ -2,22 +2,25 @@ use crate::{
     executors::{Executor, RawCallResult},
     inspectors::Fuzzer,
 };
-use alloy_primitives::{Address, FixedBytes, U256};
+use alloy_primitives::{Address, FixedBytes, Selector, U256};
 use alloy_sol_types::{sol, SolCall};
 use eyre::{eyre, ContextCompat, Result};
 use foundry_common::contracts::{ContractsByAddress, ContractsByArtifact};
 use foundry_config::InvariantConfig;
 use foundry_evm_core::{
     constants::{CALLER, CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS, MAGIC_ASSUME},
+    decode::RevertDecoder,
     utils::get_function,
 };
 use foundry_evm_fuzz::{
+    corpus::{max_seed_runs, Corpus},
     invariant::{
         ArtifactFilters, BasicTxDetails, FuzzRunIdentifiedContracts, InvariantContract,
-        RandomCallGenerator, SenderFilters, TargetedContracts,
+        InvariantMetrics, InvariantMetricsKey, RandomCallGenerator, SenderFilters,
+        TargetedContracts,
     },
     strategies::{collect_created_contracts, invariant_strat, override_call_strat, EvmFuzzState},
-    FuzzCase, FuzzFixtures, FuzzedCases,
+    EdgeCoverage, FuzzCase, FuzzFixtures, FuzzedCases,
 };
 use foundry_evm_traces::CallTraceArena;
 use parking_lot::RwLock;
@@ -25,16 +28,21 @@ use proptest::{
     strategy::{BoxedStrategy, Strategy},
     test_runner::{TestCaseError, TestRunner},
 };
-use result::{assert_invariants, can_continue};
+use result::{assert_after_invariant, assert_invariants, can_continue, optimization_value};
 use revm::primitives::HashMap;
 use shrink::shrink_sequence;
-use std::{cell::RefCell, collections::BTreeMap, sync::Arc};
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::Arc,
+};
 
 mod error;
 pub use error::{InvariantFailures, InvariantFuzzError};
 
 mod replay;
-pub use replay::{replay_error, replay_run};
+pub use replay::{replay_error, replay_optimization, replay_run};
 
 mod result;
 pub use result::InvariantFuzzTestResult;
@@ -88,6 +96,8 @@ sol! {
 
         #[derive(Default)]
         function targetInterfaces() public view returns (FuzzInterface[] memory targetedInterfaces);
+
+        function afterInvariant() external;
     }
 }
 
@@ -113,17 +123,20 @@ pub struct InvariantExecutor<'a> {
     project_contracts: &'a ContractsByArtifact,
     /// Filters contracts to be fuzzed through their artifact identifiers.
     artifact_filters: ArtifactFilters,
+    /// The files the corpus of the campaign is loaded from and saved to, one per invariant.
+    corpus_files: Vec<PathBuf>,
 }
 
 impl<'a> InvariantExecutor<'a> {
     /// Instantiates a fuzzed executor EVM given a testrunner
     pub fn new(
-        executor: Executor,
+        mut executor: Executor,
         runner: TestRunner,
         config: InvariantConfig,
         setup_contracts: &'a ContractsByAddress,
         project_contracts: &'a ContractsByArtifact,
     ) -> Self {
+        executor.set_edge_coverage(config.is_coverage_guided());
         Self {
             executor,
             runner,
@@ -131,9 +144,17 @@ impl<'a> InvariantExecutor<'a> {
             setup_contracts,
             project_contracts,
             artifact_filters: ArtifactFilters::default(),
+            corpus_files: vec![],
         }
     }
 
+    /// Sets the files the corpus of the campaign is loaded from and saved to. Every invariant of
+    /// the campaign has its own file, so its corpus is found whichever invariants run with it.
+    pub fn with_corpus_files(mut self, corpus_files: Vec<PathBuf>) -> Self {
+        self.corpus_files = corpus_files;
+        self
+    }
+
     /// Fuzzes any deployed contract and checks any broken invariant at `invariant_address`.
     pub fn invariant_fuzz(
         &mut self,
@@ -141,7 +162,7 @@ impl<'a> InvariantExecutor<'a> {
         fuzz_fixtures: &FuzzFixtures,
     ) -> Result<InvariantFuzzTestResult> {
         // Throw an error to abort test run if the invariant function accepts input params
-        if !invariant_contract.invariant_function.inputs.is_empty() {
+        if invariant_contract.invariant_functions.iter().any(|func| !func.inputs.is_empty()) {
             return Err(eyre!("Invariant test function should have no inputs"))
         }
 
@@ -160,36 +181,93 @@ impl<'a> InvariantExecutor<'a> {
         // Stores additional traces for gas report.
         let gas_report_traces: RefCell<Vec<Vec<CallTraceArena>>> = RefCell::default();
 
+        // Stores the call statistics of every targeted function.
+        let metrics: RefCell<BTreeMap<InvariantMetricsKey, InvariantMetrics>> = RefCell::default();
+        let revert_decoder = RevertDecoder::new()
+            .with_abis(targeted_contracts.targets.lock().values().map(|(_, abi, _)| abi));
+
         // Let's make sure the invariant is sound before actually starting the run:
         // We'll assert the invariant in its initial state, and if it fails, we'll
         // already know if we can early exit the invariant run.
         // This does not count as a fuzz run. It will just register the revert.
-        let last_call_results = RefCell::new(assert_invariants(
+        if !assert_invariants(
             &invariant_contract,
             &self.config,
             &targeted_contracts,
             &self.executor,
             &[],
             &mut failures.borrow_mut(),
-        )?);
-
-        if last_call_results.borrow().is_none() {
+        )? {
             fuzz_cases.borrow_mut().push(FuzzedCases::new(vec![]));
         }
 
+        // The best value of an optimization invariant and the sequence reaching it, starting from
+        // the value in the initial state.
+        let optimization = RefCell::new(if invariant_contract.is_optimization() {
+            optimization_value(
+                &self.executor,
+                invariant_contract.address,
+                invariant_contract.invariant_functions[0],
+            )?
+            .map(|value| (value, vec![]))
+        } else {
+            None
+        });
+
         // The strategy only comes with the first `input`. We fill the rest of the `inputs`
         // until the desired `depth` so we can use the evolving fuzz dictionary
         // during the run. We need another proptest runner to query for random
         // values.
         let branch_runner = RefCell::new(self.runner.clone());
+
+        // Call sequences that reached new branches, mutated into new sequences if the fuzzing is
+        // coverage-guided.
+        let corpus: RefCell<Corpus<Vec<BasicTxDetails>>> = RefCell::default();
+        let edge_coverage: RefCell<EdgeCoverage> = RefCell::default();
+        // The saved corpus is replayed first, up to a share of the runs. Sequences that still
+        // reach new branches are kept.
+        let mut seeds = Corpus::default();
+        for path in &self.corpus_files {
+            for sequence in Corpus::load_sequences(path, &targeted_contracts).entries() {
+                if !seeds.entries().contains(sequence) {
+                    seeds.push(sequence.clone());
+                }
+            }
+        }
+        let replayed_seeds = Cell::new(0);
+        let max_seeds = max_seed_runs(self.config.runs);
+
         let _ = self.runner.run(&strat, |first_input| {
-            let mut inputs = vec![first_input];
+            // The calls of a saved or mutated corpus sequence are executed before generating new
+            // ones.
+            let seed = (replayed_seeds.get() < max_seeds)
+                .then(|| seeds.entries().get(replayed_seeds.get()).cloned())
+                .flatten();
+            let mut replayed = if let Some(seed) = seed {
+                replayed_seeds.set(replayed_seeds.get() + 1);
+                seed.into_iter()
+            } else if self.config.is_coverage_guided() {
+                corpus
+                    .borrow()
+                    .next_sequence(&targeted_contracts, branch_runner.borrow_mut().rng())
+                    .into_iter()
+            } else {
+                Vec::new().into_iter()
+            };
+            let mut inputs = vec![replayed.next().unwrap_or(first_input)];
+            let mut new_coverage = false;
 
             // We stop the run immediately if we have reverted, and `fail_on_revert` is set.
             if self.config.fail_on_revert && failures.borrow().reverts > 0 {
                 return Err(TestCaseError::fail("Revert occurred."))
             }
 
+            // Failures are collected for all invariants, the campaign stops once they are all
+            // broken.
+            if failures.borrow().all_broken(&invariant_contract) {
+                return Err(TestCaseError::fail("All invariants broken."))
+            }
+
             // Before each run, we must reset the backend state.
             let mut executor = self.executor.clone();
 
@@ -222,9 +300,9 @@ impl<'a> InvariantExecutor<'a> {
                     inputs.pop();
                     assume_rejects_counter += 1;
                     if assume_rejects_counter > self.config.max_assume_rejects {
-                        failures.borrow_mut().error = Some(InvariantFuzzError::MaxAssumeRejects(
-                            self.config.max_assume_rejects,
-                        ));
+                        failures.borrow_mut().fail_unbroken(&invariant_contract, |_| {
+                            InvariantFuzzError::MaxAssumeRejects(self.config.max_assume_rejects)
+                        });
                         return Err(TestCaseError::fail("Max number of vm.assume rejects reached."))
                     }
                 } else {
@@ -232,6 +310,18 @@ impl<'a> InvariantExecutor<'a> {
                     let mut state_changeset =
                         call_result.state_changeset.to_owned().expect("no changesets");
 
+                    if let Some(hits) = &call_result.edge_coverage {
+                        new_coverage |= edge_coverage.borrow_mut().merge(hits);
+                    }
+
+                    record_metrics(
+                        &mut metrics.borrow_mut(),
+                        &targeted_contracts,
+                        &revert_decoder,
+                        tx,
+                        &call_result,
+                    );
+
                     if !&call_result.reverted {
                         collect_data(
                             &mut state_changeset,
@@ -264,7 +354,8 @@ impl<'a> InvariantExecutor<'a> {
                         stipend: call_result.stipend,
                     });
 
-                    let result = can_continue(
+                    let reverted = call_result.reverted;
+                    let keep_running = can_continue(
                         &invariant_contract,
                         &self.config,
                         call_result,
@@ -277,26 +368,65 @@ impl<'a> InvariantExecutor<'a> {
                     )
                     .map_err(|e| TestCaseError::fail(e.to_string()))?;
 
-                    if !result.can_continue || current_run == self.config.depth - 1 {
+                    if !keep_running || current_run == self.config.depth - 1 {
                         last_run_calldata.borrow_mut().clone_from(&inputs);
                     }
 
-                    if !result.can_continue {
+                    if !keep_running {
                         break
                     }
 
-                    *last_call_results.borrow_mut() = result.call_result;
+                    // Keeps the sequence if it reaches a new best value of the optimization
+                    // invariant. Such sequences are also kept in the corpus.
+                    if invariant_contract.is_optimization() && !reverted {
+                        let value = optimization_value(
+                            &executor,
+                            invariant_contract.address,
+                            invariant_contract.invariant_functions[0],
+                        )
+                        .map_err(|e| TestCaseError::fail(e.to_string()))?;
+                        if let Some(value) = value {
+                            let mut best = optimization.borrow_mut();
+                            if !matches!(&*best, Some((best_value, _)) if *best_value >= value) {
+                                *best = Some((value, inputs.clone()));
+                                new_coverage = true;
+                            }
+                        }
+                    }
+
                     current_run += 1;
                 }
 
-                // Generates the next call from the run using the recently updated
-                // dictionary.
-                inputs.push(
-                    strat
+                // Replays the next call of the mutated sequence or generates the next call from
+                // the run using the recently updated dictionary.
+                let next_input = match replayed.next() {
+                    Some(tx) => tx,
+                    None => strat
                         .new_tree(&mut branch_runner.borrow_mut())
                         .map_err(|_| TestCaseError::Fail("Could not generate case".into()))?
                         .current(),
-                );
+                };
+                inputs.push(next_input);
+            }
+
+            // Checks `afterInvariant` at the end of the run, unless the run already broke every
+            // invariant.
+            if invariant_contract.call_after_invariant &&
+                !failures.borrow().all_broken(&invariant_contract)
+            {
+                assert_after_invariant(
+                    &invariant_contract,
+                    &self.config,
+                    &targeted_contracts,
+                    &executor,
+                    &inputs[..fuzz_runs.len()],
+                    &mut failures.borrow_mut(),
+                )
+                .map_err(|e| TestCaseError::fail(e.to_string()))?;
+            }
+
+            if new_coverage {
+                corpus.borrow_mut().push(inputs[..fuzz_runs.len()].to_vec());
             }
 
             // We clear all the targeted contracts created during this run.
@@ -318,17 +448,31 @@ impl<'a> InvariantExecutor<'a> {
             Ok(())
         });
 
+        if !self.corpus_files.is_empty() {
+            let mut corpus = corpus.into_inner();
+            for seed in &seeds.entries()[replayed_seeds.get()..] {
+                corpus.push(seed.clone());
+            }
+            for path in &self.corpus_files {
+                if let Err(err) = corpus.save_sequences(path) {
+                    error!(%err, "Failed to save invariant corpus");
+                }
+            }
+        }
+
         trace!(target: "forge::test::invariant::fuzz_fixtures", "{:?}", fuzz_fixtures);
         trace!(target: "forge::test::invariant::dictionary", "{:?}", fuzz_state.dictionary_read().values().iter().map(hex::encode).collect::<Vec<_>>());
 
-        let (reverts, error) = failures.into_inner().into_inner();
+        let (reverts, errors) = failures.into_inner().into_inner();
 
         Ok(InvariantFuzzTestResult {
-            error,
+            errors,
             cases: fuzz_cases.into_inner(),
             reverts,
             last_run_inputs: last_run_calldata.take(),
             gas_report_traces: gas_report_traces.into_inner(),
+            metrics: metrics.into_inner(),
+            optimization: optimization.into_inner(),
         })
     }
 
@@ -649,6 +793,39 @@ impl<'a> InvariantExecutor<'a> {
     }
 }
 
+/// Records the call in the statistics of the targeted function, keyed by target and selector.
+fn record_metrics(
+    metrics: &mut BTreeMap<InvariantMetricsKey, InvariantMetrics>,
+    targeted_contracts: &FuzzRunIdentifiedContracts,
+    revert_decoder: &RevertDecoder,
+    tx: &BasicTxDetails,
+    call_result: &RawCallResult,
+) {
+    let targets = targeted_contracts.targets.lock();
+    let Some((name, abi, _)) = targets.get(&tx.call_details.target) else { return };
+    let Some(selector) = tx.call_details.calldata.get(..4) else { return };
+
+    let key = InvariantMetricsKey {
+        target: tx.call_details.target,
+        selector: Selector::from_slice(selector),
+    };
+    let revert_reason = call_result
+        .reverted
+        .then(|| revert_decoder.decode(call_result.result.as_ref(), Some(call_result.exit_reason)));
+
+    metrics
+        .entry(key)
+        .or_insert_with(|| InvariantMetrics {
+            contract: name.rsplit(':').next().unwrap_or(name).to_string(),
+            function: abi
+                .functions()
+                .find(|func| func.selector() == key.selector)
+                .map(|func| func.name.clone()),
+            ..Default::default()
+        })
+        .record(call_result.gas_used, revert_reason);
+}
+
 /// Collects data from call for fuzzing. However, it first verifies that the sender is not an EOA
 /// before inserting it into the dictionary. Otherwise, we flood the dictionary with
 /// randomly generated addresses.
@@ -1,7 +1,10 @@
-use super::{error::FailedInvariantCaseData, shrink_sequence};
+use super::{
+    error::FailedInvariantCaseData, result::call_after_invariant_function,
+    shrink::shrink_optimization_sequence, shrink_sequence,
+};
 use crate::executors::Executor;
 use alloy_dyn_abi::JsonAbiExt;
-use alloy_primitives::Log;
+use alloy_primitives::{Log, I256};
 use eyre::Result;
 use foundry_common::{ContractsByAddress, ContractsByArtifact};
 use foundry_evm_core::constants::CALLER;
@@ -69,23 +72,29 @@ pub fn replay_run(
         ));
     }
 
-    // Replay invariant to collect logs and traces.
+    // Replay invariants to collect logs and traces.
     // We do this only once at the end of the replayed sequence.
     // Checking after each call doesn't add valuable info for passing scenario
     // (invariant call result is always success) nor for failed scenarios
     // (invariant call result is always success until the last call that breaks it).
-    let invariant_result = executor.call_raw(
-        CALLER,
-        invariant_contract.address,
-        invariant_contract
-            .invariant_function
-            .abi_encode_input(&[])
-            .expect("invariant should have no inputs")
-            .into(),
-        U256::ZERO,
-    )?;
-    traces.push((TraceKind::Execution, invariant_result.traces.clone().unwrap()));
-    logs.extend(invariant_result.logs);
+    for func in &invariant_contract.invariant_functions {
+        let invariant_result = executor.call_raw(
+            CALLER,
+            invariant_contract.address,
+            func.abi_encode_input(&[]).expect("invariant should have no inputs").into(),
+            U256::ZERO,
+        )?;
+        traces.push((TraceKind::Execution, invariant_result.traces.clone().unwrap()));
+        logs.extend(invariant_result.logs);
+    }
+
+    // Replay `afterInvariant`, which is called at the end of every run.
+    if invariant_contract.call_after_invariant {
+        let (after_invariant_result, _) =
+            call_after_invariant_function(&executor, invariant_contract.address)?;
+        traces.push((TraceKind::Execution, after_invariant_result.traces.clone().unwrap()));
+        logs.extend(after_invariant_result.logs);
+    }
 
     Ok(counterexample_sequence)
 }
@@ -126,6 +135,43 @@ pub fn replay_error(
     }
 }
 
+/// Shrinks the sequence reaching the best value of an optimization invariant and replays it to
+/// collect all necessary traces.
+#[allow(clippy::too_many_arguments)]
+pub fn replay_optimization(
+    optimization: &(I256, Vec<BasicTxDetails>),
+    shrink_run_limit: usize,
+    invariant_contract: &InvariantContract<'_>,
+    executor: Executor,
+    known_contracts: &ContractsByArtifact,
+    ided_contracts: ContractsByAddress,
+    logs: &mut Vec<Log>,
+    traces: &mut Traces,
+    coverage: &mut Option<HitMaps>,
+) -> Result<Vec<BaseCounterExample>> {
+    let (best_value, calls) = optimization;
+    let calls = shrink_optimization_sequence(
+        *best_value,
+        calls,
+        invariant_contract.address,
+        invariant_contract.invariant_functions[0],
+        shrink_run_limit,
+        &executor,
+    )?;
+
+    // Replay calls to get the best sequence and to collect logs, traces and coverage.
+    replay_run(
+        invariant_contract,
+        executor,
+        known_contracts,
+        ided_contracts,
+        logs,
+        traces,
+        coverage,
+        calls,
+    )
+}
+
 /// Sets up the calls generated by the internal fuzzer, if they exist.
 fn set_up_inner_replay(executor: &mut Executor, inner_sequence: &[Option<BasicTxDetails>]) {
     if let Some(fuzzer) = &mut executor.inspector.fuzzer {
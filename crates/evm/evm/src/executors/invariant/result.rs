@@ -1,21 +1,30 @@
-use super::{error::FailedInvariantCaseData, InvariantFailures, InvariantFuzzError};
+use super::{
+    error::FailedInvariantCaseData, IInvariantTest, InvariantFailures, InvariantFuzzError,
+};
 use crate::executors::{Executor, RawCallResult};
-use alloy_dyn_abi::JsonAbiExt;
+use alloy_dyn_abi::{FunctionExt, JsonAbiExt};
+use alloy_json_abi::Function;
+use alloy_primitives::{Address, Bytes, I256};
+use alloy_sol_types::SolCall;
 use eyre::Result;
 use foundry_config::InvariantConfig;
 use foundry_evm_core::{constants::CALLER, utils::StateChangeset};
 use foundry_evm_fuzz::{
-    invariant::{BasicTxDetails, FuzzRunIdentifiedContracts, InvariantContract},
+    invariant::{
+        is_optimization_invariant, BasicTxDetails, FuzzRunIdentifiedContracts, InvariantContract,
+        InvariantMetrics, InvariantMetricsKey,
+    },
     FuzzedCases,
 };
 use revm::primitives::U256;
 use revm_inspectors::tracing::CallTraceArena;
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::BTreeMap};
 
 /// The outcome of an invariant fuzz test
 #[derive(Debug)]
 pub struct InvariantFuzzTestResult {
-    pub error: Option<InvariantFuzzError>,
+    /// Errors of the broken invariants, keyed by invariant function name.
+    pub errors: BTreeMap<String, InvariantFuzzError>,
     /// Every successful fuzz test case
     pub cases: Vec<FuzzedCases>,
     /// Number of reverted fuzz calls
@@ -25,25 +34,15 @@ pub struct InvariantFuzzTestResult {
     pub last_run_inputs: Vec<BasicTxDetails>,
     /// Additional traces used for gas report construction.
     pub gas_report_traces: Vec<Vec<CallTraceArena>>,
+    /// Call statistics of every targeted function, keyed by target and selector.
+    pub metrics: BTreeMap<InvariantMetricsKey, InvariantMetrics>,
+    /// The best value of an optimization invariant and the call sequence reaching it.
+    pub optimization: Option<(I256, Vec<BasicTxDetails>)>,
 }
 
-/// Enriched results of an invariant run check.
-///
-/// Contains the success condition and call results of the last run
-pub(crate) struct RichInvariantResults {
-    pub(crate) can_continue: bool,
-    pub(crate) call_result: Option<RawCallResult>,
-}
-
-impl RichInvariantResults {
-    fn new(can_continue: bool, call_result: Option<RawCallResult>) -> Self {
-        Self { can_continue, call_result }
-    }
-}
-
-/// Given the executor state, asserts that no invariant has been broken. Otherwise, it fills the
-/// external `invariant_failures.failed_invariant` map and returns a generic error.
-/// Either returns the call result if successful, or nothing if there was an error.
+/// Given the executor state, asserts the invariants that have not been broken yet. Newly broken
+/// invariants are recorded in the external `invariant_failures.errors` map.
+/// Returns whether any invariant of the campaign is still unbroken.
 pub(crate) fn assert_invariants(
     invariant_contract: &InvariantContract<'_>,
     invariant_config: &InvariantConfig,
@@ -51,7 +50,7 @@ pub(crate) fn assert_invariants(
     executor: &Executor,
     calldata: &[BasicTxDetails],
     invariant_failures: &mut InvariantFailures,
-) -> Result<Option<RawCallResult>> {
+) -> Result<bool> {
     let mut inner_sequence = vec![];
 
     if let Some(fuzzer) = &executor.inspector.fuzzer {
@@ -60,42 +59,120 @@ pub(crate) fn assert_invariants(
         }
     }
 
-    let func = invariant_contract.invariant_function;
-    let mut call_result = executor.call_raw(
-        CALLER,
-        invariant_contract.address,
-        func.abi_encode_input(&[]).expect("invariant should have no inputs").into(),
-        U256::ZERO,
-    )?;
+    for &func in &invariant_contract.invariant_functions {
+        // We only care about invariants which we haven't broken yet. Optimization invariants
+        // can't be broken, their value is tracked instead.
+        if invariant_failures.is_broken(func) || is_optimization_invariant(func) {
+            continue
+        }
 
-    let is_err = !executor.is_raw_call_success(
-        invariant_contract.address,
-        Cow::Owned(call_result.state_changeset.take().unwrap()),
-        &call_result,
-        false,
-    );
-    if is_err {
-        // We only care about invariants which we haven't broken yet.
-        if invariant_failures.error.is_none() {
+        let (call_result, success) = call_invariant_function(
+            executor,
+            invariant_contract.address,
+            func.abi_encode_input(&[]).expect("invariant should have no inputs").into(),
+        )?;
+        if !success {
             let case_data = FailedInvariantCaseData::new(
                 invariant_contract,
+                func,
                 invariant_config,
                 targeted_contracts,
                 calldata,
-                call_result,
+                &call_result,
                 &inner_sequence,
             );
-            invariant_failures.error = Some(InvariantFuzzError::BrokenInvariant(case_data));
-            return Ok(None);
+            invariant_failures
+                .errors
+                .insert(func.name.clone(), InvariantFuzzError::BrokenInvariant(case_data));
         }
     }
 
-    Ok(Some(call_result))
+    Ok(!invariant_failures.all_broken(invariant_contract))
+}
+
+/// Calls `afterInvariant` at the end of a run. If it fails, every invariant that is not broken yet
+/// is considered broken by the run's sequence.
+/// Returns whether any invariant of the campaign is still unbroken.
+pub(crate) fn assert_after_invariant(
+    invariant_contract: &InvariantContract<'_>,
+    invariant_config: &InvariantConfig,
+    targeted_contracts: &FuzzRunIdentifiedContracts,
+    executor: &Executor,
+    calldata: &[BasicTxDetails],
+    invariant_failures: &mut InvariantFailures,
+) -> Result<bool> {
+    let (call_result, success) =
+        call_after_invariant_function(executor, invariant_contract.address)?;
+    if !success {
+        invariant_failures.fail_unbroken(invariant_contract, |func| {
+            let case_data = FailedInvariantCaseData::new(
+                invariant_contract,
+                func,
+                invariant_config,
+                targeted_contracts,
+                calldata,
+                &call_result,
+                &[],
+            );
+            InvariantFuzzError::BrokenInvariant(case_data.with_after_invariant_failure())
+        });
+    }
+    Ok(!invariant_failures.all_broken(invariant_contract))
+}
+
+/// Calls an invariant function of the test contract.
+/// Returns the call result and whether the invariant holds.
+pub(crate) fn call_invariant_function(
+    executor: &Executor,
+    address: Address,
+    calldata: Bytes,
+) -> Result<(RawCallResult, bool)> {
+    let mut call_result = executor.call_raw(CALLER, address, calldata, U256::ZERO)?;
+    let success = executor.is_raw_call_success(
+        address,
+        Cow::Owned(call_result.state_changeset.take().unwrap()),
+        &call_result,
+        false,
+    );
+    Ok((call_result, success))
+}
+
+/// Calls the `afterInvariant` function of the test contract.
+/// Returns the call result and whether the call succeeded.
+pub(crate) fn call_after_invariant_function(
+    executor: &Executor,
+    address: Address,
+) -> Result<(RawCallResult, bool)> {
+    call_invariant_function(
+        executor,
+        address,
+        IInvariantTest::afterInvariantCall {}.abi_encode().into(),
+    )
+}
+
+/// Calls the optimization invariant function of the test contract.
+/// Returns the value it returned, or `None` if the call failed.
+pub(crate) fn optimization_value(
+    executor: &Executor,
+    address: Address,
+    func: &Function,
+) -> Result<Option<I256>> {
+    let calldata = func.abi_encode_input(&[]).expect("invariant should have no inputs").into();
+    let (call_result, success) = call_invariant_function(executor, address, calldata)?;
+    if !success {
+        return Ok(None)
+    }
+    let value = func
+        .abi_decode_output(&call_result.result, false)
+        .ok()
+        .and_then(|values| values.first().and_then(|value| value.as_int()))
+        .map(|(value, _)| value);
+    Ok(value)
 }
 
 /// Verifies that the invariant run execution can continue.
-/// Returns the mapping of (Invariant Function Name -> Call Result, Logs, Traces) if invariants were
-/// asserted.
+/// Returns `false` if all invariants are broken or if the call reverted and `fail_on_revert` is
+/// set.
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn can_continue(
     invariant_contract: &InvariantContract<'_>,
@@ -107,9 +184,7 @@ pub(crate) fn can_continue(
     targeted_contracts: &FuzzRunIdentifiedContracts,
     state_changeset: &StateChangeset,
     run_traces: &mut Vec<CallTraceArena>,
-) -> Result<RichInvariantResults> {
-    let mut call_results = None;
-
+) -> Result<bool> {
     // Detect handler assertion failures first.
     let handlers_failed = targeted_contracts.targets.lock().iter().any(|contract| {
         !executor.is_success(*contract.0, false, Cow::Borrowed(state_changeset), false)
@@ -121,36 +196,36 @@ pub(crate) fn can_continue(
             run_traces.push(traces);
         }
 
-        call_results = assert_invariants(
+        return assert_invariants(
             invariant_contract,
             invariant_config,
             targeted_contracts,
             executor,
             calldata,
             failures,
-        )?;
-        if call_results.is_none() {
-            return Ok(RichInvariantResults::new(false, None));
-        }
-    } else {
-        // Increase the amount of reverts.
-        failures.reverts += 1;
-        // If fail on revert is set, we must return immediately.
-        if invariant_config.fail_on_revert {
+        )
+    }
+
+    // Increase the amount of reverts.
+    failures.reverts += 1;
+    // If fail on revert is set, we must return immediately.
+    if invariant_config.fail_on_revert {
+        let mut revert_reason = None;
+        failures.fail_unbroken(invariant_contract, |func| {
             let case_data = FailedInvariantCaseData::new(
                 invariant_contract,
+                func,
                 invariant_config,
                 targeted_contracts,
                 calldata,
-                call_result,
+                &call_result,
                 &[],
             );
-            failures.revert_reason = Some(case_data.revert_reason.clone());
-            let error = InvariantFuzzError::Revert(case_data);
-            failures.error = Some(error);
-
-            return Ok(RichInvariantResults::new(false, None));
-        }
+            revert_reason = Some(case_data.revert_reason.clone());
+            InvariantFuzzError::Revert(case_data)
+        });
+        failures.revert_reason = revert_reason;
+        return Ok(false)
     }
-    Ok(RichInvariantResults::new(true, call_results))
+    Ok(true)
 }
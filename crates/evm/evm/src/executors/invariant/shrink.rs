@@ -1,9 +1,15 @@
-use crate::executors::{invariant::error::FailedInvariantCaseData, Executor};
-use alloy_primitives::{Address, Bytes, U256};
+use crate::executors::{
+    invariant::{
+        error::FailedInvariantCaseData,
+        result::{call_invariant_function, optimization_value},
+    },
+    Executor,
+};
+use alloy_json_abi::Function;
+use alloy_primitives::{Address, Bytes, I256, U256};
 use foundry_evm_core::constants::CALLER;
 use foundry_evm_fuzz::invariant::BasicTxDetails;
 use proptest::bits::{BitSetLike, VarBitSet};
-use std::borrow::Cow;
 
 #[derive(Clone, Copy, Debug)]
 struct Shrink {
@@ -102,7 +108,7 @@ pub(crate) fn shrink_sequence(
             calls,
             shrinker.current().collect(),
             failed_case.addr,
-            failed_case.func.clone(),
+            std::slice::from_ref(&failed_case.func),
             failed_case.fail_on_revert,
         ) {
             // If candidate sequence still fails then shrink more if possible.
@@ -117,16 +123,78 @@ pub(crate) fn shrink_sequence(
     Ok(shrinker.current().map(|idx| &calls[idx]).cloned().collect())
 }
 
-/// Checks if the given call sequence breaks the invariant.
-/// Used in shrinking phase for checking candidate sequences and in replay failures phase to test
-/// persisted failures.
-/// Returns the result of invariant check and if sequence was entirely applied.
+/// Shrinks the sequence reaching the best value of an optimization invariant to its smallest
+/// subsequence that still reaches at least that value.
+pub(crate) fn shrink_optimization_sequence(
+    best_value: I256,
+    calls: &[BasicTxDetails],
+    test_address: Address,
+    test_function: &Function,
+    shrink_run_limit: usize,
+    executor: &Executor,
+) -> eyre::Result<Vec<BasicTxDetails>> {
+    trace!(target: "forge::test", "Shrinking optimization sequence of {} calls.", calls.len());
+
+    let mut shrinker = CallSequenceShrinker::new(calls.len());
+    for _ in 0..shrink_run_limit {
+        // Check candidate sequence value.
+        match check_sequence_value(
+            executor.clone(),
+            calls,
+            shrinker.current().collect(),
+            test_address,
+            test_function,
+        ) {
+            // If candidate sequence still reaches the best value then shrink more if possible.
+            Ok(Some(value)) if value >= best_value => {
+                if !shrinker.simplify() {
+                    break
+                }
+            }
+            // Otherwise restore last removed call and shrink other calls if possible.
+            _ => {
+                if !shrinker.complicate() {
+                    break
+                }
+            }
+        }
+    }
+
+    Ok(shrinker.current().map(|idx| &calls[idx]).cloned().collect())
+}
+
+/// Applies the given call sequence and returns the value of the optimization invariant, or `None`
+/// if it could not be computed.
+fn check_sequence_value(
+    mut executor: Executor,
+    calls: &[BasicTxDetails],
+    sequence: Vec<usize>,
+    test_address: Address,
+    test_function: &Function,
+) -> eyre::Result<Option<I256>> {
+    for call_index in sequence {
+        let tx = &calls[call_index];
+        executor.call_raw_committing(
+            tx.sender,
+            tx.call_details.target,
+            tx.call_details.calldata.clone(),
+            U256::ZERO,
+        )?;
+    }
+    optimization_value(&executor, test_address, test_function)
+}
+
+/// Checks if the given call sequence breaks any of the given checks, the calldata of the invariant
+/// or of `afterInvariant`.
+/// Used in shrinking phase for checking candidate sequences, with the check that failed, and in
+/// replay failures phase to test persisted failures.
+/// Returns the result of the checks and if sequence was entirely applied.
 pub fn check_sequence(
     mut executor: Executor,
     calls: &[BasicTxDetails],
     sequence: Vec<usize>,
     test_address: Address,
-    test_function: Bytes,
+    checks: &[Bytes],
     fail_on_revert: bool,
 ) -> eyre::Result<(bool, bool)> {
     // Apply the call sequence.
@@ -145,15 +213,12 @@ pub fn check_sequence(
         }
     }
 
-    // Check the invariant for call sequence.
-    let mut call_result = executor.call_raw(CALLER, test_address, test_function, U256::ZERO)?;
-    Ok((
-        executor.is_raw_call_success(
-            test_address,
-            Cow::Owned(call_result.state_changeset.take().unwrap()),
-            &call_result,
-            false,
-        ),
-        true,
-    ))
+    // Check the invariant for call sequence, stopping at the first failed check.
+    for check in checks {
+        let (_, success) = call_invariant_function(&executor, test_address, check.clone())?;
+        if !success {
+            return Ok((false, true))
+        }
+    }
+    Ok((true, true))
 }
@@ -182,6 +182,12 @@ impl Executor {
         self
     }
 
+    #[inline]
+    pub fn set_edge_coverage(&mut self, edge_coverage: bool) -> &mut Self {
+        self.inspector.collect_edge_coverage(edge_coverage);
+        self
+    }
+
     #[inline]
     pub fn set_gas_limit(&mut self, gas_limit: U256) -> &mut Self {
         self.gas_limit = gas_limit;
@@ -669,6 +675,8 @@ pub struct RawCallResult {
     pub traces: Option<CallTraceArena>,
     /// The coverage info collected during the call
     pub coverage: Option<HitMaps>,
+    /// The hit counters of the branches taken during the call, used for coverage-guided fuzzing
+    pub edge_coverage: Option<HashMap<usize, u8>>,
     /// The debug nodes of the call
     pub debug: Option<DebugArena>,
     /// Scripted transactions generated from this call
@@ -702,6 +710,7 @@ impl Default for RawCallResult {
             labels: HashMap::new(),
             traces: None,
             coverage: None,
+            edge_coverage: None,
             debug: None,
             transactions: None,
             state_changeset: None,
@@ -815,8 +824,16 @@ fn convert_executed_result(
         _ => Bytes::new(),
     };
 
-    let InspectorData { logs, labels, traces, coverage, debug, cheatcodes, chisel_state } =
-        inspector.collect();
+    let InspectorData {
+        logs,
+        labels,
+        traces,
+        coverage,
+        edge_coverage,
+        debug,
+        cheatcodes,
+        chisel_state,
+    } = inspector.collect();
 
     let transactions = match cheatcodes.as_ref() {
         Some(cheats) if !cheats.broadcastable_transactions.is_empty() => {
@@ -837,6 +854,7 @@ fn convert_executed_result(
         labels,
         traces,
         coverage,
+        edge_coverage,
         debug,
         transactions,
         state_changeset: Some(state_changeset),
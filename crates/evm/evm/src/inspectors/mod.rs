@@ -2,10 +2,10 @@
 
 pub use foundry_cheatcodes::{self as cheatcodes, Cheatcodes, CheatsConfig};
 pub use foundry_evm_coverage::CoverageCollector;
-pub use foundry_evm_fuzz::Fuzzer;
+pub use foundry_evm_fuzz::{EdgeCoverageCollector, Fuzzer};
 pub use foundry_evm_traces::{StackSnapshotType, TracingInspector, TracingInspectorConfig};
 
-pub use revm_inspectors::access_list::AccessListInspector;
+pub use revm_inspectors::{access_list::AccessListInspector, tracing::FourByteInspector};
 
 mod chisel_state;
 pub use chisel_state::ChiselState;
@@ -1,6 +1,6 @@
 use super::{
-    Cheatcodes, CheatsConfig, ChiselState, CoverageCollector, Debugger, Fuzzer, LogCollector,
-    StackSnapshotType, TracingInspector, TracingInspectorConfig,
+    Cheatcodes, CheatsConfig, ChiselState, CoverageCollector, Debugger, EdgeCoverageCollector,
+    Fuzzer, LogCollector, StackSnapshotType, TracingInspector, TracingInspectorConfig,
 };
 use alloy_primitives::{Address, Bytes, Log, U256};
 use foundry_evm_core::{
@@ -251,6 +251,7 @@ pub struct InspectorData {
     pub traces: Option<CallTraceArena>,
     pub debug: Option<DebugArena>,
     pub coverage: Option<HitMaps>,
+    pub edge_coverage: Option<HashMap<usize, u8>>,
     pub cheatcodes: Option<Cheatcodes>,
     pub chisel_state: Option<(Vec<U256>, Vec<u8>, InstructionResult)>,
 }
@@ -283,6 +284,7 @@ pub struct InspectorStack {
     pub chisel_state: Option<ChiselState>,
     pub coverage: Option<CoverageCollector>,
     pub debugger: Option<Debugger>,
+    pub edge_coverage: Option<EdgeCoverageCollector>,
     pub fuzzer: Option<Fuzzer>,
     pub log_collector: Option<LogCollector>,
     pub printer: Option<CustomPrintTracer>,
@@ -352,6 +354,12 @@ impl InspectorStack {
         self.coverage = yes.then(Default::default);
     }
 
+    /// Set whether to record the branches taken, used as feedback for coverage-guided fuzzing.
+    #[inline]
+    pub fn collect_edge_coverage(&mut self, yes: bool) {
+        self.edge_coverage = yes.then(Default::default);
+    }
+
     /// Set whether to enable the debugger.
     #[inline]
     pub fn enable_debugger(&mut self, yes: bool) {
@@ -406,6 +414,7 @@ impl InspectorStack {
             traces: self.tracer.map(|tracer| tracer.get_traces().clone()),
             debug: self.debugger.map(|debugger| debugger.arena),
             coverage: self.coverage.map(|coverage| coverage.maps),
+            edge_coverage: self.edge_coverage.map(|edge_coverage| edge_coverage.hits),
             cheatcodes: self.cheatcodes,
             chisel_state: self.chisel_state.and_then(|state| state.state),
         }
@@ -616,6 +625,7 @@ impl<DB: DatabaseExt + DatabaseCommit> Inspector<&mut DB> for InspectorStack {
                 &mut self.debugger,
                 &mut self.tracer,
                 &mut self.coverage,
+                &mut self.edge_coverage,
                 &mut self.cheatcodes,
                 &mut self.printer,
             ],
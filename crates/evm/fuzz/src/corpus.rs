@@ -0,0 +1,510 @@
+use crate::invariant::{BasicTxDetails, CallDetails, FuzzRunIdentifiedContracts};
+use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
+use alloy_json_abi::Function;
+use alloy_primitives::{Address, Bytes, B256, I256, U256};
+use eyre::Result;
+use foundry_common::fs;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The maximum number of entries kept in a corpus.
+pub const MAX_CORPUS_SIZE: usize = 1024;
+
+/// The probability of mutating a corpus entry instead of generating a new input.
+pub const CORPUS_MUTATION_PROBABILITY: f64 = 0.5;
+
+/// The share of the runs of a campaign that may replay the saved corpus, the remaining runs are
+/// left to generated and mutated inputs.
+pub const MAX_SEED_RUNS_SHARE: f64 = 0.5;
+
+/// Returns the number of runs out of `runs` that may replay the saved corpus.
+pub fn max_seed_runs(runs: u32) -> usize {
+    (runs as f64 * MAX_SEED_RUNS_SHARE) as usize
+}
+
+/// The maximum number of calls made to shrink a counterexample derived from the corpus.
+pub const MAX_CORPUS_SHRINK_RUNS: usize = 256;
+
+/// The corpus of a fuzz test, as saved in the corpus directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FuzzCorpusFile {
+    /// The signature of the fuzzed function.
+    pub function: String,
+    /// The calldata of the inputs that reached new branches.
+    pub inputs: Vec<Bytes>,
+}
+
+/// The corpus of an invariant test, as saved in the corpus directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvariantCorpusFile {
+    /// The call sequences that reached new branches.
+    pub sequences: Vec<Vec<CorpusCall>>,
+}
+
+/// A call of a saved invariant call sequence.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorpusCall {
+    pub sender: Address,
+    pub target: Address,
+    pub calldata: Bytes,
+}
+
+impl From<CorpusCall> for BasicTxDetails {
+    fn from(call: CorpusCall) -> Self {
+        Self {
+            sender: call.sender,
+            call_details: CallDetails { target: call.target, calldata: call.calldata },
+        }
+    }
+}
+
+impl From<&BasicTxDetails> for CorpusCall {
+    fn from(tx: &BasicTxDetails) -> Self {
+        Self {
+            sender: tx.sender,
+            target: tx.call_details.target,
+            calldata: tx.call_details.calldata.clone(),
+        }
+    }
+}
+
+/// Inputs or call sequences that reached new edges, which are mutated into new inputs.
+#[derive(Clone, Debug)]
+pub struct Corpus<T> {
+    entries: Vec<T>,
+}
+
+impl<T> Default for Corpus<T> {
+    fn default() -> Self {
+        Self { entries: Vec::new() }
+    }
+}
+
+impl<T> Corpus<T> {
+    /// Adds an entry, evicting the oldest one if the corpus is full.
+    pub fn push(&mut self, entry: T) {
+        if self.entries.len() == MAX_CORPUS_SIZE {
+            self.entries.remove(0);
+        }
+        self.entries.push(entry);
+    }
+
+    /// Adds an entry in front of all others, evicting the newest one if the corpus is full.
+    pub fn push_front(&mut self, entry: T) {
+        if self.entries.len() == MAX_CORPUS_SIZE {
+            self.entries.pop();
+        }
+        self.entries.insert(0, entry);
+    }
+
+    /// Returns a random entry.
+    pub fn choose(&self, rng: &mut impl Rng) -> Option<&T> {
+        self.entries.choose(rng)
+    }
+
+    /// Returns the entries of the corpus.
+    pub fn entries(&self) -> &[T] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Corpus<Bytes> {
+    /// Loads the inputs of `func` saved in the corpus file at `path`.
+    ///
+    /// The whole file is stale if the signature of the function changed, and inputs that can't be
+    /// decoded anymore are dropped.
+    pub fn load_inputs(path: &Path, func: &Function) -> Self {
+        let mut corpus = Self::default();
+        let Ok(file) = fs::read_json_file::<FuzzCorpusFile>(path) else { return corpus };
+        if file.function != func.signature() {
+            return corpus
+        }
+        for input in file.inputs {
+            if input.get(..4) == Some(&func.selector()[..]) &&
+                func.abi_decode_input(&input[4..], false).is_ok()
+            {
+                corpus.push(input);
+            }
+        }
+        corpus
+    }
+
+    /// Saves the inputs to the corpus file of `func` at `path`.
+    pub fn save_inputs(&self, path: &Path, func: &Function) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = FuzzCorpusFile { function: func.signature(), inputs: self.entries.clone() };
+        fs::write_json_file(path, &file)?;
+        Ok(())
+    }
+
+    /// Returns a mutation of a random entry with the arguments of `fresh`, the newly generated
+    /// calldata of `func`, or `fresh` itself if the corpus isn't picked.
+    pub fn next_input(&self, func: &Function, fresh: Bytes, rng: &mut impl Rng) -> Bytes {
+        if !rng.gen_bool(CORPUS_MUTATION_PROBABILITY) {
+            return fresh
+        }
+        match self.choose(rng) {
+            Some(entry) => mutate_calldata(func, entry, &fresh, rng).unwrap_or(fresh),
+            None => fresh,
+        }
+    }
+}
+
+impl Corpus<Vec<BasicTxDetails>> {
+    /// Loads the call sequences saved in the corpus file at `path`.
+    ///
+    /// Calls to contracts or functions that are no longer targeted, or whose calldata can't be
+    /// decoded anymore, are dropped.
+    pub fn load_sequences(path: &Path, targets: &FuzzRunIdentifiedContracts) -> Self {
+        let mut corpus = Self::default();
+        let Ok(file) = fs::read_json_file::<InvariantCorpusFile>(path) else { return corpus };
+        let targets = targets.targets.lock();
+        let is_targeted = |call: &CorpusCall| {
+            let Some((_, abi, functions)) = targets.get(&call.target) else { return false };
+            let Some(selector) = call.calldata.get(..4) else { return false };
+            // all functions of the ABI are targeted if none were selected
+            let mut functions = if functions.is_empty() {
+                abi.functions().collect()
+            } else {
+                functions.iter().collect::<Vec<_>>()
+            };
+            functions.retain(|func| func.selector().as_slice() == selector);
+            functions.iter().any(|func| func.abi_decode_input(&call.calldata[4..], false).is_ok())
+        };
+        for sequence in file.sequences {
+            let sequence = sequence
+                .into_iter()
+                .filter(|call| is_targeted(call))
+                .map(BasicTxDetails::from)
+                .collect::<Vec<_>>();
+            if !sequence.is_empty() {
+                corpus.push(sequence);
+            }
+        }
+        corpus
+    }
+
+    /// Saves the call sequences to the corpus file at `path`.
+    pub fn save_sequences(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let sequences = self
+            .entries
+            .iter()
+            .map(|sequence| sequence.iter().map(CorpusCall::from).collect())
+            .collect();
+        fs::write_json_file(path, &InvariantCorpusFile { sequences })?;
+        Ok(())
+    }
+
+    /// Returns a mutation of a random sequence, to be executed before newly generated calls, or
+    /// an empty sequence if the corpus isn't picked.
+    pub fn next_sequence(
+        &self,
+        targets: &FuzzRunIdentifiedContracts,
+        rng: &mut impl Rng,
+    ) -> Vec<BasicTxDetails> {
+        if self.is_empty() || !rng.gen_bool(CORPUS_MUTATION_PROBABILITY) {
+            return vec![]
+        }
+        let sequence = self.choose(rng).unwrap();
+        let other = self.choose(rng).unwrap();
+        mutate_sequence(sequence, other, targets, rng)
+    }
+}
+
+/// Mutates a single argument of `calldata`, a call of `func`: the argument is either replaced with
+/// the argument of `fresh` or, for integers, nudged by a small delta. The other arguments are kept,
+/// so branches that depend on several arguments are reached one argument at a time.
+///
+/// Returns `None` if the calldata can't be decoded.
+pub fn mutate_calldata(
+    func: &Function,
+    calldata: &[u8],
+    fresh: &[u8],
+    rng: &mut impl Rng,
+) -> Option<Bytes> {
+    let mut args = func.abi_decode_input(calldata.get(4..)?, false).ok()?;
+    let mut fresh_args = func.abi_decode_input(fresh.get(4..)?, false).ok()?;
+    if !args.is_empty() {
+        let index = rng.gen_range(0..args.len());
+        args[index] = match &args[index] {
+            DynSolValue::Uint(..) | DynSolValue::Int(..) if rng.gen_bool(0.5) => {
+                nudge(args[index].clone(), rng)
+            }
+            _ => fresh_args.swap_remove(index),
+        };
+    }
+    func.abi_encode_input(&args).ok().map(Into::into)
+}
+
+/// Adds or subtracts a small delta to integers, keeping them in the range of their type.
+fn nudge(value: DynSolValue, rng: &mut impl Rng) -> DynSolValue {
+    let delta = rng.gen_range(1..=16u64);
+    let add = rng.gen_bool(0.5);
+    match value {
+        DynSolValue::Uint(value, bits) => {
+            let max = U256::MAX >> (256 - bits);
+            let value = if add {
+                value.saturating_add(U256::from(delta)).min(max)
+            } else {
+                value.saturating_sub(U256::from(delta))
+            };
+            DynSolValue::Uint(value, bits)
+        }
+        DynSolValue::Int(value, bits) => {
+            let max = I256::from_raw(U256::MAX >> (257 - bits));
+            let min = -max - I256::ONE;
+            let delta = I256::try_from(delta).unwrap();
+            let value = if add {
+                value.saturating_add(delta).min(max)
+            } else {
+                value.saturating_sub(delta).max(min)
+            };
+            DynSolValue::Int(value, bits)
+        }
+        value => value,
+    }
+}
+
+/// Returns simpler variants of `calldata`, a call of `func`, that each simplify a single argument,
+/// simplest first.
+///
+/// Returns no variants if the calldata can't be decoded.
+pub fn shrink_calldata(func: &Function, calldata: &[u8]) -> Vec<Bytes> {
+    let Some(args) = calldata.get(4..).and_then(|data| func.abi_decode_input(data, false).ok())
+    else {
+        return vec![]
+    };
+    simplify_each(&args)
+        .into_iter()
+        .filter_map(|args| func.abi_encode_input(&args).ok())
+        .map(Into::into)
+        .collect()
+}
+
+/// Returns simpler variants of `value`, simplest first.
+fn simplify(value: &DynSolValue) -> Vec<DynSolValue> {
+    let mut variants = match value {
+        DynSolValue::Bool(true) => vec![DynSolValue::Bool(false)],
+        DynSolValue::Uint(value, bits) if !value.is_zero() => {
+            [U256::ZERO, *value >> 1, *value - U256::from(1)]
+                .into_iter()
+                .map(|value| DynSolValue::Uint(value, *bits))
+                .collect()
+        }
+        DynSolValue::Int(value, bits) if !value.is_zero() => {
+            let closer = if value.is_negative() { *value + I256::ONE } else { *value - I256::ONE };
+            [I256::ZERO, *value / I256::try_from(2).unwrap(), closer]
+                .into_iter()
+                .map(|value| DynSolValue::Int(value, *bits))
+                .collect()
+        }
+        DynSolValue::Address(address) if !address.is_zero() => {
+            vec![DynSolValue::Address(Address::ZERO)]
+        }
+        DynSolValue::FixedBytes(word, size) if !word.is_zero() => {
+            vec![DynSolValue::FixedBytes(B256::ZERO, *size)]
+        }
+        DynSolValue::Bytes(bytes) if !bytes.is_empty() => {
+            vec![DynSolValue::Bytes(vec![]), DynSolValue::Bytes(bytes[..bytes.len() / 2].to_vec())]
+        }
+        DynSolValue::String(string) if !string.is_empty() => {
+            let half = string.chars().count() / 2;
+            vec![
+                DynSolValue::String(String::new()),
+                DynSolValue::String(string.chars().take(half).collect()),
+            ]
+        }
+        DynSolValue::Array(values) if !values.is_empty() => {
+            let mut variants = vec![
+                DynSolValue::Array(vec![]),
+                DynSolValue::Array(values[..values.len() / 2].to_vec()),
+            ];
+            variants.extend(simplify_each(values).into_iter().map(DynSolValue::Array));
+            variants
+        }
+        DynSolValue::FixedArray(values) => {
+            simplify_each(values).into_iter().map(DynSolValue::FixedArray).collect()
+        }
+        DynSolValue::Tuple(values) => {
+            simplify_each(values).into_iter().map(DynSolValue::Tuple).collect()
+        }
+        _ => vec![],
+    };
+    variants.dedup();
+    variants
+}
+
+/// Returns the variants of `values` that each simplify a single value.
+fn simplify_each(values: &[DynSolValue]) -> Vec<Vec<DynSolValue>> {
+    let mut variants = vec![];
+    for (i, value) in values.iter().enumerate() {
+        for simpler in simplify(value) {
+            let mut values = values.to_vec();
+            values[i] = simpler;
+            variants.push(values);
+        }
+    }
+    variants
+}
+
+/// Mutates a call sequence by splicing it with `other`, truncating it, duplicating a call,
+/// swapping two calls or nudging the integer arguments of a call.
+///
+/// Truncated sequences are meant to be continued with newly generated calls.
+pub fn mutate_sequence(
+    sequence: &[BasicTxDetails],
+    other: &[BasicTxDetails],
+    targets: &FuzzRunIdentifiedContracts,
+    rng: &mut impl Rng,
+) -> Vec<BasicTxDetails> {
+    let mut sequence = sequence.to_vec();
+    if sequence.is_empty() {
+        return other.to_vec()
+    }
+    match rng.gen_range(0..5) {
+        0 => {
+            sequence.truncate(rng.gen_range(0..=sequence.len()));
+            sequence.extend_from_slice(&other[rng.gen_range(0..=other.len())..]);
+        }
+        1 => sequence.truncate(rng.gen_range(1..=sequence.len())),
+        2 => {
+            let call = sequence[rng.gen_range(0..sequence.len())].clone();
+            sequence.insert(rng.gen_range(0..=sequence.len()), call);
+        }
+        3 => {
+            let (a, b) = (rng.gen_range(0..sequence.len()), rng.gen_range(0..sequence.len()));
+            sequence.swap(a, b);
+        }
+        _ => {
+            let index = rng.gen_range(0..sequence.len());
+            let tx = &mut sequence[index];
+            if let (_, Some(func)) = targets.fuzzed_artifacts(tx) {
+                let calldata = &tx.call_details.calldata;
+                if let Some(mutated) = mutate_calldata(&func, calldata, calldata, rng) {
+                    tx.call_details.calldata = mutated;
+                }
+            }
+        }
+    }
+    sequence
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::test_runner::{RngAlgorithm, TestRng};
+
+    #[test]
+    fn nudge_keeps_integers_in_range() {
+        let mut rng = TestRng::deterministic_rng(RngAlgorithm::ChaCha);
+        for _ in 0..100 {
+            let DynSolValue::Uint(value, 8) =
+                nudge(DynSolValue::Uint(U256::from(250), 8), &mut rng)
+            else {
+                panic!("type changed")
+            };
+            assert!(value <= U256::from(u8::MAX) && value >= U256::from(234));
+
+            let DynSolValue::Int(value, 8) =
+                nudge(DynSolValue::Int(I256::try_from(-120).unwrap(), 8), &mut rng)
+            else {
+                panic!("type changed")
+            };
+            assert!(value >= I256::try_from(i8::MIN).unwrap());
+            assert!(value <= I256::try_from(-104).unwrap());
+        }
+    }
+
+    #[test]
+    fn can_mutate_calldata() {
+        let mut rng = TestRng::deterministic_rng(RngAlgorithm::ChaCha);
+        let func = Function::parse("f(uint256,address)").unwrap();
+        let calldata = func
+            .abi_encode_input(&[
+                DynSolValue::Uint(U256::from(1), 256),
+                DynSolValue::Address(Default::default()),
+            ])
+            .unwrap();
+        let fresh = func
+            .abi_encode_input(&[
+                DynSolValue::Uint(U256::from(100), 256),
+                DynSolValue::Address([1; 20].into()),
+            ])
+            .unwrap();
+        for _ in 0..100 {
+            let mutated = mutate_calldata(&func, &calldata, &fresh, &mut rng).unwrap();
+            assert_eq!(mutated[..4], calldata[..4]);
+            let args = func.abi_decode_input(&mutated[4..], false).unwrap();
+            assert_eq!(args.len(), 2);
+            // only one of the arguments is mutated
+            let original = func.abi_decode_input(&calldata[4..], false).unwrap();
+            assert!(args[0] == original[0] || args[1] == original[1]);
+        }
+        assert!(mutate_calldata(&func, &[], &fresh, &mut rng).is_none());
+    }
+
+    #[test]
+    fn can_shrink_calldata() {
+        let func = Function::parse("f(uint256,bool,bytes)").unwrap();
+        let mut calldata: Bytes = func
+            .abi_encode_input(&[
+                DynSolValue::Uint(U256::from(1000), 256),
+                DynSolValue::Bool(true),
+                DynSolValue::Bytes(vec![1, 2, 3, 4]),
+            ])
+            .unwrap()
+            .into();
+
+        // greedily take the first variant until nothing can be simplified anymore
+        while let Some(simpler) = shrink_calldata(&func, &calldata).into_iter().next() {
+            assert_eq!(simpler[..4], calldata[..4]);
+            calldata = simpler;
+        }
+        let args = func.abi_decode_input(&calldata[4..], false).unwrap();
+        assert_eq!(
+            args,
+            [
+                DynSolValue::Uint(U256::ZERO, 256),
+                DynSolValue::Bool(false),
+                DynSolValue::Bytes(vec![])
+            ]
+        );
+        assert!(shrink_calldata(&func, &[]).is_empty());
+    }
+
+    #[test]
+    fn prunes_stale_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("CounterTest").join("testFuzz.json");
+        let func = Function::parse("testFuzz(uint8)").unwrap();
+        let valid =
+            Bytes::from(func.abi_encode_input(&[DynSolValue::Uint(U256::from(1), 8)]).unwrap());
+        let truncated = valid.slice(..20);
+
+        let mut corpus = Corpus::default();
+        corpus.push(valid.clone());
+        corpus.push(truncated);
+        corpus.save_inputs(&path, &func).unwrap();
+
+        // inputs that can't be decoded are dropped
+        assert_eq!(Corpus::load_inputs(&path, &func).entries(), [valid]);
+
+        // the whole file is stale once the signature changes
+        let changed = Function::parse("testFuzz(uint8,uint8)").unwrap();
+        assert!(Corpus::load_inputs(&path, &changed).is_empty());
+    }
+}
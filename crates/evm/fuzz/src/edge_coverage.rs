@@ -0,0 +1,140 @@
+use revm::{
+    interpreter::{opcode, Interpreter},
+    Database, EvmContext, Inspector,
+};
+use std::collections::HashMap;
+
+/// Number of buckets of an edge coverage map.
+pub const EDGE_MAP_SIZE: usize = 1 << 16;
+
+/// An inspector that records the branches taken during a call.
+///
+/// Every `JUMPI` is an edge identified by the code, the program counter and whether the jump was
+/// taken. Edges are hashed into the indices of a fixed size map, only the saturating hit counters
+/// of the touched indices are kept.
+#[derive(Clone, Debug, Default)]
+pub struct EdgeCoverageCollector {
+    /// The hit counters of the touched map indices.
+    pub hits: HashMap<usize, u8>,
+}
+
+impl<DB: Database> Inspector<DB> for EdgeCoverageCollector {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if interp.current_opcode() != opcode::JUMPI {
+            return
+        }
+        let Ok(condition) = interp.stack().peek(1) else { return };
+        let code = match interp.contract.hash {
+            Some(hash) => u64::from_be_bytes(hash[..8].try_into().unwrap()),
+            None => u64::from_be_bytes(interp.contract.target_address[..8].try_into().unwrap()),
+        };
+        self.hit(edge_index(code, interp.program_counter(), !condition.is_zero()));
+    }
+}
+
+impl EdgeCoverageCollector {
+    /// Increments the counter of the edge at the given index.
+    #[inline]
+    fn hit(&mut self, index: usize) {
+        let count = self.hits.entry(index).or_default();
+        *count = count.saturating_add(1);
+    }
+}
+
+/// Returns the map index of the branch at `pc` of the given code.
+fn edge_index(code: u64, pc: usize, taken: bool) -> usize {
+    let edge = code ^ (pc as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ taken as u64;
+    (edge ^ (edge >> 32)) as usize % EDGE_MAP_SIZE
+}
+
+/// The edge coverage accumulated over all calls of a fuzz campaign.
+///
+/// Hit counts are classified in buckets (1, 2, 3, 4-7, 8-15, 16-31, 32-127, 128+), so a call that
+/// takes a known branch a different number of times also counts as new coverage.
+#[derive(Clone, Debug, Default)]
+pub struct EdgeCoverage {
+    /// The hit count buckets seen for every edge.
+    seen: Vec<u8>,
+    /// The number of edges that were hit at least once.
+    edges: usize,
+}
+
+impl EdgeCoverage {
+    /// Merges the hit counters of a call into the accumulated coverage.
+    ///
+    /// Returns `true` if the call reached a new edge or a new hit count bucket of an edge.
+    pub fn merge(&mut self, hits: &HashMap<usize, u8>) -> bool {
+        if hits.is_empty() {
+            return false
+        }
+        if self.seen.is_empty() {
+            self.seen = vec![0; EDGE_MAP_SIZE];
+        }
+        let mut new_coverage = false;
+        for (&index, &count) in hits {
+            let seen = &mut self.seen[index];
+            let bucket = bucket(count);
+            if *seen & bucket == 0 {
+                if *seen == 0 {
+                    self.edges += 1;
+                }
+                *seen |= bucket;
+                new_coverage = true;
+            }
+        }
+        new_coverage
+    }
+
+    /// Returns the number of edges that were hit at least once.
+    pub fn edges(&self) -> usize {
+        self.edges
+    }
+}
+
+/// Returns the bit of the bucket of the given hit count.
+fn bucket(count: u8) -> u8 {
+    match count {
+        0 => 0,
+        1 => 1,
+        2 => 1 << 1,
+        3 => 1 << 2,
+        4..=7 => 1 << 3,
+        8..=15 => 1 << 4,
+        16..=31 => 1 << 5,
+        32..=127 => 1 << 6,
+        128.. => 1 << 7,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_reports_new_buckets() {
+        let mut coverage = EdgeCoverage::default();
+        let mut hits = HashMap::from([(1, 1)]);
+        assert!(coverage.merge(&hits));
+        assert!(!coverage.merge(&hits));
+
+        // same edge, different hit count bucket
+        hits.insert(1, 5);
+        assert!(coverage.merge(&hits));
+        hits.insert(1, 6);
+        assert!(!coverage.merge(&hits));
+
+        hits.insert(2, 1);
+        assert!(coverage.merge(&hits));
+        assert_eq!(coverage.edges(), 2);
+
+        // empty calls never add coverage
+        assert!(!coverage.merge(&HashMap::new()));
+    }
+
+    #[test]
+    fn branch_directions_are_distinct_edges() {
+        assert_ne!(edge_index(1, 42, true), edge_index(1, 42, false));
+        assert_ne!(edge_index(1, 42, true), edge_index(2, 42, true));
+    }
+}
@@ -0,0 +1,131 @@
+use alloy_primitives::{Address, Selector};
+use eyre::{eyre, Result};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+/// Maximum number of distinct revert reasons recorded for a single function.
+const MAX_REVERT_REASONS: usize = 32;
+
+/// Identifies a target function by the address of the target contract and its selector, so the
+/// calls to distinct contracts or overloaded functions are not merged.
+///
+/// Serialized as `<target>:<selector>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct InvariantMetricsKey {
+    /// The address of the target contract.
+    pub target: Address,
+    /// The selector of the target function.
+    pub selector: Selector,
+}
+
+impl fmt::Display for InvariantMetricsKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.target, self.selector)
+    }
+}
+
+impl FromStr for InvariantMetricsKey {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (target, selector) =
+            s.split_once(':').ok_or_else(|| eyre!("expected `<target>:<selector>`, got {s}"))?;
+        Ok(Self { target: target.parse()?, selector: selector.parse()? })
+    }
+}
+
+impl Serialize for InvariantMetricsKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for InvariantMetricsKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Call statistics of a single target function, collected during an invariant campaign.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvariantMetrics {
+    /// The name of the target contract.
+    pub contract: String,
+    /// The name of the target function, if the selector is in the ABI of the target contract.
+    pub function: Option<String>,
+    /// Number of calls made to the function.
+    pub calls: usize,
+    /// Number of calls that reverted.
+    pub reverts: usize,
+    /// Total gas used by the calls.
+    pub gas_used: u64,
+    /// Number of reverts by decoded revert reason.
+    pub revert_reasons: BTreeMap<String, usize>,
+}
+
+impl InvariantMetrics {
+    /// Records a call, with its revert reason if it reverted.
+    pub fn record(&mut self, gas_used: u64, revert_reason: Option<String>) {
+        self.calls += 1;
+        self.gas_used += gas_used;
+        if let Some(reason) = revert_reason {
+            self.reverts += 1;
+            if let Some(count) = self.revert_reasons.get_mut(&reason) {
+                *count += 1;
+            } else if self.revert_reasons.len() < MAX_REVERT_REASONS {
+                self.revert_reasons.insert(reason, 1);
+            }
+        }
+    }
+
+    /// Returns the mean gas used per call.
+    pub fn mean_gas(&self) -> u64 {
+        self.gas_used.checked_div(self.calls as u64).unwrap_or_default()
+    }
+
+    /// Returns the `n` most frequent revert reasons, most frequent first.
+    pub fn top_revert_reasons(&self, n: usize) -> Vec<(&str, usize)> {
+        let mut reasons = self
+            .revert_reasons
+            .iter()
+            .map(|(reason, count)| (reason.as_str(), *count))
+            .collect::<Vec<_>>();
+        reasons.sort_by(|a, b| b.1.cmp(&a.1));
+        reasons.truncate(n);
+        reasons
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_calls_and_reverts() {
+        let mut metrics = InvariantMetrics::default();
+        metrics.record(100, None);
+        metrics.record(200, Some("b".to_string()));
+        metrics.record(300, Some("a".to_string()));
+        metrics.record(400, Some("a".to_string()));
+
+        assert_eq!(metrics.calls, 4);
+        assert_eq!(metrics.reverts, 3);
+        assert_eq!(metrics.mean_gas(), 250);
+        assert_eq!(metrics.top_revert_reasons(1), vec![("a", 2)]);
+        assert_eq!(InvariantMetrics::default().mean_gas(), 0);
+    }
+
+    #[test]
+    fn keys_roundtrip_as_strings() {
+        let key = InvariantMetricsKey {
+            target: Address::repeat_byte(0x11),
+            selector: Selector::from([0x12, 0x34, 0x56, 0x78]),
+        };
+        let metrics = BTreeMap::from([(key, InvariantMetrics::default())]);
+        let json = serde_json::to_string(&metrics).unwrap();
+        assert!(json.contains("0x1111111111111111111111111111111111111111:0x12345678"), "{json}");
+        let decoded: BTreeMap<InvariantMetricsKey, InvariantMetrics> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, metrics);
+    }
+}
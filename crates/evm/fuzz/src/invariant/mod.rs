@@ -9,6 +9,9 @@ pub use call_override::RandomCallGenerator;
 mod filters;
 pub use filters::{ArtifactFilters, SenderFilters};
 
+mod metrics;
+pub use metrics::{InvariantMetrics, InvariantMetricsKey};
+
 pub type TargetedContracts = BTreeMap<Address, (String, JsonAbi, Vec<Function>)>;
 
 /// Contracts identified as targets during a fuzz run.
@@ -41,7 +44,7 @@ impl FuzzRunIdentifiedContracts {
 }
 
 /// Details of a transaction generated by invariant strategy for fuzzing a target.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct BasicTxDetails {
     // Transaction sender address.
     pub sender: Address,
@@ -50,7 +53,7 @@ pub struct BasicTxDetails {
 }
 
 /// Call details of a transaction generated to fuzz invariant target.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CallDetails {
     // Address of target contract.
     pub target: Address,
@@ -63,8 +66,25 @@ pub struct CallDetails {
 pub struct InvariantContract<'a> {
     /// Address of the test contract.
     pub address: Address,
-    /// Invariant function present in the test contract.
-    pub invariant_function: &'a Function,
+    /// Invariant functions checked together during the campaign.
+    pub invariant_functions: Vec<&'a Function>,
+    /// Whether the `afterInvariant` function of the test contract is called after each run.
+    pub call_after_invariant: bool,
     /// ABI of the test contract.
     pub abi: &'a JsonAbi,
 }
+
+impl InvariantContract<'_> {
+    /// Returns whether the campaign maximizes the value returned by its single invariant function
+    /// instead of checking that it holds.
+    pub fn is_optimization(&self) -> bool {
+        matches!(self.invariant_functions.as_slice(), [func] if is_optimization_invariant(func))
+    }
+}
+
+/// Returns whether the given invariant function is run in optimization mode, i.e. it is prefixed
+/// with `invariant_optimize_` and returns an `int256` value that the campaign tries to maximize.
+pub fn is_optimization_invariant(func: &Function) -> bool {
+    func.name.starts_with("invariant_optimize_") &&
+        matches!(func.outputs.as_slice(), [output] if output.ty == "int256")
+}
@@ -18,6 +18,11 @@ use std::{collections::HashMap, fmt, sync::Arc};
 
 pub use proptest::test_runner::{Config as FuzzConfig, Reason};
 
+pub mod corpus;
+
+mod edge_coverage;
+pub use edge_coverage::{EdgeCoverage, EdgeCoverageCollector, EDGE_MAP_SIZE};
+
 mod error;
 pub use error::FuzzError;
 
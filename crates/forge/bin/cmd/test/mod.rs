@@ -6,7 +6,7 @@ use forge::{
     decode::decode_console_logs,
     gas_report::GasReport,
     multi_runner::matches_contract,
-    result::{SuiteResult, TestOutcome, TestStatus},
+    result::{SuiteResult, TestKind, TestOutcome, TestStatus},
     traces::{identifier::SignaturesIdentifier, CallTraceDecoderBuilder, TraceKind},
     MultiContractRunner, MultiContractRunnerBuilder, TestFilter, TestOptions, TestOptionsBuilder,
 };
@@ -45,7 +45,7 @@ use yansi::Paint;
 
 mod filter;
 mod summary;
-use summary::TestSummaryReporter;
+use summary::{invariant_metrics_table, TestSummaryReporter};
 
 pub use filter::FilterArgs;
 use forge::traces::render_trace_arena;
@@ -424,8 +424,15 @@ impl TestArgs {
             for (name, result) in tests {
                 shell::println(result.short_result(name))?;
 
-                // We only display logs at level 2 and above
+                // We only display logs and invariant metrics at level 2 and above
                 if verbosity >= 2 {
+                    if let TestKind::Invariant { metrics, .. } = &result.kind {
+                        if !metrics.is_empty() {
+                            println!("{}", invariant_metrics_table(metrics));
+                            println!();
+                        }
+                    }
+
                     // We only decode logs from Hardhat and DS-style console events
                     let console_logs = decode_console_logs(&result.logs);
                     if !console_logs.is_empty() {
@@ -2,6 +2,38 @@ use crate::cmd::test::TestOutcome;
 use comfy_table::{
     modifiers::UTF8_ROUND_CORNERS, Attribute, Cell, CellAlignment, Color, Row, Table,
 };
+use forge::fuzz::invariant::{InvariantMetrics, InvariantMetricsKey};
+use itertools::Itertools;
+use std::collections::BTreeMap;
+
+/// Builds a table of the call statistics of every function targeted by an invariant test.
+pub(crate) fn invariant_metrics_table(
+    metrics: &BTreeMap<InvariantMetricsKey, InvariantMetrics>,
+) -> Table {
+    let mut table = Table::new();
+    table.apply_modifier(UTF8_ROUND_CORNERS);
+    table.set_header(
+        ["Contract", "Function", "Selector", "Calls", "Reverts", "Mean gas", "Top revert reasons"]
+            .map(|header| Cell::new(header).add_attribute(Attribute::Bold)),
+    );
+    for (key, metrics) in metrics {
+        let reasons = metrics
+            .top_revert_reasons(3)
+            .into_iter()
+            .map(|(reason, count)| format!("{reason} ({count})"))
+            .join("\n");
+        table.add_row([
+            Cell::new(&metrics.contract),
+            Cell::new(metrics.function.as_deref().unwrap_or_default()),
+            Cell::new(key.selector),
+            Cell::new(metrics.calls).set_alignment(CellAlignment::Right),
+            Cell::new(metrics.reverts).set_alignment(CellAlignment::Right),
+            Cell::new(metrics.mean_gas()).set_alignment(CellAlignment::Right),
+            Cell::new(reasons),
+        ]);
+    }
+    table
+}
 
 /// A simple summary reporter that prints the test results in a table.
 pub struct TestSummaryReporter {
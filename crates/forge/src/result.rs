@@ -1,6 +1,6 @@
 //! Test outcomes.
 
-use alloy_primitives::{Address, Log};
+use alloy_primitives::{Address, Log, I256};
 use foundry_common::{
     evm::Breakpoints, get_contract_name, get_file_name, shell, ContractsByArtifact,
 };
@@ -9,7 +9,10 @@ use foundry_evm::{
     coverage::HitMaps,
     debug::DebugArena,
     executors::EvmError,
-    fuzz::{CounterExample, FuzzCase, FuzzFixtures},
+    fuzz::{
+        invariant::{InvariantMetrics, InvariantMetricsKey},
+        CounterExample, FuzzCase, FuzzFixtures,
+    },
     traces::{CallTraceArena, CallTraceDecoder, TraceKind, Traces},
 };
 use serde::{Deserialize, Serialize};
@@ -357,7 +360,8 @@ pub struct TestResult {
     /// still be successful (i.e self.success == true) when it's expected to fail.
     pub reason: Option<String>,
 
-    /// Minimal reproduction test case for failing test
+    /// Minimal reproduction test case for failing test, or the shrunk sequence reaching the best
+    /// value of an optimization invariant test
     pub counterexample: Option<CounterExample>,
 
     /// Any captured & parsed as strings logs along the test's execution which should
@@ -439,7 +443,20 @@ impl TestResult {
 
     /// Formats the test result into a string (for printing).
     pub fn short_result(&self, name: &str) -> String {
-        format!("{self} {name} {}", self.kind.report())
+        let mut s = format!("{self} {name} {}", self.kind.report());
+        // Optimization invariant tests also report their best value and the sequence reaching it.
+        if let TestKind::Invariant { best_value: Some(best_value), .. } = &self.kind {
+            if self.status == TestStatus::Success {
+                write!(s, "\n\t[Best value: {best_value}]").unwrap();
+                if let Some(CounterExample::Sequence(sequence)) = &self.counterexample {
+                    s.push_str("\n\t[Sequence]");
+                    for ex in sequence {
+                        write!(s, "\n\t\t{ex}").unwrap();
+                    }
+                }
+            }
+        }
+        s
     }
 }
 
@@ -496,7 +513,15 @@ pub enum TestKind {
         median_gas: u64,
     },
     /// A solidity invariant test, that stores all test cases
-    Invariant { runs: usize, calls: usize, reverts: usize },
+    Invariant {
+        runs: usize,
+        calls: usize,
+        reverts: usize,
+        /// Call statistics of every targeted function, keyed by target and selector.
+        metrics: BTreeMap<InvariantMetricsKey, InvariantMetrics>,
+        /// The best value found by an optimization invariant test.
+        best_value: Option<I256>,
+    },
 }
 
 impl Default for TestKind {
@@ -513,7 +538,7 @@ impl TestKind {
             TestKind::Fuzz { runs, mean_gas, median_gas, .. } => {
                 TestKindReport::Fuzz { runs: *runs, mean_gas: *mean_gas, median_gas: *median_gas }
             }
-            TestKind::Invariant { runs, calls, reverts } => {
+            TestKind::Invariant { runs, calls, reverts, .. } => {
                 TestKindReport::Invariant { runs: *runs, calls: *calls, reverts: *reverts }
             }
         }
@@ -22,14 +22,14 @@ use foundry_evm::{
     executors::{
         fuzz::{CaseOutcome, CounterExampleOutcome, FuzzOutcome, FuzzedExecutor},
         invariant::{
-            check_sequence, replay_error, replay_run, InvariantExecutor, InvariantFuzzError,
-            InvariantFuzzTestResult,
+            check_sequence, replay_error, replay_optimization, replay_run, InvariantExecutor,
+            InvariantFuzzError, InvariantFuzzTestResult,
         },
         CallResult, EvmError, ExecutionErr, Executor, RawCallResult,
     },
     fuzz::{
         fixture_name,
-        invariant::{CallDetails, InvariantContract},
+        invariant::{is_optimization_invariant, CallDetails, InvariantContract},
         CounterExample, FuzzFixtures,
     },
     traces::{load_contracts, TraceKind},
@@ -294,6 +294,12 @@ impl<'a> ContractRunner<'a> {
 
         let has_invariants = self.contract.abi.functions().any(|func| func.is_invariant_test());
 
+        // `afterInvariant` takes no inputs, so there is at most one, and it is only called by
+        // invariant tests.
+        let after_invariant = has_invariants
+            .then(|| self.contract.abi.functions().find(|func| func.is_after_invariant()))
+            .flatten();
+
         // Invariant testing requires tracing to figure out what contracts were created.
         let tmp_tracing = self.executor.inspector.tracer.is_none() && has_invariants && needs_setup;
         if tmp_tracing {
@@ -347,10 +353,47 @@ impl<'a> ContractRunner<'a> {
             find_time,
         );
 
+        // Invariant tests sharing the same configuration are checked together in a single
+        // campaign. Optimization invariants are maximized in a campaign of their own. Campaigns
+        // are grouped before the test filter is applied and run whole if any of their invariants
+        // matches it, so a campaign is the same whichever of its invariants are selected.
+        let mut invariant_campaigns: Vec<(&InvariantConfig, Vec<&Function>)> = Vec::new();
+        for func in self.contract.abi.functions().filter(|func| func.is_invariant_test()) {
+            let invariant_config = test_options.invariant_config(self.name, &func.name);
+            let shared_campaign = invariant_campaigns.iter_mut().find(|(config, funcs)| {
+                *config == invariant_config &&
+                    !is_optimization_invariant(func) &&
+                    !is_optimization_invariant(funcs[0])
+            });
+            match shared_campaign {
+                Some((_, funcs)) => funcs.push(func),
+                None => invariant_campaigns.push((invariant_config, vec![func])),
+            }
+        }
+        invariant_campaigns.retain(|(_, funcs)| funcs.iter().any(|func| functions.contains(func)));
+
         let identified_contracts =
             has_invariants.then(|| load_contracts(setup.traces.clone(), &known_contracts));
+        let invariant_results = invariant_campaigns.par_iter().flat_map_iter(|(config, funcs)| {
+            let _guard = handle.enter();
+
+            let runner = test_options.invariant_runner(self.name, &funcs[0].name);
+            let mut results = self.run_invariant_tests(
+                runner,
+                setup.clone(),
+                (*config).clone(),
+                funcs,
+                after_invariant,
+                &known_contracts,
+                identified_contracts.as_ref().unwrap(),
+            );
+            // Only the selected invariants are reported.
+            results.retain(|sig, _| functions.iter().any(|func| func.signature() == *sig));
+            results
+        });
         let test_results = functions
             .par_iter()
+            .filter(|func| !func.is_invariant_test())
             .map(|&func| {
                 let _guard = handle.enter();
 
@@ -358,18 +401,7 @@ impl<'a> ContractRunner<'a> {
 
                 let setup = setup.clone();
                 let should_fail = func.is_test_fail();
-                let res = if func.is_invariant_test() {
-                    let runner = test_options.invariant_runner(self.name, &func.name);
-                    let invariant_config = test_options.invariant_config(self.name, &func.name);
-                    self.run_invariant_test(
-                        runner,
-                        setup,
-                        invariant_config.clone(),
-                        func,
-                        &known_contracts,
-                        identified_contracts.as_ref().unwrap(),
-                    )
-                } else if func.is_fuzz_test() {
+                let res = if func.is_fuzz_test() {
                     debug_assert!(func.is_test());
                     let runner = test_options.fuzz_runner(self.name, &func.name);
                     let fuzz_config = test_options.fuzz_config(self.name, &func.name);
@@ -381,6 +413,7 @@ impl<'a> ContractRunner<'a> {
 
                 (sig, res)
             })
+            .chain(invariant_results)
             .collect::<BTreeMap<_, _>>();
 
         let duration = start.elapsed();
@@ -513,87 +546,106 @@ impl<'a> ContractRunner<'a> {
         }
     }
 
+    /// Runs the given invariant tests in a single campaign, checking every call sequence against
+    /// all of them. Each broken invariant is reported with its own shrunk counterexample.
+    #[allow(clippy::too_many_arguments)]
     #[instrument(name = "invariant_test", skip_all)]
-    pub fn run_invariant_test(
+    pub fn run_invariant_tests(
         &self,
         runner: TestRunner,
         setup: TestSetup,
         invariant_config: InvariantConfig,
-        func: &Function,
+        funcs: &[&Function],
+        after_invariant: Option<&Function>,
         known_contracts: &ContractsByArtifact,
         identified_contracts: &ContractsByAddress,
-    ) -> TestResult {
-        trace!(target: "forge::test::fuzz", "executing invariant test for {:?}", func.name);
+    ) -> BTreeMap<String, TestResult> {
+        let names = funcs.iter().map(|func| &func.name).collect::<Vec<_>>();
+        trace!(target: "forge::test::fuzz", "executing invariant tests for {:?}", names);
         let TestSetup { address, logs, traces, labeled_addresses, coverage, fuzz_fixtures, .. } =
             setup;
 
-        // First, run the test normally to see if it needs to be skipped.
         let start = Instant::now();
-        if let Err(EvmError::SkipError) = self.executor.clone().execute_test(
-            self.sender,
-            address,
-            func,
-            &[],
-            U256::ZERO,
-            Some(self.revert_decoder),
-        ) {
-            return TestResult {
-                status: TestStatus::Skipped,
-                reason: None,
-                decoded_logs: decode_console_logs(&logs),
-                traces,
-                labeled_addresses,
-                kind: TestKind::Invariant { runs: 1, calls: 1, reverts: 1 },
-                coverage,
-                duration: start.elapsed(),
-                ..Default::default()
-            }
-        };
-
-        let mut evm = InvariantExecutor::new(
-            self.executor.clone(),
-            runner,
-            invariant_config.clone(),
-            identified_contracts,
-            known_contracts,
-        );
-        let invariant_contract =
-            InvariantContract { address, invariant_function: func, abi: &self.contract.abi };
-
-        let mut logs = logs.clone();
-        let mut traces = traces.clone();
-        let mut coverage = coverage.clone();
-
         let failure_dir = invariant_config.clone().failure_dir(self.name);
-        let failure_file = failure_dir.join(invariant_contract.invariant_function.clone().name);
+        let mut results = BTreeMap::new();
+        let mut invariant_functions = Vec::with_capacity(funcs.len());
 
-        // Try to replay recorded failure if any.
-        if let Ok(call_sequence) =
-            foundry_common::fs::read_json_file::<Vec<BaseCounterExample>>(failure_file.as_path())
-        {
-            // Create calls from failed sequence and check if invariant still broken.
-            let txes = call_sequence
-                .clone()
-                .into_iter()
-                .map(|seq| BasicTxDetails {
-                    sender: seq.sender.unwrap_or_default(),
-                    call_details: CallDetails {
-                        target: seq.addr.unwrap_or_default(),
-                        calldata: seq.calldata,
-                    },
-                })
-                .collect::<Vec<BasicTxDetails>>();
-            if let Ok((success, replayed_entirely)) = check_sequence(
-                self.executor.clone(),
-                &txes,
-                (0..min(txes.len(), invariant_config.depth as usize)).collect(),
-                invariant_contract.address,
-                invariant_contract.invariant_function.selector().to_vec().into(),
-                invariant_config.fail_on_revert,
+        for &func in funcs {
+            // First, run the test normally to see if it needs to be skipped.
+            if let Err(EvmError::SkipError) = self.executor.clone().execute_test(
+                self.sender,
+                address,
+                func,
+                &[],
+                U256::ZERO,
+                Some(self.revert_decoder),
             ) {
-                if !success {
+                results.insert(
+                    func.signature(),
+                    TestResult {
+                        status: TestStatus::Skipped,
+                        reason: None,
+                        decoded_logs: decode_console_logs(&logs),
+                        traces: traces.clone(),
+                        labeled_addresses: labeled_addresses.clone(),
+                        kind: TestKind::Invariant {
+                            runs: 1,
+                            calls: 1,
+                            reverts: 1,
+                            metrics: Default::default(),
+                            best_value: None,
+                        },
+                        coverage: coverage.clone(),
+                        duration: start.elapsed(),
+                        ..Default::default()
+                    },
+                );
+                continue
+            };
+
+            let invariant_contract = InvariantContract {
+                address,
+                invariant_functions: vec![func],
+                call_after_invariant: after_invariant.is_some(),
+                abi: &self.contract.abi,
+            };
+            let failure_file = failure_dir.join(&func.name);
+
+            // Try to replay recorded failure if any.
+            let persisted_failure = foundry_common::fs::read_json_file::<Vec<BaseCounterExample>>(
+                failure_file.as_path(),
+            );
+            if let Ok(call_sequence) = persisted_failure {
+                // Create calls from failed sequence and check if invariant still broken.
+                let txes = call_sequence
+                    .clone()
+                    .into_iter()
+                    .map(|seq| BasicTxDetails {
+                        sender: seq.sender.unwrap_or_default(),
+                        call_details: CallDetails {
+                            target: seq.addr.unwrap_or_default(),
+                            calldata: seq.calldata,
+                        },
+                    })
+                    .collect::<Vec<BasicTxDetails>>();
+                // The persisted failure may have broken the invariant or `afterInvariant`.
+                let checks = std::iter::once(func)
+                    .chain(after_invariant)
+                    .map(|func| func.selector().to_vec().into())
+                    .collect::<Vec<_>>();
+                if let Ok((false, replayed_entirely)) = check_sequence(
+                    self.executor.clone(),
+                    &txes,
+                    (0..min(txes.len(), invariant_config.depth as usize)).collect(),
+                    address,
+                    &checks,
+                    invariant_config.fail_on_revert,
+                ) {
                     // If sequence still fails then replay error to collect traces and
-                    // exit without executing new runs.
+                    // skip the invariant in the new runs.
+                    let mut logs = logs.clone();
+                    let mut traces = traces.clone();
+                    let mut coverage = coverage.clone();
                     let _ = replay_run(
                         &invariant_contract,
                         self.executor.clone(),
@@ -604,64 +656,214 @@ impl<'a> ContractRunner<'a> {
                         &mut coverage,
                         txes,
                     );
-                    return TestResult {
-                        status: TestStatus::Failure,
-                        reason: if replayed_entirely {
-                            Some(format!(
-                                "{} replay failure",
-                                invariant_contract.invariant_function.name
-                            ))
-                        } else {
-                            Some(format!(
-                                "{} persisted failure revert",
-                                invariant_contract.invariant_function.name
-                            ))
+                    results.insert(
+                        func.signature(),
+                        TestResult {
+                            status: TestStatus::Failure,
+                            reason: if replayed_entirely {
+                                Some(format!("{} replay failure", func.name))
+                            } else {
+                                Some(format!("{} persisted failure revert", func.name))
+                            },
+                            decoded_logs: decode_console_logs(&logs),
+                            traces,
+                            coverage,
+                            counterexample: Some(CounterExample::Sequence(call_sequence)),
+                            kind: TestKind::Invariant {
+                                runs: 1,
+                                calls: 1,
+                                reverts: 1,
+                                metrics: Default::default(),
+                                best_value: None,
+                            },
+                            duration: start.elapsed(),
+                            ..Default::default()
                         },
-                        decoded_logs: decode_console_logs(&logs),
-                        traces,
-                        coverage,
-                        counterexample: Some(CounterExample::Sequence(call_sequence)),
-                        kind: TestKind::Invariant { runs: 1, calls: 1, reverts: 1 },
-                        duration: start.elapsed(),
-                        ..Default::default()
-                    }
+                    );
+                    continue
                 }
             }
+
+            invariant_functions.push(func);
         }
 
-        let InvariantFuzzTestResult { error, cases, reverts, last_run_inputs, gas_report_traces } =
-            match evm.invariant_fuzz(invariant_contract.clone(), &fuzz_fixtures) {
-                Ok(x) => x,
-                Err(e) => {
-                    return TestResult {
-                        status: TestStatus::Failure,
-                        reason: Some(format!(
-                            "failed to set up invariant testing environment: {e}"
-                        )),
-                        decoded_logs: decode_console_logs(&logs),
-                        traces,
-                        labeled_addresses,
-                        kind: TestKind::Invariant { runs: 0, calls: 0, reverts: 0 },
-                        duration: start.elapsed(),
-                        ..Default::default()
-                    }
+        if invariant_functions.is_empty() {
+            return results
+        }
+
+        let invariant_contract = InvariantContract {
+            address,
+            invariant_functions,
+            call_after_invariant: after_invariant.is_some(),
+            abi: &self.contract.abi,
+        };
+        let corpus_files = invariant_contract
+            .invariant_functions
+            .iter()
+            .filter_map(|func| invariant_config.corpus_file(self.name, &func.name))
+            .collect();
+        let mut evm = InvariantExecutor::new(
+            self.executor.clone(),
+            runner,
+            invariant_config.clone(),
+            identified_contracts,
+            known_contracts,
+        )
+        .with_corpus_files(corpus_files);
+
+        let InvariantFuzzTestResult {
+            errors,
+            cases,
+            reverts,
+            last_run_inputs,
+            mut gas_report_traces,
+            metrics,
+            optimization,
+        } = match evm.invariant_fuzz(invariant_contract.clone(), &fuzz_fixtures) {
+            Ok(x) => x,
+            Err(e) => {
+                for func in &invariant_contract.invariant_functions {
+                    results.insert(
+                        func.signature(),
+                        TestResult {
+                            status: TestStatus::Failure,
+                            reason: Some(format!(
+                                "failed to set up invariant testing environment: {e}"
+                            )),
+                            decoded_logs: decode_console_logs(&logs),
+                            traces: traces.clone(),
+                            labeled_addresses: labeled_addresses.clone(),
+                            kind: TestKind::Invariant {
+                                runs: 0,
+                                calls: 0,
+                                reverts: 0,
+                                metrics: Default::default(),
+                                best_value: None,
+                            },
+                            duration: start.elapsed(),
+                            ..Default::default()
+                        },
+                    );
                 }
+                return results
+            }
+        };
+
+        let runs = cases.len();
+        let calls = cases.iter().map(|sequence| sequence.cases().len()).sum();
+
+        // Persists the failing sequence of an invariant in the invariant failure dir.
+        let persist_failure = |func: &Function, call_sequence: Vec<BaseCounterExample>| {
+            if call_sequence.is_empty() {
+                return None
+            }
+            if let Err(err) = foundry_common::fs::create_dir_all(&failure_dir) {
+                error!(%err, "Failed to create invariant failure dir");
+            } else if let Err(err) =
+                foundry_common::fs::write_json_file(&failure_dir.join(&func.name), &call_sequence)
+            {
+                error!(%err, "Failed to record call sequence");
+            }
+            Some(CounterExample::Sequence(call_sequence))
+        };
+
+        // With `fail_on_revert`, a revert fails every unbroken invariant with the same case, so it
+        // is shrunk and replayed once for all of them.
+        let reverted_functions = invariant_contract
+            .invariant_functions
+            .iter()
+            .copied()
+            .filter(|func| matches!(errors.get(&func.name), Some(InvariantFuzzError::Revert(_))))
+            .collect::<Vec<_>>();
+        let mut revert_replay = None;
+        if let Some(InvariantFuzzError::Revert(case_data)) =
+            reverted_functions.first().and_then(|func| errors.get(&func.name))
+        {
+            let reverted_invariants = InvariantContract {
+                invariant_functions: reverted_functions,
+                ..invariant_contract.clone()
             };
+            let mut logs = logs.clone();
+            let mut traces = traces.clone();
+            let mut coverage = coverage.clone();
+            match replay_error(
+                case_data,
+                &reverted_invariants,
+                self.executor.clone(),
+                known_contracts,
+                identified_contracts.clone(),
+                &mut logs,
+                &mut traces,
+                &mut coverage,
+            ) {
+                Ok(call_sequence) => revert_replay = Some((call_sequence, logs, traces, coverage)),
+                Err(err) => error!(%err, "Failed to replay invariant error"),
+            }
+        }
 
-        let mut counterexample = None;
-        let success = error.is_none();
-        let reason = error.as_ref().and_then(|err| err.revert_reason());
-
-        match error {
-            // If invariants were broken, replay the error to collect logs and traces
-            Some(error) => match error {
-                InvariantFuzzError::BrokenInvariant(case_data) |
-                InvariantFuzzError::Revert(case_data) => {
-                    // Replay error to create counterexample and to collect logs, traces and
-                    // coverage.
-                    match replay_error(
-                        &case_data,
-                        &invariant_contract,
+        for &func in &invariant_contract.invariant_functions {
+            // Each invariant is replayed on its own to collect its logs, traces and coverage.
+            let single_invariant =
+                InvariantContract { invariant_functions: vec![func], ..invariant_contract.clone() };
+            let mut logs = logs.clone();
+            let mut traces = traces.clone();
+            let mut coverage = coverage.clone();
+
+            let error = errors.get(&func.name);
+            let mut counterexample = None;
+            // An optimization invariant that never returned a value, e.g. because it always
+            // reverted, has nothing to report and fails.
+            let missing_value =
+                error.is_none() && invariant_contract.is_optimization() && optimization.is_none();
+            let success = error.is_none() && !missing_value;
+            let reason = error.and_then(|err| err.revert_reason()).or_else(|| {
+                missing_value.then(|| "optimization invariant never returned a value".to_string())
+            });
+
+            match error {
+                // If the invariant was broken, replay the error to collect logs and traces
+                Some(error) => match error {
+                    InvariantFuzzError::BrokenInvariant(case_data) => {
+                        // Replay error to create counterexample and to collect logs, traces and
+                        // coverage.
+                        match replay_error(
+                            case_data,
+                            &single_invariant,
+                            self.executor.clone(),
+                            known_contracts,
+                            identified_contracts.clone(),
+                            &mut logs,
+                            &mut traces,
+                            &mut coverage,
+                        ) {
+                            Ok(call_sequence) => {
+                                counterexample = persist_failure(func, call_sequence)
+                            }
+                            Err(err) => {
+                                error!(%err, "Failed to replay invariant error");
+                            }
+                        };
+                    }
+                    InvariantFuzzError::Revert(_) => {
+                        if let Some((call_sequence, revert_logs, revert_traces, revert_coverage)) =
+                            &revert_replay
+                        {
+                            logs.clone_from(revert_logs);
+                            traces.clone_from(revert_traces);
+                            coverage.clone_from(revert_coverage);
+                            counterexample = persist_failure(func, call_sequence.clone());
+                        }
+                    }
+                    InvariantFuzzError::MaxAssumeRejects(_) => {}
+                },
+
+                // If an optimization invariant reached a best value, shrink and replay the sequence
+                // reaching it to collect logs and traces.
+                None if optimization.is_some() => {
+                    match replay_optimization(
+                        optimization.as_ref().unwrap(),
+                        invariant_config.shrink_run_limit,
+                        &single_invariant,
                         self.executor.clone(),
                         known_contracts,
                         identified_contracts.clone(),
@@ -670,66 +872,61 @@ impl<'a> ContractRunner<'a> {
                         &mut coverage,
                     ) {
                         Ok(call_sequence) => {
-                            if !call_sequence.is_empty() {
-                                // Persist error in invariant failure dir.
-                                if let Err(err) = foundry_common::fs::create_dir_all(failure_dir) {
-                                    error!(%err, "Failed to create invariant failure dir");
-                                } else if let Err(err) = foundry_common::fs::write_json_file(
-                                    failure_file.as_path(),
-                                    &call_sequence,
-                                ) {
-                                    error!(%err, "Failed to record call sequence");
-                                }
-                                counterexample = Some(CounterExample::Sequence(call_sequence))
-                            }
+                            counterexample = Some(CounterExample::Sequence(call_sequence))
                         }
                         Err(err) => {
-                            error!(%err, "Failed to replay invariant error");
+                            error!(%err, "Failed to replay optimization sequence");
                         }
-                    };
+                    }
                 }
-                InvariantFuzzError::MaxAssumeRejects(_) => {}
-            },
 
-            // If invariants ran successfully, replay the last run to collect logs and
-            // traces.
-            _ => {
-                if let Err(err) = replay_run(
-                    &invariant_contract,
-                    self.executor.clone(),
-                    known_contracts,
-                    identified_contracts.clone(),
-                    &mut logs,
-                    &mut traces,
-                    &mut coverage,
-                    last_run_inputs.clone(),
-                ) {
-                    error!(%err, "Failed to replay last invariant run");
+                // If the invariant held, replay the last run to collect logs and traces.
+                _ => {
+                    if let Err(err) = replay_run(
+                        &single_invariant,
+                        self.executor.clone(),
+                        known_contracts,
+                        identified_contracts.clone(),
+                        &mut logs,
+                        &mut traces,
+                        &mut coverage,
+                        last_run_inputs.clone(),
+                    ) {
+                        error!(%err, "Failed to replay last invariant run");
+                    }
                 }
             }
-        }
 
-        TestResult {
-            status: match success {
-                true => TestStatus::Success,
-                false => TestStatus::Failure,
-            },
-            reason,
-            counterexample,
-            decoded_logs: decode_console_logs(&logs),
-            logs,
-            kind: TestKind::Invariant {
-                runs: cases.len(),
-                calls: cases.iter().map(|sequence| sequence.cases().len()).sum(),
-                reverts,
-            },
-            coverage,
-            traces,
-            labeled_addresses: labeled_addresses.clone(),
-            duration: start.elapsed(),
-            gas_report_traces,
-            ..Default::default() // TODO collect debug traces on the last run or error
+            results.insert(
+                func.signature(),
+                TestResult {
+                    status: match success {
+                        true => TestStatus::Success,
+                        false => TestStatus::Failure,
+                    },
+                    reason,
+                    counterexample,
+                    decoded_logs: decode_console_logs(&logs),
+                    logs,
+                    kind: TestKind::Invariant {
+                        runs,
+                        calls,
+                        reverts,
+                        metrics: metrics.clone(),
+                        best_value: optimization.as_ref().map(|(best_value, _)| *best_value),
+                    },
+                    coverage,
+                    traces,
+                    labeled_addresses: labeled_addresses.clone(),
+                    duration: start.elapsed(),
+                    // The campaign's gas is only reported once.
+                    gas_report_traces: std::mem::take(&mut gas_report_traces),
+                    ..Default::default() // TODO collect debug traces on the last run or error
+                },
+            );
         }
+
+        results
     }
 
     #[instrument(name = "fuzz_test", skip_all, fields(name = %func.signature(), %should_fail))]
@@ -769,7 +966,8 @@ impl<'a> ContractRunner<'a> {
             runner.clone(),
             self.sender,
             fuzz_config.clone(),
-        );
+        )
+        .with_corpus_file(fuzz_config.corpus_file(self.name, &func.name));
         let result =
             fuzzed_executor.fuzz(func, &fuzz_fixtures, address, should_fail, self.revert_decoder);
 
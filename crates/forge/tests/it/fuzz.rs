@@ -3,16 +3,17 @@
 use crate::{config::*, test_helpers::TEST_DATA_DEFAULT};
 use alloy_primitives::{Bytes, U256};
 use forge::{
-    fuzz::CounterExample,
-    result::{SuiteResult, TestStatus},
+    fuzz::{corpus::FuzzCorpusFile, CounterExample},
+    result::{SuiteResult, TestKind, TestStatus},
 };
+use foundry_common::fs;
 use foundry_test_utils::Filter;
 use std::collections::BTreeMap;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_fuzz() {
     let filter = Filter::new(".*", ".*", ".*fuzz/")
-        .exclude_tests(r"invariantCounter|testIncrement\(address\)|testNeedle\(uint256\)|testSuccessChecker\(uint256\)|testSuccessChecker2\(int256\)|testSuccessChecker3\(uint32\)|testStorageOwner\(address\)|testImmutableOwner\(address\)")
+        .exclude_tests(r"invariantCounter|testIncrement\(address\)|testNeedle\(uint256\)|testSuccessChecker\(uint256\)|testSuccessChecker2\(int256\)|testSuccessChecker3\(uint32\)|testStorageOwner\(address\)|testImmutableOwner\(address\)|testMagic")
         .exclude_paths("invariant");
     let mut runner = TEST_DATA_DEFAULT.runner();
     let suite_result = runner.test_collect(&filter);
@@ -175,3 +176,46 @@ async fn test_scrape_bytecode() {
         }
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_coverage_guided_fuzz() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/FuzzCoverageGuided");
+    let mut runner = TEST_DATA_DEFAULT.runner();
+    let results = runner.test_collect(&filter);
+
+    assert_multiple(
+        &results,
+        BTreeMap::from([(
+            "default/fuzz/FuzzCoverageGuided.t.sol:FuzzCoverageGuided",
+            vec![(
+                "testMagicGuided(uint256,uint256,uint256,uint256,uint256,uint256)",
+                false,
+                Some("magic found".into()),
+                None,
+                None,
+            )],
+        )]),
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuzz_corpus_seeds_next_run() {
+    let filter = Filter::new(r"testSuccessChecker3\(uint32\)", ".*", ".*fuzz/FuzzPositive");
+    let corpus_dir = tempfile::tempdir().unwrap();
+    let corpus_file = corpus_dir.path().join("FuzzPositive").join("testSuccessChecker3.json");
+    let mut runner = TEST_DATA_DEFAULT.runner();
+    runner.test_options.fuzz.corpus_dir = Some(corpus_dir.path().into());
+
+    runner.test_collect(&filter);
+    let corpus: FuzzCorpusFile = fs::read_json_file(&corpus_file).unwrap();
+    assert_eq!(corpus.function, "testSuccessChecker3(uint32)");
+    assert!(!corpus.inputs.is_empty());
+
+    // the next run replays the saved inputs before generating new ones
+    let results = runner.test_collect(&filter);
+    let result = &results["default/fuzz/FuzzPositive.t.sol:FuzzPositive"].test_results
+        ["testSuccessChecker3(uint32)"];
+    assert_eq!(result.status, TestStatus::Success);
+    let TestKind::Fuzz { first_case, .. } = &result.kind else { panic!("not a fuzz test") };
+    assert_eq!(first_case.calldata, corpus.inputs[0]);
+}
@@ -1,8 +1,16 @@
 //! Invariant tests.
 
 use crate::{config::*, test_helpers::TEST_DATA_DEFAULT};
-use alloy_primitives::U256;
-use forge::{fuzz::CounterExample, TestOptions};
+use alloy_primitives::{keccak256, Address, Selector, I256, U256};
+use forge::{
+    fuzz::{
+        corpus::{CorpusCall, InvariantCorpusFile},
+        CounterExample,
+    },
+    result::{TestKind, TestStatus},
+    TestOptions,
+};
+use foundry_common::fs;
 use foundry_test_utils::Filter;
 use std::collections::BTreeMap;
 
@@ -34,6 +42,29 @@ async fn test_invariant() {
     assert_multiple(
         &results,
         BTreeMap::from([
+            (
+                "default/fuzz/invariant/common/InvariantAfterInvariant.t.sol:InvariantAfterInvariantTest",
+                vec![
+                    (
+                        "invariant_always_true()",
+                        false,
+                        Some("revert: afterInvariant failure".into()),
+                        None,
+                        None,
+                    ),
+                    (
+                        "invariant_count_below_three()",
+                        false,
+                        Some("revert: count reached 3".into()),
+                        None,
+                        None,
+                    ),
+                ],
+            ),
+            (
+                "default/fuzz/invariant/common/InvariantAfterInvariant.t.sol:InvariantAfterInvariantNameTest",
+                vec![("invariant_true()", true, None, None, None)],
+            ),
             (
                 "default/fuzz/invariant/common/InvariantHandlerFailure.t.sol:InvariantHandlerFailure",
                 vec![("statefulFuzz_BrokenInvariant()", true, None, None, None)],
@@ -156,6 +187,21 @@ async fn test_invariant() {
                 "default/fuzz/invariant/common/InvariantPreserveState.t.sol:InvariantPreserveState",
                 vec![("invariant_preserve_state()", true, None, None, None)],
             ),
+            (
+                "default/fuzz/invariant/common/InvariantOptimization.t.sol:InvariantOptimization",
+                vec![
+                    ("invariant_optimize_value()", true, None, None, None),
+                    (
+                        "invariant_optimize_reverts()",
+                        false,
+                        Some("optimization invariant never returned a value".into()),
+                        None,
+                        None,
+                    ),
+                    ("invariant_value_returned()", true, None, None, None),
+                    ("invariant_value_nonnegative()", true, None, None, None),
+                ],
+            ),
             (
                 "default/fuzz/invariant/common/InvariantCalldataDictionary.t.sol:InvariantCalldataDictionary",
                 vec![(
@@ -229,6 +275,16 @@ async fn test_invariant() {
                     None,
                     None,
                 )],
+            ),
+            (
+                "default/fuzz/invariant/common/InvariantCoverageGuided.t.sol:InvariantCoverageGuided",
+                vec![(
+                    "invariant_guided_locked()",
+                    false,
+                    Some("revert: unlocked".into()),
+                    None,
+                    None,
+                )],
             )
         ]),
     );
@@ -561,6 +617,123 @@ async fn test_invariant_decode_custom_error() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invariant_after_invariant() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/invariant/common/InvariantAfterInvariant.t.sol");
+    let mut runner = TEST_DATA_DEFAULT.runner();
+    runner.test_options.invariant.failure_persist_dir =
+        Some(tempfile::tempdir().unwrap().into_path());
+    let results = runner.test_collect(&filter);
+    let test_results = &results
+        ["default/fuzz/invariant/common/InvariantAfterInvariant.t.sol:InvariantAfterInvariantTest"]
+        .test_results;
+
+    // Every broken invariant gets its own counterexample, shrunk against its own failure.
+    for (test, calls) in [("invariant_count_below_three()", 3), ("invariant_always_true()", 5)] {
+        match test_results[test].counterexample.as_ref().unwrap() {
+            CounterExample::Single(_) => panic!("CounterExample should be a sequence."),
+            CounterExample::Sequence(sequence) => assert_eq!(sequence.len(), calls),
+        }
+    }
+
+    // `afterinvariant` doesn't match the exact name and is never called.
+    let test_results = &results
+        ["default/fuzz/invariant/common/InvariantAfterInvariant.t.sol:InvariantAfterInvariantNameTest"]
+        .test_results;
+    assert_eq!(test_results["invariant_true()"].status, TestStatus::Success);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invariant_optimization() {
+    let filter = Filter::new(
+        "invariant_optimize_value",
+        ".*",
+        ".*fuzz/invariant/common/InvariantOptimization.t.sol",
+    );
+    let mut runner = TEST_DATA_DEFAULT.runner();
+    let results = runner.test_collect(&filter);
+    let result = results
+        .values()
+        .last()
+        .expect("Invariant contract should be testable.")
+        .test_results
+        .values()
+        .last()
+        .expect("Invariant contract should be testable.");
+    let TestKind::Invariant { best_value: Some(best_value), .. } = result.kind else {
+        panic!("expected an optimization invariant test")
+    };
+    assert!(best_value > I256::ZERO);
+
+    // The best sequence is shrunk to the increments reaching the best value.
+    match result.counterexample.as_ref().unwrap() {
+        CounterExample::Single(_) => panic!("CounterExample should be a sequence."),
+        CounterExample::Sequence(sequence) => {
+            assert_eq!(I256::try_from(sequence.len()).unwrap(), best_value);
+            assert!(sequence.iter().all(|call| call.signature.as_deref() == Some("inc()")));
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invariant_optimization_without_value() {
+    let filter = Filter::new(
+        "invariant_optimize_reverts|invariant_value_returned",
+        ".*",
+        ".*fuzz/invariant/common/InvariantOptimization.t.sol",
+    );
+    let mut runner = TEST_DATA_DEFAULT.runner();
+    let results = runner.test_collect(&filter);
+    let test_results = &results
+        ["default/fuzz/invariant/common/InvariantOptimization.t.sol:InvariantOptimization"]
+        .test_results;
+
+    // An optimization invariant that always reverts fails instead of reporting no best value.
+    let result = &test_results["invariant_optimize_reverts()"];
+    assert_eq!(result.status, TestStatus::Failure);
+    assert_eq!(result.reason.as_deref(), Some("optimization invariant never returned a value"));
+    assert!(matches!(result.kind, TestKind::Invariant { best_value: None, .. }));
+
+    // Returning an `int256` without the `invariant_optimize_` prefix is a regular invariant.
+    let result = &test_results["invariant_value_returned()"];
+    assert_eq!(result.status, TestStatus::Success);
+    assert!(matches!(result.kind, TestKind::Invariant { best_value: None, .. }));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invariant_metrics() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/invariant/common/InvariantCustomError.t.sol");
+    let mut runner = TEST_DATA_DEFAULT.runner();
+    runner.test_options.invariant.runs = 10;
+    let results = runner.test_collect(&filter);
+    let result = results
+        .values()
+        .last()
+        .expect("Invariant contract should be testable.")
+        .test_results
+        .values()
+        .last()
+        .expect("Invariant contract should be testable.");
+    let TestKind::Invariant { calls, reverts, metrics, .. } = &result.kind else {
+        panic!("expected an invariant test")
+    };
+
+    assert_eq!(metrics.values().map(|metrics| metrics.calls).sum::<usize>(), *calls);
+    assert_eq!(metrics.values().map(|metrics| metrics.reverts).sum::<usize>(), *reverts);
+    let (key, handler) = metrics
+        .iter()
+        .find(|(_, metrics)| metrics.function.as_deref() == Some("revertTarget"))
+        .expect("no metrics of the handler");
+    assert_eq!(handler.contract, "Handler");
+    assert_eq!(key.selector, Selector::from_slice(&keccak256("revertTarget()")[..4]));
+    assert!(handler.calls > 0);
+    assert_eq!(handler.reverts, handler.calls);
+    assert_eq!(
+        handler.top_revert_reasons(1),
+        vec![("InvariantCustomError(111, \"custom\")", handler.reverts)]
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_invariant_fuzzed_selected_targets() {
     let filter = Filter::new(".*", ".*", ".*fuzz/invariant/target/FuzzedTargetContracts.t.sol");
@@ -668,3 +841,48 @@ async fn test_invariant_roll_fork_handler() {
         )]),
     );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invariant_corpus_prunes_stale_calls() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/invariant/common/InvariantReentrancy.t.sol");
+    let corpus_dir = tempfile::tempdir().unwrap();
+    let corpus_file = corpus_dir.path().join("InvariantReentrancy").join("invariantNotStolen.json");
+    let mut runner = TEST_DATA_DEFAULT.runner();
+    runner.test_options.invariant.corpus_dir = Some(corpus_dir.path().into());
+
+    runner.test_collect(&filter);
+    let mut corpus: InvariantCorpusFile = fs::read_json_file(&corpus_file).unwrap();
+    let call = corpus.sequences[0][0].clone();
+
+    // a call to a contract that is no longer targeted and a call that can't be decoded anymore
+    let stale = vec![
+        CorpusCall { target: Address::repeat_byte(0x11), ..call.clone() },
+        CorpusCall { calldata: call.calldata.slice(..2), ..call.clone() },
+    ];
+    corpus.sequences.push(stale.clone());
+    fs::write_json_file(&corpus_file, &corpus).unwrap();
+
+    // the next run is seeded with the saved sequences without the stale calls
+    runner.test_collect(&filter);
+    let corpus: InvariantCorpusFile = fs::read_json_file(&corpus_file).unwrap();
+    let calls = corpus.sequences.iter().flatten().collect::<Vec<_>>();
+    assert!(calls.contains(&&call));
+    assert!(stale.iter().all(|stale| !calls.contains(&stale)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invariant_corpus_saved_per_invariant() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/invariant/common/InvariantTest1.t.sol");
+    let corpus_dir = tempfile::tempdir().unwrap();
+    let mut runner = TEST_DATA_DEFAULT.runner();
+    runner.test_options.invariant.corpus_dir = Some(corpus_dir.path().into());
+    runner.test_collect(&filter);
+
+    // both invariants are checked in one campaign, its corpus is found when either runs alone
+    let dir = corpus_dir.path().join("InvariantTest");
+    let first: InvariantCorpusFile =
+        fs::read_json_file(&dir.join("invariant_neverFalse.json")).unwrap();
+    let second: InvariantCorpusFile =
+        fs::read_json_file(&dir.join("statefulFuzz_neverFalseWithInvariantAlias.json")).unwrap();
+    assert_eq!(first, second);
+}
@@ -89,6 +89,8 @@ impl ForgeTestProfile {
                     max_fuzz_dictionary_values: 10_000,
                 },
                 gas_report_samples: 256,
+                coverage_guided: false,
+                corpus_dir: None,
                 failure_persist_dir: Some(tempfile::tempdir().unwrap().into_path()),
                 failure_persist_file: Some("testfailure".to_string()),
             })
@@ -107,6 +109,8 @@ impl ForgeTestProfile {
                 shrink_run_limit: 2usize.pow(18u32),
                 max_assume_rejects: 65536,
                 gas_report_samples: 256,
+                coverage_guided: false,
+                corpus_dir: None,
                 failure_persist_dir: Some(tempfile::tempdir().unwrap().into_path()),
             })
             .build(output, Path::new(self.project().root()))
@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+
+// Every argument only passes its branch once every 16 values, and hashing them keeps the fuzz
+// dictionary from providing the magic values. Unguided fuzzing has to guess all arguments at once.
+contract FuzzCoverageGuided is DSTest {
+    function isMagic(uint256 value) internal pure returns (bool) {
+        return uint256(keccak256(abi.encode(value))) % 16 == 0;
+    }
+
+    function checkMagic(uint256 a, uint256 b, uint256 c, uint256 d, uint256 e, uint256 f)
+        internal
+        pure
+    {
+        if (isMagic(a)) {
+            if (isMagic(b)) {
+                if (isMagic(c)) {
+                    if (isMagic(d)) {
+                        if (isMagic(e)) {
+                            if (isMagic(f)) {
+                                revert("magic found");
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// forge-config: default.fuzz.runs = 20000
+    /// forge-config: default.fuzz.coverage-guided = true
+    function testMagicGuided(uint256 a, uint256 b, uint256 c, uint256 d, uint256 e, uint256 f)
+        public
+        pure
+    {
+        checkMagic(a, b, c, d, e, f);
+    }
+}
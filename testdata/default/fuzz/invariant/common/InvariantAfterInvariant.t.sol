@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.13;
+
+import "ds-test/test.sol";
+
+struct FuzzSelector {
+    address addr;
+    bytes4[] selectors;
+}
+
+contract AfterInvariantHandler {
+    uint256 public count;
+
+    function inc() external {
+        count += 1;
+    }
+}
+
+contract InvariantAfterInvariantTest is DSTest {
+    AfterInvariantHandler handler;
+
+    function setUp() public {
+        handler = new AfterInvariantHandler();
+    }
+
+    function targetSelectors() public returns (FuzzSelector[] memory) {
+        FuzzSelector[] memory targets = new FuzzSelector[](1);
+        bytes4[] memory selectors = new bytes4[](1);
+        selectors[0] = handler.inc.selector;
+        targets[0] = FuzzSelector(address(handler), selectors);
+        return targets;
+    }
+
+    function afterInvariant() public {
+        require(handler.count() < 5, "afterInvariant failure");
+    }
+
+    // Broken after 3 calls, before the end of the first run.
+    function invariant_count_below_three() public {
+        require(handler.count() < 3, "count reached 3");
+    }
+
+    // Never broken by a single call, only by `afterInvariant` at the end of a run.
+    function invariant_always_true() public {
+        require(true, "unreachable");
+    }
+}
+
+contract InvariantAfterInvariantNameTest is DSTest {
+    AfterInvariantHandler handler;
+
+    function setUp() public {
+        handler = new AfterInvariantHandler();
+    }
+
+    // Only the exact `afterInvariant` name without inputs is called at the end of a run.
+    function afterinvariant() public {
+        require(false, "afterinvariant called");
+    }
+
+    function afterInvariant(uint256) public {
+        require(false, "afterInvariant(uint256) called");
+    }
+
+    function invariant_true() public {
+        require(true, "unreachable");
+    }
+}
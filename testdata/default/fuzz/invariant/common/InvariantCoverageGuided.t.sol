@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+
+// A call only advances the stage once every 64 values, and hashing them keeps the fuzz dictionary
+// from providing the magic values. Every stage is a new branch, so guided fuzzing keeps the
+// sequences that advanced and builds on them.
+contract MagicStages {
+    uint256 public stage;
+
+    function step(uint256 value) public {
+        if (uint256(keccak256(abi.encode(value))) % 64 != 0) {
+            return;
+        }
+        if (stage == 0) {
+            stage = 1;
+        } else if (stage == 1) {
+            stage = 2;
+        } else if (stage == 2) {
+            stage = 3;
+        } else if (stage == 3) {
+            stage = 4;
+        } else if (stage == 4) {
+            stage = 5;
+        } else if (stage == 5) {
+            stage = 6;
+        }
+    }
+}
+
+contract InvariantCoverageGuided is DSTest {
+    MagicStages stages;
+
+    function setUp() public {
+        stages = new MagicStages();
+    }
+
+    /// forge-config: default.invariant.runs = 5000
+    /// forge-config: default.invariant.depth = 10
+    /// forge-config: default.invariant.coverage-guided = true
+    function invariant_guided_locked() public {
+        require(stages.stage() < 6, "unlocked");
+    }
+}
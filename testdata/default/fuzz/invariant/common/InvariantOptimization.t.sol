@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.13;
+
+import "ds-test/test.sol";
+
+contract OptimizationCounter {
+    int256 public value;
+
+    function inc() external {
+        value += 1;
+    }
+
+    function reset() external {
+        value = 0;
+    }
+}
+
+contract InvariantOptimization is DSTest {
+    OptimizationCounter counter;
+
+    function setUp() public {
+        counter = new OptimizationCounter();
+    }
+
+    function invariant_optimize_value() public view returns (int256) {
+        return counter.value();
+    }
+
+    function invariant_optimize_reverts() public pure returns (int256) {
+        revert("no value");
+    }
+
+    function invariant_value_returned() public view returns (int256) {
+        require(counter.value() >= 0, "negative value");
+        return counter.value();
+    }
+
+    function invariant_value_nonnegative() public view {
+        require(counter.value() >= 0, "negative value");
+    }
+}